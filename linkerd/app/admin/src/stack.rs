@@ -116,6 +116,7 @@ impl Config {
             .push_map_target(|(permit, http)| Permitted { permit, http })
             .push(inbound::policy::NewHttpPolicy::layer(
                 metrics.http_authz.clone(),
+                metrics.http_faults.clone(),
             ))
             .push(Rescue::layer())
             .push_on_service(http::BoxResponse::layer())
@@ -2,6 +2,7 @@ pub use crate::exp_backoff::ExponentialBackoff;
 use crate::{
     proxy::http::{self, h1, h2},
     svc::{queue, CloneParam, ExtractParam, Param},
+    tls,
     transport::{Keepalive, ListenAddr},
 };
 use std::time::Duration;
@@ -63,6 +64,17 @@ impl ProxyConfig {
     pub fn detect_http(&self) -> CloneParam<linkerd_detect::Config<http::DetectHttp>> {
         linkerd_detect::Config::from_timeout(self.detect_protocol_timeout).into()
     }
+
+    pub fn detect_sni(&self) -> CloneParam<linkerd_detect::Config<tls::DetectSni>> {
+        linkerd_detect::Config {
+            detect: tls::DetectSni::default(),
+            // The ClientHello must be buffered in its entirety, and it may
+            // span a full TLS record.
+            capacity: 16 * 1024 + 5,
+            timeout: self.detect_protocol_timeout,
+        }
+        .into()
+    }
 }
 
 // === impl ServerConfig ===
@@ -75,6 +75,7 @@ pub type RspBody =
 const EWMA_CONFIG: http::balance::EwmaConfig = http::balance::EwmaConfig {
     default_rtt: time::Duration::from_millis(30),
     decay: time::Duration::from_secs(10),
+    slow_start: None,
 };
 
 impl Config {
@@ -1,22 +1,44 @@
 use linkerd_error::Error;
 use linkerd_opencensus::proto::trace::v1 as oc;
+use linkerd_opentelemetry::proto::{common::v1 as otel_common, trace::v1 as otel};
 use linkerd_stack::layer;
-use linkerd_trace_context::{self as trace_context, TraceContext};
-use std::{collections::HashMap, sync::Arc};
+use linkerd_trace_context::{self as trace_context, Sampler, TraceContext};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use thiserror::Error;
 use tokio::sync::mpsc;
 
-pub type OpenCensusSink = Option<mpsc::Sender<oc::Span>>;
 pub type Labels = Arc<HashMap<String, String>>;
 
-/// SpanConverter converts trace_context::Span objects into OpenCensus agent
-/// protobuf span objects. SpanConverter receives trace_context::Span objects by
-/// implmenting the SpanSink trait. For each span that it receives, it converts
-/// it to an OpenCensus span and then sends it on the provided mpsc::Sender.
+/// Sends spans to a trace collector, subject to the proxy's sampling policy.
+#[derive(Clone, Debug)]
+pub struct SpanSink {
+    pub exporter: Exporter,
+    pub sampler: Sampler,
+}
+
+/// Sends converted spans to a trace collector's export task.
+///
+/// The variant determines the protocol that spans are converted into.
+#[derive(Clone, Debug)]
+pub enum Exporter {
+    OpenCensus(mpsc::Sender<oc::Span>),
+    OpenTelemetry(mpsc::Sender<otel::Span>),
+}
+
+/// SpanConverter converts trace_context::Span objects into OpenCensus or
+/// OpenTelemetry protobuf span objects. SpanConverter receives
+/// trace_context::Span objects by implmenting the SpanSink trait. For each span
+/// that it receives, it converts it to the sink's protocol and then sends it on
+/// the provided mpsc::Sender.
 #[derive(Clone)]
 pub struct SpanConverter {
     kind: Kind,
-    sink: mpsc::Sender<oc::Span>,
+    exporter: Exporter,
     labels: Labels,
 }
 
@@ -29,14 +51,14 @@ pub struct IdLengthError {
 }
 
 pub fn server<S>(
-    sink: OpenCensusSink,
+    sink: Option<SpanSink>,
     labels: impl Into<Labels>,
 ) -> impl layer::Layer<S, Service = TraceContext<Option<SpanConverter>, S>> + Clone {
     SpanConverter::layer(Kind::Server, sink, labels)
 }
 
 pub fn client<S>(
-    sink: OpenCensusSink,
+    sink: Option<SpanSink>,
     labels: impl Into<Labels>,
 ) -> impl layer::Layer<S, Service = TraceContext<Option<SpanConverter>, S>> + Clone {
     SpanConverter::layer(Kind::Client, sink, labels)
@@ -51,17 +73,19 @@ enum Kind {
 impl SpanConverter {
     fn layer<S>(
         kind: Kind,
-        sink: OpenCensusSink,
+        sink: Option<SpanSink>,
         labels: impl Into<Labels>,
     ) -> impl layer::Layer<S, Service = TraceContext<Option<Self>, S>> + Clone {
-        TraceContext::layer(sink.map(move |sink| Self {
+        let sampler = sink.as_ref().map(|s| s.sampler.clone()).unwrap_or_default();
+        let converter = sink.map(move |SpanSink { exporter, .. }| Self {
             kind,
-            sink,
+            exporter,
             labels: labels.into(),
-        }))
+        });
+        TraceContext::layer(converter, sampler)
     }
 
-    fn mk_span(&self, mut span: trace_context::Span) -> Result<oc::Span, IdLengthError> {
+    fn mk_oc_span(&self, mut span: trace_context::Span) -> Result<oc::Span, IdLengthError> {
         let mut attributes = HashMap::<String, oc::AttributeValue>::new();
         for (k, v) in self.labels.iter() {
             attributes.insert(
@@ -84,8 +108,8 @@ impl SpanConverter {
         Ok(oc::Span {
             trace_id: into_bytes(span.trace_id, 16)?,
             span_id: into_bytes(span.span_id, 8)?,
-            tracestate: None,
-            parent_span_id: into_bytes(span.parent_id, 8)?,
+            tracestate: span.trace_state.as_deref().map(oc_tracestate),
+            parent_span_id: into_parent_bytes(span.parent_id)?,
             name: Some(truncatable(span.span_name)),
             kind: self.kind as i32,
             start_time: Some(span.start.into()),
@@ -103,6 +127,87 @@ impl SpanConverter {
             child_span_count: None,
         })
     }
+
+    /// Converts a span into an OpenTelemetry span, mapping the proxy's HTTP
+    /// labels onto OpenTelemetry's semantic conventions.
+    fn mk_otel_span(&self, mut span: trace_context::Span) -> Result<otel::Span, IdLengthError> {
+        let mut attributes = Vec::with_capacity(self.labels.len() + span.labels.len() + 2);
+        for (k, v) in self.labels.iter() {
+            attributes.push(string_attr(k.clone(), v.clone()));
+        }
+
+        // The request's authority is preferred over its host header when both
+        // are present.
+        let authority = span
+            .labels
+            .remove("http.authority")
+            .into_iter()
+            .chain(span.labels.remove("http.host"))
+            .find_map(|a| http::uri::Authority::from_str(&a).ok());
+        if let Some(authority) = authority {
+            attributes.push(string_attr("server.address", authority.host()));
+            if let Some(port) = authority.port_u16() {
+                attributes.push(int_attr("server.port", port.into()));
+            }
+        }
+
+        let mut status = None;
+        for (k, v) in span.labels.drain() {
+            match k {
+                "http.method" => attributes.push(string_attr("http.request.method", v)),
+                "http.path" => match v.split_once('?') {
+                    Some((path, query)) => {
+                        attributes.push(string_attr("url.path", path));
+                        attributes.push(string_attr("url.query", query));
+                    }
+                    None => attributes.push(string_attr("url.path", v)),
+                },
+                "http.status_code" => match http::StatusCode::from_str(&v) {
+                    Ok(code) => {
+                        attributes
+                            .push(int_attr("http.response.status_code", code.as_u16().into()));
+                        status = Some(self.otel_status(code));
+                    }
+                    Err(_) => attributes.push(string_attr("http.response.status_code", v)),
+                },
+                k => attributes.push(string_attr(k, v)),
+            }
+        }
+
+        let kind = match self.kind {
+            Kind::Server => otel::span::SpanKind::Server,
+            Kind::Client => otel::span::SpanKind::Client,
+        };
+        Ok(otel::Span {
+            trace_id: into_bytes(span.trace_id, 16)?,
+            span_id: into_bytes(span.span_id, 8)?,
+            trace_state: span.trace_state.unwrap_or_default(),
+            parent_span_id: into_parent_bytes(span.parent_id)?,
+            name: span.span_name,
+            kind: kind as i32,
+            start_time_unix_nano: unix_nanos(span.start),
+            end_time_unix_nano: unix_nanos(span.end),
+            attributes,
+            status,
+            ..Default::default()
+        })
+    }
+
+    /// Per OpenTelemetry's HTTP semantic conventions, 5xx responses are
+    /// errors. 4xx responses are only errors from the client's perspective.
+    fn otel_status(&self, code: http::StatusCode) -> otel::Status {
+        let is_error =
+            code.is_server_error() || (self.kind == Kind::Client && code.is_client_error());
+        let code = if is_error {
+            otel::status::StatusCode::Error
+        } else {
+            otel::status::StatusCode::Unset
+        };
+        otel::Status {
+            code: code as i32,
+            ..Default::default()
+        }
+    }
 }
 
 impl trace_context::SpanSink for SpanConverter {
@@ -112,8 +217,16 @@ impl trace_context::SpanSink for SpanConverter {
     }
 
     fn try_send(&mut self, span: trace_context::Span) -> Result<(), Error> {
-        let span = self.mk_span(span)?;
-        self.sink.try_send(span).map_err(Into::into)
+        match self.exporter {
+            Exporter::OpenCensus(ref sink) => {
+                let span = self.mk_oc_span(span)?;
+                sink.try_send(span).map_err(Into::into)
+            }
+            Exporter::OpenTelemetry(ref sink) => {
+                let span = self.mk_otel_span(span)?;
+                sink.try_send(span).map_err(Into::into)
+            }
+        }
     }
 }
 
@@ -131,9 +244,183 @@ fn into_bytes(id: trace_context::Id, size: usize) -> Result<Vec<u8>, IdLengthErr
     }
 }
 
+/// Root spans have no parent.
+fn into_parent_bytes(id: trace_context::Id) -> Result<Vec<u8>, IdLengthError> {
+    if id.is_empty() {
+        return Ok(Vec::new());
+    }
+    into_bytes(id, 8)
+}
+
+fn oc_tracestate(trace_state: &str) -> oc::span::Tracestate {
+    let entries = trace_state
+        .split(',')
+        .filter_map(|member| {
+            let (key, value) = member.split_once('=')?;
+            Some(oc::span::tracestate::Entry {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+        })
+        .collect();
+    oc::span::Tracestate { entries }
+}
+
 fn truncatable(value: String) -> oc::TruncatableString {
     oc::TruncatableString {
         value,
         truncated_byte_count: 0,
     }
 }
+
+fn unix_nanos(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+fn string_attr(key: impl Into<String>, value: impl Into<String>) -> otel_common::KeyValue {
+    otel_common::KeyValue {
+        key: key.into(),
+        value: Some(otel_common::AnyValue {
+            value: Some(otel_common::any_value::Value::StringValue(value.into())),
+        }),
+    }
+}
+
+fn int_attr(key: impl Into<String>, value: i64) -> otel_common::KeyValue {
+    otel_common::KeyValue {
+        key: key.into(),
+        value: Some(otel_common::AnyValue {
+            value: Some(otel_common::any_value::Value::IntValue(value)),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn converter(kind: Kind) -> (SpanConverter, mpsc::Receiver<otel::Span>) {
+        let (tx, rx) = mpsc::channel(1);
+        let labels = Arc::new(
+            Some(("direction".to_string(), "inbound".to_string()))
+                .into_iter()
+                .collect(),
+        );
+        let converter = SpanConverter {
+            kind,
+            exporter: Exporter::OpenTelemetry(tx),
+            labels,
+        };
+        (converter, rx)
+    }
+
+    fn span(labels: &[(&'static str, &str)]) -> trace_context::Span {
+        let start = UNIX_EPOCH + Duration::from_secs(1);
+        trace_context::Span {
+            trace_id: vec![1; 16].into(),
+            span_id: vec![2; 8].into(),
+            parent_id: vec![3; 8].into(),
+            span_name: "test".to_string(),
+            trace_state: Some("rojo=00f067aa0ba902b7".to_string()),
+            start,
+            end: start + Duration::from_millis(5),
+            labels: labels.iter().map(|(k, v)| (*k, v.to_string())).collect(),
+        }
+    }
+
+    fn attr<'a>(span: &'a otel::Span, key: &str) -> Option<&'a otel_common::any_value::Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key == key)
+            .and_then(|kv| kv.value.as_ref()?.value.as_ref())
+    }
+
+    fn string(v: &str) -> otel_common::any_value::Value {
+        otel_common::any_value::Value::StringValue(v.to_string())
+    }
+
+    #[test]
+    fn otel_semantic_conventions() {
+        let (mut converter, mut rx) = converter(Kind::Server);
+        trace_context::SpanSink::try_send(
+            &mut converter,
+            span(&[
+                ("http.method", "GET"),
+                ("http.path", "/foo?bar=baz"),
+                ("http.authority", "example.com:8080"),
+                ("http.host", "example.com:8080"),
+                ("http.status_code", "200"),
+            ]),
+        )
+        .expect("span must be converted");
+        let span = rx.try_recv().expect("span must be sent");
+
+        assert_eq!(span.name, "test");
+        assert_eq!(span.kind, otel::span::SpanKind::Server as i32);
+        assert_eq!(span.trace_id, vec![1; 16]);
+        assert_eq!(span.span_id, vec![2; 8]);
+        assert_eq!(span.parent_span_id, vec![3; 8]);
+        assert_eq!(span.trace_state, "rojo=00f067aa0ba902b7");
+        assert_eq!(span.start_time_unix_nano, 1_000_000_000);
+        assert_eq!(span.end_time_unix_nano, 1_005_000_000);
+
+        assert_eq!(attr(&span, "direction"), Some(&string("inbound")));
+        assert_eq!(attr(&span, "http.request.method"), Some(&string("GET")));
+        assert_eq!(attr(&span, "url.path"), Some(&string("/foo")));
+        assert_eq!(attr(&span, "url.query"), Some(&string("bar=baz")));
+        assert_eq!(attr(&span, "server.address"), Some(&string("example.com")));
+        assert_eq!(
+            attr(&span, "server.port"),
+            Some(&otel_common::any_value::Value::IntValue(8080))
+        );
+        assert_eq!(
+            attr(&span, "http.response.status_code"),
+            Some(&otel_common::any_value::Value::IntValue(200))
+        );
+        assert_eq!(
+            span.attributes
+                .iter()
+                .filter(|kv| kv.key == "server.address")
+                .count(),
+            1,
+            "the host header must not duplicate the authority"
+        );
+        assert_eq!(
+            span.status.unwrap().code,
+            otel::status::StatusCode::Unset as i32
+        );
+    }
+
+    #[test]
+    fn otel_error_status() {
+        for (kind, code, expected) in [
+            (Kind::Server, "404", otel::status::StatusCode::Unset),
+            (Kind::Server, "503", otel::status::StatusCode::Error),
+            (Kind::Client, "404", otel::status::StatusCode::Error),
+            (Kind::Client, "503", otel::status::StatusCode::Error),
+        ] {
+            let (mut converter, mut rx) = converter(kind);
+            trace_context::SpanSink::try_send(&mut converter, span(&[("http.status_code", code)]))
+                .expect("span must be converted");
+            let span = rx.try_recv().expect("span must be sent");
+            assert_eq!(
+                span.status.unwrap().code,
+                expected as i32,
+                "{kind:?} span with status {code}"
+            );
+        }
+    }
+
+    #[test]
+    fn root_span_has_no_parent() {
+        let (mut converter, mut rx) = converter(Kind::Server);
+        let mut root = span(&[]);
+        root.parent_id = Vec::new().into();
+        trace_context::SpanSink::try_send(&mut converter, root).expect("span must be converted");
+        let span = rx.try_recv().expect("span must be sent");
+        assert!(span.parent_span_id.is_empty());
+    }
+}
@@ -40,10 +40,12 @@ pub use linkerd_http_metrics as http_metrics;
 pub use linkerd_idle_cache as idle_cache;
 pub use linkerd_io as io;
 pub use linkerd_opencensus as opencensus;
+pub use linkerd_opentelemetry as opentelemetry;
 pub use linkerd_service_profiles as profiles;
 pub use linkerd_stack_metrics as stack_metrics;
 pub use linkerd_stack_tracing as stack_tracing;
 pub use linkerd_tls as tls;
+pub use linkerd_trace_context as trace_context;
 pub use linkerd_tracing as trace;
 pub use linkerd_transport_header as transport_header;
 
@@ -51,6 +53,7 @@ pub mod identity {
     pub use linkerd_identity::*;
     pub use linkerd_meshtls::*;
     pub use linkerd_proxy_identity_client as client;
+    pub use linkerd_proxy_spiffe_client as spiffe;
 }
 
 pub const CANONICAL_DST_HEADER: &str = "l5d-dst-canonical";
@@ -62,7 +65,8 @@ pub struct ProxyRuntime {
     pub identity: identity::creds::Receiver,
     pub metrics: metrics::Proxy,
     pub tap: proxy::tap::Registry,
-    pub span_sink: http_tracing::OpenCensusSink,
+    pub span_sink: Option<http_tracing::SpanSink>,
+    pub access_log_headers: trace::access_log::CaptureHeaders,
     pub drain: drain::Watch,
 }
 
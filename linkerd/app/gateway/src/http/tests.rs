@@ -129,8 +129,11 @@ async fn upgraded_request_remains_relative_form() {
                             kind: "authorizationpolicy".into(),
                             name: "testsaz".into(),
                         }),
+                        requests: vec![],
+                        action: policy::Action::Allow,
                     }]))]),
                 },
+                audit: false,
             };
             let (policy, tx) = inbound::policy::AllowPolicy::for_test(self.param(), policy);
             tokio::spawn(async move {
@@ -20,7 +20,7 @@ impl Gateway {
         T: svc::Param<inbound::policy::AllowPolicy>,
         T: Clone + Send + Sync + Unpin + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + Send + Unpin + 'static,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Reset + Send + Unpin + 'static,
         // Opaq outbound stack.
         N: svc::NewService<Target, Service = NSvc> + Clone + Send + Sync + Unpin + 'static,
         NSvc: svc::Service<I, Response = (), Error = Error>,
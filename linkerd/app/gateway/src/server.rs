@@ -41,7 +41,7 @@ impl Gateway {
         T: svc::Param<Option<SessionProtocol>>,
         T: Clone + Send + Sync + Unpin + 'static,
         // Server-side socket
-        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Reset,
         I: Debug + Send + Sync + Unpin + 'static,
         // Opaq outbound stack
         O: svc::NewService<Opaq<T>, Service = OSvc>,
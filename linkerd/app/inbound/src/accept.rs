@@ -117,7 +117,7 @@ mod tests {
         svc::{NewService, ServiceExt},
         Error,
     };
-    use linkerd_proxy_server_policy::{Authentication, Authorization, Meta, ServerPolicy};
+    use linkerd_proxy_server_policy::{Action, Authentication, Authorization, Meta, ServerPolicy};
     use std::sync::Arc;
 
     #[tokio::test(flavor = "current_thread")]
@@ -134,6 +134,8 @@ mod tests {
                             kind: "serverauthorization".into(),
                             name: "testsaz".into(),
                         }),
+                        requests: vec![],
+                        action: Action::Allow,
                     },
                 ])),
                 meta: Arc::new(Meta::Resource {
@@ -141,6 +143,7 @@ mod tests {
                     kind: "server".into(),
                     name: "testsrv".into(),
                 }),
+                audit: false,
             },
             None,
         );
@@ -14,6 +14,7 @@ use linkerd_app_core::{
     },
     Error, Infallible,
 };
+use linkerd_tcp_access_log as tcp_access_log;
 use std::{fmt::Debug, time};
 use tracing::info;
 
@@ -99,6 +100,8 @@ impl Inbound<svc::ArcNewTcp<Http, io::BoxedIo>> {
                 ))
                 .push_map_target(Forward::from)
                 .push(policy::NewTcpPolicy::layer(rt.metrics.tcp_authz.clone()))
+                // Records an access log for each forwarded connection.
+                .push(tcp_access_log::NewAccessLog::layer())
                 .arc_new_tcp();
 
             let detect_timeout = cfg.proxy.detect_protocol_timeout;
@@ -210,6 +213,8 @@ impl<I> Inbound<svc::ArcNewTcp<Tls, TlsIo<I>>> {
                 ))
                 .push_map_target(Forward::from)
                 .push(policy::NewTcpPolicy::layer(rt.metrics.tcp_authz.clone()))
+                // Records an access log for each forwarded connection.
+                .push(tcp_access_log::NewAccessLog::layer())
                 .arc_new_tcp();
 
             let detect_timeout = cfg.proxy.detect_protocol_timeout;
@@ -320,6 +325,12 @@ impl svc::Param<OrigDstAddr> for Tls {
     }
 }
 
+impl svc::Param<Remote<ServerAddr>> for Tls {
+    fn param(&self) -> Remote<ServerAddr> {
+        Remote(ServerAddr(self.orig_dst_addr.into()))
+    }
+}
+
 impl svc::Param<Remote<ClientAddr>> for Tls {
     fn param(&self) -> Remote<ClientAddr> {
         self.client_addr
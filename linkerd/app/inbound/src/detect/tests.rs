@@ -6,7 +6,9 @@ use linkerd_app_core::{
     svc::{NewService, ServiceExt},
     trace, Error,
 };
-use linkerd_proxy_server_policy::{Authentication, Authorization, Meta, Protocol, ServerPolicy};
+use linkerd_proxy_server_policy::{
+    Action, Authentication, Authorization, Meta, Protocol, ServerPolicy,
+};
 use std::sync::Arc;
 
 const HTTP1: &[u8] = b"GET / HTTP/1.1\r\nhost: example.com\r\n\r\n";
@@ -22,6 +24,8 @@ fn authzs() -> Arc<[Authorization]> {
             kind: "authorizationpolicy".into(),
             name: "testsaz".into(),
         }),
+        requests: vec![],
+        action: Action::Allow,
     }])
 }
 
@@ -35,6 +39,7 @@ fn allow(protocol: Protocol) -> AllowPolicy {
                 kind: "server".into(),
                 name: "testsrv".into(),
             }),
+            audit: false,
         },
     );
     allow
@@ -223,6 +223,8 @@ pub mod fuzz {
                                     kind: "server".into(),
                                     name: "testsaz".into(),
                                 }),
+                                requests: vec![],
+                                action: policy::Action::Allow,
                             },
                         ])),
                     ])),
@@ -231,6 +233,7 @@ pub mod fuzz {
                         kind: "server".into(),
                         name: "testsrv".into(),
                     }),
+                    audit: false,
                 },
             );
             policy
@@ -232,7 +232,10 @@ impl<C> Inbound<C> {
                 }))
                 .check_new_service::<(policy::HttpRoutePermit, T), http::Request<http::BoxBody>>()
                 .push(svc::ArcNewService::layer())
-                .push(policy::NewHttpPolicy::layer(rt.metrics.http_authz.clone()))
+                .push(policy::NewHttpPolicy::layer(
+                    rt.metrics.http_authz.clone(),
+                    rt.metrics.http_faults.clone(),
+                ))
                 // Used by tap.
                 .push_http_insert_target::<tls::ConditionalServerTls>()
                 .push_http_insert_target::<Remote<ClientAddr>>()
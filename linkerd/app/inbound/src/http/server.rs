@@ -85,7 +85,7 @@ impl<H> Inbound<H> {
                 // Record when an HTTP/1 URI was in absolute form
                 .push_on_service(http::normalize_uri::MarkAbsoluteForm::layer())
                 .push_on_service(http::BoxResponse::layer())
-                .push(NewAccessLog::layer())
+                .push(NewAccessLog::layer(rt.access_log_headers.clone()))
                 .arc_new_clone_http()
         })
     }
@@ -187,10 +187,18 @@ impl errors::HttpRescue<Error> for ServerRescue {
             return Ok(errors::SyntheticHttpResponse::permission_denied(error));
         }
 
+        if errors::is_caused_by::<policy::HttpRouteRateLimited>(&*error) {
+            return Ok(errors::SyntheticHttpResponse::rate_limited(error));
+        }
+
         if errors::is_caused_by::<policy::HttpRouteInvalidRedirect>(&*error) {
             tracing::warn!(%error);
             return Ok(errors::SyntheticHttpResponse::unexpected_error());
         }
+        if errors::is_caused_by::<policy::HttpRouteInvalidUrlRewrite>(&*error) {
+            tracing::warn!(%error);
+            return Ok(errors::SyntheticHttpResponse::unexpected_error());
+        }
         if let Some(policy::HttpRouteRedirect { status, location }) =
             errors::cause_ref::<policy::HttpRouteRedirect>(&*error)
         {
@@ -736,6 +736,8 @@ impl svc::Param<policy::AllowPolicy> for Target {
                 kind: "serverauthorization".into(),
                 name: "testsaz".into(),
             }),
+            requests: vec![],
+            action: policy::Action::Allow,
         }]);
         let (policy, _) = policy::AllowPolicy::for_test(
             self.param(),
@@ -748,6 +750,7 @@ impl svc::Param<policy::AllowPolicy> for Target {
                     kind: "server".into(),
                     name: "testsrv".into(),
                 }),
+                audit: false,
             },
         );
         policy
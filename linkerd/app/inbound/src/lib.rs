@@ -22,10 +22,10 @@ pub use self::{metrics::InboundMetrics, policy::DefaultPolicy};
 use linkerd_app_core::{
     config::{ConnectConfig, ProxyConfig, QueueConfig},
     drain,
-    http_tracing::OpenCensusSink,
+    http_tracing::SpanSink,
     identity, io,
     proxy::{tap, tcp},
-    svc,
+    svc, trace,
     transport::{self, Remote, ServerAddr},
     Error, NameAddr, NameMatch, ProxyRuntime,
 };
@@ -67,7 +67,8 @@ struct Runtime {
     metrics: InboundMetrics,
     identity: identity::creds::Receiver,
     tap: tap::Registry,
-    span_sink: OpenCensusSink,
+    span_sink: Option<SpanSink>,
+    access_log_headers: trace::access_log::CaptureHeaders,
     drain: drain::Watch,
 }
 
@@ -116,7 +117,10 @@ impl<S> Inbound<S> {
     pub fn authorize_http<N>(
         &self,
     ) -> impl svc::layer::Layer<N, Service = policy::NewHttpPolicy<N>> + Clone {
-        policy::NewHttpPolicy::layer(self.runtime.metrics.http_authz.clone())
+        policy::NewHttpPolicy::layer(
+            self.runtime.metrics.http_authz.clone(),
+            self.runtime.metrics.http_faults.clone(),
+        )
     }
 
     /// A helper for gateways to instrument policy checks.
@@ -155,6 +159,7 @@ impl Inbound<()> {
             identity: runtime.identity,
             tap: runtime.tap,
             span_sink: runtime.span_sink,
+            access_log_headers: runtime.access_log_headers,
             drain: runtime.drain,
         };
         Self {
@@ -10,6 +10,7 @@
 
 pub(crate) mod authz;
 pub(crate) mod error;
+pub(crate) mod fault;
 
 pub use linkerd_app_core::metrics::*;
 
@@ -18,6 +19,7 @@ pub use linkerd_app_core::metrics::*;
 pub struct InboundMetrics {
     pub http_authz: authz::HttpAuthzMetrics,
     pub http_errors: error::HttpErrorMetrics,
+    pub http_faults: fault::HttpFaultMetrics,
 
     pub(crate) tcp_authz: authz::TcpAuthzMetrics,
     pub tcp_errors: error::TcpErrorMetrics,
@@ -32,6 +34,7 @@ impl InboundMetrics {
         Self {
             http_authz: authz::HttpAuthzMetrics::default(),
             http_errors: error::HttpErrorMetrics::default(),
+            http_faults: fault::HttpFaultMetrics::default(),
             tcp_authz: authz::TcpAuthzMetrics::default(),
             tcp_errors: error::TcpErrorMetrics::default(),
             proxy,
@@ -43,6 +46,7 @@ impl FmtMetrics for InboundMetrics {
     fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.http_authz.fmt_metrics(f)?;
         self.http_errors.fmt_metrics(f)?;
+        self.http_faults.fmt_metrics(f)?;
 
         self.tcp_authz.fmt_metrics(f)?;
         self.tcp_errors.fmt_metrics(f)?;
@@ -17,6 +17,9 @@ metrics! {
     inbound_http_authz_deny_total: Counter {
         "The total number of inbound HTTP requests that could not be processed due to a proxy error."
     },
+    inbound_http_authz_would_deny_total: Counter {
+        "The total number of inbound HTTP requests that were not authorized but were allowed in audit mode"
+    },
     inbound_http_route_not_found_total: Counter {
         "The total number of inbound HTTP requests that could not be associated with a route"
     },
@@ -27,6 +30,9 @@ metrics! {
     inbound_tcp_authz_deny_total: Counter {
         "The total number of inbound TCP connections that were denied"
     },
+    inbound_tcp_authz_would_deny_total: Counter {
+        "The total number of inbound TCP connections that were not authorized but were allowed in audit mode"
+    },
     inbound_tcp_authz_terminate_total: Counter {
         "The total number of inbound TCP connections that were terminated due to an authorization change"
     }
@@ -42,6 +48,7 @@ pub(crate) struct TcpAuthzMetrics(Arc<TcpInner>);
 struct HttpInner {
     allow: Mutex<HashMap<RouteAuthzKey, Counter>>,
     deny: Mutex<HashMap<RouteKey, Counter>>,
+    would_deny: Mutex<HashMap<RouteKey, Counter>>,
     route_not_found: Mutex<HashMap<ServerKey, Counter>>,
 }
 
@@ -49,19 +56,20 @@ struct HttpInner {
 struct TcpInner {
     allow: Mutex<HashMap<ServerAuthzKey, Counter>>,
     deny: Mutex<HashMap<ServerKey, Counter>>,
+    would_deny: Mutex<HashMap<ServerKey, Counter>>,
     terminate: Mutex<HashMap<ServerKey, Counter>>,
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
-struct Key<L> {
-    target: TargetAddr,
-    tls: tls::ConditionalServerTls,
-    labels: L,
+pub(super) struct Key<L> {
+    pub(super) target: TargetAddr,
+    pub(super) tls: tls::ConditionalServerTls,
+    pub(super) labels: L,
 }
 
 type ServerKey = Key<ServerLabel>;
 type ServerAuthzKey = Key<ServerAuthzLabels>;
-type RouteKey = Key<RouteLabels>;
+pub(super) type RouteKey = Key<RouteLabels>;
 type RouteAuthzKey = Key<RouteAuthzLabels>;
 
 // === impl HttpAuthzMetrics ===
@@ -98,6 +106,20 @@ impl HttpAuthzMetrics {
             .or_default()
             .incr();
     }
+
+    pub fn would_deny(
+        &self,
+        labels: RouteLabels,
+        dst: OrigDstAddr,
+        tls: tls::ConditionalServerTls,
+    ) {
+        self.0
+            .would_deny
+            .lock()
+            .entry(RouteKey::new(labels, dst, tls))
+            .or_default()
+            .incr();
+    }
 }
 
 impl FmtMetrics for HttpAuthzMetrics {
@@ -127,6 +149,19 @@ impl FmtMetrics for HttpAuthzMetrics {
         }
         drop(deny);
 
+        let would_deny = self.0.would_deny.lock();
+        if !would_deny.is_empty() {
+            inbound_http_authz_would_deny_total.fmt_help(f)?;
+            inbound_http_authz_would_deny_total.fmt_scopes(
+                f,
+                would_deny
+                    .iter()
+                    .map(|(k, c)| ((k.target, (&k.labels, TlsAccept(&k.tls))), c)),
+                |c| c,
+            )?;
+        }
+        drop(would_deny);
+
         let route_not_found = self.0.route_not_found.lock();
         if !route_not_found.is_empty() {
             inbound_http_route_not_found_total.fmt_help(f)?;
@@ -165,6 +200,15 @@ impl TcpAuthzMetrics {
             .incr();
     }
 
+    pub fn would_deny(&self, policy: &AllowPolicy, tls: tls::ConditionalServerTls) {
+        self.0
+            .would_deny
+            .lock()
+            .entry(ServerKey::from_policy(policy, tls))
+            .or_default()
+            .incr();
+    }
+
     pub fn terminate(&self, policy: &AllowPolicy, tls: tls::ConditionalServerTls) {
         self.0
             .terminate
@@ -191,6 +235,13 @@ impl FmtMetrics for TcpAuthzMetrics {
         }
         drop(deny);
 
+        let would_deny = self.0.would_deny.lock();
+        if !would_deny.is_empty() {
+            inbound_tcp_authz_would_deny_total.fmt_help(f)?;
+            inbound_tcp_authz_would_deny_total.fmt_scopes(f, &*would_deny, |c| c)?;
+        }
+        drop(would_deny);
+
         let terminate = self.0.terminate.lock();
         if !terminate.is_empty() {
             inbound_tcp_authz_terminate_total.fmt_help(f)?;
@@ -205,7 +256,7 @@ impl FmtMetrics for TcpAuthzMetrics {
 // === impl Key ===
 
 impl<L> Key<L> {
-    fn new(labels: L, dst: OrigDstAddr, tls: tls::ConditionalServerTls) -> Self {
+    pub(super) fn new(labels: L, dst: OrigDstAddr, tls: tls::ConditionalServerTls) -> Self {
         Self {
             tls,
             target: TargetAddr(dst.into()),
@@ -3,7 +3,7 @@ mod tcp;
 
 pub(crate) use self::{http::HttpErrorMetrics, tcp::TcpErrorMetrics};
 use crate::{
-    policy::{HttpRouteNotFound, HttpRouteUnauthorized, ServerUnauthorized},
+    policy::{HttpRouteNotFound, HttpRouteRateLimited, HttpRouteUnauthorized, ServerUnauthorized},
     GatewayDomainInvalid, GatewayIdentityRequired, GatewayLoop,
 };
 use linkerd_app_core::{
@@ -22,6 +22,7 @@ enum ErrorKind {
     GatewayIdentityRequired,
     GatewayLoop,
     Io,
+    RateLimited,
     TlsDetectTimeout,
     Unexpected,
 }
@@ -52,6 +53,8 @@ impl ErrorKind {
             Some(ErrorKind::GatewayLoop)
         } else if err.is::<LoadShedError>() {
             Some(ErrorKind::LoadShed)
+        } else if err.is::<HttpRouteRateLimited>() {
+            Some(ErrorKind::RateLimited)
         } else if let Some(e) = err.source() {
             Self::mk(e)
         } else {
@@ -73,6 +76,7 @@ impl FmtLabels for ErrorKind {
                 ErrorKind::GatewayLoop => "gateway loop",
                 ErrorKind::GatewayDomainInvalid => "gateway domain invalid",
                 ErrorKind::Io => "i/o",
+                ErrorKind::RateLimited => "rate limited",
                 ErrorKind::Unexpected => "unexpected",
             }
         )
@@ -0,0 +1,55 @@
+use super::authz::RouteKey;
+use crate::policy::HttpRoutePermit;
+use linkerd_app_core::{
+    metrics::{metrics, Counter, FmtMetrics, TlsAccept},
+    tls,
+};
+use parking_lot::Mutex;
+use std::{collections::HashMap, sync::Arc};
+
+metrics! {
+    inbound_http_route_injected_delays_total: Counter {
+        "The total number of inbound HTTP requests delayed by a route's fault injection filter"
+    }
+}
+
+/// Counts the faults that inbound HTTP routes' filters inject into requests.
+#[derive(Clone, Debug, Default)]
+pub struct HttpFaultMetrics(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    injected_delay: Mutex<HashMap<RouteKey, Counter>>,
+}
+
+// === impl HttpFaultMetrics ===
+
+impl HttpFaultMetrics {
+    pub fn injected_delay(&self, permit: &HttpRoutePermit, tls: tls::ConditionalServerTls) {
+        self.0
+            .injected_delay
+            .lock()
+            .entry(RouteKey::new(permit.labels.route.clone(), permit.dst, tls))
+            .or_default()
+            .incr();
+    }
+}
+
+impl FmtMetrics for HttpFaultMetrics {
+    fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let injected_delay = self.0.injected_delay.lock();
+        if !injected_delay.is_empty() {
+            inbound_http_route_injected_delays_total.fmt_help(f)?;
+            inbound_http_route_injected_delays_total.fmt_scopes(
+                f,
+                injected_delay
+                    .iter()
+                    .map(|(k, c)| ((k.target, (&k.labels, TlsAccept(&k.tls))), c)),
+                |c| c,
+            )?;
+        }
+        drop(injected_delay);
+
+        Ok(())
+    }
+}
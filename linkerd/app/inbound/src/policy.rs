@@ -9,8 +9,8 @@ pub(crate) use self::store::Store;
 pub use self::{
     config::Config,
     http::{
-        HttpInvalidPolicy, HttpRouteInvalidRedirect, HttpRouteNotFound, HttpRouteRedirect,
-        HttpRouteUnauthorized, NewHttpPolicy,
+        HttpInvalidPolicy, HttpRouteInvalidRedirect, HttpRouteInvalidUrlRewrite, HttpRouteNotFound,
+        HttpRouteRateLimited, HttpRouteRedirect, HttpRouteUnauthorized, NewHttpPolicy,
     },
     tcp::NewTcpPolicy,
 };
@@ -24,10 +24,10 @@ use linkerd_app_core::{
 };
 use linkerd_idle_cache::Cached;
 pub use linkerd_proxy_server_policy::{
-    authz::Suffix,
+    authz::{Suffix, TrustDomain},
     grpc::Route as GrpcRoute,
     http::{filter::Redirection, Route as HttpRoute},
-    route, Authentication, Authorization, Meta, Protocol, RoutePolicy, ServerPolicy,
+    route, Action, Authentication, Authorization, Meta, Protocol, RoutePolicy, ServerPolicy,
 };
 use std::sync::Arc;
 use thiserror::Error;
@@ -91,6 +91,7 @@ impl From<DefaultPolicy> for ServerPolicy {
             DefaultPolicy::Deny => ServerPolicy {
                 protocol: Protocol::Opaque(Arc::new([])),
                 meta: Meta::new_default("deny"),
+                audit: false,
             },
         }
     }
@@ -165,12 +166,21 @@ fn is_tls_authorized(tls: &tls::ConditionalServerTls, authz: &Authorization) ->
         Authentication::TlsAuthenticated {
             ref identities,
             ref suffixes,
+            ref trust_domains,
         } => match tls {
             tls::ConditionalServerTls::Some(tls::ServerTls::Established {
                 client_id: Some(tls::server::ClientId(ref id)),
                 ..
             }) => match id {
-                id::Id::Uri(_) => identities.contains(&*id.to_str()),
+                id::Id::Uri(_) => {
+                    identities.contains(&*id.to_str())
+                        || match (id.spiffe_trust_domain(), id.spiffe_path()) {
+                            (Some(td), Some(path)) => {
+                                trust_domains.iter().any(|t| t.contains(td, path))
+                            }
+                            _ => false,
+                        }
+                }
                 id::Id::Dns(_) => {
                     identities.contains(&*id.to_str())
                         || suffixes.iter().any(|s| s.contains(&id.to_str()))
@@ -178,30 +188,80 @@ fn is_tls_authorized(tls: &tls::ConditionalServerTls, authz: &Authorization) ->
             },
             _ => false,
         },
+
+        // JWTs are authenticated per-request.
+        Authentication::Jwt(_) => false,
     }
 }
 
+/// Checks whether the authorization applies to the client. Request headers
+/// are only available when authorizing HTTP requests.
 fn is_authorized(
     authz: &Authorization,
     client_addr: Remote<ClientAddr>,
     tls: &tls::ConditionalServerTls,
+    headers: Option<&::http::HeaderMap>,
 ) -> bool {
     if !authz.networks.iter().any(|n| n.contains(&client_addr.ip())) {
         return false;
     }
 
+    if let Authentication::Jwt(ref jwt) = authz.authentication {
+        let Some(headers) = headers else {
+            return false;
+        };
+        return match jwt.authenticate(headers) {
+            Ok(()) => true,
+            Err(error) => {
+                tracing::debug!(%error, authz.name = %authz.meta.name(), "JWT not authenticated");
+                false
+            }
+        };
+    }
+
     is_tls_authorized(tls, authz)
 }
 
+/// Finds the first authorization that applies to the client, evaluating deny
+/// authorizations first, followed by audit and then allow authorizations.
+fn find_authorization<'a, I>(
+    authzs: I,
+    client_addr: Remote<ClientAddr>,
+    tls: &tls::ConditionalServerTls,
+    headers: Option<&::http::HeaderMap>,
+) -> Option<&'a Authorization>
+where
+    I: Iterator<Item = &'a Authorization>,
+{
+    // Each authorization is only evaluated once, since evaluating it may
+    // require validating a JWT.
+    let authzs = authzs
+        .filter(|a| is_authorized(a, client_addr, tls, headers))
+        .collect::<Vec<_>>();
+    [Action::Deny, Action::Audit, Action::Allow]
+        .into_iter()
+        .find_map(|action| authzs.iter().find(|a| a.action == action).copied())
+}
+
+/// Returns the authorization metadata used to label clients that are permitted
+/// by audit mode without a matching authorization.
+fn audit_meta() -> Arc<Meta> {
+    Meta::new_default("audit")
+}
+
 // === impl Permit ===
 
 impl ServerPermit {
     fn new(dst: OrigDstAddr, server: &ServerPolicy, authz: &Authorization) -> Self {
+        Self::with_authz(dst, server, authz.meta.clone())
+    }
+
+    fn with_authz(dst: OrigDstAddr, server: &ServerPolicy, authz: Arc<Meta>) -> Self {
         Self {
             dst,
             protocol: server.protocol.clone(),
             labels: ServerAuthzLabels {
-                authz: authz.meta.clone(),
+                authz,
                 server: ServerLabel(server.meta.clone()),
             },
         }
@@ -213,13 +273,22 @@ mod tests {
     use super::is_tls_authorized;
     use super::Meta;
     use super::Suffix;
-    use super::{Authentication, Authorization};
+    use super::TrustDomain;
+    use super::{Action, Authentication, Authorization};
     use linkerd_app_core::tls;
     use std::collections::BTreeSet;
     use std::str::FromStr;
     use std::sync::Arc;
 
     fn authorization(identities: BTreeSet<String>, suffixes: Vec<Suffix>) -> Authorization {
+        authorization_with_trust_domains(identities, suffixes, vec![])
+    }
+
+    fn authorization_with_trust_domains(
+        identities: BTreeSet<String>,
+        suffixes: Vec<Suffix>,
+        trust_domains: Vec<TrustDomain>,
+    ) -> Authorization {
         Authorization {
             networks: vec![],
             meta: Arc::new(Meta::Default {
@@ -228,7 +297,10 @@ mod tests {
             authentication: Authentication::TlsAuthenticated {
                 identities,
                 suffixes,
+                trust_domains,
             },
+            requests: vec![],
+            action: Action::Allow,
         }
     }
 
@@ -322,4 +394,55 @@ mod tests {
         );
         assert!(is_tls_authorized(&tls, &authz))
     }
+
+    #[test]
+    fn is_authorized_for_matching_spiffe_trust_domain() {
+        let tls = server_tls("spiffe://some-root/ns/web/sa/default");
+        let authz = authorization_with_trust_domains(
+            BTreeSet::new(),
+            vec![],
+            vec![TrustDomain::new("some-root", "/ns/web")],
+        );
+        assert!(is_tls_authorized(&tls, &authz));
+
+        let authz = authorization_with_trust_domains(
+            BTreeSet::new(),
+            vec![],
+            vec![TrustDomain::new("some-root", "/")],
+        );
+        assert!(is_tls_authorized(&tls, &authz));
+    }
+
+    #[test]
+    fn is_not_authorized_for_non_matching_spiffe_trust_domain() {
+        let tls = server_tls("spiffe://other-root/ns/web/sa/default");
+        let authz = authorization_with_trust_domains(
+            BTreeSet::new(),
+            vec![],
+            vec![TrustDomain::new("some-root", "")],
+        );
+        assert!(!is_tls_authorized(&tls, &authz));
+    }
+
+    #[test]
+    fn is_not_authorized_for_partial_spiffe_path_segment() {
+        let tls = server_tls("spiffe://some-root/ns/website/sa/default");
+        let authz = authorization_with_trust_domains(
+            BTreeSet::new(),
+            vec![],
+            vec![TrustDomain::new("some-root", "/ns/web")],
+        );
+        assert!(!is_tls_authorized(&tls, &authz));
+    }
+
+    #[test]
+    fn is_not_authorized_for_trust_domain_and_dns_id() {
+        let tls = server_tls("some-root");
+        let authz = authorization_with_trust_domains(
+            BTreeSet::new(),
+            vec![],
+            vec![TrustDomain::new("some-root", "")],
+        );
+        assert!(!is_tls_authorized(&tls, &authz));
+    }
 }
@@ -1,6 +1,6 @@
 use linkerd_app_core::{IpNet, Ipv4Net, Ipv6Net};
 use linkerd_proxy_server_policy::{
-    authz::Suffix, http, Authentication, Authorization, Meta, Protocol, ServerPolicy,
+    authz::Suffix, http, Action, Authentication, Authorization, Meta, Protocol, ServerPolicy,
 };
 use std::{sync::Arc, time::Duration};
 
@@ -53,6 +53,7 @@ fn authenticated() -> Authentication {
     Authentication::TlsAuthenticated {
         identities: Default::default(),
         suffixes: vec![Suffix::from(vec![])],
+        trust_domains: vec![],
     }
 }
 
@@ -66,6 +67,8 @@ fn mk(
         meta: Meta::new_default(name),
         networks: nets.into_iter().map(Into::into).collect(),
         authentication,
+        requests: vec![],
+        action: Action::Allow,
     }]);
 
     // The default policy supports protocol detection and uses the default
@@ -79,5 +82,6 @@ fn mk(
     ServerPolicy {
         meta: Meta::new_default(name),
         protocol,
+        audit: false,
     }
 }
@@ -1,18 +1,20 @@
 use super::{RoutePolicy, Routes};
 use crate::{
-    metrics::authz::HttpAuthzMetrics,
+    metrics::{authz::HttpAuthzMetrics, fault::HttpFaultMetrics},
     policy::{AllowPolicy, HttpRoutePermit},
 };
-use futures::{future, TryFutureExt};
+use futures::{future, FutureExt, TryFutureExt};
 use linkerd_app_core::{
+    identity as id,
     metrics::{RouteAuthzLabels, RouteLabels},
     svc::{self, ServiceExt},
     tls,
     transport::{ClientAddr, OrigDstAddr, Remote},
     Error, Result,
 };
-use linkerd_proxy_server_policy::{grpc, http, route::RouteMatch};
-use std::{sync::Arc, task};
+use linkerd_proxy_server_policy::{grpc, http, route::RouteMatch, Action, Meta};
+use std::{future::Future, pin::Pin, sync::Arc, task};
+use tokio::time;
 
 #[cfg(test)]
 mod tests;
@@ -27,6 +29,7 @@ mod tests;
 #[derive(Clone, Debug)]
 pub struct NewHttpPolicy<N> {
     metrics: HttpAuthzMetrics,
+    faults: HttpFaultMetrics,
     inner: N,
 }
 
@@ -36,6 +39,7 @@ pub struct HttpPolicyService<T, N> {
     connection: ConnectionMeta,
     policy: AllowPolicy,
     metrics: HttpAuthzMetrics,
+    faults: HttpFaultMetrics,
     inner: N,
 }
 
@@ -54,6 +58,10 @@ pub struct HttpRouteNotFound(());
 #[error("invalid redirect: {0}")]
 pub struct HttpRouteInvalidRedirect(#[from] pub http::filter::InvalidRedirect);
 
+#[derive(Debug, thiserror::Error)]
+#[error("invalid URL rewrite: {0}")]
+pub struct HttpRouteInvalidUrlRewrite(#[from] pub http::filter::InvalidUrlRewrite);
+
 #[derive(Debug, thiserror::Error)]
 #[error("request redirected to {location}")]
 pub struct HttpRouteRedirect {
@@ -65,6 +73,10 @@ pub struct HttpRouteRedirect {
 #[error("unauthorized request on route")]
 pub struct HttpRouteUnauthorized(());
 
+#[derive(Debug, thiserror::Error)]
+#[error("rate limit exceeded on route")]
+pub struct HttpRouteRateLimited(());
+
 #[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
 #[error("HTTP request configured to fail with {status}: {message}")]
 pub struct HttpRouteInjectedFailure {
@@ -83,12 +95,30 @@ pub struct GrpcRouteInjectedFailure {
 #[error("invalid server policy: {0}")]
 pub struct HttpInvalidPolicy(&'static str);
 
+// === impl ConnectionMeta ===
+
+impl ConnectionMeta {
+    fn client_id(&self) -> Option<&id::Id> {
+        match self.tls.value()? {
+            tls::ServerTls::Established {
+                client_id: Some(tls::ClientId(id)),
+                ..
+            } => Some(id),
+            _ => None,
+        }
+    }
+}
+
 // === impl NewHttpPolicy ===
 
 impl<N> NewHttpPolicy<N> {
-    pub fn layer(metrics: HttpAuthzMetrics) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+    pub fn layer(
+        metrics: HttpAuthzMetrics,
+        faults: HttpFaultMetrics,
+    ) -> impl svc::layer::Layer<N, Service = Self> + Clone {
         svc::layer::mk(move |inner| Self {
             metrics: metrics.clone(),
+            faults: faults.clone(),
             inner,
         })
     }
@@ -113,6 +143,7 @@ where
             policy,
             connection: ConnectionMeta { client, dst, tls },
             metrics: self.metrics.clone(),
+            faults: self.faults.clone(),
             inner: self.inner.clone(),
         }
     }
@@ -139,13 +170,18 @@ impl<B, T, N, S> svc::Service<::http::Request<B>> for HttpPolicyService<T, N>
 where
     T: Clone,
     N: svc::NewService<(HttpRoutePermit, T), Service = S>,
-    S: svc::Service<::http::Request<B>>,
+    S: svc::Service<::http::Request<B>> + Send + 'static,
     S::Error: Into<Error>,
+    S::Future: Send,
+    B: Send + 'static,
 {
     type Response = S::Response;
     type Error = Error;
     type Future = future::Either<
-        future::ErrInto<svc::stack::Oneshot<S, ::http::Request<B>>, Error>,
+        future::Either<
+            future::ErrInto<svc::stack::Oneshot<S, ::http::Request<B>>, Error>,
+            Pin<Box<dyn Future<Output = Result<Self::Response>> + Send + 'static>>,
+        >,
         future::Ready<Result<Self::Response>>,
     >;
 
@@ -157,26 +193,45 @@ where
     fn call(&mut self, mut req: ::http::Request<B>) -> Self::Future {
         // Find an appropriate route for the request and ensure that it's
         // authorized.
-        let permit = match self.policy.routes() {
+        let (permit, delay) = match self.policy.routes() {
             None => err!(self.mk_route_not_found()),
             Some(Routes::Http(routes)) => {
                 let (permit, mtch, route) = try_fut!(self.authorize(&routes, &req));
-                try_fut!(apply_http_filters(mtch, route, &mut req));
-                permit
+                let delay = try_fut!(apply_http_filters(
+                    mtch,
+                    route,
+                    self.connection.client_id(),
+                    &mut req
+                ));
+                (permit, delay)
             }
             Some(Routes::Grpc(routes)) => {
                 let (permit, _, route) = try_fut!(self.authorize(&routes, &req));
-                try_fut!(apply_grpc_filters(route, &mut req));
-                permit
+                let delay = try_fut!(apply_grpc_filters(
+                    route,
+                    self.connection.client_id(),
+                    &mut req
+                ));
+                (permit, delay)
             }
         };
 
-        future::Either::Left(
-            self.inner
-                .new_service((permit, self.target.clone()))
-                .oneshot(req)
-                .err_into::<Error>(),
-        )
+        let Some(delay) = delay else {
+            return future::Either::Left(future::Either::Left(
+                self.inner
+                    .new_service((permit, self.target.clone()))
+                    .oneshot(req)
+                    .err_into::<Error>(),
+            ));
+        };
+
+        tracing::debug!(?delay, "Delaying request");
+        self.faults
+            .injected_delay(&permit, self.connection.tls.clone());
+        let svc = self.inner.new_service((permit, self.target.clone()));
+        future::Either::Left(future::Either::Right(Box::pin(
+            time::sleep(delay).then(move |()| svc.oneshot(req).err_into::<Error>()),
+        )))
     }
 }
 
@@ -197,12 +252,46 @@ impl<T, N> HttpPolicyService<T, N> {
             server: self.policy.server_label(),
         };
 
-        let authz = match route
+        let authzs = route
             .authorizations
             .iter()
-            .find(|a| super::is_authorized(a, self.connection.client, &self.connection.tls))
-        {
-            Some(authz) => authz,
+            .filter(|a| a.matches_request(req));
+        let found = super::find_authorization(
+            authzs,
+            self.connection.client,
+            &self.connection.tls,
+            Some(req.headers()),
+        );
+        let audit = self.policy.borrow().audit;
+        let authz = match found {
+            Some(authz) if authz.action == Action::Allow => authz,
+            Some(authz) if authz.action == Action::Audit || audit => {
+                let permit = self.mk_audit_permit(labels, authz.meta.clone());
+                return Ok((permit, r#match, route));
+            }
+            None if audit => {
+                let permit = self.mk_audit_permit(labels, super::audit_meta());
+                return Ok((permit, r#match, route));
+            }
+            Some(authz) => {
+                tracing::info!(
+                    server.group = %labels.server.0.group(),
+                    server.kind = %labels.server.0.kind(),
+                    server.name = %labels.server.0.name(),
+                    route.group = %labels.route.group(),
+                    route.kind = %labels.route.kind(),
+                    route.name = %labels.route.name(),
+                    authz.group = %authz.meta.group(),
+                    authz.kind = %authz.meta.kind(),
+                    authz.name = %authz.meta.name(),
+                    client.tls = ?self.connection.tls,
+                    client.ip = %self.connection.client.ip(),
+                    "Request denied by authorization",
+                );
+                self.metrics
+                    .deny(labels, self.connection.dst, self.connection.tls.clone());
+                return Err(HttpRouteUnauthorized(()).into());
+            }
             None => {
                 tracing::info!(
                     server.group = %labels.server.0.group(),
@@ -263,6 +352,37 @@ impl<T, N> HttpPolicyService<T, N> {
         Ok((permit, r#match, route))
     }
 
+    /// Permits a request that is not authorized because the server or a
+    /// matching authorization is in audit mode.
+    fn mk_audit_permit(&self, labels: RouteLabels, authz: Arc<Meta>) -> HttpRoutePermit {
+        tracing::info!(
+            server.group = %labels.server.0.group(),
+            server.kind = %labels.server.0.kind(),
+            server.name = %labels.server.0.name(),
+            route.group = %labels.route.group(),
+            route.kind = %labels.route.kind(),
+            route.name = %labels.route.name(),
+            authz.group = %authz.group(),
+            authz.kind = %authz.kind(),
+            authz.name = %authz.name(),
+            client.tls = ?self.connection.tls,
+            client.ip = %self.connection.client.ip(),
+            "Request would be denied",
+        );
+        self.metrics.would_deny(
+            labels.clone(),
+            self.connection.dst,
+            self.connection.tls.clone(),
+        );
+        HttpRoutePermit {
+            dst: self.connection.dst,
+            labels: RouteAuthzLabels {
+                route: labels,
+                authz,
+            },
+        }
+    }
+
     fn mk_route_not_found(&self) -> Error {
         let labels = self.policy.server_label();
         self.metrics
@@ -271,12 +391,16 @@ impl<T, N> HttpPolicyService<T, N> {
     }
 }
 
+/// Applies a route's filters to a request, returning the duration by which the
+/// request should be delayed, if any.
 fn apply_http_filters<B>(
     r#match: http::RouteMatch,
     route: &http::Policy,
+    client: Option<&id::Id>,
     req: &mut ::http::Request<B>,
-) -> Result<()> {
+) -> Result<Option<time::Duration>> {
     // TODO Do any metrics apply here?
+    let mut delay = None;
     for filter in &route.filters {
         match filter {
             http::Filter::InjectFailure(fail) => {
@@ -299,20 +423,43 @@ fn apply_http_filters<B>(
                 }
             },
 
+            http::Filter::UrlRewrite(rewrite) => {
+                if let Err(invalid) = rewrite.apply(&r#match, req) {
+                    return Err(HttpRouteInvalidUrlRewrite(invalid).into());
+                }
+            }
+
             http::Filter::RequestHeaders(rh) => {
                 rh.apply(req.headers_mut());
             }
 
+            http::Filter::RateLimit(limit) => {
+                if !limit.check(client) {
+                    return Err(HttpRouteRateLimited(()).into());
+                }
+            }
+
             http::Filter::InternalError(msg) => {
                 return Err(HttpInvalidPolicy(msg).into());
             }
+
+            http::Filter::InjectDelay(inject) => {
+                delay = add_delay(delay, inject.apply());
+            }
         }
     }
 
-    Ok(())
+    Ok(delay)
 }
 
-fn apply_grpc_filters<B>(route: &grpc::Policy, req: &mut ::http::Request<B>) -> Result<()> {
+/// Applies a route's filters to a request, returning the duration by which the
+/// request should be delayed, if any.
+fn apply_grpc_filters<B>(
+    route: &grpc::Policy,
+    client: Option<&id::Id>,
+    req: &mut ::http::Request<B>,
+) -> Result<Option<time::Duration>> {
+    let mut delay = None;
     for filter in &route.filters {
         match filter {
             grpc::Filter::InjectFailure(fail) => {
@@ -325,11 +472,33 @@ fn apply_grpc_filters<B>(route: &grpc::Policy, req: &mut ::http::Request<B>) ->
                 rh.apply(req.headers_mut());
             }
 
+            grpc::Filter::RateLimit(limit) => {
+                if !limit.check(client) {
+                    return Err(HttpRouteRateLimited(()).into());
+                }
+            }
+
             grpc::Filter::InternalError(msg) => {
                 return Err(HttpInvalidPolicy(msg).into());
             }
+
+            grpc::Filter::InjectDelay(inject) => {
+                delay = add_delay(delay, inject.apply());
+            }
         }
     }
 
-    Ok(())
+    Ok(delay)
+}
+
+/// When more than one delay filter is configured, each is sampled
+/// independently and the selected delays accumulate.
+fn add_delay(
+    delay: Option<time::Duration>,
+    sampled: Option<time::Duration>,
+) -> Option<time::Duration> {
+    match (delay, sampled) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
 }
@@ -1,5 +1,5 @@
 use super::*;
-use crate::policy::{Authentication, Authorization, Meta, Protocol, ServerPolicy};
+use crate::policy::{Action, Authentication, Authorization, Meta, Protocol, ServerPolicy};
 use linkerd_app_core::{svc::Service, Infallible};
 use std::sync::Arc;
 
@@ -30,6 +30,7 @@ macro_rules! new_svc {
                     kind: "Server".into(),
                     name: "testsrv".into(),
                 }),
+                audit: false,
             },
         );
         let svc = HttpPolicyService {
@@ -37,6 +38,7 @@ macro_rules! new_svc {
             policy,
             connection: $conn,
             metrics: HttpAuthzMetrics::default(),
+            faults: Default::default(),
             inner: |(permit, _): (HttpRoutePermit, ())| {
                 let f = $rsp;
                 svc::mk(move |req: ::http::Request<hyper::Body>| {
@@ -88,6 +90,8 @@ async fn http_route() {
                             kind: "AuthorizationPolicy".into(),
                             name: "test".into(),
                         }),
+                        requests: vec![],
+                        action: Action::Allow,
                     }]),
                     filters: vec![],
                     meta: rmeta.clone(),
@@ -172,6 +176,8 @@ async fn http_route() {
                                 kind: "AuthorizationPolicy".into(),
                                 name: "other".into(),
                             }),
+                            requests: vec![],
+                            action: Action::Allow,
                         }]),
                         filters: vec![],
                         meta: rmeta.clone(),
@@ -191,6 +197,8 @@ async fn http_route() {
                                 kind: "AuthorizationPolicy".into(),
                                 name: "test".into(),
                             }),
+                            requests: vec![],
+                            action: Action::Allow,
                         }]),
                         filters: vec![],
                         meta: rmeta.clone(),
@@ -198,6 +206,7 @@ async fn http_route() {
                 },
             ],
         }])),
+        audit: false,
     })
     .expect("must send");
 
@@ -265,6 +274,8 @@ async fn http_filter_header() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    requests: vec![],
+                    action: Action::Allow,
                 }]),
                 filters: vec![Filter::RequestHeaders(filter::ModifyHeader {
                     add: vec![("testkey".parse().unwrap(), "testval".parse().unwrap())],
@@ -330,6 +341,8 @@ async fn http_filter_inject_failure() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    requests: vec![],
+                    action: Action::Allow,
                 }]),
                 filters: vec![Filter::InjectFailure(filter::InjectFailure {
                     distribution: filter::Distribution::from_ratio(1, 1).unwrap(),
@@ -364,6 +377,457 @@ async fn http_filter_inject_failure() {
     );
 }
 
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn http_filter_inject_delay() {
+    use linkerd_proxy_server_policy::http::{
+        filter, r#match::MatchRequest, Filter, Policy, Route, Rule,
+    };
+
+    const DELAY: time::Duration = time::Duration::from_secs(3);
+
+    let rmeta = Arc::new(Meta::Resource {
+        group: "gateway.networking.k8s.io".into(),
+        kind: "httproute".into(),
+        name: "testrt".into(),
+    });
+    let proto = Protocol::Http1(Arc::new([Route {
+        hosts: vec![],
+        rules: vec![Rule {
+            matches: vec![MatchRequest::default()],
+            policy: Policy {
+                authorizations: Arc::new([Authorization {
+                    authentication: Authentication::Unauthenticated,
+                    networks: vec![std::net::IpAddr::from([192, 168, 3, 3]).into()],
+                    meta: Arc::new(Meta::Resource {
+                        group: "policy.linkerd.io".into(),
+                        kind: "AuthorizationPolicy".into(),
+                        name: "test".into(),
+                    }),
+                    requests: vec![],
+                    action: Action::Allow,
+                }]),
+                filters: vec![Filter::InjectDelay(filter::InjectDelay {
+                    delay: filter::Delay::Fixed(DELAY),
+                    distribution: filter::Distribution::from_ratio(1, 1).unwrap(),
+                })],
+                meta: rmeta.clone(),
+            },
+        }],
+    }]));
+    let inner = |_: HttpRoutePermit,
+                 _: ::http::Request<hyper::Body>|
+     -> Result<::http::Response<hyper::Body>> { Ok(::http::Response::default()) };
+    let (mut svc, _tx) = new_svc!(proto, conn!(), inner);
+
+    let start = time::Instant::now();
+    svc.call(
+        ::http::Request::builder()
+            .body(hyper::Body::default())
+            .unwrap(),
+    )
+    .await
+    .expect("delayed request is served");
+    assert_eq!(time::Instant::now().saturating_duration_since(start), DELAY);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn http_filter_rate_limit() {
+    use linkerd_proxy_server_policy::{
+        http::{r#match::MatchRequest, Filter, Policy, Route, Rule},
+        rate_limit, RateLimit,
+    };
+
+    let rmeta = Arc::new(Meta::Resource {
+        group: "gateway.networking.k8s.io".into(),
+        kind: "httproute".into(),
+        name: "testrt".into(),
+    });
+    let proto = Protocol::Http1(Arc::new([Route {
+        hosts: vec![],
+        rules: vec![Rule {
+            matches: vec![MatchRequest::default()],
+            policy: Policy {
+                authorizations: Arc::new([Authorization {
+                    authentication: Authentication::Unauthenticated,
+                    networks: vec![std::net::IpAddr::from([192, 168, 3, 3]).into()],
+                    meta: Arc::new(Meta::Resource {
+                        group: "policy.linkerd.io".into(),
+                        kind: "AuthorizatoinPolicy".into(),
+                        name: "test".into(),
+                    }),
+                    requests: vec![],
+                    action: Action::Allow,
+                }]),
+                filters: vec![Filter::RateLimit(RateLimit::new(
+                    rate_limit::Key::ClientId,
+                    rate_limit::Limit {
+                        requests_per_second: 1,
+                        burst: 1,
+                    },
+                ))],
+                meta: rmeta.clone(),
+            },
+        }],
+    }]));
+    let inner = |_: HttpRoutePermit,
+                 _: ::http::Request<hyper::Body>|
+     -> Result<::http::Response<hyper::Body>> { Ok(::http::Response::default()) };
+    let (mut svc, _tx) = new_svc!(proto, conn!(), inner);
+
+    svc.call(
+        ::http::Request::builder()
+            .body(hyper::Body::default())
+            .unwrap(),
+    )
+    .await
+    .expect("first request is permitted");
+
+    let err = svc
+        .call(
+            ::http::Request::builder()
+                .body(hyper::Body::default())
+                .unwrap(),
+        )
+        .await
+        .expect_err("second request is rate limited");
+    assert!(err.is::<HttpRouteRateLimited>());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn http_filter_url_rewrite() {
+    use linkerd_proxy_server_policy::http::{
+        filter, r#match::MatchPath, r#match::MatchRequest, Filter, Policy, Route, Rule,
+    };
+
+    let rmeta = Arc::new(Meta::Resource {
+        group: "gateway.networking.k8s.io".into(),
+        kind: "httproute".into(),
+        name: "testrt".into(),
+    });
+    let proto = Protocol::Http1(Arc::new([Route {
+        hosts: vec![],
+        rules: vec![Rule {
+            matches: vec![MatchRequest {
+                path: Some(MatchPath::Prefix("/api".to_string())),
+                ..MatchRequest::default()
+            }],
+            policy: Policy {
+                authorizations: Arc::new([Authorization {
+                    authentication: Authentication::Unauthenticated,
+                    networks: vec![std::net::IpAddr::from([192, 168, 3, 3]).into()],
+                    meta: Arc::new(Meta::Resource {
+                        group: "policy.linkerd.io".into(),
+                        kind: "AuthorizatoinPolicy".into(),
+                        name: "test".into(),
+                    }),
+                    requests: vec![],
+                    action: Action::Allow,
+                }]),
+                filters: vec![Filter::UrlRewrite(filter::UrlRewrite {
+                    authority: Some("backend.example.com".parse().unwrap()),
+                    path: Some(filter::ModifyPath::ReplacePrefixMatch("/".to_string())),
+                })],
+                meta: rmeta.clone(),
+            },
+        }],
+    }]));
+    let inner = |permit: HttpRoutePermit, req: ::http::Request<hyper::Body>| -> Result<_> {
+        assert_eq!(req.uri(), "/v1/things?limit=1");
+        assert_eq!(
+            req.headers().get(::http::header::HOST),
+            Some(&"backend.example.com".parse().unwrap())
+        );
+        let mut rsp = ::http::Response::builder()
+            .body(hyper::Body::default())
+            .unwrap();
+        rsp.extensions_mut().insert(permit);
+        Ok(rsp)
+    };
+    let (mut svc, _tx) = new_svc!(proto, conn!(), inner);
+
+    let rsp = svc
+        .call(
+            ::http::Request::builder()
+                .uri("/api/v1/things?limit=1")
+                .header(::http::header::HOST, "example.com")
+                .body(hyper::Body::default())
+                .unwrap(),
+        )
+        .await
+        .expect("serves");
+    let permit = rsp
+        .extensions()
+        .get::<HttpRoutePermit>()
+        .expect("permitted");
+    assert_eq!(permit.labels.route.route, rmeta);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn http_route_authz_requests() {
+    use linkerd_proxy_server_policy::http::{
+        r#match::MatchPath, r#match::MatchRequest, Policy, Route, Rule,
+    };
+
+    let rmeta = Arc::new(Meta::Resource {
+        group: "gateway.networking.k8s.io".into(),
+        kind: "httproute".into(),
+        name: "testrt".into(),
+    });
+    let deny_meta = Arc::new(Meta::Resource {
+        group: "policy.linkerd.io".into(),
+        kind: "AuthorizationPolicy".into(),
+        name: "deny-admin".into(),
+    });
+    let (mut svc, _tx) = new_svc!(Protocol::Http1(Arc::new([Route {
+        hosts: vec![],
+        rules: vec![Rule {
+            matches: vec![MatchRequest::default()],
+            policy: Policy {
+                authorizations: Arc::new([
+                    Authorization {
+                        authentication: Authentication::Unauthenticated,
+                        networks: vec![std::net::IpAddr::from([192, 168, 3, 3]).into()],
+                        meta: Arc::new(Meta::Resource {
+                            group: "policy.linkerd.io".into(),
+                            kind: "AuthorizationPolicy".into(),
+                            name: "allow".into(),
+                        }),
+                        requests: vec![],
+                        action: Action::Allow,
+                    },
+                    Authorization {
+                        authentication: Authentication::Unauthenticated,
+                        networks: vec![std::net::IpAddr::from([192, 168, 3, 3]).into()],
+                        meta: deny_meta,
+                        requests: vec![MatchRequest {
+                            method: Some(::http::Method::POST),
+                            path: Some(MatchPath::Prefix("/admin".to_string())),
+                            ..MatchRequest::default()
+                        }],
+                        action: Action::Deny,
+                    },
+                ]),
+                filters: vec![],
+                meta: rmeta.clone(),
+            },
+        }],
+    }])));
+
+    // Requests that don't match the deny authorization are allowed:
+    for (method, path) in [
+        (::http::Method::GET, "/admin"),
+        (::http::Method::POST, "/other"),
+    ] {
+        let rsp = svc
+            .call(
+                ::http::Request::builder()
+                    .method(method)
+                    .uri(path)
+                    .body(hyper::Body::default())
+                    .unwrap(),
+            )
+            .await
+            .expect("serves");
+        let permit = rsp
+            .extensions()
+            .get::<HttpRoutePermit>()
+            .expect("permitted");
+        assert_eq!(permit.labels.authz.name(), "allow");
+    }
+
+    // The deny authorization takes precedence over the allow, regardless of
+    // order:
+    assert!(svc
+        .call(
+            ::http::Request::builder()
+                .method(::http::Method::POST)
+                .uri("/admin/users")
+                .body(hyper::Body::default())
+                .unwrap(),
+        )
+        .await
+        .expect_err("fails")
+        .is::<HttpRouteUnauthorized>());
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn http_route_authz_jwt() {
+    use linkerd_proxy_server_policy::{
+        authz::jwt::{Jwks, Jwt, MatchClaim},
+        http::{r#match::MatchRequest, Policy, Route, Rule},
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("jwks.json");
+    // The key is `secret`, base64url-encoded.
+    std::fs::write(
+        &path,
+        r#"{"keys":[{"kty":"oct","kid":"k1","alg":"HS256","k":"c2VjcmV0"}]}"#,
+    )
+    .unwrap();
+    let jwt = Jwt {
+        header: ::http::header::AUTHORIZATION,
+        issuer: "https://issuer.example.com".to_string(),
+        audiences: vec!["api".to_string()],
+        claims: vec![MatchClaim {
+            name: "role".to_string(),
+            values: vec!["admin".to_string()],
+        }],
+        jwks: Jwks::spawn(&path, tokio::time::Duration::from_secs(10)).unwrap(),
+    };
+    let token = |role: &str| {
+        let header = jsonwebtoken::Header {
+            kid: Some("k1".to_string()),
+            ..jsonwebtoken::Header::default()
+        };
+        let claims = serde_json::json!({
+            "iss": "https://issuer.example.com",
+            "aud": "api",
+            "exp": jsonwebtoken::get_current_timestamp() + 600,
+            "role": role,
+        });
+        let key = jsonwebtoken::EncodingKey::from_secret(b"secret");
+        format!(
+            "Bearer {}",
+            jsonwebtoken::encode(&header, &claims, &key).unwrap()
+        )
+    };
+
+    let (mut svc, _tx) = new_svc!(Protocol::Http1(Arc::new([Route {
+        hosts: vec![],
+        rules: vec![Rule {
+            matches: vec![MatchRequest::default()],
+            policy: Policy {
+                authorizations: Arc::new([Authorization {
+                    authentication: Authentication::Jwt(jwt),
+                    networks: vec![std::net::IpAddr::from([192, 168, 3, 3]).into()],
+                    meta: Arc::new(Meta::Resource {
+                        group: "policy.linkerd.io".into(),
+                        kind: "AuthorizationPolicy".into(),
+                        name: "jwt".into(),
+                    }),
+                    requests: vec![],
+                    action: Action::Allow,
+                }]),
+                filters: vec![],
+                meta: Arc::new(Meta::Resource {
+                    group: "gateway.networking.k8s.io".into(),
+                    kind: "httproute".into(),
+                    name: "testrt".into(),
+                }),
+            },
+        }],
+    }])));
+
+    // Requests with a valid token are allowed:
+    let rsp = svc
+        .call(
+            ::http::Request::builder()
+                .header(::http::header::AUTHORIZATION, token("admin"))
+                .body(hyper::Body::default())
+                .unwrap(),
+        )
+        .await
+        .expect("serves");
+    let permit = rsp
+        .extensions()
+        .get::<HttpRoutePermit>()
+        .expect("permitted");
+    assert_eq!(permit.labels.authz.name(), "jwt");
+
+    // Requests without a token, or whose claims don't match, are denied:
+    for req in [
+        ::http::Request::builder(),
+        ::http::Request::builder().header(::http::header::AUTHORIZATION, token("viewer")),
+    ] {
+        assert!(svc
+            .call(req.body(hyper::Body::default()).unwrap())
+            .await
+            .expect_err("fails")
+            .is::<HttpRouteUnauthorized>());
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn http_route_audit() {
+    use linkerd_proxy_server_policy::http::{r#match::MatchRequest, Policy, Route, Rule};
+
+    let smeta = Arc::new(Meta::Resource {
+        group: "policy.linkerd.io".into(),
+        kind: "Server".into(),
+        name: "testsrv".into(),
+    });
+    let rmeta = Arc::new(Meta::Resource {
+        group: "gateway.networking.k8s.io".into(),
+        kind: "httproute".into(),
+        name: "testrt".into(),
+    });
+    let mk_policy = |action, audit| ServerPolicy {
+        meta: smeta.clone(),
+        protocol: Protocol::Http1(Arc::new([Route {
+            hosts: vec![],
+            rules: vec![Rule {
+                matches: vec![MatchRequest::default()],
+                policy: Policy {
+                    authorizations: Arc::new([Authorization {
+                        authentication: Authentication::Unauthenticated,
+                        networks: vec![std::net::IpAddr::from([192, 168, 3, 3]).into()],
+                        meta: Arc::new(Meta::Resource {
+                            group: "policy.linkerd.io".into(),
+                            kind: "AuthorizationPolicy".into(),
+                            name: "test".into(),
+                        }),
+                        requests: vec![],
+                        action,
+                    }]),
+                    filters: vec![],
+                    meta: rmeta.clone(),
+                },
+            }],
+        }])),
+        audit,
+    };
+    let (mut svc, tx) = new_svc!(mk_policy(Action::Deny, false).protocol);
+    let call = |svc: &mut HttpPolicyService<_, _>| {
+        svc.call(
+            ::http::Request::builder()
+                .body(hyper::Body::default())
+                .unwrap(),
+        )
+    };
+
+    assert!(call(&mut svc)
+        .await
+        .expect_err("fails")
+        .is::<HttpRouteUnauthorized>());
+
+    // Requests that would be denied are permitted in audit mode.
+    for (action, audit) in [(Action::Audit, false), (Action::Deny, true)] {
+        tx.send(mk_policy(action, audit)).expect("must send");
+        let rsp = call(&mut svc).await.expect("serves");
+        let permit = rsp
+            .extensions()
+            .get::<HttpRoutePermit>()
+            .expect("permitted");
+        assert_eq!(permit.labels.authz.name(), "test");
+    }
+
+    // When no authorization applies, requests are permitted in audit mode.
+    let mut policy = mk_policy(Action::Allow, true);
+    if let Protocol::Http1(routes) = &mut policy.protocol {
+        let mut route = routes[0].clone();
+        route.rules[0].policy.authorizations = Arc::new([]);
+        *routes = Arc::new([route]);
+    }
+    tx.send(policy).expect("must send");
+    let rsp = call(&mut svc).await.expect("serves");
+    let permit = rsp
+        .extensions()
+        .get::<HttpRoutePermit>()
+        .expect("permitted");
+    assert_eq!(permit.labels.authz.name(), "audit");
+}
+
 #[tokio::test(flavor = "current_thread")]
 async fn grpc_route() {
     use linkerd_proxy_server_policy::grpc::{
@@ -396,6 +860,8 @@ async fn grpc_route() {
                             kind: "AuthorizationPolicy".into(),
                             name: "test".into(),
                         }),
+                        requests: vec![],
+                        action: Action::Allow,
                     }]),
                     filters: vec![],
                     meta: rmeta.clone(),
@@ -494,6 +960,8 @@ async fn grpc_filter_header() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    requests: vec![],
+                    action: Action::Allow,
                 }]),
                 filters: vec![Filter::RequestHeaders(http::filter::ModifyHeader {
                     add: vec![("testkey".parse().unwrap(), "testval".parse().unwrap())],
@@ -567,6 +1035,8 @@ async fn grpc_filter_inject_failure() {
                         kind: "AuthorizatoinPolicy".into(),
                         name: "test".into(),
                     }),
+                    requests: vec![],
+                    action: Action::Allow,
                 }]),
                 filters: vec![Filter::InjectFailure(filter::InjectFailure {
                     distribution: filter::Distribution::from_ratio(1, 1).unwrap(),
@@ -4,11 +4,12 @@ use crate::{
 };
 use futures::future;
 use linkerd_app_core::{
+    metrics::ServerAuthzLabels,
     svc, tls,
     transport::{ClientAddr, OrigDstAddr, Remote},
     Error, Result,
 };
-use linkerd_proxy_server_policy::{Protocol, ServerPolicy};
+use linkerd_proxy_server_policy::{Action, Protocol, ServerPolicy};
 use std::{future::Future, pin::Pin, task};
 #[cfg(test)]
 mod tests;
@@ -70,25 +71,32 @@ where
             tracing::trace!(policy = ?p, "Authorizing connection");
             check_authorized(&p, policy.dst, client, &tls)
         };
+        let mk_authorized = |permit| {
+            let inner = self.inner.new_service((permit, target));
+            TcpPolicy::Authorized(Authorized {
+                inner,
+                policy: policy.clone(),
+                client,
+                tls: tls.clone(),
+                metrics: self.metrics.clone(),
+            })
+        };
         match authorized {
-            Ok(permit) => {
+            Check::Allow(permit) => {
                 tracing::debug!(?permit, ?tls, %client, "Connection authorized");
 
                 // This new services requires a ClientAddr, so it must necessarily be built for each
                 // connection. So we can just increment the counter here since the service can only
                 // be used at most once.
                 self.metrics.allow(&permit, tls.clone());
-
-                let inner = self.inner.new_service((permit, target));
-                TcpPolicy::Authorized(Authorized {
-                    inner,
-                    policy,
-                    client,
-                    tls,
-                    metrics: self.metrics.clone(),
-                })
+                mk_authorized(permit)
             }
-            Err(deny) => {
+            Check::Audit(permit) => {
+                log_audit(&permit, &tls, client, "Connection would be denied");
+                self.metrics.would_deny(&policy, tls.clone());
+                mk_authorized(permit)
+            }
+            Check::Deny(deny) => {
                 let meta = policy.meta();
                 tracing::info!(
                     server.group = %meta.group(),
@@ -157,7 +165,12 @@ where
                 tokio::select! {
                     res = &mut call => return res.map_err(Into::into),
                     _ = policy.changed() => {
-                        if let Err(denied) = check_authorized(&policy.server.borrow(), policy.dst, client, &tls) {
+                        let check = check_authorized(&policy.server.borrow(), policy.dst, client, &tls);
+                        if let Check::Audit(permit) = &check {
+                            log_audit(permit, &tls, client, "Connection would be terminated due to policy change");
+                            metrics.would_deny(&policy, tls.clone());
+                        }
+                        if let Check::Deny(denied) = check {
                             let meta = policy.meta();
                             tracing::info!(
                                 server.group = %meta.group(),
@@ -177,6 +190,18 @@ where
     }
 }
 
+/// The result of authorizing a connection.
+#[derive(Debug)]
+enum Check {
+    Allow(ServerPermit),
+
+    /// The connection is not authorized, but it is permitted because the
+    /// server or a matching authorization is in audit mode.
+    Audit(ServerPermit),
+
+    Deny(ServerUnauthorized),
+}
+
 /// Checks whether the destination port's `AllowPolicy` is authorized to
 /// accept connections given the provided TLS state.
 fn check_authorized(
@@ -184,7 +209,7 @@ fn check_authorized(
     dst: OrigDstAddr,
     client_addr: Remote<ClientAddr>,
     tls: &tls::ConditionalServerTls,
-) -> Result<ServerPermit, ServerUnauthorized> {
+) -> Check {
     if let Protocol::Detect {
         tcp_authorizations: authzs,
         ..
@@ -192,14 +217,50 @@ fn check_authorized(
     | Protocol::Tls(authzs)
     | Protocol::Opaque(authzs) = &server.protocol
     {
-        for authz in &**authzs {
-            if super::is_authorized(authz, client_addr, tls) {
-                return Ok(ServerPermit::new(dst, server, authz));
+        // Authorizations with request conditions cannot apply to connections.
+        let authzs = authzs.iter().filter(|a| a.is_unconditional());
+        if let Some(authz) = super::find_authorization(authzs, client_addr, tls, None) {
+            let permit = ServerPermit::new(dst, server, authz);
+            match authz.action {
+                Action::Allow => return Check::Allow(permit),
+                Action::Audit => return Check::Audit(permit),
+                Action::Deny if server.audit => return Check::Audit(permit),
+                Action::Deny => {}
             }
         }
     }
 
-    Err(ServerUnauthorized {
+    if server.audit {
+        return Check::Audit(ServerPermit::with_authz(dst, server, super::audit_meta()));
+    }
+    Check::Deny(ServerUnauthorized {
         server: server.meta.clone(),
     })
 }
+
+fn log_audit(
+    permit: &ServerPermit,
+    tls: &tls::ConditionalServerTls,
+    client: Remote<ClientAddr>,
+    msg: &'static str,
+) {
+    let ServerAuthzLabels { server, authz } = &permit.labels;
+    let client_id = match tls {
+        tls::ConditionalServerTls::Some(tls::ServerTls::Established {
+            client_id: Some(tls::server::ClientId(id)),
+            ..
+        }) => Some(id),
+        _ => None,
+    };
+    tracing::info!(
+        server.group = %server.0.group(),
+        server.kind = %server.0.kind(),
+        server.name = %server.0.name(),
+        authz.group = %authz.group(),
+        authz.kind = %authz.kind(),
+        authz.name = %authz.name(),
+        client.id = ?client_id,
+        client.addr = %client,
+        "{msg}",
+    );
+}
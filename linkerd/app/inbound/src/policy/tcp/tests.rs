@@ -21,6 +21,8 @@ async fn unauthenticated_allowed() {
                     kind: "serverauthorization".into(),
                     name: "unauth".into(),
                 }),
+                requests: vec![],
+                action: Action::Allow,
             }]
             .into(),
         ),
@@ -29,6 +31,7 @@ async fn unauthenticated_allowed() {
             kind: "server".into(),
             name: "test".into(),
         }),
+        audit: false,
     };
 
     let tls = tls::ConditionalServerTls::None(tls::NoServerTls::NoClientHello);
@@ -63,6 +66,7 @@ async fn authenticated_identity() {
                 authentication: Authentication::TlsAuthenticated {
                     suffixes: vec![],
                     identities: vec![client_id().to_string()].into_iter().collect(),
+                    trust_domains: vec![],
                 },
                 networks: vec!["192.0.2.0/24".parse().unwrap()],
                 meta: Arc::new(Meta::Resource {
@@ -70,6 +74,8 @@ async fn authenticated_identity() {
                     kind: "serverauthorization".into(),
                     name: "tls-auth".into(),
                 }),
+                requests: vec![],
+                action: Action::Allow,
             }]
             .into(),
         ),
@@ -78,6 +84,7 @@ async fn authenticated_identity() {
             kind: "server".into(),
             name: "test".into(),
         }),
+        audit: false,
     };
 
     let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
@@ -126,6 +133,7 @@ async fn authenticated_suffix() {
                 authentication: Authentication::TlsAuthenticated {
                     identities: BTreeSet::default(),
                     suffixes: vec![Suffix::from(vec!["cluster".into(), "local".into()])],
+                    trust_domains: vec![],
                 },
                 networks: vec!["192.0.2.0/24".parse().unwrap()],
                 meta: Arc::new(Meta::Resource {
@@ -133,6 +141,8 @@ async fn authenticated_suffix() {
                     kind: "serverauthorization".into(),
                     name: "tls-auth".into(),
                 }),
+                requests: vec![],
+                action: Action::Allow,
             }]
             .into(),
         ),
@@ -141,6 +151,7 @@ async fn authenticated_suffix() {
             kind: "server".into(),
             name: "test".into(),
         }),
+        audit: false,
     };
 
     let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
@@ -192,6 +203,8 @@ async fn tls_unauthenticated() {
                     kind: "serverauthorization".into(),
                     name: "tls-unauth".into(),
                 }),
+                requests: vec![],
+                action: Action::Allow,
             }]
             .into(),
         ),
@@ -200,6 +213,7 @@ async fn tls_unauthenticated() {
             kind: "server".into(),
             name: "test".into(),
         }),
+        audit: false,
     };
 
     let tls = tls::ConditionalServerTls::Some(tls::ServerTls::Established {
@@ -236,6 +250,69 @@ async fn tls_unauthenticated() {
         .expect_err("policy must require a TLS termination identity");
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn audit() {
+    let server = Arc::new(Meta::Resource {
+        group: "policy.linkerd.io".into(),
+        kind: "server".into(),
+        name: "test".into(),
+    });
+    let deny = Arc::new(Meta::Resource {
+        group: "policy.linkerd.io".into(),
+        kind: "authorizationpolicy".into(),
+        name: "deny".into(),
+    });
+    let mut policy = ServerPolicy {
+        protocol: Protocol::Opaque(
+            vec![Authorization {
+                authentication: Authentication::Unauthenticated,
+                networks: vec!["192.0.2.0/24".parse().unwrap()],
+                meta: deny.clone(),
+                requests: vec![],
+                action: Action::Audit,
+            }]
+            .into(),
+        ),
+        meta: server.clone(),
+        audit: false,
+    };
+    let tls = tls::ConditionalServerTls::None(tls::NoServerTls::NoClientHello);
+
+    // An audit authorization permits the connection, though it is recorded as
+    // a denial.
+    match check_authorized(&policy, orig_dst_addr(), client_addr(), &tls) {
+        Check::Audit(permit) => assert_eq!(permit.labels.authz, deny),
+        check => panic!("connection must be audited: {check:?}"),
+    }
+
+    // Deny authorizations are audited when the server is in audit mode.
+    policy.protocol = Protocol::Opaque(
+        vec![Authorization {
+            authentication: Authentication::Unauthenticated,
+            networks: vec!["192.0.2.0/24".parse().unwrap()],
+            meta: deny.clone(),
+            requests: vec![],
+            action: Action::Deny,
+        }]
+        .into(),
+    );
+    check_authorized(&policy, orig_dst_addr(), client_addr(), &tls)
+        .expect_err("deny authorization must deny the connection");
+    policy.audit = true;
+    match check_authorized(&policy, orig_dst_addr(), client_addr(), &tls) {
+        Check::Audit(permit) => assert_eq!(permit.labels.authz, deny),
+        check => panic!("connection must be audited: {check:?}"),
+    }
+
+    // Connections that match no authorization are audited when the server is
+    // in audit mode.
+    policy.protocol = Protocol::Opaque(Arc::new([]));
+    match check_authorized(&policy, orig_dst_addr(), client_addr(), &tls) {
+        Check::Audit(permit) => assert_eq!(permit.labels.server, ServerLabel(server)),
+        check => panic!("connection must be audited: {check:?}"),
+    }
+}
+
 fn client_id() -> tls::ClientId {
     "testsa.testns.serviceaccount.identity.linkerd.cluster.local"
         .parse()
@@ -250,6 +327,22 @@ fn orig_dst_addr() -> OrigDstAddr {
     OrigDstAddr(([192, 0, 2, 2], 1000).into())
 }
 
+impl Check {
+    fn expect(self, msg: &str) -> ServerPermit {
+        match self {
+            Check::Allow(permit) => permit,
+            check => panic!("{msg}: {check:?}"),
+        }
+    }
+
+    fn expect_err(self, msg: &str) -> ServerUnauthorized {
+        match self {
+            Check::Deny(deny) => deny,
+            check => panic!("{msg}: {check:?}"),
+        }
+    }
+}
+
 impl tonic::client::GrpcService<tonic::body::BoxBody> for MockSvc {
     type ResponseBody = linkerd_app_core::control::RspBody;
     type Error = Error;
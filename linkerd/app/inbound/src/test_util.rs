@@ -14,7 +14,9 @@ use linkerd_app_core::{
     ProxyRuntime,
 };
 pub use linkerd_app_test as support;
-use linkerd_proxy_server_policy::{Authentication, Authorization, Meta, Protocol, ServerPolicy};
+use linkerd_proxy_server_policy::{
+    Action, Authentication, Authorization, Meta, Protocol, ServerPolicy,
+};
 use std::{sync::Arc, time::Duration};
 
 pub fn default_config() -> Config {
@@ -30,6 +32,8 @@ pub fn default_config() -> Config {
             kind: "serverauthorization".into(),
             name: "testsaz".into(),
         }),
+        requests: vec![],
+        action: Action::Allow,
     }]);
     let policy = policy::Config::Fixed {
         cache_max_idle_age: Duration::from_secs(20),
@@ -46,6 +50,7 @@ pub fn default_config() -> Config {
                 kind: "server".into(),
                 name: "testsrv".into(),
             }),
+            audit: false,
         }
         .into(),
         ports: Default::default(),
@@ -98,6 +103,7 @@ pub fn runtime() -> (ProxyRuntime, drain::Signal) {
         metrics: metrics.proxy,
         tap,
         span_sink: None,
+        access_log_headers: Default::default(),
         drain,
     };
     (runtime, drain_tx)
@@ -2,6 +2,8 @@ use std::collections::HashMap;
 use std::fmt;
 use std::string::ToString;
 
+pub mod openmetrics;
+
 #[derive(Debug, Clone)]
 pub struct MetricMatch {
     name: String,
@@ -0,0 +1,373 @@
+//! A validating parser for the OpenMetrics text format.
+//!
+//! This checks the structure of an exposition---metric family metadata,
+//! sample names, labels, values, exemplars, and the EOF marker---rather than
+//! every detail of the specification.
+//!
+//! See <https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md>.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The metric families in an exposition, by name, with the number of samples
+/// in each.
+#[derive(Debug, Default)]
+pub struct Families(HashMap<String, Family>);
+
+#[derive(Debug)]
+pub struct Family {
+    pub kind: String,
+    pub samples: usize,
+}
+
+type Labels = BTreeMap<String, String>;
+
+/// Parses an OpenMetrics text exposition, returning a description of the first
+/// line that does not conform.
+pub fn parse(text: &str) -> Result<Families, String> {
+    let body = text
+        .strip_suffix("# EOF\n")
+        .or_else(|| text.strip_suffix("# EOF"))
+        .ok_or("exposition must end with '# EOF'")?;
+
+    let mut families = Families::default();
+    let mut current: Option<Current> = None;
+    for (i, line) in body.lines().enumerate() {
+        let n = i + 1;
+        let err = |e: String| format!("line {n}: {e}: {line:?}");
+
+        if let Some(comment) = line.strip_prefix('#') {
+            let (name, kind, value) = parse_metadata(comment).map_err(err)?;
+            if current.as_ref().map_or(true, |c| c.name != name) {
+                if let Some(c) = current.take() {
+                    c.finish(&mut families).map_err(err)?;
+                }
+                if families.0.contains_key(name) {
+                    return Err(err(format!("family {name} is not contiguous")));
+                }
+                current = Some(Current::new(name));
+            }
+            let c = current.as_mut().expect("family must be set");
+            c.metadata(kind, value).map_err(err)?;
+            continue;
+        }
+
+        let sample = Sample::parse(line).map_err(err)?;
+        if !current.as_ref().map_or(false, |c| c.contains(&sample.name)) {
+            // Samples without metadata form a family of unknown type.
+            if let Some(c) = current.take() {
+                c.finish(&mut families).map_err(err)?;
+            }
+            if families.0.contains_key(&sample.name) {
+                return Err(err(format!("family {} is not contiguous", sample.name)));
+            }
+            current = Some(Current::new(&sample.name));
+        }
+        current
+            .as_mut()
+            .expect("family must be set")
+            .sample(sample)
+            .map_err(err)?;
+    }
+    if let Some(c) = current.take() {
+        c.finish(&mut families)
+            .map_err(|e| format!("at EOF: {e}"))?;
+    }
+
+    Ok(families)
+}
+
+// === impl Families ===
+
+impl Families {
+    pub fn get(&self, name: &str) -> Option<&Family> {
+        self.0.get(name)
+    }
+}
+
+/// The family that is currently being parsed.
+struct Current {
+    name: String,
+    kind: Option<String>,
+    has_help: bool,
+    has_unit: bool,
+    samples: HashSet<(String, Labels)>,
+    /// Histogram buckets' label sets, excluding `le`, and whether each has a
+    /// `+Inf` bucket.
+    buckets: HashMap<Labels, bool>,
+}
+
+impl Current {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: None,
+            has_help: false,
+            has_unit: false,
+            samples: HashSet::new(),
+            buckets: HashMap::new(),
+        }
+    }
+
+    fn kind(&self) -> &str {
+        self.kind.as_deref().unwrap_or("unknown")
+    }
+
+    fn metadata(&mut self, kind: &str, value: &str) -> Result<(), String> {
+        if !self.samples.is_empty() {
+            return Err("metadata must precede samples".to_string());
+        }
+        match kind {
+            "TYPE" => {
+                if self.kind.is_some() {
+                    return Err("duplicate TYPE".to_string());
+                }
+                const TYPES: &[&str] = &[
+                    "counter",
+                    "gauge",
+                    "histogram",
+                    "gaugehistogram",
+                    "stateset",
+                    "info",
+                    "summary",
+                    "unknown",
+                ];
+                if !TYPES.contains(&value) {
+                    return Err(format!("invalid type {value}"));
+                }
+                self.kind = Some(value.to_string());
+            }
+            "HELP" => {
+                if std::mem::replace(&mut self.has_help, true) {
+                    return Err("duplicate HELP".to_string());
+                }
+                check_escapes(value, &['\\', 'n', '"'])?;
+            }
+            "UNIT" => {
+                if std::mem::replace(&mut self.has_unit, true) {
+                    return Err("duplicate UNIT".to_string());
+                }
+            }
+            kind => return Err(format!("invalid metadata {kind}")),
+        }
+        Ok(())
+    }
+
+    fn suffixes(&self) -> &'static [&'static str] {
+        match self.kind() {
+            "counter" => &["_total", "_created"],
+            "histogram" => &["_bucket", "_count", "_sum", "_created"],
+            "gaugehistogram" => &["_bucket", "_gcount", "_gsum"],
+            "summary" => &["", "_count", "_sum", "_created"],
+            "info" => &["_info"],
+            _ => &[""],
+        }
+    }
+
+    fn contains(&self, sample: &str) -> bool {
+        self.suffix(sample).is_some()
+    }
+
+    fn suffix(&self, sample: &str) -> Option<&'static str> {
+        let suffix = sample.strip_prefix(&self.name)?;
+        self.suffixes().iter().copied().find(|s| *s == suffix)
+    }
+
+    fn sample(&mut self, sample: Sample) -> Result<(), String> {
+        let suffix = self
+            .suffix(&sample.name)
+            .ok_or_else(|| format!("sample does not belong to family {}", self.name))?;
+
+        if sample.has_exemplar {
+            let permitted = matches!(
+                (self.kind(), suffix),
+                ("counter", "_total") | ("histogram" | "gaugehistogram", "_bucket")
+            );
+            if !permitted {
+                return Err("exemplars are only permitted on counters and buckets".to_string());
+            }
+        }
+
+        if suffix == "_bucket" {
+            let mut labels = sample.labels.clone();
+            let le = labels.remove("le").ok_or("buckets must have an le label")?;
+            parse_value(&le)?;
+            *self.buckets.entry(labels).or_default() |= le == "+Inf";
+        }
+
+        if !self.samples.insert((sample.name, sample.labels)) {
+            return Err("duplicate sample".to_string());
+        }
+        Ok(())
+    }
+
+    fn finish(self, families: &mut Families) -> Result<(), String> {
+        if self.buckets.values().any(|inf| !inf) {
+            return Err(format!("histogram {} is missing a +Inf bucket", self.name));
+        }
+        let kind = self.kind().to_string();
+        families.0.insert(
+            self.name,
+            Family {
+                kind,
+                samples: self.samples.len(),
+            },
+        );
+        Ok(())
+    }
+}
+
+struct Sample {
+    name: String,
+    labels: Labels,
+    has_exemplar: bool,
+}
+
+impl Sample {
+    fn parse(line: &str) -> Result<Self, String> {
+        let (name, rest) = parse_name(line)?;
+        let (labels, rest) = parse_labels(rest)?;
+
+        let (value, exemplar) = match rest.split_once(" # ") {
+            Some((value, exemplar)) => (value, Some(exemplar)),
+            None => (rest, None),
+        };
+        let mut value = value
+            .strip_prefix(' ')
+            .ok_or("sample must have a value")?
+            .split(' ');
+        parse_value(value.next().unwrap_or_default())?;
+        if let Some(ts) = value.next() {
+            parse_value(ts)?;
+        }
+        if value.next().is_some() {
+            return Err("unexpected trailing data".to_string());
+        }
+
+        if let Some(exemplar) = exemplar {
+            let (_, rest) = parse_labels(exemplar)?;
+            let mut value = rest
+                .strip_prefix(' ')
+                .ok_or("exemplar must have a value")?
+                .split(' ');
+            parse_value(value.next().unwrap_or_default())?;
+            if let Some(ts) = value.next() {
+                parse_value(ts)?;
+            }
+            if value.next().is_some() {
+                return Err("unexpected trailing exemplar data".to_string());
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            labels,
+            has_exemplar: exemplar.is_some(),
+        })
+    }
+}
+
+/// Parses a `# HELP`, `# TYPE`, or `# UNIT` line, after its `#`.
+fn parse_metadata(comment: &str) -> Result<(&str, &str, &str), String> {
+    let comment = comment
+        .strip_prefix(' ')
+        .ok_or("comments are not permitted")?;
+    let (kind, rest) = comment
+        .split_once(' ')
+        .ok_or("metadata must name a family")?;
+    let (name, rest) = parse_name(rest)?;
+    let value = match rest.strip_prefix(' ') {
+        Some(value) => value,
+        None if rest.is_empty() => "",
+        None => return Err("invalid family name".to_string()),
+    };
+    Ok((name, kind, value))
+}
+
+fn parse_name(s: &str) -> Result<(&str, &str), String> {
+    let end = s
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':'))
+        .unwrap_or(s.len());
+    let (name, rest) = s.split_at(end);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(format!("invalid metric name {name:?}"));
+    }
+    Ok((name, rest))
+}
+
+/// Parses an optional label set, returning the labels and the remainder of the
+/// line.
+fn parse_labels(s: &str) -> Result<(Labels, &str), String> {
+    let mut labels = Labels::new();
+    let Some(mut rest) = s.strip_prefix('{') else {
+        return Ok((labels, s));
+    };
+    if let Some(rest) = rest.strip_prefix('}') {
+        return Ok((labels, rest));
+    }
+    loop {
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let (name, r) = rest.split_at(end);
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(format!("invalid label name {name:?}"));
+        }
+        let r = r
+            .strip_prefix("=\"")
+            .ok_or_else(|| format!("label {name} must have a quoted value"))?;
+
+        // Find the closing quote, skipping escaped characters.
+        let mut escaped = false;
+        let close = r
+            .char_indices()
+            .find(|&(_, c)| {
+                let close = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                close
+            })
+            .map(|(i, _)| i)
+            .ok_or_else(|| format!("label {name} is not terminated"))?;
+        let value = &r[..close];
+        check_escapes(value, &['\\', 'n', '"'])?;
+        if labels.insert(name.to_string(), value.to_string()).is_some() {
+            return Err(format!("duplicate label {name}"));
+        }
+
+        let r = &r[close + 1..];
+        if let Some(r) = r.strip_prefix(',') {
+            rest = r;
+        } else if let Some(r) = r.strip_prefix('}') {
+            return Ok((labels, r));
+        } else {
+            return Err("label set is not terminated".to_string());
+        }
+    }
+}
+
+fn parse_value(s: &str) -> Result<f64, String> {
+    match s {
+        "+Inf" => Ok(f64::INFINITY),
+        "-Inf" => Ok(f64::NEG_INFINITY),
+        "NaN" => Ok(f64::NAN),
+        // Rust also accepts spellings, like `inf`, that OpenMetrics does not.
+        s if s.contains(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E') => {
+            Err(format!("invalid number {s:?}"))
+        }
+        s => s.parse().map_err(|_| format!("invalid number {s:?}")),
+    }
+}
+
+fn check_escapes(s: &str, permitted: &[char]) -> Result<(), String> {
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => return Err("unescaped newline".to_string()),
+            '\\' => match chars.next() {
+                Some(e) if permitted.contains(&e) => {}
+                e => return Err(format!("invalid escape {e:?}")),
+            },
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -1260,6 +1260,65 @@ mod transport {
     }
 }
 
+/// Tests that the admin server's metrics are a valid OpenMetrics exposition
+/// when the client negotiates the format.
+#[tokio::test]
+async fn metrics_openmetrics() {
+    let _trace = trace_init();
+
+    let Fixture {
+        client,
+        metrics,
+        proxy: _proxy,
+        _profile,
+        dst_tx: _dst_tx,
+        pol_out_tx: _pol_out_tx,
+        labels,
+        ..
+    } = Fixture::outbound().await;
+
+    info!("client.get(/)");
+    assert_eq!(client.get("/").await, "hello");
+    labels
+        .metric("response_latency_ms_count")
+        .value(1u64)
+        .assert_in(&metrics)
+        .await;
+
+    // Prometheus' default scrape header.
+    let accept = "application/openmetrics-text;version=1.0.0,application/openmetrics-text;version=0.0.1;q=0.75,text/plain;version=0.0.4;q=0.5,*/*;q=0.1";
+    let rsp = metrics
+        .request(
+            metrics
+                .request_builder("/metrics")
+                .method("GET")
+                .header(http::header::ACCEPT, accept),
+        )
+        .await
+        .expect("scrape");
+    assert_eq!(
+        rsp.headers().get(http::header::CONTENT_TYPE).unwrap(),
+        "application/openmetrics-text; version=1.0.0; charset=utf-8"
+    );
+    let scrape = http_util::body_to_string(rsp.into_body()).await.unwrap();
+
+    let families =
+        metrics::openmetrics::parse(&scrape).unwrap_or_else(|error| panic!("{error}\n{scrape}"));
+    for (name, kind) in [
+        ("request", "counter"),
+        ("response", "counter"),
+        ("response_latency_ms", "histogram"),
+        ("tcp_open_connections", "gauge"),
+        ("process_start_time_seconds", "gauge"),
+    ] {
+        let family = families
+            .get(name)
+            .unwrap_or_else(|| panic!("{name} must be reported\n{scrape}"));
+        assert_eq!(family.kind, kind, "{name}");
+        assert!(family.samples > 0, "{name} must have samples");
+    }
+}
+
 // linkerd/linkerd2#613
 #[tokio::test]
 async fn metrics_compression() {
@@ -198,6 +198,7 @@ pub fn synthesize_forward_policy(
             meta: meta.clone(),
             queue,
             dispatcher: policy::BackendDispatcher::Forward(addr, metadata),
+            tls: None,
         },
     )
 }
@@ -221,6 +222,7 @@ pub fn synthesize_balance_policy(
                     path: addr.to_string(),
                 },
             ),
+            tls: None,
         },
     )
 }
@@ -239,6 +241,8 @@ fn policy_for_backend(
             filters: NO_OPAQ_FILTERS.clone(),
             failure_policy: Default::default(),
             request_timeout: None,
+            retry: None,
+            hedge: None,
             distribution: policy::RouteDistribution::FirstAvailable(Arc::new([
                 policy::RouteBackend {
                     filters: NO_OPAQ_FILTERS.clone(),
@@ -258,6 +262,8 @@ fn policy_for_backend(
                 filters: NO_HTTP_FILTERS.clone(),
                 failure_policy: Default::default(),
                 request_timeout: None,
+                retry: None,
+                hedge: None,
                 distribution: policy::RouteDistribution::FirstAvailable(Arc::new([
                     policy::RouteBackend {
                         filters: NO_HTTP_FILTERS.clone(),
@@ -17,6 +17,7 @@ mod breaker;
 pub mod concrete;
 mod endpoint;
 mod handle_proxy_error_headers;
+mod hedge;
 pub mod logical;
 mod require_id_header;
 mod retry;
@@ -3,14 +3,29 @@ use linkerd_proxy_client_policy::FailureAccrual;
 use tracing::{trace_span, Instrument};
 
 mod consecutive_failures;
+mod success_rate;
 
-use self::consecutive_failures::ConsecutiveFailures;
+use self::{consecutive_failures::ConsecutiveFailures, success_rate::SuccessRate};
 
 /// Params configuring a circuit breaker stack.
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub(crate) struct Params {
-    pub(crate) accrual: FailureAccrual,
-    pub(crate) channel_capacity: usize,
+    accrual: FailureAccrual,
+    channel_capacity: usize,
+    /// Success rates shared by all of a balancer's endpoints.
+    success_rates: success_rate::Pool,
+}
+
+// === impl Params ===
+
+impl Params {
+    pub(crate) fn new(accrual: FailureAccrual, channel_capacity: usize) -> Self {
+        Self {
+            accrual,
+            channel_capacity,
+            success_rates: Default::default(),
+        }
+    }
 }
 
 impl<T> svc::ExtractParam<gate::Params<classify::Class>, T> for Params {
@@ -48,6 +63,43 @@ impl<T> svc::ExtractParam<gate::Params<classify::Class>, T> for Params {
                         .instrument(trace_span!("consecutive_failures").or_current()),
                 );
 
+                prms
+            }
+            FailureAccrual::SuccessRate {
+                window,
+                min_requests,
+                std_dev_factor_milli,
+                max_ejection_percent,
+                backoff,
+            } => {
+                tracing::trace!(
+                    ?window,
+                    min_requests,
+                    std_dev_factor_milli,
+                    max_ejection_percent,
+                    backoff = ?backoff,
+                    "Using success-rate failure accrual policy.",
+                );
+
+                // 1. Each time the window advances, compare the endpoint's
+                //    success rate to the mean success rate of the balancer's
+                //    endpoints. If it is too far below the mean, shut the gate.
+                // 2. After an ejection timeout, open the gate so that 1 request can be processed.
+                // 3. If that request succeeds, open the gate. If it fails, increase the
+                //    ejection timeout and repeat.
+                let config = success_rate::Config {
+                    window,
+                    min_requests,
+                    std_dev_factor: f64::from(std_dev_factor_milli) / 1000.0,
+                    max_ejection_percent,
+                };
+                let breaker = SuccessRate::new(config, backoff, &self.success_rates, gate, rsps);
+                tokio::spawn(
+                    breaker
+                        .run()
+                        .instrument(trace_span!("success_rate").or_current()),
+                );
+
                 prms
             }
         }
@@ -0,0 +1,395 @@
+use ahash::AHashMap;
+use futures::stream::StreamExt;
+use linkerd_app_core::{classify, exp_backoff::ExponentialBackoff, proxy::http::classify::gate};
+use parking_lot::Mutex;
+use std::{collections::VecDeque, sync::Arc};
+use tokio::{sync::mpsc, time};
+
+/// The number of buckets into which the sliding window is divided. An
+/// endpoint's success rate is recomputed each time the window advances by one
+/// bucket.
+const WINDOW_BUCKETS: u32 = 10;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Config {
+    pub window: time::Duration,
+    pub min_requests: usize,
+    pub std_dev_factor: f64,
+    pub max_ejection_percent: u32,
+}
+
+/// The success rates of all endpoints in a balancer.
+#[derive(Clone, Debug, Default)]
+pub struct Pool(Arc<Mutex<PoolState>>);
+
+#[derive(Debug, Default)]
+struct PoolState {
+    next_id: usize,
+    endpoints: AHashMap<usize, Status>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Status {
+    /// The endpoint has not received enough requests within the window for
+    /// its success rate to be considered.
+    Unknown,
+    Rate(f64),
+    Ejected,
+}
+
+/// An endpoint's entry in a [`Pool`], removed when the endpoint's breaker
+/// task completes.
+#[derive(Debug)]
+struct Registration {
+    pool: Pool,
+    id: usize,
+}
+
+/// Counts of responses observed over a sliding window.
+#[derive(Debug, Default)]
+struct Window {
+    buckets: VecDeque<(usize, usize)>,
+}
+
+pub struct SuccessRate {
+    config: Config,
+    backoff: ExponentialBackoff,
+    registration: Registration,
+    gate: gate::Tx,
+    rsps: mpsc::Receiver<classify::Class>,
+}
+
+// === impl SuccessRate ===
+
+impl SuccessRate {
+    pub fn new(
+        config: Config,
+        backoff: ExponentialBackoff,
+        pool: &Pool,
+        gate: gate::Tx,
+        rsps: mpsc::Receiver<classify::Class>,
+    ) -> Self {
+        Self {
+            config,
+            backoff,
+            registration: pool.register(),
+            gate,
+            rsps,
+        }
+    }
+
+    pub(super) async fn run(mut self) {
+        loop {
+            if self.open().await.is_err() {
+                return;
+            }
+
+            tracing::info!("Success-rate failure-accrual breaker closed");
+            if self.closed().await.is_err() {
+                return;
+            }
+
+            tracing::info!("Success-rate failure-accrual breaker reopened");
+        }
+    }
+
+    /// Keep the breaker open until the endpoint's success rate falls too far
+    /// below the pool's mean success rate.
+    async fn open(&mut self) -> Result<(), ()> {
+        tracing::debug!("Open");
+        self.gate.open().map_err(|_| ())?;
+        self.registration.reset();
+
+        let mut window = Window::default();
+        let period = self.config.window / WINDOW_BUCKETS;
+        let mut advance = time::interval_at(time::Instant::now() + period, period);
+        advance.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                rsp = self.rsps.recv() => {
+                    let class = rsp.ok_or(())?;
+                    tracing::trace!(?class, "Response");
+                    window.record(class.is_success());
+                }
+                _ = advance.tick() => {
+                    let rate = window.success_rate(self.config.min_requests);
+                    window.advance();
+                    tracing::trace!(?rate, "Window advanced");
+                    if self.registration.update(rate, &self.config) {
+                        return Ok(());
+                    }
+                }
+                _ = self.gate.lost() => return Err(()),
+            }
+        }
+    }
+
+    /// Keep the breaker closed for at least the initial backoff, and then,
+    /// once the timeout expires, go into probation to admit a single request
+    /// before reverting to the open state or continuing in the shut state.
+    async fn closed(&mut self) -> Result<(), ()> {
+        let mut backoff = self.backoff.stream();
+        loop {
+            // The breaker is shut now. Wait until we can open it again.
+            tracing::debug!(backoff = ?backoff.duration(), "Shut");
+            self.gate.shut().map_err(|_| ())?;
+
+            loop {
+                tokio::select! {
+                    _ = backoff.next() => break,
+                    // Ignore responses while the breaker is shut.
+                    _ = self.rsps.recv() => continue,
+                    _ = self.gate.lost() => return Err(()),
+                }
+            }
+
+            let class = self.probation().await?;
+            tracing::trace!(?class, "Response");
+            if class.is_success() {
+                // Open!
+                return Ok(());
+            }
+        }
+    }
+
+    /// Wait for a response to determine whether the breaker should be opened.
+    async fn probation(&mut self) -> Result<classify::Class, ()> {
+        tracing::debug!("Probation");
+        let _sem = self.gate.limit(1).map_err(|_| ())?;
+        tokio::select! {
+            rsp = self.rsps.recv() => rsp.ok_or(()),
+            _ = self.gate.lost() => Err(()),
+        }
+    }
+}
+
+// === impl Pool ===
+
+impl Pool {
+    fn register(&self) -> Registration {
+        let mut state = self.0.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.endpoints.insert(id, Status::Unknown);
+        Registration {
+            pool: self.clone(),
+            id,
+        }
+    }
+}
+
+// === impl PoolState ===
+
+impl PoolState {
+    /// Records an endpoint's success rate, returning true if the endpoint
+    /// should be ejected.
+    fn update(&mut self, id: usize, rate: Option<f64>, config: &Config) -> bool {
+        self.endpoints
+            .insert(id, rate.map_or(Status::Unknown, Status::Rate));
+        let rate = match rate {
+            Some(rate) => rate,
+            None => return false,
+        };
+
+        let rates = self.endpoints.values().filter_map(|s| match s {
+            Status::Rate(r) => Some(*r),
+            _ => None,
+        });
+        let (n, sum) = rates
+            .clone()
+            .fold((0.0, 0.0), |(n, sum), r| (n + 1.0, sum + r));
+        let mean = sum / n;
+        let variance = rates.map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+        let threshold = mean - config.std_dev_factor * variance.sqrt();
+        tracing::trace!(rate, mean, threshold, "Success rate");
+        if rate >= threshold {
+            return false;
+        }
+
+        let ejected = self
+            .endpoints
+            .values()
+            .filter(|s| **s == Status::Ejected)
+            .count();
+        if (ejected + 1) * 100 > self.endpoints.len() * config.max_ejection_percent as usize {
+            tracing::debug!(ejected, "Maximum ejections reached");
+            return false;
+        }
+
+        self.endpoints.insert(id, Status::Ejected);
+        true
+    }
+}
+
+// === impl Registration ===
+
+impl Registration {
+    /// Marks the endpoint's success rate as unknown, i.e. after it has been
+    /// restored to the pool.
+    fn reset(&self) {
+        self.pool
+            .0
+            .lock()
+            .endpoints
+            .insert(self.id, Status::Unknown);
+    }
+
+    fn update(&self, rate: Option<f64>, config: &Config) -> bool {
+        self.pool.0.lock().update(self.id, rate, config)
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.pool.0.lock().endpoints.remove(&self.id);
+    }
+}
+
+// === impl Window ===
+
+impl Window {
+    fn record(&mut self, success: bool) {
+        if self.buckets.is_empty() {
+            self.buckets.push_back((0, 0));
+        }
+        let (successes, failures) = self.buckets.back_mut().expect("bucket must exist");
+        if success {
+            *successes += 1;
+        } else {
+            *failures += 1;
+        }
+    }
+
+    /// Returns the success rate over the window, if at least `min_requests`
+    /// responses have been observed.
+    fn success_rate(&self, min_requests: usize) -> Option<f64> {
+        let (successes, failures) = self
+            .buckets
+            .iter()
+            .fold((0, 0), |(s, f), (bs, bf)| (s + bs, f + bf));
+        let total = successes + failures;
+        if total == 0 || total < min_requests {
+            return None;
+        }
+        Some(successes as f64 / total as f64)
+    }
+
+    /// Starts a new bucket, discarding the oldest bucket once the window is
+    /// full.
+    fn advance(&mut self) {
+        self.buckets.push_back((0, 0));
+        while self.buckets.len() > WINDOW_BUCKETS as usize {
+            self.buckets.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_test::{assert_pending, task};
+
+    const CONFIG: Config = Config {
+        window: time::Duration::from_secs(10),
+        min_requests: 10,
+        std_dev_factor: 1.0,
+        max_ejection_percent: 50,
+    };
+
+    type Endpoint = (
+        gate::Params<classify::Class>,
+        task::Spawn<futures::future::BoxFuture<'static, ()>>,
+    );
+
+    /// Sends 10 responses to each endpoint, `failures` of which fail on the
+    /// first endpoint.
+    fn send(endpoints: &mut [Endpoint], failures: usize) {
+        for (i, (params, task)) in endpoints.iter_mut().enumerate() {
+            for n in 0..10 {
+                let res = if i == 0 && n < failures {
+                    Err(http::StatusCode::BAD_GATEWAY)
+                } else {
+                    Ok(http::StatusCode::OK)
+                };
+                params
+                    .responses
+                    .try_send(classify::Class::Http(res))
+                    .unwrap();
+            }
+            assert_pending!(task.poll());
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn ejects_outliers() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let backoff = ExponentialBackoff::try_new(
+            time::Duration::from_secs(1),
+            time::Duration::from_secs(100),
+            // Don't jitter backoffs to ensure tests are deterministic.
+            0.0,
+        )
+        .expect("backoff params are valid");
+        let pool = Pool::default();
+
+        let mut endpoints = (0..4)
+            .map(|_| {
+                let (params, gate, rsps) = gate::Params::channel(100);
+                let breaker = SuccessRate::new(CONFIG, backoff, &pool, gate, rsps);
+                let task =
+                    task::spawn(Box::pin(breaker.run()) as futures::future::BoxFuture<'_, ()>);
+                (params, task)
+            })
+            .collect::<Vec<_>>();
+        for (params, task) in endpoints.iter_mut() {
+            assert_pending!(task.poll());
+            assert!(params.gate.is_open());
+        }
+
+        // Healthy endpoints are not ejected.
+        send(&mut endpoints, 0);
+        time::sleep(CONFIG.window / WINDOW_BUCKETS).await;
+        send(&mut endpoints, 0);
+        for (params, task) in endpoints.iter_mut() {
+            assert_pending!(task.poll());
+            assert!(params.gate.is_open());
+        }
+
+        // An endpoint that fails 30% of its requests is ejected once its
+        // success rate is recomputed, even though its failures are not
+        // consecutive.
+        send(&mut endpoints, 3);
+        time::sleep(CONFIG.window / WINDOW_BUCKETS).await;
+        for (i, (params, task)) in endpoints.iter_mut().enumerate() {
+            assert_pending!(task.poll());
+            assert_eq!(params.gate.is_shut(), i == 0, "endpoint {i}");
+        }
+
+        // Once the ejected endpoint's backoff elapses, it is probed with a
+        // single request.
+        time::sleep(time::Duration::from_secs(1)).await;
+        assert_pending!(endpoints[0].1.poll());
+        assert!(endpoints[0].0.gate.is_limited());
+    }
+
+    #[test]
+    fn limits_ejections() {
+        let config = Config {
+            max_ejection_percent: 25,
+            ..CONFIG
+        };
+        let pool = Pool::default();
+        let _registrations = (0..4).map(|_| pool.register()).collect::<Vec<_>>();
+        let mut state = pool.0.lock();
+
+        assert!(!state.update(0, Some(1.0), &config));
+        assert!(!state.update(1, Some(1.0), &config));
+        assert!(!state.update(2, Some(1.0), &config));
+        assert!(!state.update(3, None, &config), "unknown rates are ignored");
+        assert!(state.update(3, Some(0.1), &config), "outliers are ejected");
+
+        // Only one of the four endpoints may be ejected at once.
+        assert!(!state.update(2, Some(0.1), &config));
+    }
+}
@@ -1,7 +1,10 @@
 //! A stack that (optionally) resolves a service to a set of endpoint replicas
 //! and distributes HTTP requests among them.
 
-use super::{balance::EwmaConfig, client, handle_proxy_error_headers};
+use super::{
+    balance::{EwmaConfig, HashKey, LeastRequestConfig},
+    client, handle_proxy_error_headers,
+};
 use crate::{http, stack_labels, BackendRef, Outbound, ParentRef};
 use linkerd_app_core::{
     config::QueueConfig,
@@ -16,7 +19,7 @@ use linkerd_app_core::{
     transport::{self, addrs::*},
     Error, Infallible, NameAddr, Result,
 };
-use linkerd_proxy_client_policy::FailureAccrual;
+use linkerd_proxy_client_policy::{BackendTls, FailureAccrual};
 use std::{fmt::Debug, net::SocketAddr, sync::Arc};
 use tracing::info_span;
 
@@ -30,11 +33,29 @@ pub use self::metrics::BalancerMetrics;
 /// Parameter configuring dispatcher behavior.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Dispatch {
-    Balance(NameAddr, EwmaConfig),
+    Balance(NameAddr, Balancer),
     Forward(Remote<ServerAddr>, Metadata),
     Fail { message: Arc<str> },
 }
 
+/// Configures how a balancer selects endpoints.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Balancer {
+    /// Selects endpoints by the power-of-two-choices algorithm, comparing
+    /// their peak-EWMA latencies.
+    PeakEwma(EwmaConfig),
+
+    /// Selects endpoints by consistent hashing of a request attribute.
+    RingHash(HashKey),
+
+    /// Selects the endpoint with the fewest outstanding requests from a random
+    /// sample of endpoints.
+    LeastRequest(LeastRequestConfig),
+
+    /// Selects each endpoint in turn.
+    RoundRobin,
+}
+
 /// A backend dispatcher explicitly fails all requests.
 #[derive(Debug, thiserror::Error)]
 #[error("{0}")]
@@ -49,6 +70,7 @@ pub struct Endpoint<T> {
     parent: T,
     queue: QueueConfig,
     close_server_connection_on_remote_proxy_error: bool,
+    tls: Option<BackendTls>,
 }
 
 // === impl Outbound ===
@@ -74,6 +96,7 @@ impl<N> Outbound<N> {
         T: svc::Param<BackendRef>,
         T: svc::Param<Dispatch>,
         T: svc::Param<FailureAccrual>,
+        T: svc::Param<Option<BackendTls>>,
         T: Clone + Debug + Send + Sync + 'static,
         // Endpoint resolution.
         R: Resolve<ConcreteAddr, Error = Error, Endpoint = Metadata>,
@@ -119,10 +142,10 @@ impl<N> Outbound<N> {
                 .push_switch(
                     move |parent: T| -> Result<_, Infallible> {
                         Ok(match parent.param() {
-                            Dispatch::Balance(addr, ewma) => {
+                            Dispatch::Balance(addr, balancer) => {
                                 svc::Either::A(svc::Either::A(balance::Balance {
                                     addr,
-                                    ewma,
+                                    balancer,
                                     parent,
                                     queue,
                                 }))
@@ -133,6 +156,7 @@ impl<N> Outbound<N> {
                                     is_local,
                                     addr,
                                     metadata,
+                                    tls: parent.param(),
                                     parent,
                                     queue,
                                     close_server_connection_on_remote_proxy_error: true,
@@ -152,7 +176,15 @@ impl<N> Outbound<N> {
 
 impl<T> svc::Param<Remote<ServerAddr>> for Endpoint<T> {
     fn param(&self) -> Remote<ServerAddr> {
-        self.addr
+        match self.tls {
+            // Endpoints in the mesh are always reached on their own port.
+            Some(BackendTls {
+                port: Some(port), ..
+            }) if self.metadata.identity().is_none() => {
+                Remote(ServerAddr(SocketAddr::new(self.addr.ip(), port.get())))
+            }
+            _ => self.addr,
+        }
     }
 }
 
@@ -202,6 +234,7 @@ impl<T> svc::Param<handle_proxy_error_headers::CloseServerConnection> for Endpoi
 impl<T> svc::Param<transport::labels::Key> for Endpoint<T>
 where
     T: svc::Param<Option<http::uri::Authority>>,
+    T: svc::Param<http::Version>,
 {
     fn param(&self) -> transport::labels::Key {
         transport::labels::Key::OutboundClient(self.param())
@@ -211,6 +244,7 @@ where
 impl<T> svc::Param<OutboundEndpointLabels> for Endpoint<T>
 where
     T: svc::Param<Option<http::uri::Authority>>,
+    T: svc::Param<http::Version>,
 {
     fn param(&self) -> OutboundEndpointLabels {
         OutboundEndpointLabels {
@@ -225,13 +259,17 @@ where
 impl<T> svc::Param<EndpointLabels> for Endpoint<T>
 where
     T: svc::Param<Option<http::uri::Authority>>,
+    T: svc::Param<http::Version>,
 {
     fn param(&self) -> EndpointLabels {
         EndpointLabels::Outbound(self.param())
     }
 }
 
-impl<T> svc::Param<tls::ConditionalClientTls> for Endpoint<T> {
+impl<T> svc::Param<tls::ConditionalClientTls> for Endpoint<T>
+where
+    T: svc::Param<http::Version>,
+{
     fn param(&self) -> tls::ConditionalClientTls {
         if self.is_local {
             return tls::ConditionalClientTls::None(tls::NoClientTls::Loopback);
@@ -242,22 +280,38 @@ impl<T> svc::Param<tls::ConditionalClientTls> for Endpoint<T> {
         // header.
         let use_transport_header = self.metadata.tagged_transport_port().is_some()
             || self.metadata.authority_override().is_some();
-        self.metadata
-            .identity()
-            .cloned()
-            .map(move |mut client_tls| {
-                client_tls.alpn = if use_transport_header {
-                    use linkerd_app_core::transport_header::PROTOCOL;
-                    Some(tls::client::AlpnProtocols(vec![PROTOCOL.into()]))
-                } else {
-                    None
-                };
-
-                tls::ConditionalClientTls::Some(client_tls)
-            })
-            .unwrap_or(tls::ConditionalClientTls::None(
-                tls::NoClientTls::NotProvidedByServiceDiscovery,
-            ))
+        let Some(mut client_tls) = self.metadata.identity().cloned() else {
+            // Endpoints outside of the mesh may be configured to use TLS that
+            // is originated by the proxy. The client's protocol is negotiated
+            // with ALPN, since the server cannot detect it.
+            return match self.tls {
+                Some(BackendTls {
+                    ref server_name,
+                    ref originate,
+                    ..
+                }) => {
+                    let mut client_tls =
+                        tls::ClientTls::originate(server_name.clone(), originate.clone());
+                    let protocol: &[u8] = match svc::Param::<client::Settings>::param(self) {
+                        client::Settings::H2 | client::Settings::OrigProtoUpgrade => b"h2",
+                        client::Settings::Http1 => b"http/1.1",
+                    };
+                    client_tls.alpn = Some(tls::client::AlpnProtocols(vec![protocol.into()]));
+                    tls::ConditionalClientTls::Some(client_tls)
+                }
+                None => {
+                    tls::ConditionalClientTls::None(tls::NoClientTls::NotProvidedByServiceDiscovery)
+                }
+            };
+        };
+
+        client_tls.alpn = if use_transport_header {
+            use linkerd_app_core::transport_header::PROTOCOL;
+            Some(tls::client::AlpnProtocols(vec![PROTOCOL.into()]))
+        } else {
+            None
+        };
+        tls::ConditionalClientTls::Some(client_tls)
     }
 }
 
@@ -302,7 +356,10 @@ impl<T> svc::Param<ProtocolHint> for Endpoint<T> {
 }
 
 // TODO(ver) move this into the endpoint stack?
-impl<T> tap::Inspect for Endpoint<T> {
+impl<T> tap::Inspect for Endpoint<T>
+where
+    T: svc::Param<http::Version>,
+{
     fn src_addr<B>(&self, req: &http::Request<B>) -> Option<SocketAddr> {
         req.extensions().get::<http::ClientHandle>().map(|c| c.addr)
     }
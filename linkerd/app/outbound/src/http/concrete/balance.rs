@@ -1,4 +1,4 @@
-use super::Endpoint;
+use super::{Balancer, Endpoint};
 use crate::{
     http::{self, balance, breaker},
     metrics::BalancerMetricsParams,
@@ -16,7 +16,7 @@ use linkerd_app_core::{
     transport::addrs::*,
     Error, NameAddr,
 };
-use linkerd_proxy_client_policy::FailureAccrual;
+use linkerd_proxy_client_policy::{BackendTls, FailureAccrual};
 use std::{fmt::Debug, net::SocketAddr};
 use tracing::info_span;
 
@@ -24,11 +24,28 @@ use tracing::info_span;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Balance<T> {
     pub addr: NameAddr,
-    pub ewma: balance::EwmaConfig,
+    pub balancer: Balancer,
     pub queue: QueueConfig,
     pub parent: T,
 }
 
+/// A target for the balancer stack of a single algorithm, carrying that
+/// algorithm's `A`-typed configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Balancing<A, T> {
+    config: A,
+    target: Balance<T>,
+}
+
+/// Builds the balancer stack for each target's configured algorithm.
+#[derive(Clone, Debug)]
+struct NewBalancer<E, H, L, R> {
+    peak_ewma: E,
+    ring_hash: H,
+    least_request: L,
+    round_robin: R,
+}
+
 /// Wraps errors encountered in this module.
 #[derive(Debug, thiserror::Error)]
 #[error("{}: {source}", backend.0)]
@@ -40,9 +57,14 @@ pub struct BalanceError {
 
 // === impl Balance ===
 
-impl<T> svc::Param<http::balance::EwmaConfig> for Balance<T> {
-    fn param(&self) -> http::balance::EwmaConfig {
-        self.ewma
+impl<T> svc::Param<balance::Algorithm> for Balance<T> {
+    fn param(&self) -> balance::Algorithm {
+        match self.balancer {
+            Balancer::PeakEwma(_) => balance::Algorithm::PeakEwma,
+            Balancer::RingHash(_) => balance::Algorithm::RingHash,
+            Balancer::LeastRequest(_) => balance::Algorithm::LeastRequest,
+            Balancer::RoundRobin => balance::Algorithm::RoundRobin,
+        }
     }
 }
 
@@ -76,6 +98,7 @@ where
     T: svc::Param<ParentRef>,
     T: svc::Param<BackendRef>,
     T: svc::Param<FailureAccrual>,
+    T: svc::Param<Option<BackendTls>>,
     T: Clone + Debug + Send + Sync + 'static,
 {
     pub(super) fn layer<N, NSvc, R>(
@@ -101,9 +124,7 @@ where
         let inbound_ips = config.inbound_ips.clone();
         let metrics = rt.metrics.clone();
 
-        let resolve = svc::stack(resolve.into_service())
-            .push_map_target(|t: Self| ConcreteAddr(t.addr))
-            .into_inner();
+        let resolve = resolve.into_service();
 
         let metrics_params = BalancerMetricsParams::register(registry);
 
@@ -118,6 +139,7 @@ where
                             addr: Remote(ServerAddr(addr)),
                             metadata,
                             is_local,
+                            tls: target.parent.param(),
                             parent: target.parent,
                             queue: http_queue,
                             // We don't close server-side connections when we
@@ -132,9 +154,8 @@ where
                 .push(
                     http::NewClassifyGateSet::<classify::Response, _, _, _>::layer_via({
                         let channel_capacity = http_queue.capacity;
-                        move |target: &Self| breaker::Params {
-                            accrual: target.parent.param(),
-                            channel_capacity,
+                        move |target: &Self| {
+                            breaker::Params::new(target.parent.param(), channel_capacity)
                         }
                     }),
                 )
@@ -154,29 +175,183 @@ where
                 .push_on_service(svc::ArcNewService::layer())
                 .push(svc::ArcNewService::layer());
 
-            endpoint
+            let peak_ewma = endpoint
+                .clone()
+                .push_map_target(Balancing::into_target)
                 .push(http::NewBalancePeakEwma::layer(
-                    resolve.clone(),
+                    svc::stack(resolve.clone())
+                        .push_map_target(Balancing::concrete_addr)
+                        .into_inner(),
                     metrics_params.clone(),
                 ))
                 .push_on_service(http::BoxResponse::layer())
                 .push_on_service(metrics.proxy.stack.layer(stack_labels("http", "balance")))
-                .push(svc::NewMapErr::layer_from_target::<BalanceError, _>())
-                .instrument(|t: &Self| {
-                    let BackendRef(meta) = t.parent.param();
-                    info_span!(
-                        "service",
-                        ns = %meta.namespace(),
-                        name = %meta.name(),
-                        port = %meta.port().map(u16::from).unwrap_or(0),
-                    )
-                })
-                .arc_new_clone_http()
-                .into_inner()
+                .arc_new_clone_http();
+
+            let ring_hash = endpoint
+                .clone()
+                .push_map_target(Balancing::into_target)
+                .push(http::NewBalanceRingHash::layer(
+                    svc::stack(resolve.clone())
+                        .push_map_target(Balancing::concrete_addr)
+                        .into_inner(),
+                    metrics_params.clone(),
+                ))
+                .push_on_service(http::BoxResponse::layer())
+                .push_on_service(metrics.proxy.stack.layer(stack_labels("http", "balance")))
+                .arc_new_clone_http();
+
+            let least_request = endpoint
+                .clone()
+                .push_map_target(Balancing::into_target)
+                .push(http::NewBalanceLeastRequest::layer(
+                    svc::stack(resolve.clone())
+                        .push_map_target(Balancing::concrete_addr)
+                        .into_inner(),
+                    metrics_params.clone(),
+                ))
+                .push_on_service(http::BoxResponse::layer())
+                .push_on_service(metrics.proxy.stack.layer(stack_labels("http", "balance")))
+                .arc_new_clone_http();
+
+            let round_robin = endpoint
+                .push_map_target(Balancing::into_target)
+                .push(http::NewBalanceRoundRobin::layer(
+                    svc::stack(resolve.clone())
+                        .push_map_target(Balancing::concrete_addr)
+                        .into_inner(),
+                    metrics_params.clone(),
+                ))
+                .push_on_service(http::BoxResponse::layer())
+                .push_on_service(metrics.proxy.stack.layer(stack_labels("http", "balance")))
+                .arc_new_clone_http();
+
+            svc::stack(NewBalancer {
+                peak_ewma: peak_ewma.into_inner(),
+                ring_hash: ring_hash.into_inner(),
+                least_request: least_request.into_inner(),
+                round_robin: round_robin.into_inner(),
+            })
+            .push(svc::NewMapErr::layer_from_target::<BalanceError, _>())
+            .instrument(|t: &Self| {
+                let BackendRef(meta) = t.parent.param();
+                info_span!(
+                    "service",
+                    ns = %meta.namespace(),
+                    name = %meta.name(),
+                    port = %meta.port().map(u16::from).unwrap_or(0),
+                )
+            })
+            .arc_new_clone_http()
+            .into_inner()
         })
     }
 }
 
+// === impl Balancing ===
+
+impl<A, T> Balancing<A, T> {
+    fn into_target(self) -> Balance<T> {
+        self.target
+    }
+
+    fn concrete_addr(self) -> ConcreteAddr {
+        ConcreteAddr(self.target.addr)
+    }
+}
+
+impl<T> svc::Param<http::balance::EwmaConfig> for Balancing<http::balance::EwmaConfig, T> {
+    fn param(&self) -> http::balance::EwmaConfig {
+        self.config
+    }
+}
+
+impl<T> svc::Param<http::balance::RingHashConfig<http::balance::HashKey>>
+    for Balancing<http::balance::RingHashConfig<http::balance::HashKey>, T>
+{
+    fn param(&self) -> http::balance::RingHashConfig<http::balance::HashKey> {
+        self.config.clone()
+    }
+}
+
+impl<T> svc::Param<http::balance::LeastRequestConfig>
+    for Balancing<http::balance::LeastRequestConfig, T>
+{
+    fn param(&self) -> http::balance::LeastRequestConfig {
+        self.config
+    }
+}
+
+impl<T> svc::Param<http::balance::RoundRobinConfig>
+    for Balancing<http::balance::RoundRobinConfig, T>
+{
+    fn param(&self) -> http::balance::RoundRobinConfig {
+        self.config
+    }
+}
+
+impl<A, T> svc::Param<balance::Algorithm> for Balancing<A, T> {
+    fn param(&self) -> balance::Algorithm {
+        self.target.param()
+    }
+}
+
+impl<A, T> svc::Param<svc::queue::Capacity> for Balancing<A, T> {
+    fn param(&self) -> svc::queue::Capacity {
+        self.target.param()
+    }
+}
+
+impl<A, T> svc::Param<svc::queue::Timeout> for Balancing<A, T> {
+    fn param(&self) -> svc::queue::Timeout {
+        self.target.param()
+    }
+}
+
+impl<A, T: svc::Param<ParentRef>> svc::Param<ParentRef> for Balancing<A, T> {
+    fn param(&self) -> ParentRef {
+        self.target.param()
+    }
+}
+
+impl<A, T: svc::Param<BackendRef>> svc::Param<BackendRef> for Balancing<A, T> {
+    fn param(&self) -> BackendRef {
+        self.target.param()
+    }
+}
+
+// === impl NewBalancer ===
+
+impl<T, E, H, L, R, S> svc::NewService<Balance<T>> for NewBalancer<E, H, L, R>
+where
+    E: svc::NewService<Balancing<http::balance::EwmaConfig, T>, Service = S>,
+    H: svc::NewService<
+        Balancing<http::balance::RingHashConfig<http::balance::HashKey>, T>,
+        Service = S,
+    >,
+    L: svc::NewService<Balancing<http::balance::LeastRequestConfig, T>, Service = S>,
+    R: svc::NewService<Balancing<http::balance::RoundRobinConfig, T>, Service = S>,
+{
+    type Service = S;
+
+    fn new_service(&self, target: Balance<T>) -> S {
+        match target.balancer.clone() {
+            Balancer::PeakEwma(config) => self.peak_ewma.new_service(Balancing { config, target }),
+            Balancer::RingHash(key) => self.ring_hash.new_service(Balancing {
+                config: http::balance::RingHashConfig { key },
+                target,
+            }),
+            Balancer::LeastRequest(config) => {
+                self.least_request.new_service(Balancing { config, target })
+            }
+            Balancer::RoundRobin => self.round_robin.new_service(Balancing {
+                config: Default::default(),
+                target,
+            }),
+        }
+    }
+}
+
 // === impl BalanceError ===
 
 impl<T> From<(&Balance<T>, Error)> for BalanceError
@@ -1,6 +1,10 @@
 use super::{balance::*, *};
 use crate::test_util::*;
-use linkerd_app_core::{proxy::http::balance::EwmaConfig, svc::NewService, trace};
+use linkerd_app_core::{
+    proxy::http::balance::EwmaConfig,
+    svc::{NewService, ServiceExt},
+    trace,
+};
 use linkerd_proxy_client_policy as policy;
 use std::{net::SocketAddr, num::NonZeroU16, sync::Arc};
 use tokio::{task, time};
@@ -48,10 +52,11 @@ async fn gauges_endpoints() {
                 capacity: 100,
                 failfast_timeout: time::Duration::from_secs(3),
             },
-            ewma: EwmaConfig {
+            balancer: Balancer::PeakEwma(EwmaConfig {
                 default_rtt: time::Duration::from_millis(100),
                 decay: time::Duration::from_secs(10),
-            },
+                slow_start: None,
+            }),
         });
 
     let gauge = outbound
@@ -113,6 +118,157 @@ async fn gauges_endpoints() {
     );
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn ring_hash_balances_by_key() {
+    let _trace = trace::test::trace_init();
+    let (rt, _shutdown) = runtime();
+    let outbound = Outbound::new(default_config(), rt);
+
+    let (svc, _resolve_tx) = echo_balancer(
+        &outbound,
+        Balancer::RingHash(http::balance::HashKey::Header(
+            http::HeaderName::from_static("x-user"),
+        )),
+    );
+
+    let send = |user: &'static str| {
+        let svc = svc.clone();
+        async move {
+            let req = http::Request::builder()
+                .header("x-user", user)
+                .body(http::BoxBody::default())
+                .unwrap();
+            let rsp = svc.oneshot(req).await.expect("request must succeed");
+            rsp.headers()["x-endpoint"].clone()
+        }
+    };
+
+    for user in ["alice", "bob", "carol"] {
+        let endpoint = send(user).await;
+        for _ in 0..10 {
+            assert_eq!(
+                send(user).await,
+                endpoint,
+                "{user} must stick to {endpoint:?}"
+            );
+        }
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn round_robin_balances_in_turn() {
+    let _trace = trace::test::trace_init();
+    let (rt, _shutdown) = runtime();
+    let outbound = Outbound::new(default_config(), rt);
+
+    let (svc, _resolve_tx) = echo_balancer(&outbound, Balancer::RoundRobin);
+
+    let mut seen = Vec::new();
+    for _ in 0..ECHO_ENDPOINTS * 2 {
+        let rsp = svc
+            .clone()
+            .oneshot(http::Request::new(http::BoxBody::default()))
+            .await
+            .expect("request must succeed");
+        seen.push(rsp.headers()["x-endpoint"].clone());
+    }
+    let (first, second) = seen.split_at(ECHO_ENDPOINTS.into());
+    assert_eq!(first, second, "endpoints must be selected in turn");
+    let mut first = first.to_vec();
+    first.sort();
+    first.dedup();
+    assert_eq!(
+        first.len(),
+        usize::from(ECHO_ENDPOINTS),
+        "each endpoint must be selected once per turn"
+    );
+}
+
+#[test]
+fn originates_tls_to_unmeshed_endpoints() {
+    let originate = tls::OriginateTls::new(tls::client::TrustRoots::Public, None);
+    let ep = Endpoint {
+        addr: Remote(ServerAddr(([192, 0, 2, 3], 80).into())),
+        is_local: false,
+        metadata: Metadata::default(),
+        parent: Target,
+        queue: default_config().http_request_queue,
+        close_server_connection_on_remote_proxy_error: true,
+        tls: Some(policy::BackendTls {
+            server_name: "api.example.com".parse().unwrap(),
+            port: NonZeroU16::new(443),
+            originate: originate.clone(),
+        }),
+    };
+
+    let client_tls = match svc::Param::<tls::ConditionalClientTls>::param(&ep) {
+        tls::ConditionalClientTls::Some(client_tls) => client_tls,
+        tls::ConditionalClientTls::None(reason) => panic!("TLS must be originated: {reason}"),
+    };
+    assert_eq!(client_tls.server_name.to_string(), "api.example.com");
+    assert_eq!(client_tls.originate, Some(originate));
+    // The target's protocol is negotiated with the server.
+    assert_eq!(
+        client_tls.alpn,
+        Some(tls::client::AlpnProtocols(vec![b"h2".to_vec()]))
+    );
+
+    let Remote(ServerAddr(addr)) = svc::Param::<Remote<ServerAddr>>::param(&ep);
+    assert_eq!(addr, ([192, 0, 2, 3], 443).into());
+}
+
+const ECHO_ENDPOINTS: u8 = 5;
+
+/// Builds a balancer over endpoints that respond with their own address in an
+/// `x-endpoint` header.
+fn echo_balancer(
+    outbound: &Outbound<()>,
+    balancer: Balancer,
+) -> (svc::BoxCloneHttp, support::resolver::DstSender<Metadata>) {
+    let addr = "mysvc.myns.svc.cluster.local:80"
+        .parse::<NameAddr>()
+        .unwrap();
+    let resolve = support::resolver::<Metadata>();
+    let mut resolve_tx = resolve.endpoint_tx(addr.clone());
+
+    let stk = move |ep: Endpoint<_>| {
+        let addr = *ep.addr;
+        svc::mk(move |_: http::Request<http::BoxBody>| {
+            let mut rsp = http::Response::new(http::BoxBody::default());
+            rsp.headers_mut()
+                .insert("x-endpoint", addr.to_string().parse().unwrap());
+            futures::future::ok::<_, Error>(rsp)
+        })
+    };
+
+    let svc = svc::stack(stk)
+        .push(Balance::layer(
+            &outbound.config,
+            &outbound.runtime,
+            &mut Default::default(),
+            resolve,
+        ))
+        .into_inner()
+        .new_service(balance::Balance {
+            addr,
+            parent: Target,
+            queue: QueueConfig {
+                capacity: 100,
+                failfast_timeout: time::Duration::from_secs(3),
+            },
+            balancer,
+        });
+
+    resolve_tx
+        .add((1..=ECHO_ENDPOINTS).map(|i| {
+            let addr = SocketAddr::new([192, 0, 2, i].into(), 8080);
+            (addr, Metadata::default())
+        }))
+        .unwrap();
+
+    (svc, resolve_tx)
+}
+
 #[derive(Clone, Debug)]
 struct Target;
 
@@ -144,8 +300,20 @@ impl svc::Param<BackendRef> for Target {
     }
 }
 
+impl svc::Param<http::Version> for Target {
+    fn param(&self) -> http::Version {
+        http::Version::H2
+    }
+}
+
 impl svc::Param<FailureAccrual> for Target {
     fn param(&self) -> FailureAccrual {
         FailureAccrual::default()
     }
 }
+
+impl svc::Param<Option<policy::BackendTls>> for Target {
+    fn param(&self) -> Option<policy::BackendTls> {
+        None
+    }
+}
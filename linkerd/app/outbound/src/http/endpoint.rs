@@ -14,6 +14,7 @@ use linkerd_app_core::{
     transport_header::SessionProtocol,
     Error, Result, CANONICAL_DST_HEADER,
 };
+use linkerd_http_access_log::{AccessLogField, NewRecordAccessLog};
 
 #[cfg(test)]
 mod tests;
@@ -116,6 +117,10 @@ impl<T> Outbound<svc::ArcNewHttp<T, http::BoxBody>> {
                 .push(ClientRescue::layer(config.emit_headers))
                 .push_on_service(http::BoxRequest::layer())
                 .push(tap::NewTapHttp::layer(rt.tap.clone()))
+                .push(NewRecordAccessLog::layer_via(|t: &T| {
+                    let Remote(ServerAddr(addr)) = svc::Param::param(t);
+                    AccessLogField::Endpoint(addr)
+                }))
                 .push(
                     rt.metrics
                         .proxy
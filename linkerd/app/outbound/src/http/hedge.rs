@@ -0,0 +1,397 @@
+//! Hedges requests on idempotent routes.
+//!
+//! When a request has not received a response after a configured delay, a
+//! second attempt is dispatched through the inner stack and whichever attempt
+//! responds successfully first is used.
+//!
+//! Both attempts share a [`balance::Attempts`] request extension, so the
+//! balancer dispatches the hedged attempt to a different endpoint than the
+//! initial attempt whenever another endpoint is ready.
+//!
+//! Unlike retries, hedged attempts are in flight concurrently, so request
+//! bodies cannot be shared with a `ReplayBody`, which only permits a single
+//! clone to be polled at a time. Instead, the first attempt's body is recorded
+//! as it is sent, and a request is only hedged if its body has been sent in
+//! full (and fits in the buffer) when the hedging delay elapses.
+
+use super::retry::clone_request;
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{future, FutureExt};
+use linkerd_app_core::{
+    metrics::Counter,
+    proxy::http::{self, balance, BoxBody, HttpBody},
+    svc::{self, ServiceExt},
+    Error,
+};
+use linkerd_proxy_client_policy::grpc::r#match::MatchRpc;
+use linkerd_retry::Budget;
+use parking_lot::Mutex;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time,
+};
+
+/// Requests with bodies larger than 64 KB are not hedged.
+const MAX_BUFFERED_BYTES: usize = 64 * 1024;
+
+/// Configures hedging for a route.
+#[derive(Clone, Debug)]
+pub struct Params {
+    /// The time to wait for a response before issuing a hedged request.
+    pub delay: time::Duration,
+
+    /// Limits the rate of hedged requests.
+    pub budget: Arc<Budget>,
+
+    /// Identifies the requests that may be hedged.
+    pub idempotent: Idempotent,
+
+    /// Counts hedged requests.
+    pub hedges: Arc<Counter>,
+}
+
+/// Identifies idempotent requests, which may be hedged.
+#[derive(Clone, Debug)]
+pub enum Idempotent {
+    /// Requests with idempotent HTTP methods.
+    Methods,
+
+    /// gRPC requests for any of the given RPCs.
+    Rpcs(Arc<[MatchRpc]>),
+}
+
+/// Builds [`Hedge`] services for targets that configure hedging.
+#[derive(Clone, Debug)]
+pub struct NewHedge<X, N> {
+    inner: N,
+    extract: X,
+}
+
+#[derive(Clone, Debug)]
+pub struct Hedge<S> {
+    inner: S,
+    params: Option<Params>,
+}
+
+/// A request body that records the data it yields, so that it may be sent
+/// again with a hedged attempt.
+struct Recording {
+    inner: BoxBody,
+    recorded: Arc<Mutex<Recorded>>,
+}
+
+#[derive(Debug, Default)]
+struct Recorded {
+    data: BytesMut,
+    trailers: Option<::http::HeaderMap>,
+    /// Set once the body has been read in full.
+    complete: bool,
+    /// Set if the body exceeds [`MAX_BUFFERED_BYTES`], in which case it is no
+    /// longer recorded.
+    overflowed: bool,
+}
+
+/// A fully buffered request body that is sent with a hedged attempt.
+#[derive(Debug)]
+struct Buffered {
+    data: Option<Bytes>,
+    trailers: Option<::http::HeaderMap>,
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'static>>;
+
+// === impl NewHedge ===
+
+impl<X: Clone, N> NewHedge<X, N> {
+    pub fn layer_via(extract: X) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(move |inner| Self {
+            inner,
+            extract: extract.clone(),
+        })
+    }
+}
+
+impl<N> NewHedge<(), N> {
+    pub fn layer() -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        Self::layer_via(())
+    }
+}
+
+impl<T, X, N> svc::NewService<T> for NewHedge<X, N>
+where
+    X: svc::ExtractParam<Option<Params>, T>,
+    N: svc::NewService<T>,
+{
+    type Service = Hedge<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let params = self.extract.extract_param(&target);
+        let inner = self.inner.new_service(target);
+        Hedge { inner, params }
+    }
+}
+
+// === impl Hedge ===
+
+impl<S> svc::Service<http::Request<BoxBody>> for Hedge<S>
+where
+    S: svc::Service<http::Request<BoxBody>, Error = Error>,
+    S: Clone + Send + 'static,
+    S::Response: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = future::Either<S::Future, BoxFuture<S::Response>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let params = match self.params.as_ref() {
+            Some(params) if params.permits(&req) => params.clone(),
+            _ => return future::Either::Left(self.inner.call(req)),
+        };
+
+        // The initial attempt uses the service that was driven to readiness,
+        // so a clone is left in its place.
+        let inner = self.inner.clone();
+        let inner = std::mem::replace(&mut self.inner, inner);
+        future::Either::Right(Box::pin(hedge(inner, params, req)))
+    }
+}
+
+async fn hedge<S>(
+    mut inner: S,
+    params: Params,
+    req: http::Request<BoxBody>,
+) -> Result<S::Response, Error>
+where
+    S: svc::Service<http::Request<BoxBody>, Error = Error>,
+    S: Clone + Send + 'static,
+    S::Response: Send + 'static,
+    S::Future: Send + 'static,
+{
+    let (mut parts, body) = req.into_parts();
+    if body.size_hint().lower() > MAX_BUFFERED_BYTES as u64 {
+        tracing::debug!("Request body is too large to hedge");
+        return inner.call(http::Request::from_parts(parts, body)).await;
+    }
+    params.budget.deposit();
+
+    if parts.extensions.get::<balance::Attempts>().is_none() {
+        parts.extensions.insert(balance::Attempts::default());
+    }
+
+    let (body, recorded) = Recording::new(body);
+    let req = http::Request::from_parts(parts, BoxBody::new(body));
+    let hedge_req = clone_request(&req, ());
+    let mut hedge_svc = inner.clone();
+    let primary = Box::pin(inner.call(req));
+
+    let delay = tokio::time::sleep(params.delay);
+    let primary = match future::select(primary, Box::pin(delay)).await {
+        future::Either::Left((res, _)) => return res,
+        future::Either::Right(((), primary)) => primary,
+    };
+
+    let Some(body) = Recording::take(&recorded) else {
+        tracing::debug!("Request body has not been sent in full; not hedging");
+        return primary.await;
+    };
+    if params.budget.withdraw().is_err() {
+        tracing::debug!("Hedge budget exhausted");
+        return primary.await;
+    }
+    tracing::debug!(delay = ?params.delay, "Hedging request");
+    params.hedges.incr();
+    let hedge_req = hedge_req.map(|()| BoxBody::new(body));
+    let hedge = async move { hedge_svc.ready().await?.call(hedge_req).await }.boxed();
+
+    // Use the first successful response. If one attempt fails, wait for the
+    // other.
+    match future::select(primary, hedge).await {
+        future::Either::Left((Ok(rsp), _)) | future::Either::Right((Ok(rsp), _)) => Ok(rsp),
+        future::Either::Left((Err(error), hedge)) => {
+            tracing::debug!(%error, "Initial request failed; awaiting hedged request");
+            hedge.await
+        }
+        future::Either::Right((Err(error), primary)) => {
+            tracing::debug!(%error, "Hedged request failed; awaiting initial request");
+            primary.await
+        }
+    }
+}
+
+// === impl Params ===
+
+impl Params {
+    fn permits<B>(&self, req: &http::Request<B>) -> bool {
+        match &self.idempotent {
+            Idempotent::Methods => req.method().is_idempotent(),
+            Idempotent::Rpcs(rpcs) => rpcs.iter().any(|rpc| rpc.is_match(req.uri().path())),
+        }
+    }
+}
+
+// === impl Recording ===
+
+impl Recording {
+    fn new(inner: BoxBody) -> (Self, Arc<Mutex<Recorded>>) {
+        let recorded = Arc::new(Mutex::new(Recorded {
+            complete: inner.is_end_stream(),
+            ..Default::default()
+        }));
+        let body = Self {
+            inner,
+            recorded: recorded.clone(),
+        };
+        (body, recorded)
+    }
+
+    /// Returns a copy of the recorded body, if it was read in full.
+    fn take(recorded: &Mutex<Recorded>) -> Option<Buffered> {
+        let recorded = recorded.lock();
+        if !recorded.complete || recorded.overflowed {
+            return None;
+        }
+        Some(Buffered {
+            data: Some(recorded.data.clone().freeze()).filter(|b| !b.is_empty()),
+            trailers: recorded.trailers.clone(),
+        })
+    }
+}
+
+impl HttpBody for Recording {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Error>>> {
+        let this = &mut *self;
+        let data = match futures::ready!(Pin::new(&mut this.inner).poll_data(cx)) {
+            Some(Ok(mut data)) => data.copy_to_bytes(data.remaining()),
+            Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+            None => {
+                if this.inner.is_end_stream() {
+                    this.recorded.lock().complete = true;
+                }
+                return Poll::Ready(None);
+            }
+        };
+
+        let mut recorded = this.recorded.lock();
+        if !recorded.overflowed {
+            if recorded.data.len() + data.len() > MAX_BUFFERED_BYTES {
+                recorded.overflowed = true;
+                recorded.data = BytesMut::new();
+            } else {
+                recorded.data.extend_from_slice(&data);
+            }
+        }
+        if this.inner.is_end_stream() {
+            recorded.complete = true;
+        }
+        Poll::Ready(Some(Ok(data)))
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<::http::HeaderMap>, Error>> {
+        let this = &mut *self;
+        let trailers = futures::ready!(Pin::new(&mut this.inner).poll_trailers(cx))?;
+        let mut recorded = this.recorded.lock();
+        recorded.trailers = trailers.clone();
+        recorded.complete = true;
+        Poll::Ready(Ok(trailers))
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// === impl Buffered ===
+
+impl HttpBody for Buffered {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn is_end_stream(&self) -> bool {
+        self.data.is_none() && self.trailers.is_none()
+    }
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Error>>> {
+        Poll::Ready(self.data.take().map(Ok))
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Result<Option<::http::HeaderMap>, Error>> {
+        Poll::Ready(Ok(self.trailers.take()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(idempotent: Idempotent) -> Params {
+        Params {
+            delay: time::Duration::from_millis(100),
+            budget: Arc::new(Budget::new(time::Duration::from_secs(10), 10, 0.2)),
+            idempotent,
+            hedges: Default::default(),
+        }
+    }
+
+    fn req(method: http::Method, path: &str) -> http::Request<()> {
+        http::Request::builder()
+            .method(method)
+            .uri(path)
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn permits_idempotent_methods() {
+        let params = params(Idempotent::Methods);
+        assert!(params.permits(&req(http::Method::GET, "/")));
+        assert!(params.permits(&req(http::Method::PUT, "/")));
+        assert!(!params.permits(&req(http::Method::POST, "/")));
+    }
+
+    #[test]
+    fn permits_idempotent_rpcs() {
+        let params = params(Idempotent::Rpcs(Arc::new([
+            MatchRpc {
+                service: Some("foo.Foo".to_string()),
+                method: Some("Get".to_string()),
+            },
+            MatchRpc {
+                service: Some("foo.Bar".to_string()),
+                method: None,
+            },
+        ])));
+        assert!(params.permits(&req(http::Method::POST, "/foo.Foo/Get")));
+        assert!(params.permits(&req(http::Method::POST, "/foo.Bar/Put")));
+        assert!(!params.permits(&req(http::Method::POST, "/foo.Foo/Put")));
+        assert!(!params.permits(&req(http::Method::POST, "/foo.Baz/Get")));
+    }
+}
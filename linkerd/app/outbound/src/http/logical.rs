@@ -43,6 +43,7 @@ pub struct Concrete<T> {
     parent_ref: ParentRef,
     backend_ref: BackendRef,
     failure_accrual: policy::FailureAccrual,
+    tls: Option<policy::BackendTls>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -132,7 +133,7 @@ where
     {
         svc::layer::mk(move |concrete: N| {
             let policy = svc::stack(concrete.clone())
-                .push(policy::Policy::layer(metrics.http_route_backends.clone()));
+                .push(policy::Policy::layer(metrics.http_routes.clone()));
             let profile =
                 svc::stack(concrete.clone()).push(profile::Params::layer(metrics.proxy.clone()));
             svc::stack(concrete)
@@ -153,6 +154,7 @@ where
                                     authority: None,
                                     parent,
                                     failure_accrual: Default::default(),
+                                    tls: None,
                                 })
                             }
                             Self::Profile(profile) => svc::Either::B(svc::Either::A(profile)),
@@ -253,6 +255,12 @@ impl<T> svc::Param<policy::FailureAccrual> for Concrete<T> {
     }
 }
 
+impl<T> svc::Param<Option<policy::BackendTls>> for Concrete<T> {
+    fn param(&self) -> Option<policy::BackendTls> {
+        self.tls.clone()
+    }
+}
+
 // === impl CanonicalDstHeader ===
 
 impl From<CanonicalDstHeader> for http::HeaderPair {
@@ -8,10 +8,10 @@ mod router;
 mod tests;
 
 pub use self::{
-    route::{backend::RouteBackendMetrics, errors},
+    route::{errors, RouteMetrics},
     router::{GrpcParams, HttpParams},
 };
-pub use linkerd_proxy_client_policy::{ClientPolicy, FailureAccrual};
+pub use linkerd_proxy_client_policy::{BackendTls, ClientPolicy, FailureAccrual};
 
 /// HTTP or gRPC policy route parameters.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -50,7 +50,7 @@ where
     /// routing configurations to route requests over cached inner backend
     /// services.
     pub(super) fn layer<N, S>(
-        route_backend_metrics: RouteBackendMetrics,
+        route_metrics: RouteMetrics,
     ) -> impl svc::Layer<N, Service = svc::ArcNewCloneHttp<Self>> + Clone
     where
         // Inner stack.
@@ -65,9 +65,8 @@ where
         S::Future: Send,
     {
         svc::layer::mk(move |inner: N| {
-            let http =
-                svc::stack(inner.clone()).push(router::Http::layer(route_backend_metrics.clone()));
-            let grpc = svc::stack(inner).push(router::Grpc::layer(route_backend_metrics.clone()));
+            let http = svc::stack(inner.clone()).push(router::Http::layer(route_metrics.clone()));
+            let grpc = svc::stack(inner).push(router::Grpc::layer(route_metrics.clone()));
 
             http.push_switch(
                 |pp: Policy<T>| {
@@ -1,13 +1,24 @@
 use super::super::Concrete;
-use crate::RouteRef;
-use linkerd_app_core::{classify, proxy::http, svc, Addr, Error, Result};
+use crate::{ParentRef, RouteRef};
+use linkerd_app_core::{
+    classify,
+    metrics::FmtMetrics,
+    proxy::http,
+    svc::{self, ExtractParam},
+    Addr, Error, Result,
+};
 use linkerd_distribute as distribute;
+use linkerd_http_access_log::{AccessLogField, NewRecordAccessLog};
 use linkerd_http_route as http_route;
 use linkerd_proxy_client_policy as policy;
 use std::{fmt::Debug, hash::Hash, sync::Arc};
 
 pub(crate) mod backend;
+pub(crate) mod delay;
 pub(crate) mod filters;
+pub(crate) mod hedge;
+pub(crate) mod mirror;
+pub(crate) mod retry;
 
 pub(crate) use self::backend::{Backend, MatchedBackend};
 pub use self::filters::errors;
@@ -26,11 +37,25 @@ pub(crate) struct Matched<M, P> {
 pub(crate) struct Route<T, F, E> {
     pub(super) parent: T,
     pub(super) addr: Addr,
+    pub(super) parent_ref: ParentRef,
     pub(super) route_ref: RouteRef,
     pub(super) filters: Arc<[F]>,
     pub(super) distribution: BackendDistribution<T, F>,
     pub(super) failure_policy: E,
     pub(super) request_timeout: Option<std::time::Duration>,
+    pub(super) retry: Option<policy::RouteRetry<E>>,
+    pub(super) hedge: Option<policy::RouteHedge>,
+    pub(super) mirror: Option<mirror::Params<T>>,
+}
+
+/// Metrics for policy routes and their backends.
+#[derive(Clone, Debug, Default)]
+pub struct RouteMetrics {
+    pub(super) backend: backend::RouteBackendMetrics,
+    pub(super) delay: delay::RouteDelayMetrics,
+    pub(super) retry: retry::RouteRetryMetrics,
+    pub(super) hedge: hedge::RouteHedgeMetrics,
+    pub(super) mirror: mirror::RouteMirrorMetrics,
 }
 
 pub(crate) type MatchedRoute<T, M, F, E> = Matched<M, Route<T, F, E>>;
@@ -78,12 +103,19 @@ where
     Self: svc::Param<classify::Request>,
     MatchedBackend<T, M, F>: filters::Apply,
     backend::ExtractMetrics: svc::ExtractParam<backend::RequestCount, MatchedBackend<T, M, F>>,
+    retry::NewRetryPolicy: linkerd_retry::NewPolicy<
+        Self,
+        Policy = crate::http::retry::RetryPolicy<retry::RetryCounts>,
+    >,
+    retry::ExtractTimeout: ExtractParam<http::ResponseTimeout, Self>,
+    hedge::ExtractHedge: ExtractParam<Option<crate::http::hedge::Params>, Self>,
+    delay::ExtractDelay: ExtractParam<Option<delay::Params>, Self>,
 {
     /// Builds a route stack that applies policy filters to requests and
     /// distributes requests over each route's backends. These [`Concrete`]
     /// backends are expected to be cached/shared by the inner stack.
     pub(crate) fn layer<N, S>(
-        backend_metrics: backend::RouteBackendMetrics,
+        metrics: RouteMetrics,
     ) -> impl svc::Layer<N, Service = svc::ArcNewCloneHttp<Self>> + Clone
     where
         // Inner stack.
@@ -97,22 +129,44 @@ where
         S: Clone + Send + Sync + 'static,
         S::Future: Send,
     {
-        svc::layer::mk(move |inner| {
+        svc::layer::mk(move |inner: N| {
+            let backends = inner.clone();
             svc::stack(inner)
                 // Distribute requests across route backends, applying policies
                 // and filters for each of the route-backends.
-                .push(MatchedBackend::layer(backend_metrics.clone()))
+                .push(MatchedBackend::layer(metrics.backend.clone()))
                 .lift_new_with_target()
                 .push(NewDistribute::layer())
                 // The router does not take the backend's availability into
                 // consideration, so we must eagerly fail requests to prevent
                 // leaking tasks onto the runtime.
                 .push_on_service(svc::LoadShed::layer())
+                // Bounds each attempt by the route's per-try timeout, if one
+                // is configured.
+                .push(http::NewTimeout::layer_via(retry::ExtractTimeout))
+                // Sends a second attempt if the first has not been answered
+                // within the route's hedging delay.
+                .push(hedge::layer(metrics.hedge.clone()))
+                // Depending on whether or not the request can be retried, it
+                // may have one of two `Body` types. This layer unifies any
+                // `Body` type into `BoxBody`.
+                .push_on_service(http::BoxRequest::erased())
+                // Sets an optional retry policy.
+                .push(retry::layer(metrics.retry.clone()))
+                // Sends a copy of sampled requests to the route's mirror
+                // backend, if one is configured.
+                .push(mirror::NewMirror::layer(backends, metrics.mirror.clone()))
+                // Delays a sample of requests, if the route configures an
+                // injected delay. The delay is subject to the request timeout.
+                .push(delay::layer(metrics.delay.clone()))
                 // TODO(ver) attach the `E` typed failure policy to requests.
                 .push(filters::NewApplyFilters::<Self, _, _>::layer())
                 // Sets an optional request timeout.
                 .push(http::NewTimeout::layer())
                 .push(classify::NewClassify::layer())
+                .push(NewRecordAccessLog::layer_via(|rt: &Self| {
+                    AccessLogField::route(&*rt.params.route_ref.0)
+                }))
                 .push(svc::NewMapErr::layer_with(|rt: &Self| {
                     let route = rt.params.route_ref.clone();
                     move |source| RouteError {
@@ -126,6 +180,19 @@ where
     }
 }
 
+// === impl RouteMetrics ===
+
+impl FmtMetrics for RouteMetrics {
+    fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.backend.fmt_metrics(f)?;
+        self.delay.fmt_metrics(f)?;
+        self.retry.fmt_metrics(f)?;
+        self.hedge.fmt_metrics(f)?;
+        self.mirror.fmt_metrics(f)?;
+        Ok(())
+    }
+}
+
 impl<T: Clone, M, F, E> svc::Param<BackendDistribution<T, F>> for MatchedRoute<T, M, F, E> {
     fn param(&self) -> BackendDistribution<T, F> {
         self.params.distribution.clone()
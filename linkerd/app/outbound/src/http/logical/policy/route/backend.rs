@@ -1,6 +1,7 @@
 use super::{super::Concrete, filters};
 use crate::{BackendRef, RouteRef};
 use linkerd_app_core::{proxy::http, svc, Error, Result};
+use linkerd_http_access_log::{AccessLogField, NewRecordAccessLog};
 use linkerd_http_route as http_route;
 use linkerd_proxy_client_policy as policy;
 use std::{fmt::Debug, hash::Hash, sync::Arc};
@@ -111,6 +112,9 @@ where
                 .push(count_reqs::NewCountRequests::layer_via(ExtractMetrics {
                     metrics: metrics.clone(),
                 }))
+                .push(NewRecordAccessLog::layer_via(|t: &Self| {
+                    AccessLogField::backend(&*t.params.concrete.backend_ref.0)
+                }))
                 .push(svc::NewMapErr::layer_with(|t: &Self| {
                     let backend = t.params.concrete.backend_ref.clone();
                     move |source| {
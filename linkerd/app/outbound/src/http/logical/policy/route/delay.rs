@@ -0,0 +1,248 @@
+//! Delays a sample of a route's requests before they are dispatched.
+//!
+//! Injected delays are applied within the route's request timeout so that
+//! they may be used to exercise timeouts and retries.
+
+use super::{Grpc, Http};
+use crate::{
+    metrics::{write_meta_labels, write_service_meta_labels},
+    ParentRef, RouteRef,
+};
+use ahash::AHashMap;
+use futures::{future, prelude::*};
+use linkerd_app_core::{
+    metrics::{metrics, Counter, FmtLabels, FmtMetrics},
+    svc::{self, ServiceExt},
+};
+use linkerd_proxy_client_policy::{self as policy, http::filter::InjectDelay};
+use parking_lot::Mutex;
+use std::{
+    fmt::Write,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::time;
+
+metrics! {
+    outbound_http_route_injected_delays_total: Counter {
+        "The total number of HTTP requests delayed by a route's fault injection filter"
+    },
+    outbound_grpc_route_injected_delays_total: Counter {
+        "The total number of gRPC requests delayed by a route's fault injection filter"
+    }
+}
+
+/// Configures a route to delay requests.
+#[derive(Clone, Debug)]
+pub struct Params {
+    delays: Arc<[InjectDelay]>,
+    delayed: Arc<Counter>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RouteDelayMetrics {
+    http: Arc<Mutex<AHashMap<Labels, Arc<Counter>>>>,
+    grpc: Arc<Mutex<AHashMap<Labels, Arc<Counter>>>>,
+}
+
+/// Extracts delay parameters from a route's filters.
+#[derive(Clone, Debug)]
+pub struct ExtractDelay {
+    metrics: RouteDelayMetrics,
+}
+
+#[derive(Clone, Debug)]
+pub struct NewDelay<X, N> {
+    inner: N,
+    extract: X,
+}
+
+#[derive(Clone, Debug)]
+pub struct Delay<S> {
+    inner: S,
+    params: Option<Params>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Labels(ParentRef, RouteRef);
+
+pub(crate) fn layer<N>(
+    metrics: RouteDelayMetrics,
+) -> impl svc::Layer<N, Service = NewDelay<ExtractDelay, N>> + Clone {
+    NewDelay::layer_via(ExtractDelay { metrics })
+}
+
+// === impl ExtractDelay ===
+
+impl ExtractDelay {
+    fn mk_params(
+        delays: Vec<InjectDelay>,
+        delayed: impl FnOnce() -> Arc<Counter>,
+    ) -> Option<Params> {
+        if delays.is_empty() {
+            return None;
+        }
+        Some(Params {
+            delays: delays.into(),
+            delayed: delayed(),
+        })
+    }
+}
+
+impl<T> svc::ExtractParam<Option<Params>, Http<T>> for ExtractDelay {
+    fn extract_param(&self, route: &Http<T>) -> Option<Params> {
+        let delays = route
+            .params
+            .filters
+            .iter()
+            .filter_map(|f| match f {
+                policy::http::Filter::InjectDelay(delay) => Some(delay.clone()),
+                _ => None,
+            })
+            .collect();
+        Self::mk_params(delays, || {
+            self.metrics.http(
+                route.params.parent_ref.clone(),
+                route.params.route_ref.clone(),
+            )
+        })
+    }
+}
+
+impl<T> svc::ExtractParam<Option<Params>, Grpc<T>> for ExtractDelay {
+    fn extract_param(&self, route: &Grpc<T>) -> Option<Params> {
+        let delays = route
+            .params
+            .filters
+            .iter()
+            .filter_map(|f| match f {
+                policy::grpc::Filter::InjectDelay(delay) => Some(delay.clone()),
+                _ => None,
+            })
+            .collect();
+        Self::mk_params(delays, || {
+            self.metrics.grpc(
+                route.params.parent_ref.clone(),
+                route.params.route_ref.clone(),
+            )
+        })
+    }
+}
+
+// === impl NewDelay ===
+
+impl<X: Clone, N> NewDelay<X, N> {
+    pub fn layer_via(extract: X) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(move |inner| Self {
+            inner,
+            extract: extract.clone(),
+        })
+    }
+}
+
+impl<T, X, N> svc::NewService<T> for NewDelay<X, N>
+where
+    X: svc::ExtractParam<Option<Params>, T>,
+    N: svc::NewService<T>,
+{
+    type Service = Delay<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let params = self.extract.extract_param(&target);
+        let inner = self.inner.new_service(target);
+        Delay { inner, params }
+    }
+}
+
+// === impl Delay ===
+
+impl<Req, S> svc::Service<Req> for Delay<S>
+where
+    Req: Send + 'static,
+    S: svc::Service<Req> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = future::Either<
+        S::Future,
+        Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send + 'static>>,
+    >;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        // When more than one delay filter is configured, each is sampled
+        // independently and the selected delays accumulate.
+        let delay = self.params.as_ref().and_then(|params| {
+            let delay = params
+                .delays
+                .iter()
+                .filter_map(InjectDelay::apply)
+                .reduce(|a, b| a + b)?;
+            params.delayed.incr();
+            Some(delay)
+        });
+        let Some(delay) = delay else {
+            return future::Either::Left(self.inner.call(req));
+        };
+
+        // Take the service that has been driven to readiness so that it may
+        // be called once the delay elapses.
+        let clone = self.inner.clone();
+        let inner = std::mem::replace(&mut self.inner, clone);
+        tracing::debug!(?delay, "Delaying request");
+        future::Either::Right(Box::pin(async move {
+            time::sleep(delay).await;
+            inner.oneshot(req).await
+        }))
+    }
+}
+
+// === impl RouteDelayMetrics ===
+
+impl RouteDelayMetrics {
+    fn http(&self, pr: ParentRef, rr: RouteRef) -> Arc<Counter> {
+        self.http.lock().entry(Labels(pr, rr)).or_default().clone()
+    }
+
+    fn grpc(&self, pr: ParentRef, rr: RouteRef) -> Arc<Counter> {
+        self.grpc.lock().entry(Labels(pr, rr)).or_default().clone()
+    }
+}
+
+impl FmtMetrics for RouteDelayMetrics {
+    fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let http = self.http.lock();
+        if !http.is_empty() {
+            outbound_http_route_injected_delays_total.fmt_help(f)?;
+            outbound_http_route_injected_delays_total.fmt_scopes(f, http.iter(), |c| &**c)?;
+        }
+        drop(http);
+
+        let grpc = self.grpc.lock();
+        if !grpc.is_empty() {
+            outbound_grpc_route_injected_delays_total.fmt_help(f)?;
+            outbound_grpc_route_injected_delays_total.fmt_scopes(f, grpc.iter(), |c| &**c)?;
+        }
+        drop(grpc);
+
+        Ok(())
+    }
+}
+
+// === impl Labels ===
+
+impl FmtLabels for Labels {
+    fn fmt_labels(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Labels(parent, route) = self;
+        write_service_meta_labels("parent", parent, f)?;
+        f.write_char(',')?;
+        write_meta_labels("route", route, f)?;
+        Ok(())
+    }
+}
@@ -48,6 +48,10 @@ pub mod errors {
     #[error("invalid redirect: {0}")]
     pub struct HttpRouteInvalidRedirect(#[from] pub http::filter::InvalidRedirect);
 
+    #[derive(Debug, thiserror::Error)]
+    #[error("invalid URL rewrite: {0}")]
+    pub struct HttpRouteInvalidUrlRewrite(#[from] pub http::filter::InvalidUrlRewrite);
+
     #[derive(Debug, thiserror::Error)]
     #[error("request redirected to {location}")]
     pub struct HttpRouteRedirect {
@@ -107,6 +111,12 @@ pub fn apply_http_request<B>(
                 }
             },
 
+            http::Filter::UrlRewrite(rewrite) => {
+                if let Err(invalid) = rewrite.apply(r#match, req) {
+                    return Err(errors::HttpRouteInvalidUrlRewrite(invalid).into());
+                }
+            }
+
             http::Filter::RequestHeaders(rh) => {
                 rh.apply(req.headers_mut());
             }
@@ -115,6 +125,8 @@ pub fn apply_http_request<B>(
                 return Err(errors::HttpInvalidPolicy(msg).into());
             }
             http::Filter::ResponseHeaders(_) => {} // ResponseHeaders filter does not apply to requests.
+            http::Filter::RequestMirror(_) => {} // RequestMirror filter is applied by the route's mirror layer.
+            http::Filter::InjectDelay(_) => {} // InjectDelay filter is applied by the route's delay layer.
         }
     }
 
@@ -130,9 +142,12 @@ pub fn apply_http_response<B>(
         match filter {
             http::Filter::InjectFailure(_) => {} // InjectFailure filter does not apply to responses.
             http::Filter::Redirect(_) => {}      // Redirect filter does not apply to responses.
+            http::Filter::UrlRewrite(_) => {}    // UrlRewrite filter does not apply to responses.
             http::Filter::RequestHeaders(_) => {} // RequestHeaders filter does not apply to responses.
             http::Filter::InternalError(_) => {} // InternalError filter does not apply to responses.
             http::Filter::ResponseHeaders(rh) => rh.apply(rsp.headers_mut()),
+            http::Filter::RequestMirror(_) => {} // RequestMirror filter does not apply to responses.
+            http::Filter::InjectDelay(_) => {}   // InjectDelay filter does not apply to responses.
         }
     }
 
@@ -159,6 +174,8 @@ pub fn apply_grpc_request<B>(
             grpc::Filter::InternalError(msg) => {
                 return Err(errors::HttpInvalidPolicy(msg).into());
             }
+
+            grpc::Filter::InjectDelay(_) => {} // InjectDelay filter is applied by the route's delay layer.
         }
     }
 
@@ -174,6 +191,7 @@ pub fn apply_grpc_response<B>(
             grpc::Filter::InjectFailure(_) => {} // InjectFailure filter does not apply to responses.
             grpc::Filter::RequestHeaders(_) => {} // RequestHeaders filter does not apply to responses.
             grpc::Filter::InternalError(_) => {} // InternalError filter does not apply to responses.
+            grpc::Filter::InjectDelay(_) => {}   // InjectDelay filter does not apply to responses.
         }
     }
 
@@ -0,0 +1,133 @@
+use super::{Grpc, Http};
+use crate::{
+    http::hedge,
+    metrics::{write_meta_labels, write_service_meta_labels},
+    ParentRef, RouteRef,
+};
+use ahash::AHashMap;
+use linkerd_app_core::{
+    metrics::{metrics, Counter, FmtLabels, FmtMetrics},
+    svc,
+};
+use linkerd_proxy_client_policy as policy;
+use parking_lot::Mutex;
+use std::{fmt::Write, sync::Arc};
+
+metrics! {
+    outbound_http_route_hedge_requests_total: Counter {
+        "The total number of hedged HTTP requests sent on a route"
+    },
+    outbound_grpc_route_hedge_requests_total: Counter {
+        "The total number of hedged gRPC requests sent on a route"
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RouteHedgeMetrics {
+    http: Arc<Mutex<AHashMap<Labels, Arc<Counter>>>>,
+    grpc: Arc<Mutex<AHashMap<Labels, Arc<Counter>>>>,
+}
+
+/// Extracts hedging parameters from a route's hedge configuration.
+#[derive(Clone, Debug)]
+pub struct ExtractHedge {
+    metrics: RouteHedgeMetrics,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Labels(ParentRef, RouteRef);
+
+pub(crate) fn layer<N>(
+    metrics: RouteHedgeMetrics,
+) -> impl svc::Layer<N, Service = hedge::NewHedge<ExtractHedge, N>> + Clone {
+    hedge::NewHedge::layer_via(ExtractHedge { metrics })
+}
+
+// === impl ExtractHedge ===
+
+impl ExtractHedge {
+    fn mk_params(
+        hedge: &policy::RouteHedge,
+        hedges: Arc<Counter>,
+        idempotent: hedge::Idempotent,
+    ) -> hedge::Params {
+        hedge::Params {
+            delay: hedge.delay,
+            budget: hedge.budget.budget().clone(),
+            idempotent,
+            hedges,
+        }
+    }
+}
+
+impl<T> svc::ExtractParam<Option<hedge::Params>, Http<T>> for ExtractHedge {
+    fn extract_param(&self, route: &Http<T>) -> Option<hedge::Params> {
+        let hedge = route.params.hedge.as_ref()?;
+        let hedges = self.metrics.http(
+            route.params.parent_ref.clone(),
+            route.params.route_ref.clone(),
+        );
+        Some(Self::mk_params(hedge, hedges, hedge::Idempotent::Methods))
+    }
+}
+
+impl<T> svc::ExtractParam<Option<hedge::Params>, Grpc<T>> for ExtractHedge {
+    fn extract_param(&self, route: &Grpc<T>) -> Option<hedge::Params> {
+        // gRPC requests are always POSTs, so only the RPCs that the route's
+        // configuration names as idempotent are hedged.
+        let hedge = route.params.hedge.as_ref()?;
+        if hedge.idempotent_rpcs.is_empty() {
+            return None;
+        }
+        let hedges = self.metrics.grpc(
+            route.params.parent_ref.clone(),
+            route.params.route_ref.clone(),
+        );
+        let idempotent = hedge::Idempotent::Rpcs(hedge.idempotent_rpcs.clone());
+        Some(Self::mk_params(hedge, hedges, idempotent))
+    }
+}
+
+// === impl RouteHedgeMetrics ===
+
+impl RouteHedgeMetrics {
+    fn http(&self, pr: ParentRef, rr: RouteRef) -> Arc<Counter> {
+        self.http.lock().entry(Labels(pr, rr)).or_default().clone()
+    }
+
+    fn grpc(&self, pr: ParentRef, rr: RouteRef) -> Arc<Counter> {
+        self.grpc.lock().entry(Labels(pr, rr)).or_default().clone()
+    }
+}
+
+impl FmtMetrics for RouteHedgeMetrics {
+    fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let http = self.http.lock();
+        if !http.is_empty() {
+            outbound_http_route_hedge_requests_total.fmt_help(f)?;
+            outbound_http_route_hedge_requests_total.fmt_scopes(f, http.iter(), |c| &**c)?;
+        }
+        drop(http);
+
+        let grpc = self.grpc.lock();
+        if !grpc.is_empty() {
+            outbound_grpc_route_hedge_requests_total.fmt_help(f)?;
+            outbound_grpc_route_hedge_requests_total.fmt_scopes(f, grpc.iter(), |c| &**c)?;
+        }
+        drop(grpc);
+
+        Ok(())
+    }
+}
+
+// === impl Labels ===
+
+impl FmtLabels for Labels {
+    fn fmt_labels(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Labels(parent, route) = self;
+        write_service_meta_labels("parent", parent, f)?;
+        f.write_char(',')?;
+        write_meta_labels("route", route, f)?;
+        Ok(())
+    }
+}
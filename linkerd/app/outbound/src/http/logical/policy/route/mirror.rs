@@ -0,0 +1,369 @@
+//! Mirrors a sample of a route's requests to an additional backend.
+//!
+//! Mirrored requests bypass the route's distribution, filters, and backend
+//! metrics so that shadow traffic never affects the route's primary
+//! responses or statistics. Mirrored responses are discarded.
+//!
+//! The mirrored request replays the primary request's buffered body, and a
+//! [`ReplayBody`] may only be read by one request at a time, so a mirrored
+//! request waits for the primary request to drop its body. This delays mirrors
+//! of streaming or long-lived requests until the primary request completes,
+//! including any retries of it, while holding an in-flight permit.
+
+use super::{super::super::Concrete, MatchedRoute};
+use crate::{
+    http::retry::clone_request,
+    metrics::{write_meta_labels, write_service_meta_labels},
+    BackendRef, ParentRef, RouteRef,
+};
+use ahash::AHashMap;
+use linkerd_app_core::{
+    metrics::{metrics, Counter, FmtLabels, FmtMetrics},
+    proxy::http::{self, BoxBody, HttpBody},
+    svc::{self, ServiceExt},
+    Error,
+};
+use linkerd_http_access_log::AccessLogSpan;
+use linkerd_http_retry::ReplayBody;
+use linkerd_proxy_client_policy as policy;
+use parking_lot::Mutex;
+use std::{
+    fmt::Write,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::sync::{oneshot, Semaphore};
+use tracing::Instrument;
+
+#[cfg(test)]
+mod tests;
+
+/// The maximum number of mirrored requests that may be in flight at once.
+///
+/// Sampled requests are not mirrored while this many mirrored requests are
+/// pending, so that a slow mirror backend cannot accumulate unbounded tasks.
+const MAX_IN_FLIGHT: usize = 100;
+
+metrics! {
+    outbound_http_route_mirror_requests_total: Counter {
+        "The total number of HTTP requests mirrored to a backend"
+    },
+    outbound_http_route_mirror_errors_total: Counter {
+        "The total number of mirrored HTTP requests that failed"
+    },
+    outbound_http_route_mirror_skipped_total: Counter {
+        "The total number of sampled HTTP requests that were not mirrored because their bodies were too large"
+    },
+    outbound_http_route_mirror_dropped_total: Counter {
+        "The total number of sampled HTTP requests that were not mirrored because too many mirrored requests were in flight"
+    }
+}
+
+/// Configures a route to mirror requests to a concrete backend.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Params<T> {
+    pub(crate) concrete: Concrete<T>,
+    pub(crate) distribution: policy::http::filter::Distribution,
+    pub(crate) max_body_bytes: usize,
+}
+
+/// Route filters that may configure request mirroring.
+pub(crate) trait MirrorFilter {
+    fn request_mirror(&self) -> Option<&policy::http::RequestMirror>;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RouteMirrorMetrics(Arc<Mutex<AHashMap<Labels, Arc<Counters>>>>);
+
+#[derive(Debug, Default)]
+struct Counters {
+    requests: Counter,
+    errors: Counter,
+    skipped: Counter,
+    dropped: Counter,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Labels(ParentRef, RouteRef, BackendRef);
+
+/// Builds [`Mirror`] services for routes, using the `backends` stack to
+/// build services for mirrored backends.
+#[derive(Clone, Debug)]
+pub struct NewMirror<B, N> {
+    inner: N,
+    backends: B,
+    metrics: RouteMirrorMetrics,
+    in_flight: Arc<Semaphore>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Mirror<S, B> {
+    inner: S,
+    mirror: Option<Mirrored<B>>,
+}
+
+#[derive(Clone, Debug)]
+struct Mirrored<B> {
+    backend: B,
+    distribution: policy::http::filter::Distribution,
+    max_body_bytes: usize,
+    counts: Arc<Counters>,
+    in_flight: Arc<Semaphore>,
+}
+
+/// A request body that notifies the mirror task when it is dropped.
+///
+/// Fields are dropped in declaration order, so the body's buffered state is
+/// released before the mirror task is notified.
+struct NotifyOnDrop<B> {
+    inner: B,
+    _released: oneshot::Sender<()>,
+}
+
+// === impl MirrorFilter ===
+
+impl MirrorFilter for policy::http::Filter {
+    fn request_mirror(&self) -> Option<&policy::http::RequestMirror> {
+        match self {
+            Self::RequestMirror(mirror) => Some(mirror),
+            _ => None,
+        }
+    }
+}
+
+impl MirrorFilter for policy::grpc::Filter {
+    fn request_mirror(&self) -> Option<&policy::http::RequestMirror> {
+        None
+    }
+}
+
+// === impl NewMirror ===
+
+impl<B: Clone, N> NewMirror<B, N> {
+    pub fn layer(
+        backends: B,
+        metrics: RouteMirrorMetrics,
+    ) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        let in_flight = Arc::new(Semaphore::new(MAX_IN_FLIGHT));
+        svc::layer::mk(move |inner| Self {
+            inner,
+            backends: backends.clone(),
+            metrics: metrics.clone(),
+            in_flight: in_flight.clone(),
+        })
+    }
+}
+
+impl<T, M, F, E, B, N> svc::NewService<MatchedRoute<T, M, F, E>> for NewMirror<B, N>
+where
+    T: Clone,
+    B: svc::NewService<Concrete<T>>,
+    N: svc::NewService<MatchedRoute<T, M, F, E>>,
+{
+    type Service = Mirror<N::Service, B::Service>;
+
+    fn new_service(&self, route: MatchedRoute<T, M, F, E>) -> Self::Service {
+        let mirror = route.params.mirror.as_ref().map(|params| {
+            let counts = self.metrics.counts(
+                route.params.parent_ref.clone(),
+                route.params.route_ref.clone(),
+                params.concrete.backend_ref.clone(),
+            );
+            Mirrored {
+                backend: self.backends.new_service(params.concrete.clone()),
+                distribution: params.distribution.clone(),
+                max_body_bytes: params.max_body_bytes,
+                counts,
+                in_flight: self.in_flight.clone(),
+            }
+        });
+        let inner = self.inner.new_service(route);
+        Mirror { inner, mirror }
+    }
+}
+
+// === impl Mirror ===
+
+impl<S, B> svc::Service<http::Request<BoxBody>> for Mirror<S, B>
+where
+    S: svc::Service<http::Request<BoxBody>, Error = Error>,
+    B: svc::Service<http::Request<BoxBody>, Response = http::Response<BoxBody>, Error = Error>,
+    B: Clone + Send + 'static,
+    B::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = S::Future;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let mirror = match self.mirror.as_ref() {
+            Some(mirror) if mirror.sample() => mirror.clone(),
+            _ => return self.inner.call(req),
+        };
+
+        // The permit is held until the mirrored request completes.
+        let permit = match mirror.in_flight.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                tracing::debug!("Too many mirrored requests in flight");
+                mirror.counts.dropped.incr();
+                return self.inner.call(req);
+            }
+        };
+
+        let (head, body) = req.into_parts();
+        let body = match ReplayBody::try_new(body, mirror.max_body_bytes) {
+            Ok(body) => body,
+            Err(body) => {
+                tracing::debug!(
+                    size = body.size_hint().lower(),
+                    "Body is too large to mirror"
+                );
+                mirror.counts.skipped.incr();
+                return self.inner.call(http::Request::from_parts(head, body));
+            }
+        };
+
+        // A `ReplayBody` may only be read by one request at a time, so the
+        // mirrored request is not dispatched until the primary request's body
+        // has been dropped.
+        let (released, primary_dropped) = oneshot::channel::<()>();
+        let mirror_body = body.clone();
+        let req = http::Request::from_parts(
+            head,
+            BoxBody::new(NotifyOnDrop {
+                inner: body,
+                _released: released,
+            }),
+        );
+        let mut mirror_req = clone_request(&req, mirror_body);
+        // Mirrored requests must not be recorded in the primary request's
+        // access log.
+        mirror_req.extensions_mut().remove::<AccessLogSpan>();
+        tokio::spawn(
+            async move {
+                let _ = primary_dropped.await;
+                mirror.send(mirror_req).await;
+                drop(permit);
+            }
+            .in_current_span(),
+        );
+
+        self.inner.call(req)
+    }
+}
+
+// === impl Mirrored ===
+
+impl<B> Mirrored<B>
+where
+    B: svc::Service<http::Request<BoxBody>, Response = http::Response<BoxBody>, Error = Error>,
+{
+    fn sample(&self) -> bool {
+        use rand::distributions::Distribution;
+        self.distribution.sample(&mut rand::thread_rng())
+    }
+
+    async fn send(self, req: http::Request<ReplayBody<BoxBody>>) {
+        let Self {
+            backend, counts, ..
+        } = self;
+        let (head, body) = req.into_parts();
+        if body.is_capped() {
+            tracing::debug!("Body is too large to mirror");
+            counts.skipped.incr();
+            return;
+        }
+
+        counts.requests.incr();
+        let req = http::Request::from_parts(head, BoxBody::new(body));
+        match backend.oneshot(req).await {
+            Ok(rsp) => tracing::debug!(status = %rsp.status(), "Discarding mirrored response"),
+            Err(error) => {
+                tracing::debug!(%error, "Mirrored request failed");
+                counts.errors.incr();
+            }
+        }
+    }
+}
+
+// === impl NotifyOnDrop ===
+
+impl<B: HttpBody + Unpin> HttpBody for NotifyOnDrop<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    #[inline]
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_data(cx)
+    }
+
+    #[inline]
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<::http::HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.inner).poll_trailers(cx)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+// === impl RouteMirrorMetrics ===
+
+impl RouteMirrorMetrics {
+    fn counts(&self, pr: ParentRef, rr: RouteRef, br: BackendRef) -> Arc<Counters> {
+        self.0.lock().entry(Labels(pr, rr, br)).or_default().clone()
+    }
+}
+
+impl FmtMetrics for RouteMirrorMetrics {
+    fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts = self.0.lock();
+        if counts.is_empty() {
+            return Ok(());
+        }
+
+        outbound_http_route_mirror_requests_total.fmt_help(f)?;
+        outbound_http_route_mirror_requests_total.fmt_scopes(f, counts.iter(), |c| &c.requests)?;
+        outbound_http_route_mirror_errors_total.fmt_help(f)?;
+        outbound_http_route_mirror_errors_total.fmt_scopes(f, counts.iter(), |c| &c.errors)?;
+        outbound_http_route_mirror_skipped_total.fmt_help(f)?;
+        outbound_http_route_mirror_skipped_total.fmt_scopes(f, counts.iter(), |c| &c.skipped)?;
+        outbound_http_route_mirror_dropped_total.fmt_help(f)?;
+        outbound_http_route_mirror_dropped_total.fmt_scopes(f, counts.iter(), |c| &c.dropped)?;
+
+        Ok(())
+    }
+}
+
+// === impl Labels ===
+
+impl FmtLabels for Labels {
+    fn fmt_labels(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Labels(parent, route, backend) = self;
+        write_service_meta_labels("parent", parent, f)?;
+        f.write_char(',')?;
+        write_meta_labels("route", route, f)?;
+        f.write_char(',')?;
+        write_service_meta_labels("backend", backend, f)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+use futures::future;
+use linkerd_app_core::svc::Service;
+use tower_test::mock;
+
+type MockBackend = mock::Mock<http::Request<BoxBody>, http::Response<BoxBody>>;
+
+#[tokio::test(flavor = "current_thread")]
+async fn drops_mirrors_when_saturated() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let (backend, mut handle) = mock::pair();
+    let counts = Arc::new(Counters::default());
+    let mut svc = mk_mirror(backend, counts.clone(), 1);
+
+    // The first request is mirrored. The mirrored request holds the only
+    // permit until it completes.
+    handle.allow(1);
+    send_req(&mut svc, "/a").await;
+    let (req, send_rsp) = handle
+        .next_request()
+        .await
+        .expect("mirror must receive request");
+    assert_eq!(req.uri().path(), "/a");
+
+    // While the mirrored request is in flight, sampled requests are dropped.
+    send_req(&mut svc, "/b").await;
+    assert_eq!(counts.dropped.value(), 1.0);
+    assert_eq!(counts.requests.value(), 1.0);
+
+    // Once the mirrored request completes, requests are mirrored again.
+    send_rsp.send_response(http::Response::default());
+    let in_flight = svc.mirror.as_ref().unwrap().in_flight.clone();
+    while in_flight.available_permits() == 0 {
+        tokio::task::yield_now().await;
+    }
+    handle.allow(1);
+    send_req(&mut svc, "/c").await;
+    let (req, _send_rsp) = handle
+        .next_request()
+        .await
+        .expect("mirror must receive request");
+    assert_eq!(req.uri().path(), "/c");
+    assert_eq!(counts.requests.value(), 2.0);
+}
+
+fn mk_mirror(
+    backend: MockBackend,
+    counts: Arc<Counters>,
+    max_in_flight: usize,
+) -> Mirror<svc::BoxHttp, MockBackend> {
+    let inner = svc::BoxHttp::new(svc::mk(|_: http::Request<BoxBody>| {
+        future::ok::<_, Error>(http::Response::default())
+    }));
+    Mirror {
+        inner,
+        mirror: Some(Mirrored {
+            backend,
+            distribution: Default::default(),
+            max_body_bytes: 1024,
+            counts,
+            in_flight: Arc::new(Semaphore::new(max_in_flight)),
+        }),
+    }
+}
+
+async fn send_req(svc: &mut Mirror<svc::BoxHttp, MockBackend>, path: &str) {
+    let req = http::Request::get(path)
+        .body(BoxBody::default())
+        .expect("request must be valid");
+    svc.ready()
+        .await
+        .expect("service must be ready")
+        .call(req)
+        .await
+        .expect("request must succeed");
+}
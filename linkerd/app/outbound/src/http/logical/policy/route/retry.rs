@@ -0,0 +1,208 @@
+use super::{Grpc, Http, MatchedRoute};
+use crate::{
+    http::retry::{RetryMetrics, RetryPolicy},
+    metrics::{write_meta_labels, write_service_meta_labels},
+    ParentRef, RouteRef,
+};
+use ahash::AHashMap;
+use linkerd_app_core::{
+    classify,
+    metrics::{metrics, Counter, FmtLabels, FmtMetrics},
+    proxy::http::{self, EraseResponse},
+    svc,
+};
+use linkerd_proxy_client_policy as policy;
+use linkerd_retry as retry;
+use parking_lot::Mutex;
+use std::{fmt::Write, sync::Arc};
+
+metrics! {
+    outbound_http_route_retry_requests_total: Counter {
+        "The total number of HTTP requests retried on a route"
+    },
+    outbound_http_route_retry_overflow_total: Counter {
+        "The total number of retryable HTTP responses that were not retried because the route's retry budget was exhausted"
+    },
+    outbound_http_route_retry_limit_exceeded_total: Counter {
+        "The total number of retryable HTTP responses that were not retried because the request exceeded its maximum number of retries"
+    },
+    outbound_grpc_route_retry_requests_total: Counter {
+        "The total number of gRPC requests retried on a route"
+    },
+    outbound_grpc_route_retry_overflow_total: Counter {
+        "The total number of retryable gRPC responses that were not retried because the route's retry budget was exhausted"
+    },
+    outbound_grpc_route_retry_limit_exceeded_total: Counter {
+        "The total number of retryable gRPC responses that were not retried because the request exceeded its maximum number of retries"
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RouteRetryMetrics {
+    http: Arc<Mutex<AHashMap<Labels, Arc<Counters>>>>,
+    grpc: Arc<Mutex<AHashMap<Labels, Arc<Counters>>>>,
+}
+
+/// Records retry decisions for a single route.
+#[derive(Clone, Debug, Default)]
+pub struct RetryCounts(Arc<Counters>);
+
+#[derive(Debug, Default)]
+struct Counters {
+    retries: Counter,
+    overflow: Counter,
+    limit_exceeded: Counter,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Labels(ParentRef, RouteRef);
+
+/// Builds retry policies from a route's retry configuration.
+#[derive(Clone, Debug)]
+pub struct NewRetryPolicy {
+    metrics: RouteRetryMetrics,
+}
+
+/// Extracts the per-attempt timeout from a route's retry configuration.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExtractTimeout;
+
+pub(crate) fn layer<N>(
+    metrics: RouteRetryMetrics,
+) -> impl svc::Layer<N, Service = retry::NewRetry<NewRetryPolicy, N, EraseResponse<()>>> + Clone {
+    retry::layer(NewRetryPolicy { metrics })
+        // Because we wrap the response body type on retries, we must include a
+        // `Proxy` middleware for unifying the response body types of the retry
+        // and non-retry services.
+        .with_proxy(EraseResponse::new(()))
+}
+
+// === impl NewRetryPolicy ===
+
+impl NewRetryPolicy {
+    fn mk_policy<R>(
+        counts: RetryCounts,
+        retry: &policy::RouteRetry<R>,
+        classify: classify::Request,
+    ) -> RetryPolicy<RetryCounts> {
+        // The route's request timeout is enforced outside of retries, so any
+        // timeout observed by the retry policy is bounded to a single attempt.
+        RetryPolicy::new(
+            counts,
+            retry.budget.budget().clone(),
+            classify,
+            Some(retry.max_retries),
+        )
+        .with_retry_timeouts()
+    }
+}
+
+impl<T> retry::NewPolicy<Http<T>> for NewRetryPolicy {
+    type Policy = RetryPolicy<RetryCounts>;
+
+    fn new_policy(&self, route: &Http<T>) -> Option<Self::Policy> {
+        let retry = route.params.retry.as_ref()?;
+        let counts = self.metrics.http(
+            route.params.parent_ref.clone(),
+            route.params.route_ref.clone(),
+        );
+        let classify =
+            classify::Request::ClientPolicy(classify::ClientPolicy::Http(retry.retryable.clone()));
+        Some(Self::mk_policy(counts, retry, classify))
+    }
+}
+
+impl<T> retry::NewPolicy<Grpc<T>> for NewRetryPolicy {
+    type Policy = RetryPolicy<RetryCounts>;
+
+    fn new_policy(&self, route: &Grpc<T>) -> Option<Self::Policy> {
+        let retry = route.params.retry.as_ref()?;
+        let counts = self.metrics.grpc(
+            route.params.parent_ref.clone(),
+            route.params.route_ref.clone(),
+        );
+        let classify =
+            classify::Request::ClientPolicy(classify::ClientPolicy::Grpc(retry.retryable.clone()));
+        Some(Self::mk_policy(counts, retry, classify))
+    }
+}
+
+// === impl ExtractTimeout ===
+
+impl<T, M, F, E> svc::ExtractParam<http::ResponseTimeout, MatchedRoute<T, M, F, E>>
+    for ExtractTimeout
+{
+    fn extract_param(&self, route: &MatchedRoute<T, M, F, E>) -> http::ResponseTimeout {
+        http::ResponseTimeout(route.params.retry.as_ref().and_then(|r| r.timeout))
+    }
+}
+
+// === impl RouteRetryMetrics ===
+
+impl RouteRetryMetrics {
+    fn http(&self, pr: ParentRef, rr: RouteRef) -> RetryCounts {
+        RetryCounts(self.http.lock().entry(Labels(pr, rr)).or_default().clone())
+    }
+
+    fn grpc(&self, pr: ParentRef, rr: RouteRef) -> RetryCounts {
+        RetryCounts(self.grpc.lock().entry(Labels(pr, rr)).or_default().clone())
+    }
+}
+
+impl FmtMetrics for RouteRetryMetrics {
+    fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let http = self.http.lock();
+        if !http.is_empty() {
+            outbound_http_route_retry_requests_total.fmt_help(f)?;
+            outbound_http_route_retry_requests_total.fmt_scopes(f, http.iter(), |c| &c.retries)?;
+            outbound_http_route_retry_overflow_total.fmt_help(f)?;
+            outbound_http_route_retry_overflow_total.fmt_scopes(f, http.iter(), |c| &c.overflow)?;
+            outbound_http_route_retry_limit_exceeded_total.fmt_help(f)?;
+            outbound_http_route_retry_limit_exceeded_total
+                .fmt_scopes(f, http.iter(), |c| &c.limit_exceeded)?;
+        }
+        drop(http);
+
+        let grpc = self.grpc.lock();
+        if !grpc.is_empty() {
+            outbound_grpc_route_retry_requests_total.fmt_help(f)?;
+            outbound_grpc_route_retry_requests_total.fmt_scopes(f, grpc.iter(), |c| &c.retries)?;
+            outbound_grpc_route_retry_overflow_total.fmt_help(f)?;
+            outbound_grpc_route_retry_overflow_total.fmt_scopes(f, grpc.iter(), |c| &c.overflow)?;
+            outbound_grpc_route_retry_limit_exceeded_total.fmt_help(f)?;
+            outbound_grpc_route_retry_limit_exceeded_total
+                .fmt_scopes(f, grpc.iter(), |c| &c.limit_exceeded)?;
+        }
+        drop(grpc);
+
+        Ok(())
+    }
+}
+
+// === impl RetryCounts ===
+
+impl RetryMetrics for RetryCounts {
+    fn incr_retryable(&self, has_budget: bool) {
+        if has_budget {
+            self.0.retries.incr();
+        } else {
+            self.0.overflow.incr();
+        }
+    }
+
+    fn incr_limit_exceeded(&self) {
+        self.0.limit_exceeded.incr();
+    }
+}
+
+// === impl Labels ===
+
+impl FmtLabels for Labels {
+    fn fmt_labels(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Labels(parent, route) = self;
+        write_service_meta_labels("parent", parent, f)?;
+        f.write_char(',')?;
+        write_meta_labels("route", route, f)?;
+        Ok(())
+    }
+}
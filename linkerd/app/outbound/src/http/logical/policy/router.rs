@@ -1,6 +1,6 @@
 use super::{
     super::{concrete, Concrete, LogicalAddr, NoRoute},
-    route, RouteBackendMetrics,
+    route, RouteMetrics,
 };
 use crate::{BackendRef, EndpointRef, ParentRef, RouteRef};
 use linkerd_app_core::{
@@ -66,11 +66,23 @@ where
     route::MatchedBackend<T, M::Summary, F>: route::filters::Apply,
     route::backend::ExtractMetrics:
         svc::ExtractParam<route::backend::RequestCount, route::MatchedBackend<T, M::Summary, F>>,
+    route::retry::NewRetryPolicy: linkerd_retry::NewPolicy<
+        route::MatchedRoute<T, M::Summary, F, E>,
+        Policy = crate::http::retry::RetryPolicy<route::retry::RetryCounts>,
+    >,
+    route::retry::ExtractTimeout:
+        svc::ExtractParam<http::ResponseTimeout, route::MatchedRoute<T, M::Summary, F, E>>,
+    route::hedge::ExtractHedge: svc::ExtractParam<
+        Option<crate::http::hedge::Params>,
+        route::MatchedRoute<T, M::Summary, F, E>,
+    >,
+    route::delay::ExtractDelay:
+        svc::ExtractParam<Option<route::delay::Params>, route::MatchedRoute<T, M::Summary, F, E>>,
 {
     /// Builds a stack that applies routes to distribute requests over a cached
     /// set of inner services so that.
     pub(super) fn layer<N, S>(
-        route_backend_metrics: RouteBackendMetrics,
+        route_metrics: RouteMetrics,
     ) -> impl svc::Layer<N, Service = svc::ArcNewCloneHttp<Self>> + Clone
     where
         // Inner stack.
@@ -92,7 +104,7 @@ where
                 .push(NewBackendCache::layer())
                 // Lazily cache a service for each `RouteParams` returned from the
                 // `SelectRoute` impl.
-                .push_on_service(route::MatchedRoute::layer(route_backend_metrics.clone()))
+                .push_on_service(route::MatchedRoute::layer(route_metrics.clone()))
                 .push(svc::NewOneshotRoute::<Self, (), _>::layer_cached())
                 .arc_new_clone_http()
                 .into_inner()
@@ -104,7 +116,7 @@ impl<T, M, F, E> From<(Params<M, F, E>, T)> for Router<T, M, F, E>
 where
     T: Eq + Hash + Clone + Debug,
     M: Clone,
-    F: Clone,
+    F: Clone + route::mirror::MirrorFilter,
     E: Clone,
 {
     fn from((rts, parent): (Params<M, F, E>, T)) -> Self {
@@ -118,7 +130,10 @@ where
 
         let mk_concrete = {
             let parent = parent.clone();
-            move |backend_ref: BackendRef, target: concrete::Dispatch| {
+            let parent_ref = parent_ref.clone();
+            move |backend_ref: BackendRef,
+                  target: concrete::Dispatch,
+                  tls: Option<policy::BackendTls>| {
                 // XXX With policies we don't have a top-level authority name at
                 // the moment. So, instead, we use the concrete addr used for
                 // discovery for now.
@@ -133,31 +148,35 @@ where
                     backend_ref,
                     parent_ref: parent_ref.clone(),
                     failure_accrual,
+                    tls,
                 }
             }
         };
 
         let mk_dispatch = move |bke: &policy::Backend| match bke.dispatcher {
             policy::BackendDispatcher::BalanceP2c(
-                policy::Load::PeakEwma(policy::PeakEwma { decay, default_rtt }),
+                ref load,
                 policy::EndpointDiscovery::DestinationGet { ref path },
             ) => mk_concrete(
                 BackendRef(bke.meta.clone()),
                 concrete::Dispatch::Balance(
                     path.parse::<NameAddr>()
                         .expect("destination must be a nameaddr"),
-                    http::balance::EwmaConfig { decay, default_rtt },
+                    mk_balancer(load),
                 ),
+                bke.tls.clone(),
             ),
             policy::BackendDispatcher::Forward(addr, ref md) => mk_concrete(
                 EndpointRef::new(md, addr.port().try_into().expect("port must not be 0")).into(),
                 concrete::Dispatch::Forward(Remote(ServerAddr(addr)), md.clone()),
+                bke.tls.clone(),
             ),
             policy::BackendDispatcher::Fail { ref message } => mk_concrete(
                 BackendRef(policy::Meta::new_default("fail")),
                 concrete::Dispatch::Fail {
                     message: message.clone(),
                 },
+                None,
             ),
         };
 
@@ -187,6 +206,14 @@ where
                 )
                 .expect("distribution must be valid")
             }
+            policy::RouteDistribution::RandomWeighted(backends) => {
+                route::BackendDistribution::random_weighted(
+                    backends
+                        .iter()
+                        .map(|(rb, weight)| (mk_route_backend(rr, rb), *weight)),
+                )
+                .expect("distribution must be valid")
+            }
         };
 
         let mk_policy = |policy::RoutePolicy::<F, E> {
@@ -195,21 +222,35 @@ where
                              distribution,
                              failure_policy,
                              request_timeout,
+                             retry,
+                             hedge,
                          }| {
             let route_ref = RouteRef(meta);
             let distribution = mk_distribution(&route_ref, &distribution);
+            let mirror = filters
+                .iter()
+                .find_map(route::mirror::MirrorFilter::request_mirror)
+                .map(|mirror| route::mirror::Params {
+                    concrete: mk_dispatch(&mirror.backend),
+                    distribution: mirror.distribution.clone(),
+                    max_body_bytes: mirror.max_body_bytes,
+                });
             route::Route {
                 addr: addr.clone(),
                 parent: parent.clone(),
+                parent_ref: parent_ref.clone(),
                 route_ref,
                 filters,
                 failure_policy,
                 distribution,
                 request_timeout,
+                retry,
+                hedge,
+                mirror,
             }
         };
 
-        let routes = routes
+        let routes: Arc<[_]> = routes
             .iter()
             .map(|route| http_route::Route {
                 hosts: route.hosts.clone(),
@@ -225,7 +266,15 @@ where
             })
             .collect();
 
-        let backends = backends.iter().map(mk_dispatch).collect();
+        // Mirrored backends are not part of any route's distribution, but they
+        // must be cached alongside the route backends.
+        let mirrors = routes
+            .iter()
+            .flat_map(|route| route.rules.iter())
+            .filter_map(|rule| rule.policy.mirror.as_ref())
+            .map(|mirror| mirror.concrete.clone())
+            .collect::<Vec<_>>();
+        let backends = backends.iter().map(mk_dispatch).chain(mirrors).collect();
 
         Self {
             routes,
@@ -291,3 +340,30 @@ where
         self.backends.clone()
     }
 }
+
+fn mk_balancer(load: &policy::Load) -> concrete::Balancer {
+    match *load {
+        policy::Load::PeakEwma(policy::PeakEwma {
+            decay,
+            default_rtt,
+            slow_start,
+        }) => concrete::Balancer::PeakEwma(http::balance::EwmaConfig {
+            decay,
+            default_rtt,
+            slow_start,
+        }),
+        policy::Load::RingHash(policy::RingHash { ref key }) => {
+            concrete::Balancer::RingHash(match key {
+                policy::HashKey::Header(name) | policy::HashKey::GrpcMetadata(name) => {
+                    http::balance::HashKey::Header(name.clone())
+                }
+                policy::HashKey::Cookie(name) => http::balance::HashKey::Cookie(name.clone()),
+                policy::HashKey::SourceIp => http::balance::HashKey::SourceIp,
+            })
+        }
+        policy::Load::LeastRequest(policy::LeastRequest { choice_count }) => {
+            concrete::Balancer::LeastRequest(http::balance::LeastRequestConfig { choice_count })
+        }
+        policy::Load::RoundRobin => concrete::Balancer::RoundRobin,
+    }
+}
@@ -31,11 +31,13 @@ async fn header_based_route() {
             policy::Load::PeakEwma(policy::PeakEwma {
                 decay: time::Duration::from_secs(10),
                 default_rtt: time::Duration::from_millis(300),
+                slow_start: None,
             }),
             policy::EndpointDiscovery::DestinationGet {
                 path: format!("{name}.ns.svc.cluster.local:8080"),
             },
         ),
+        tls: None,
     };
     let mk_policy = |name: &'static str, backend: policy::Backend| policy::RoutePolicy {
         meta: Arc::new(policy::Meta::Resource {
@@ -49,6 +51,8 @@ async fn header_based_route() {
         filters: Arc::new([]),
         failure_policy: Default::default(),
         request_timeout: None,
+        retry: None,
+        hedge: None,
         distribution: policy::RouteDistribution::FirstAvailable(Arc::new([policy::RouteBackend {
             filters: Arc::new([]),
             backend,
@@ -115,7 +119,7 @@ async fn header_based_route() {
         }
     });
 
-    let metrics = RouteBackendMetrics::default();
+    let metrics = RouteMetrics::default();
     let router = Policy::layer(metrics.clone())
         .layer(inner)
         .new_service(Policy::from((routes, ())));
@@ -177,6 +181,7 @@ async fn http_filter_request_headers() {
             failfast_timeout: time::Duration::from_secs(1),
         },
         dispatcher: policy::BackendDispatcher::Forward(addr, Default::default()),
+        tls: None,
     };
 
     // Stack that produces mock services.
@@ -200,6 +205,8 @@ async fn http_filter_request_headers() {
                         meta: policy::Meta::new_default("turtles"),
                         failure_policy: Default::default(),
                         request_timeout: None,
+                        retry: None,
+                        hedge: None,
                         filters: Arc::new([policy::http::Filter::RequestHeaders(
                             policy::http::filter::ModifyHeader {
                                 add: vec![(PIZZA.clone(), TUBULAR.clone())],
@@ -1,5 +1,5 @@
 use super::{
-    super::{concrete, retry},
+    super::{concrete, hedge, retry},
     CanonicalDstHeader, Concrete, NoRoute,
 };
 use crate::{policy, BackendRef, ParentRef, UNKNOWN_META};
@@ -47,6 +47,7 @@ type Distribution<T> = distribute::Distribution<Concrete<T>>;
 pub(crate) const DEFAULT_EWMA: balance::EwmaConfig = balance::EwmaConfig {
     default_rtt: time::Duration::from_millis(30),
     decay: time::Duration::from_secs(10),
+    slow_start: None,
 };
 
 pub(crate) fn should_override_policy(rx: &watch::Receiver<Profile>) -> Option<LogicalAddr> {
@@ -134,10 +135,14 @@ where
             let concrete = Concrete {
                 parent_ref: ParentRef(parent_meta.clone()),
                 backend_ref: BackendRef(parent_meta),
-                target: concrete::Dispatch::Balance(addr.clone(), DEFAULT_EWMA),
+                target: concrete::Dispatch::Balance(
+                    addr.clone(),
+                    concrete::Balancer::PeakEwma(DEFAULT_EWMA),
+                ),
                 authority: Some(addr.as_http_authority()),
                 parent: parent.clone(),
                 failure_accrual: Default::default(),
+                tls: None,
             };
             let backends = std::iter::once(concrete.clone()).collect();
             let distribution = Distribution::first_available(std::iter::once(concrete));
@@ -150,10 +155,14 @@ where
                     backend_ref: BackendRef(
                         service_meta(&t.addr).unwrap_or_else(|| UNKNOWN_META.clone()),
                     ),
-                    target: concrete::Dispatch::Balance(t.addr.clone(), DEFAULT_EWMA),
+                    target: concrete::Dispatch::Balance(
+                        t.addr.clone(),
+                        concrete::Balancer::PeakEwma(DEFAULT_EWMA),
+                    ),
                     authority: Some(t.addr.as_http_authority()),
                     parent: parent.clone(),
                     failure_accrual: Default::default(),
+                    tls: None,
                 })
                 .collect();
             let distribution = Distribution::random_available(targets.iter().cloned().map(
@@ -164,9 +173,13 @@ where
                             service_meta(&addr).unwrap_or_else(|| UNKNOWN_META.clone()),
                         ),
                         authority: Some(addr.as_http_authority()),
-                        target: concrete::Dispatch::Balance(addr, DEFAULT_EWMA),
+                        target: concrete::Dispatch::Balance(
+                            addr,
+                            concrete::Balancer::PeakEwma(DEFAULT_EWMA),
+                        ),
                         parent: parent.clone(),
                         failure_accrual: Default::default(),
+                        tls: None,
                     };
                     (concrete, weight)
                 },
@@ -277,6 +290,9 @@ impl<T> RouteParams<T> {
                         .http_profile_route_actual
                         .to_layer::<classify::Response, _, RouteParams<T>>(),
                 )
+                // Sends a second attempt if the first has not been answered
+                // within the route's hedging delay.
+                .push(hedge::NewHedge::layer())
                 // Depending on whether or not the request can be
                 // retried, it may have one of two `Body` types. This
                 // layer unifies any `Body` type into `BoxBody`.
@@ -343,6 +359,19 @@ impl<T> svc::Param<http::ResponseTimeout> for RouteParams<T> {
     }
 }
 
+impl<T> svc::Param<Option<hedge::Params>> for RouteParams<T> {
+    fn param(&self) -> Option<hedge::Params> {
+        let hedge = self.profile.hedge()?;
+        Some(hedge::Params {
+            delay: hedge.delay(),
+            budget: hedge.budget().clone(),
+            idempotent: hedge::Idempotent::Methods,
+            // Profile routes do not record hedging metrics.
+            hedges: Default::default(),
+        })
+    }
+}
+
 impl<T> svc::Param<classify::Request> for RouteParams<T> {
     fn param(&self) -> classify::Request {
         self.profile.response_classes().clone().into()
@@ -450,6 +450,383 @@ async fn backend_request_timeout() {
     ));
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn route_retries() {
+    tokio::time::pause();
+    let _trace = trace::test::trace_init();
+
+    let (svc, mut handle, _guard) = mk_route_svc(|backend| {
+        retry_route(
+            backend,
+            Some(client_policy::RouteRetry {
+                max_retries: 1,
+                retryable: Default::default(),
+                timeout: None,
+                budget: client_policy::RetryBudget::new(Duration::from_secs(10), 10, 0.2),
+            }),
+            None,
+        )
+    });
+
+    // A failed response is retried.
+    handle.allow(2);
+    let rsp = send_req(svc.clone(), http::Request::get("/"));
+    serve_req(
+        &mut handle,
+        mk_rsp(StatusCode::INTERNAL_SERVER_ERROR, "bad"),
+    )
+    .await;
+    serve_req(&mut handle, mk_rsp(StatusCode::OK, "good")).await;
+    assert_rsp(rsp, StatusCode::OK, "good").await;
+
+    // Requests are not retried more than `max_retries` times.
+    handle.allow(2);
+    let rsp = send_req(svc.clone(), http::Request::get("/"));
+    serve_req(
+        &mut handle,
+        mk_rsp(StatusCode::INTERNAL_SERVER_ERROR, "bad"),
+    )
+    .await;
+    serve_req(
+        &mut handle,
+        mk_rsp(StatusCode::SERVICE_UNAVAILABLE, "worse"),
+    )
+    .await;
+    assert_rsp(rsp, StatusCode::SERVICE_UNAVAILABLE, "worse").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn route_retry_timeout() {
+    tokio::time::pause();
+    let _trace = trace::test::trace_init();
+    const RETRY_TIMEOUT: Duration = std::time::Duration::from_secs(1);
+
+    let (svc, mut handle, _guard) = mk_route_svc(|backend| {
+        retry_route(
+            backend,
+            Some(client_policy::RouteRetry {
+                max_retries: 1,
+                retryable: Default::default(),
+                timeout: Some(RETRY_TIMEOUT),
+                budget: client_policy::RetryBudget::new(Duration::from_secs(10), 10, 0.2),
+            }),
+            None,
+        )
+    });
+
+    // The first attempt times out and is retried.
+    handle.allow(1);
+    let rsp = send_req(svc.clone(), http::Request::get("/"));
+    let (_, send_rsp) = handle
+        .next_request()
+        .await
+        .expect("service must receive request");
+    tokio::time::sleep(RETRY_TIMEOUT + Duration::from_millis(1)).await;
+    drop(send_rsp);
+    handle.allow(1);
+    serve_req(&mut handle, mk_rsp(StatusCode::OK, "good")).await;
+    assert_rsp(rsp, StatusCode::OK, "good").await;
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn route_hedge() {
+    tokio::time::pause();
+    let _trace = trace::test::trace_init();
+    const HEDGE_DELAY: Duration = std::time::Duration::from_millis(100);
+
+    let (svc, mut handle, _guard) = mk_route_svc(|backend| {
+        retry_route(
+            backend,
+            None,
+            Some(client_policy::RouteHedge {
+                delay: HEDGE_DELAY,
+                budget: client_policy::RetryBudget::new(Duration::from_secs(10), 10, 0.2),
+                idempotent_rpcs: Arc::new([]),
+            }),
+        )
+    });
+
+    // A slow idempotent request is hedged, and the first response is used.
+    handle.allow(2);
+    let rsp = send_req(svc.clone(), http::Request::get("/"));
+    let (_, _slow_rsp) = handle
+        .next_request()
+        .await
+        .expect("service must receive request");
+    tokio::time::sleep(HEDGE_DELAY + Duration::from_millis(1)).await;
+    serve_req(&mut handle, mk_rsp(StatusCode::OK, "hedged")).await;
+    assert_rsp(rsp, StatusCode::OK, "hedged").await;
+
+    // Request bodies are sent with hedged requests.
+    handle.allow(2);
+    let rsp = send_req_body(
+        svc.clone(),
+        http::Request::put("/"),
+        http::BoxBody::new("hello".to_string()),
+    );
+    let (req, _slow_rsp) = handle
+        .next_request()
+        .await
+        .expect("service must receive request");
+    let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+    assert_eq!(body, "hello");
+    tokio::time::sleep(HEDGE_DELAY + Duration::from_millis(1)).await;
+    let (req, send_rsp) = handle.next_request().await.expect("request must be hedged");
+    let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+    assert_eq!(body, "hello");
+    send_rsp.send_response(mk_rsp(StatusCode::OK, "hedged"));
+    assert_rsp(rsp, StatusCode::OK, "hedged").await;
+
+    // Requests are not hedged until their bodies have been sent in full.
+    handle.allow(2);
+    let (mut tx, body) = hyper::Body::channel();
+    let rsp = send_req_body(
+        svc.clone(),
+        http::Request::put("/"),
+        http::BoxBody::new(body),
+    );
+    tx.send_data("hello".into()).await.unwrap();
+    let (req, send_rsp) = handle
+        .next_request()
+        .await
+        .expect("service must receive request");
+    let mut req_body = req.into_body();
+    let mut data = http::HttpBody::data(&mut req_body).await.unwrap().unwrap();
+    assert_eq!(bytes::Buf::copy_to_bytes(&mut data, 5), "hello");
+    tokio::time::sleep(HEDGE_DELAY * 2).await;
+    send_rsp.send_response(mk_rsp(StatusCode::OK, "slow"));
+    assert_rsp(rsp, StatusCode::OK, "slow").await;
+    assert!(
+        tokio::time::timeout(HEDGE_DELAY, handle.next_request())
+            .await
+            .is_err(),
+        "request must not be hedged"
+    );
+    drop(tx);
+
+    // Non-idempotent requests are not hedged.
+    handle.allow(2);
+    let rsp = send_req(svc.clone(), http::Request::post("/"));
+    let (_, send_rsp) = handle
+        .next_request()
+        .await
+        .expect("service must receive request");
+    tokio::time::sleep(HEDGE_DELAY * 2).await;
+    send_rsp.send_response(mk_rsp(StatusCode::OK, "slow"));
+    assert_rsp(rsp, StatusCode::OK, "slow").await;
+    assert!(
+        tokio::time::timeout(HEDGE_DELAY, handle.next_request())
+            .await
+            .is_err(),
+        "request must not be hedged"
+    );
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn profile_route_hedge() {
+    tokio::time::pause();
+    let _trace = trace::test::trace_init();
+    const HEDGE_DELAY: Duration = std::time::Duration::from_millis(100);
+
+    let addr0 = SocketAddr::new([192, 0, 2, 41].into(), PORT);
+    let addr1 = SocketAddr::new([192, 0, 2, 42].into(), PORT);
+    let dest: NameAddr = format!("{AUTHORITY}:{PORT}")
+        .parse::<NameAddr>()
+        .expect("dest addr is valid");
+    let (svc0, mut handle0) = tower_test::mock::pair();
+    let (svc1, mut handle1) = tower_test::mock::pair();
+    let connect = HttpConnect::default()
+        .service(addr0, svc0)
+        .service(addr1, svc1);
+    let resolve = support::resolver();
+    let mut dst_tx = resolve.endpoint_tx(dest.clone());
+    dst_tx
+        .add([(addr0, Default::default()), (addr1, Default::default())])
+        .unwrap();
+    let (rt, _shutdown) = runtime();
+    let stack = Outbound::new(default_config(), rt)
+        .with_stack(svc::ArcNewService::new(connect))
+        .push_http_cached(&mut Default::default(), resolve)
+        .into_inner();
+
+    let mut route = profile::Route::default();
+    route.set_hedge(
+        HEDGE_DELAY,
+        Arc::new(linkerd_retry::Budget::new(Duration::from_secs(10), 10, 0.2)),
+    );
+    let (_route_tx, routes) = watch::channel(Routes::Profile(profile::Routes {
+        addr: profile::LogicalAddr(dest.clone()),
+        routes: Arc::new([(profile::RequestMatch::Default, route)]),
+        targets: Arc::new([profile::Target {
+            addr: dest,
+            weight: 1,
+        }]),
+    }));
+    let target = Target {
+        num: 1,
+        version: http::Version::H2,
+        routes,
+    };
+    let svc = stack.new_service(target);
+
+    // A slow idempotent request is hedged to the other endpoint, and the first
+    // response is used.
+    handle0.allow(2);
+    handle1.allow(2);
+    let rsp = send_req(svc.clone(), http::Request::get("/"));
+    tokio::time::sleep(HEDGE_DELAY + Duration::from_millis(1)).await;
+    let (_, _rsp0) = handle0
+        .next_request()
+        .await
+        .expect("an attempt must be sent to each endpoint");
+    let (_, rsp1) = handle1
+        .next_request()
+        .await
+        .expect("an attempt must be sent to each endpoint");
+    rsp1.send_response(mk_rsp(StatusCode::OK, "good"));
+    assert_rsp(rsp, StatusCode::OK, "good").await;
+
+    // Non-idempotent requests are not hedged.
+    let rsp = send_req(svc.clone(), http::Request::post("/"));
+    tokio::time::sleep(HEDGE_DELAY * 2).await;
+    let send_rsp = tokio::select! {
+        r = handle0.next_request() => r.expect("service must receive request").1,
+        r = handle1.next_request() => r.expect("service must receive request").1,
+    };
+    send_rsp.send_response(mk_rsp(StatusCode::OK, "slow"));
+    assert_rsp(rsp, StatusCode::OK, "slow").await;
+    let hedged = tokio::select! {
+        _ = handle0.next_request() => true,
+        _ = handle1.next_request() => true,
+        _ = tokio::time::sleep(HEDGE_DELAY) => false,
+    };
+    assert!(!hedged, "request must not be hedged");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn route_mirror() {
+    let _trace = trace::test::trace_init();
+
+    let addr = SocketAddr::new([192, 0, 2, 41].into(), PORT);
+    let dest: NameAddr = format!("{AUTHORITY}:{PORT}")
+        .parse::<NameAddr>()
+        .expect("dest addr is valid");
+    let mirror_addr = SocketAddr::new([192, 0, 2, 42].into(), PORT);
+    let mirror_dest: NameAddr = format!("mirror.test.svc.cluster.local:{PORT}")
+        .parse::<NameAddr>()
+        .expect("mirror addr is valid");
+    let (svc, mut handle) = tower_test::mock::pair();
+    let (mirror_svc, mut mirror_handle) = tower_test::mock::pair();
+    let connect = HttpConnect::default()
+        .service(addr, svc)
+        .service(mirror_addr, mirror_svc);
+    let resolve = support::resolver()
+        .endpoint_exists(dest.clone(), addr, Default::default())
+        .endpoint_exists(mirror_dest.clone(), mirror_addr, Default::default());
+    let (rt, _shutdown) = runtime();
+    let outbound = Outbound::new(default_config(), rt);
+    let metrics = outbound.metrics().http_routes;
+    let stack = outbound
+        .with_stack(svc::ArcNewService::new(connect))
+        .push_http_cached(&mut Default::default(), resolve)
+        .into_inner();
+
+    let (_route_tx, routes) = {
+        let backend = default_backend(&dest);
+        let mut route = default_route(backend.clone());
+        route.rules[0].policy.filters = Arc::new([client_policy::http::Filter::RequestMirror(
+            client_policy::http::RequestMirror {
+                backend: default_backend(&mirror_dest),
+                distribution: Default::default(),
+                max_body_bytes: 1024,
+            },
+        )]);
+        // The mirrored backend is intentionally omitted from the parent's
+        // backends.
+        watch::channel(Routes::Policy(policy::Params::Http(policy::HttpParams {
+            addr: dest.into(),
+            meta: ParentRef(client_policy::Meta::new_default("parent")),
+            backends: Arc::new([backend]),
+            routes: Arc::new([route]),
+            failure_accrual: client_policy::FailureAccrual::None,
+        })))
+    };
+    let target = Target {
+        num: 1,
+        version: http::Version::H2,
+        routes,
+    };
+    let svc = stack.new_service(target);
+
+    // The primary response is returned and a copy of the request is sent to
+    // the mirror, whose response is discarded.
+    handle.allow(1);
+    mirror_handle.allow(1);
+    let rsp = send_req(svc.clone(), http::Request::get("/mirrored"));
+    serve_req(&mut handle, mk_rsp(StatusCode::OK, "good")).await;
+    assert_rsp(rsp, StatusCode::OK, "good").await;
+
+    let (req, send_rsp) = mirror_handle
+        .next_request()
+        .await
+        .expect("mirror must receive request");
+    assert_eq!(req.uri().path(), "/mirrored");
+    send_rsp.send_response(mk_rsp(StatusCode::INTERNAL_SERVER_ERROR, "ignored"));
+
+    // The primary request is counted by the route's backend metrics, and the
+    // mirrored request is only counted by the mirror metrics.
+    let report = linkerd_app_core::metrics::FmtMetrics::as_display(&metrics).to_string();
+    let counts = |name: &str| {
+        report
+            .lines()
+            .filter(|l| l.starts_with(name))
+            .map(|l| l.rsplit(' ').next().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        counts("outbound_http_route_backend_requests_total{"),
+        vec!["1"],
+        "{report}"
+    );
+    assert_eq!(
+        counts("outbound_http_route_mirror_requests_total{"),
+        vec!["1"],
+        "{report}"
+    );
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn route_delay() {
+    let _trace = trace::test::trace_init();
+    const DELAY: Duration = Duration::from_secs(1);
+
+    let (svc, mut handle, _guard) = mk_route_svc(|backend| {
+        let mut route = default_route(backend);
+        route.rules[0].policy.filters = Arc::new([client_policy::http::Filter::InjectDelay(
+            client_policy::http::filter::InjectDelay {
+                delay: client_policy::http::filter::Delay::Fixed(DELAY),
+                distribution: Default::default(),
+            },
+        )]);
+        route
+    });
+
+    // The request is not dispatched to the backend until the delay elapses.
+    handle.allow(1);
+    let start = time::Instant::now();
+    let rsp = send_req(svc.clone(), http::Request::get("/"));
+    let (_, send_rsp) = handle
+        .next_request()
+        .await
+        .expect("service must receive request");
+    assert!(
+        time::Instant::now().saturating_duration_since(start) >= DELAY,
+        "request must be delayed"
+    );
+    send_rsp.send_response(mk_rsp(StatusCode::OK, "good"));
+    assert_rsp(rsp, StatusCode::OK, "good").await;
+}
+
 #[derive(Clone, Debug)]
 struct Target {
     num: usize,
@@ -515,6 +892,51 @@ impl<T: svc::Param<Remote<ServerAddr>>> svc::NewService<T> for HttpConnect {
     }
 }
 
+/// Builds a logical HTTP service for a destination with a single endpoint and
+/// the route returned by `mk_route`.
+///
+/// Returns the service, the endpoint's mock handle, and a guard that must be
+/// held for as long as the service is used.
+fn mk_route_svc(
+    mk_route: impl FnOnce(client_policy::Backend) -> client_policy::http::Route,
+) -> (
+    svc::BoxCloneHttp,
+    tower_test::mock::Handle<Request, Response>,
+    impl Sized,
+) {
+    let addr = SocketAddr::new([192, 0, 2, 41].into(), PORT);
+    let dest: NameAddr = format!("{AUTHORITY}:{PORT}")
+        .parse::<NameAddr>()
+        .expect("dest addr is valid");
+    let (svc, handle) = tower_test::mock::pair();
+    let connect = HttpConnect::default().service(addr, svc);
+    let resolve = support::resolver().endpoint_exists(dest.clone(), addr, Default::default());
+    let (rt, shutdown) = runtime();
+    let stack = Outbound::new(default_config(), rt)
+        .with_stack(svc::ArcNewService::new(connect))
+        .push_http_cached(&mut Default::default(), resolve)
+        .into_inner();
+
+    let backend = default_backend(&dest);
+    let route = mk_route(backend.clone());
+    let (route_tx, routes) =
+        watch::channel(Routes::Policy(policy::Params::Http(policy::HttpParams {
+            addr: dest.into(),
+            meta: ParentRef(client_policy::Meta::new_default("parent")),
+            backends: Arc::new([backend]),
+            routes: Arc::new([route]),
+            failure_accrual: client_policy::FailureAccrual::None,
+        })));
+    let target = Target {
+        num: 1,
+        version: http::Version::H2,
+        routes,
+    };
+    let svc = stack.new_service(target);
+
+    (svc, handle, (route_tx, shutdown))
+}
+
 #[track_caller]
 fn send_req(
     svc: impl svc::Service<Request, Response = Response, Error = Error, Future = impl Send + 'static>
@@ -522,7 +944,17 @@ fn send_req(
         + 'static,
     builder: ::http::request::Builder,
 ) -> impl Future<Output = Result<Response, Error>> + Send + 'static {
-    let mut req = builder.body(http::BoxBody::default()).unwrap();
+    send_req_body(svc, builder, http::BoxBody::default())
+}
+
+fn send_req_body(
+    svc: impl svc::Service<Request, Response = Response, Error = Error, Future = impl Send + 'static>
+        + Send
+        + 'static,
+    builder: ::http::request::Builder,
+    body: http::BoxBody,
+) -> impl Future<Output = Result<Response, Error>> + Send + 'static {
+    let mut req = builder.body(body).unwrap();
     let span = tracing::info_span!(
         "send_req",
         "{} {} {:?}",
@@ -593,11 +1025,13 @@ fn default_backend(path: impl ToString) -> client_policy::Backend {
             Load::PeakEwma(PeakEwma {
                 decay: Duration::from_secs(10),
                 default_rtt: Duration::from_millis(30),
+                slow_start: None,
             }),
             EndpointDiscovery::DestinationGet {
                 path: path.to_string(),
             },
         ),
+        tls: None,
     }
 }
 
@@ -617,6 +1051,8 @@ fn default_route(backend: client_policy::Backend) -> client_policy::http::Route
                 filters: NO_FILTERS.clone(),
                 failure_policy: Default::default(),
                 request_timeout: None,
+                retry: None,
+                hedge: None,
                 distribution: RouteDistribution::FirstAvailable(Arc::new([RouteBackend {
                     filters: NO_FILTERS.clone(),
                     backend,
@@ -647,6 +1083,8 @@ fn timeout_route(
                 filters: NO_FILTERS.clone(),
                 failure_policy: Default::default(),
                 request_timeout: route_timeout,
+                retry: None,
+                hedge: None,
                 distribution: RouteDistribution::FirstAvailable(Arc::new([RouteBackend {
                     filters: NO_FILTERS.clone(),
                     backend,
@@ -656,3 +1094,15 @@ fn timeout_route(
         }],
     }
 }
+
+fn retry_route(
+    backend: client_policy::Backend,
+    retry: Option<client_policy::RouteRetry<client_policy::http::StatusRanges>>,
+    hedge: Option<client_policy::RouteHedge>,
+) -> client_policy::http::Route {
+    let mut route = default_route(backend);
+    let policy = &mut route.rules[0].policy;
+    policy.retry = retry;
+    policy.hedge = hedge;
+    route
+}
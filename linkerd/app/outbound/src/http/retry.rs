@@ -1,13 +1,15 @@
 use futures::{future, FutureExt};
 use linkerd_app_core::{
     classify,
+    errors::cause_ref,
     http_metrics::retries::Handle,
     metrics::{self, ProfileRouteLabels},
-    profiles::{self, http::Route},
-    proxy::http::{ClientHandle, EraseResponse, HttpBody},
+    profiles::http::Route,
+    proxy::http::{balance, ClientHandle, EraseResponse, HttpBody, ResponseTimeoutError},
     svc::{layer, Either, Param},
     Error,
 };
+use linkerd_http_access_log::AccessLogSpan;
 use linkerd_http_classify::{Classify, ClassifyEos, ClassifyResponse};
 use linkerd_http_retry::{
     with_trailers::{self, WithTrailers},
@@ -32,10 +34,28 @@ pub struct NewRetryPolicy {
 }
 
 #[derive(Clone, Debug)]
-pub struct RetryPolicy {
-    metrics: Handle,
+pub struct RetryPolicy<M = Handle> {
+    metrics: M,
     budget: Arc<retry::Budget>,
-    response_classes: profiles::http::ResponseClasses,
+    classify: classify::Request,
+    /// The number of retries that may still be attempted for a request, if
+    /// limited.
+    remaining: Option<usize>,
+    /// The number of retries that have been attempted for a request.
+    retries: usize,
+    /// Whether failed attempts that timed out may be retried.
+    retry_timeouts: bool,
+}
+
+/// Records the outcomes of retry decisions.
+pub trait RetryMetrics {
+    /// Records that a response was retryable and whether the budget permitted
+    /// a retry.
+    fn incr_retryable(&self, has_budget: bool);
+
+    /// Records that a request could not be retried because it exhausted its
+    /// maximum number of retries.
+    fn incr_limit_exceeded(&self) {}
 }
 
 /// Allow buffering requests up to 64 kb
@@ -58,35 +78,85 @@ where
     fn new_policy(&self, target: &T) -> Option<Self::Policy> {
         let route: Route = target.param();
         let labels: ProfileRouteLabels = target.param();
-        Some(RetryPolicy {
-            metrics: self.metrics.get_handle(labels),
-            budget: route.retries()?.budget().clone(),
-            response_classes: route.response_classes().clone(),
-        })
+        Some(RetryPolicy::new(
+            self.metrics.get_handle(labels),
+            route.retries()?.budget().clone(),
+            route.response_classes().clone().into(),
+            None,
+        ))
+    }
+}
+
+// === impl RetryPolicy ===
+
+impl<M> RetryPolicy<M> {
+    /// Creates a policy that retries failures, as determined by `classify`,
+    /// until `max_retries` is exhausted or the budget is depleted.
+    pub(crate) fn new(
+        metrics: M,
+        budget: Arc<retry::Budget>,
+        classify: classify::Request,
+        max_retries: Option<usize>,
+    ) -> Self {
+        Self {
+            metrics,
+            budget,
+            classify,
+            remaining: max_retries,
+            retries: 0,
+            retry_timeouts: false,
+        }
+    }
+
+    /// Permits attempts that failed with a response timeout to be retried.
+    ///
+    /// This should only be set when each attempt has its own timeout, so that
+    /// the overall request timeout is not retried.
+    pub(crate) fn with_retry_timeouts(self) -> Self {
+        Self {
+            retry_timeouts: true,
+            ..self
+        }
     }
 }
 
-// === impl Retry ===
+impl RetryMetrics for Handle {
+    #[inline]
+    fn incr_retryable(&self, has_budget: bool) {
+        Handle::incr_retryable(self, has_budget)
+    }
+}
 
-impl<A, B, E> retry::Policy<http::Request<ReplayBody<A>>, http::Response<WithTrailers<B>>, E>
-    for RetryPolicy
+impl<A, B, M> retry::Policy<http::Request<ReplayBody<A>>, http::Response<WithTrailers<B>>, Error>
+    for RetryPolicy<M>
 where
     A: HttpBody + Unpin,
     A::Error: Into<Error>,
     B: HttpBody + Unpin,
+    M: RetryMetrics + Clone,
 {
     type Future = future::Ready<Self>;
 
     fn retry(
         &self,
         req: &http::Request<ReplayBody<A>>,
-        result: Result<&http::Response<WithTrailers<B>>, &E>,
+        result: Result<&http::Response<WithTrailers<B>>, &Error>,
     ) -> Option<Self::Future> {
         let retryable = match result {
-            Err(_) => false,
+            Err(error) => {
+                // Attempts that timed out may be retried when each attempt is
+                // bounded by its own timeout.
+                let timed_out =
+                    self.retry_timeouts && cause_ref::<ResponseTimeoutError>(&**error).is_some();
+                let exceeded_max_len = req.body().is_capped();
+                let retryable = timed_out && !exceeded_max_len;
+                tracing::trace!(timed_out, exceeded_max_len, retryable);
+                retryable
+            }
             Ok(rsp) => {
                 // is the request a failure?
-                let is_failure = classify::Request::from(self.response_classes.clone())
+                let is_failure = self
+                    .classify
                     .classify(req)
                     .start(rsp)
                     .eos(rsp.body().trailers())
@@ -104,13 +174,25 @@ where
             return None;
         }
 
+        if self.remaining == Some(0) {
+            tracing::debug!("Request exceeded its maximum number of retries");
+            self.metrics.incr_limit_exceeded();
+            return None;
+        }
+
         let withdrew = self.budget.withdraw().is_ok();
         self.metrics.incr_retryable(withdrew);
         if !withdrew {
             return None;
         }
 
-        Some(future::ready(self.clone()))
+        let mut policy = self.clone();
+        policy.remaining = self.remaining.map(|n| n - 1);
+        policy.retries += 1;
+        if let Some(span) = req.extensions().get::<AccessLogSpan>() {
+            span.record_retries(policy.retries);
+        }
+        Some(future::ready(policy))
     }
 
     fn clone_request(
@@ -119,39 +201,56 @@ where
     ) -> Option<http::Request<ReplayBody<A>>> {
         // Since the body is already wrapped in a ReplayBody, it must not be obviously too large to
         // buffer/clone.
-        let mut clone = http::Request::new(req.body().clone());
-        *clone.method_mut() = req.method().clone();
-        *clone.uri_mut() = req.uri().clone();
-        *clone.headers_mut() = req.headers().clone();
-        *clone.version_mut() = req.version();
-
-        // The HTTP server sets a ClientHandle with the client's address and a means to close the
-        // server-side connection.
-        if let Some(client_handle) = req.extensions().get::<ClientHandle>().cloned() {
-            clone.extensions_mut().insert(client_handle);
-        }
+        Some(clone_request(req, req.body().clone()))
+    }
+}
 
-        if let Some(classify) = req.extensions().get::<classify::Response>().cloned() {
-            clone.extensions_mut().insert(classify);
-        }
+/// Copies a request's head, including the extensions required by the inner
+/// stack, onto a new body.
+pub(super) fn clone_request<A, B>(req: &http::Request<A>, body: B) -> http::Request<B> {
+    let mut clone = http::Request::new(body);
+    *clone.method_mut() = req.method().clone();
+    *clone.uri_mut() = req.uri().clone();
+    *clone.headers_mut() = req.headers().clone();
+    *clone.version_mut() = req.version();
 
-        Some(clone)
+    // The HTTP server sets a ClientHandle with the client's address and a means to close the
+    // server-side connection.
+    if let Some(client_handle) = req.extensions().get::<ClientHandle>().cloned() {
+        clone.extensions_mut().insert(client_handle);
     }
+
+    if let Some(classify) = req.extensions().get::<classify::Response>().cloned() {
+        clone.extensions_mut().insert(classify);
+    }
+
+    // The outbound access log records how each attempt is routed.
+    if let Some(span) = req.extensions().get::<AccessLogSpan>().cloned() {
+        clone.extensions_mut().insert(span);
+    }
+
+    // Balancers avoid the endpoints that prior attempts were dispatched to.
+    if let Some(attempts) = req.extensions().get::<balance::Attempts>().cloned() {
+        clone.extensions_mut().insert(attempts);
+    }
+
+    clone
 }
 
-impl<A, B, E> retry::PrepareRetry<http::Request<A>, http::Response<B>, E> for RetryPolicy
+impl<A, B, M> retry::PrepareRetry<http::Request<A>, http::Response<B>, Error> for RetryPolicy<M>
 where
     A: HttpBody + Unpin,
     A::Error: Into<Error>,
     B: HttpBody + Unpin + Send + 'static,
     B::Data: Unpin + Send,
     B::Error: Unpin + Send,
+    M: RetryMetrics + Clone,
 {
     type RetryRequest = http::Request<ReplayBody<A>>;
     type RetryResponse = http::Response<WithTrailers<B>>;
     type ResponseFuture = future::Map<
         with_trailers::WithTrailersFuture<B>,
-        fn(http::Response<WithTrailers<B>>) -> Result<http::Response<WithTrailers<B>>, E>,
+        fn(http::Response<WithTrailers<B>>) -> Result<http::Response<WithTrailers<B>>, Error>,
     >;
 
     fn prepare_request(
@@ -5,6 +5,7 @@ use linkerd_app_core::{
     svc::{self, ExtractParam},
     Error, Result,
 };
+use linkerd_http_access_log::NewOutboundAccessLog;
 
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct ServerRescue {
@@ -53,6 +54,9 @@ impl<T> Outbound<svc::ArcNewCloneHttp<T>> {
                 .push(http::NewNormalizeUri::layer())
                 // Record when a HTTP/1 URI originated in absolute form
                 .push_on_service(http::normalize_uri::MarkAbsoluteForm::layer())
+                // Records an access log for each request, including how it
+                // was routed by the inner stack.
+                .push(NewOutboundAccessLog::layer(rt.access_log_headers.clone()))
                 .arc_new_clone_http()
         })
     }
@@ -80,7 +80,7 @@ impl Outbound<()> {
         T: svc::Param<OrigDstAddr>,
         T: Clone + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Reset,
         I: Debug + Unpin + Send + Sync + 'static,
         // Endpoint resolver.
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
@@ -143,9 +143,16 @@ impl Outbound<()> {
             }
         };
         let load = {
-            let balance::EwmaConfig { decay, default_rtt } =
-                crate::http::logical::profile::DEFAULT_EWMA;
-            policy::Load::PeakEwma(policy::PeakEwma { decay, default_rtt })
+            let balance::EwmaConfig {
+                decay,
+                default_rtt,
+                slow_start,
+            } = crate::http::logical::profile::DEFAULT_EWMA;
+            policy::Load::PeakEwma(policy::PeakEwma {
+                decay,
+                default_rtt,
+                slow_start,
+            })
         };
         svc::mk(move |DiscoverAddr(addr)| {
             tracing::debug!(%addr, "Discover");
@@ -157,6 +164,7 @@ impl Outbound<()> {
             let policy = policies
                 .get_policy(addr)
                 .instrument(tracing::debug_span!("policy"));
+            let load = load.clone();
 
             Box::pin(async move {
                 let (profile, policy) = tokio::join!(profile, policy);
@@ -226,7 +234,7 @@ impl Outbound<()> {
                                 &PROFILE_META,
                                 detect_timeout,
                                 queue,
-                                load,
+                                load.clone(),
                                 logical,
                             );
                         }
@@ -626,6 +634,12 @@ where
     }
 }
 
+impl<T> svc::Param<Option<policy::Receiver>> for Opaq<T> {
+    fn param(&self) -> Option<policy::Receiver> {
+        Some(svc::Param::<policy::Receiver>::param(&self.0))
+    }
+}
+
 impl<T> svc::Param<opaq::Logical> for Opaq<T>
 where
     T: svc::Param<OrigDstAddr>,
@@ -10,7 +10,7 @@ use linkerd_app_core::{
     config::{ProxyConfig, QueueConfig},
     drain,
     exp_backoff::ExponentialBackoff,
-    http_tracing::OpenCensusSink,
+    http_tracing::SpanSink,
     identity, io,
     metrics::prom,
     profiles,
@@ -21,7 +21,7 @@ use linkerd_app_core::{
         tap,
     },
     svc::{self, ServiceExt},
-    tls,
+    tls, trace,
     transport::addrs::*,
     AddrMatch, Error, ProxyRuntime,
 };
@@ -42,6 +42,7 @@ pub mod opaq;
 pub mod policy;
 mod protocol;
 mod sidecar;
+mod sni;
 pub mod tcp;
 #[cfg(any(test, feature = "test-util"))]
 pub mod test_util;
@@ -96,7 +97,8 @@ struct Runtime {
     metrics: OutboundMetrics,
     identity: identity::NewClient,
     tap: tap::Registry,
-    span_sink: OpenCensusSink,
+    span_sink: Option<SpanSink>,
+    access_log_headers: trace::access_log::CaptureHeaders,
     drain: drain::Watch,
 }
 
@@ -127,6 +129,7 @@ impl Outbound<()> {
             identity: runtime.identity.new_client(),
             tap: runtime.tap,
             span_sink: runtime.span_sink,
+            access_log_headers: runtime.access_log_headers,
             drain: runtime.drain,
         };
         Self {
@@ -234,7 +237,7 @@ impl Outbound<()> {
         T: svc::Param<OrigDstAddr>,
         T: Clone + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Reset,
         I: Debug + Unpin + Send + Sync + 'static,
         // Endpoint resolution.
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
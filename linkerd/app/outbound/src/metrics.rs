@@ -9,8 +9,8 @@
 //! `DashMap` as we migrate other metrics registries.
 
 use crate::{
-    http::{concrete::BalancerMetrics, policy::RouteBackendMetrics},
-    policy, BackendRef, ParentRef,
+    http::{concrete::BalancerMetrics, policy::RouteMetrics},
+    policy, BackendRef, ParentRef, RouteRef,
 };
 use linkerd_app_core::{
     metrics::prom::{encoding::*, EncodeLabelSetMut},
@@ -27,7 +27,7 @@ pub struct OutboundMetrics {
     pub(crate) http_errors: error::Http,
     pub(crate) tcp_errors: error::Tcp,
 
-    pub(crate) http_route_backends: RouteBackendMetrics,
+    pub(crate) http_routes: RouteMetrics,
     pub(crate) http_balancer: BalancerMetrics,
 
     /// Holds metrics that are common to both inbound and outbound proxies. These metrics are
@@ -60,10 +60,13 @@ where
 
 impl<T> svc::ExtractParam<balance::Metrics, T> for BalancerMetricsParams<ConcreteLabels>
 where
-    T: svc::Param<ParentRef> + svc::Param<BackendRef>,
+    T: svc::Param<ParentRef> + svc::Param<BackendRef> + svc::Param<balance::Algorithm>,
 {
     fn extract_param(&self, target: &T) -> balance::Metrics {
-        self.metrics(&ConcreteLabels(target.param(), target.param()))
+        self.0.algorithm_metrics(
+            target.param(),
+            &ConcreteLabels(target.param(), target.param()),
+        )
     }
 }
 
@@ -75,7 +78,7 @@ impl OutboundMetrics {
             proxy,
             http_errors: error::Http::default(),
             tcp_errors: error::Tcp::default(),
-            http_route_backends: RouteBackendMetrics::default(),
+            http_routes: RouteMetrics::default(),
             http_balancer: BalancerMetrics::default(),
         }
     }
@@ -83,7 +86,7 @@ impl OutboundMetrics {
 
 impl FmtMetrics for OutboundMetrics {
     fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.http_route_backends.fmt_metrics(f)?;
+        self.http_routes.fmt_metrics(f)?;
         self.http_balancer.fmt_metrics(f)?;
         self.http_errors.fmt_metrics(f)?;
         self.tcp_errors.fmt_metrics(f)?;
@@ -166,6 +169,20 @@ impl EncodeLabelSet for ParentRef {
     }
 }
 
+// === impl RouteRef ===
+
+impl RouteRef {
+    pub fn encode_label_set(&self, enc: &mut LabelSetEncoder<'_>) -> std::fmt::Result {
+        prom_encode_meta_labels("route", &self.0, enc)
+    }
+}
+
+impl EncodeLabelSet for RouteRef {
+    fn encode(&self, mut enc: LabelSetEncoder<'_>) -> std::fmt::Result {
+        self.encode_label_set(&mut enc)
+    }
+}
+
 // === impl BackendRef ===
 
 impl BackendRef {
@@ -1,4 +1,4 @@
-use crate::{tcp, Outbound};
+use crate::{policy, tcp, Outbound};
 use linkerd_app_core::{
     io,
     metrics::prom,
@@ -13,13 +13,17 @@ use linkerd_app_core::{
 };
 use std::{fmt::Debug, hash::Hash};
 
-mod concrete;
+pub(crate) mod concrete;
+pub(crate) mod filters;
 mod logical;
 
 pub use self::logical::Logical;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-struct Opaq(Logical);
+#[derive(Clone, Debug)]
+struct Opaq {
+    logical: Logical,
+    policy: Option<policy::Receiver>,
+}
 
 // === impl Outbound ===
 
@@ -36,9 +40,10 @@ impl<C> Outbound<C> {
     where
         // Opaque target
         T: svc::Param<Logical>,
+        T: svc::Param<Option<policy::Receiver>>,
         T: Clone + Send + Sync + 'static,
         // Server-side connection
-        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::PeerAddr + io::Reset,
         I: Debug + Send + Sync + Unpin + 'static,
         // Endpoint discovery
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
@@ -49,14 +54,19 @@ impl<C> Outbound<C> {
         C::Connection: Send + Unpin,
         C::Future: Send + Unpin,
     {
+        let filters =
+            filters::RouteFilterMetrics::register(registry.sub_registry_with_prefix("route"));
         self.push_tcp_endpoint()
             .push_opaq_concrete(registry, resolve)
-            .push_opaq_logical()
+            .push_opaq_logical(filters)
             .map_stack(|config, _rt, stk| {
                 stk.push_new_idle_cached(config.discovery_idle_timeout)
                     // Use a dedicated target type to configure parameters for
                     // the opaque stack. It also helps narrow the cache key.
-                    .push_map_target(|t: T| Opaq(t.param()))
+                    .push_map_target(|t: T| Opaq {
+                        logical: t.param(),
+                        policy: t.param(),
+                    })
                     .arc_new_clone_tcp()
             })
     }
@@ -66,15 +76,45 @@ impl<C> Outbound<C> {
 
 impl svc::Param<Logical> for Opaq {
     fn param(&self) -> Logical {
-        self.0.clone()
+        self.logical.clone()
     }
 }
 
 impl svc::Param<Option<profiles::Receiver>> for Opaq {
     fn param(&self) -> Option<profiles::Receiver> {
-        match self.0.param() {
-            Logical::Route(_, rx) => Some(rx),
+        match self.logical {
+            Logical::Route(_, ref rx) => Some(rx.clone()),
             _ => None,
         }
     }
 }
+
+impl svc::Param<Option<filters::Filters>> for Opaq {
+    fn param(&self) -> Option<filters::Filters> {
+        self.policy.clone().map(filters::Filters::Policy)
+    }
+}
+
+// Targets are only equal when they share a policy watch, so that a cached
+// stack never applies another target's route filters.
+impl PartialEq for Opaq {
+    fn eq(&self, other: &Self) -> bool {
+        let same_policy = match (&self.policy, &other.policy) {
+            (Some(a), Some(b)) => a.same_channel(b),
+            (None, None) => true,
+            _ => false,
+        };
+        self.logical == other.logical && same_policy
+    }
+}
+
+impl Eq for Opaq {}
+
+impl Hash for Opaq {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Policy watches cannot be hashed, so only whether the target has one
+        // is hashed.
+        self.logical.hash(state);
+        self.policy.is_some().hash(state);
+    }
+}
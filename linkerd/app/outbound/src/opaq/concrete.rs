@@ -19,6 +19,7 @@ use linkerd_app_core::{
     transport_header::SessionProtocol,
     Error, Infallible, NameAddr,
 };
+use linkerd_tcp_access_log as tcp_access_log;
 use std::{fmt::Debug, net::SocketAddr, sync::Arc};
 use tracing::info_span;
 
@@ -27,8 +28,14 @@ use tracing::info_span;
 pub enum Dispatch {
     Balance(NameAddr, NameAddr, balance::EwmaConfig),
     Forward(Remote<ServerAddr>, Metadata),
+    Fail { message: Arc<str> },
 }
 
+/// A backend dispatcher explicitly fails all connections.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct DispatcherFailed(Arc<str>);
+
 /// Wraps errors encountered in this module.
 #[derive(Debug, thiserror::Error)]
 #[error("concrete service {addr}: {source}")]
@@ -121,6 +128,7 @@ impl<C> Outbound<C> {
         R::Resolution: Unpin,
         // Endpoint connector.
         C: svc::MakeConnection<Endpoint<T>> + Clone + Send + 'static,
+        C::Error: std::fmt::Display,
         C::Connection: Send + Unpin,
         C::Metadata: Send + Unpin,
         C::Future: Send,
@@ -137,6 +145,8 @@ impl<C> Outbound<C> {
             let queue = config.tcp_connection_queue;
 
             let connect = inner
+                // Records an access log for each connection to an endpoint.
+                .push(tcp_access_log::ConnectAccessLog::layer())
                 .push(svc::stack::WithoutConnectionMetadata::layer())
                 .push_new_thunk();
 
@@ -184,29 +194,39 @@ impl<C> Outbound<C> {
                 )
                 .instrument(|t: &Balance<T>| info_span!("balance", addr = %t.concrete));
 
+            let fail = svc::ArcNewService::new(|message: Arc<str>| {
+                svc::mk(move |_| futures::future::ready(Err(DispatcherFailed(message.clone()))))
+            });
+
             balance
+                .push_switch(Ok::<_, Infallible>, forward.into_inner())
+                .push_on_service(tcp::Forward::layer())
+                .push_on_service(drain::Retain::layer(rt.drain.clone()))
                 .push_switch(
                     move |parent: T| -> Result<_, Infallible> {
                         Ok(match parent.param() {
-                            Dispatch::Balance(logical, concrete, ewma) => svc::Either::A(Balance {
-                                logical,
-                                concrete,
-                                ewma,
-                                queue,
-                                parent,
-                            }),
-                            Dispatch::Forward(addr, meta) => svc::Either::B(Endpoint {
-                                addr,
-                                is_local: false,
-                                metadata: meta,
-                                parent,
-                            }),
+                            Dispatch::Balance(logical, concrete, ewma) => {
+                                svc::Either::A(svc::Either::A(Balance {
+                                    logical,
+                                    concrete,
+                                    ewma,
+                                    queue,
+                                    parent,
+                                }))
+                            }
+                            Dispatch::Forward(addr, meta) => {
+                                svc::Either::A(svc::Either::B(Endpoint {
+                                    addr,
+                                    is_local: false,
+                                    metadata: meta,
+                                    parent,
+                                }))
+                            }
+                            Dispatch::Fail { message } => svc::Either::B(message),
                         })
                     },
-                    forward.into_inner(),
+                    svc::stack(fail).check_new_clone().into_inner(),
                 )
-                .push_on_service(tcp::Forward::layer())
-                .push_on_service(drain::Retain::layer(rt.drain.clone()))
                 .push(svc::ArcNewService::layer())
         })
     }
@@ -0,0 +1,448 @@
+//! Enforces opaque route filters on each connection.
+
+use crate::{policy, ParentRef, RouteRef};
+use futures::{future, prelude::*};
+use linkerd_app_core::{io, metrics::prom, svc, Error, Result};
+use parking_lot::Mutex;
+use rand::distributions::Distribution;
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{Context, Poll},
+};
+use tokio::{sync::Semaphore, time};
+
+#[cfg(test)]
+mod tests;
+
+/// A `NewService` that applies a route's filters to each connection.
+///
+/// Connection limits are tracked in a map that is shared by all of the
+/// services built by this stack, so that a route's limit is enforced across
+/// all of the targets that use it and across policy updates.
+#[derive(Clone, Debug)]
+pub struct NewApplyFilters<N> {
+    metrics: RouteFilterMetrics,
+    limits: ConnectionLimits,
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub struct ApplyFilters<S> {
+    filters: Option<Filters>,
+    metrics: RouteFilterMetrics,
+    limits: ConnectionLimits,
+    inner: S,
+}
+
+/// Describes where a target's route filters are configured.
+#[derive(Clone, Debug)]
+pub enum Filters {
+    /// The filters configured on a single route.
+    Route(Route),
+
+    /// The filters configured on a client policy's opaque route. The policy
+    /// is read as each connection is accepted, so that updates apply to new
+    /// connections without rebuilding the stack.
+    Policy(policy::Receiver),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Route {
+    pub parent_ref: ParentRef,
+    pub route_ref: RouteRef,
+    pub filters: Arc<[policy::opaq::Filter]>,
+}
+
+/// Records the outcome of each connection that is closed or refused by a
+/// route filter.
+#[derive(Clone, Debug, Default)]
+pub struct RouteFilterMetrics {
+    connections: prom::Family<Labels, prom::Counter>,
+}
+
+/// Tracks the last time that data was read or written on a connection.
+#[derive(Clone, Debug)]
+pub struct Activity(Arc<Mutex<time::Instant>>);
+
+pub type FilteredIo<I> = io::SensorIo<I, Activity>;
+
+pub mod errors {
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("route connection limit of {limit} exceeded")]
+    pub struct OpaqRouteConnectionLimit {
+        pub limit: NonZeroUsize,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("connection idle for {0:?}")]
+    pub struct OpaqRouteIdleTimeout(pub time::Duration);
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("connection exceeded its maximum lifetime of {0:?}")]
+    pub struct OpaqRouteMaxLifetime(pub time::Duration);
+
+    #[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+    #[error("connection configured to fail: {message}")]
+    pub struct OpaqRouteInjectedFailure {
+        pub message: Arc<str>,
+    }
+
+    #[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+    #[error("connection configured to reset")]
+    pub struct OpaqRouteInjectedReset(pub(super) ());
+}
+
+/// Holds a semaphore for each route that configures a connection limit.
+///
+/// Only weak references are held so that a route's semaphore is released
+/// once it has no open connections.
+#[derive(Clone, Debug, Default)]
+struct ConnectionLimits(Arc<Mutex<HashMap<RouteRef, ConnectionLimit>>>);
+
+#[derive(Debug)]
+struct ConnectionLimit {
+    limit: NonZeroUsize,
+    semaphore: Weak<Semaphore>,
+}
+
+/// The most restrictive configuration of each of a route's filters.
+#[derive(Debug, Default)]
+struct Config {
+    limit: Option<NonZeroUsize>,
+    idle_timeout: Option<time::Duration>,
+    max_lifetime: Option<time::Duration>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+struct Labels {
+    parent: ParentRef,
+    route: RouteRef,
+    outcome: Outcome,
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+enum Outcome {
+    ConnectionLimit,
+    IdleTimeout,
+    MaxLifetime,
+    InjectedFailure,
+    InjectedReset,
+}
+
+// === impl NewApplyFilters ===
+
+impl<N> NewApplyFilters<N> {
+    pub fn layer(metrics: RouteFilterMetrics) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        let limits = ConnectionLimits::default();
+        svc::layer::mk(move |inner| Self {
+            metrics: metrics.clone(),
+            limits: limits.clone(),
+            inner,
+        })
+    }
+}
+
+impl<T, N> svc::NewService<T> for NewApplyFilters<N>
+where
+    T: svc::Param<Option<Filters>>,
+    N: svc::NewService<T>,
+{
+    type Service = ApplyFilters<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        ApplyFilters {
+            filters: target.param(),
+            metrics: self.metrics.clone(),
+            limits: self.limits.clone(),
+            inner: self.inner.new_service(target),
+        }
+    }
+}
+
+// === impl ApplyFilters ===
+
+impl<I, S> svc::Service<I> for ApplyFilters<S>
+where
+    I: io::Reset,
+    S: svc::Service<FilteredIo<I>, Response = ()>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+{
+    type Response = ();
+    type Error = Error;
+    type Future = future::Either<
+        Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
+        future::Ready<Result<()>>,
+    >;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, io: I) -> Self::Future {
+        let activity = Activity::new();
+        let route = match self.filters {
+            Some(Filters::Route(ref route)) => Some(route.clone()),
+            Some(Filters::Policy(ref policy)) => Route::from_policy(&policy.borrow()),
+            None => None,
+        };
+        let Some(route) = route else {
+            let call = self.inner.call(io::SensorIo::new(io, activity));
+            return future::Either::Left(Box::pin(call.err_into::<Error>()));
+        };
+
+        let mut config = Config::default();
+        for filter in &*route.filters {
+            match filter {
+                policy::opaq::Filter::ConnectionLimit(limit) => {
+                    config.limit = min(config.limit, *limit);
+                }
+                policy::opaq::Filter::IdleTimeout(timeout) => {
+                    config.idle_timeout = min(config.idle_timeout, *timeout);
+                }
+                policy::opaq::Filter::MaxLifetime(timeout) => {
+                    config.max_lifetime = min(config.max_lifetime, *timeout);
+                }
+                policy::opaq::Filter::InjectFailure(fail) => {
+                    if let Some(message) = fail.apply() {
+                        self.metrics.inc(&route, Outcome::InjectedFailure);
+                        let error = errors::OpaqRouteInjectedFailure { message };
+                        return future::Either::Right(future::err(error.into()));
+                    }
+                }
+                policy::opaq::Filter::InjectReset(distribution) => {
+                    if distribution.sample(&mut rand::thread_rng()) {
+                        // The connection is reset when it is dropped.
+                        if let Err(error) = io.reset_on_drop() {
+                            tracing::debug!(%error, "Failed to configure connection reset");
+                        }
+                        self.metrics.inc(&route, Outcome::InjectedReset);
+                        let error = errors::OpaqRouteInjectedReset(());
+                        return future::Either::Right(future::err(error.into()));
+                    }
+                }
+            }
+        }
+
+        // The permit is held until the connection completes.
+        let permit = match config.limit {
+            None => None,
+            Some(limit) => {
+                let semaphore = self.limits.semaphore(&route.route_ref, limit);
+                match semaphore.try_acquire_owned() {
+                    Ok(permit) => Some(permit),
+                    Err(_) => {
+                        tracing::debug!(%limit, "Route connection limit exceeded");
+                        self.metrics.inc(&route, Outcome::ConnectionLimit);
+                        let error = errors::OpaqRouteConnectionLimit { limit };
+                        return future::Either::Right(future::err(error.into()));
+                    }
+                }
+            }
+        };
+
+        let call = self
+            .inner
+            .call(io::SensorIo::new(io, activity.clone()))
+            .err_into::<Error>();
+        let Config {
+            idle_timeout,
+            max_lifetime,
+            ..
+        } = config;
+        let metrics = self.metrics.clone();
+        future::Either::Left(Box::pin(async move {
+            let _permit = permit;
+            let idle = async move {
+                match idle_timeout {
+                    Some(timeout) => activity.idle(timeout).await,
+                    None => future::pending().await,
+                }
+            };
+            let lifetime = async move {
+                match max_lifetime {
+                    Some(timeout) => time::sleep(timeout).await,
+                    None => future::pending().await,
+                }
+            };
+            tokio::select! {
+                res = call => res,
+                () = idle => {
+                    let timeout = idle_timeout.expect("idle timeout must be set");
+                    tracing::debug!(?timeout, "Closing idle connection");
+                    metrics.inc(&route, Outcome::IdleTimeout);
+                    Err(errors::OpaqRouteIdleTimeout(timeout).into())
+                }
+                () = lifetime => {
+                    let timeout = max_lifetime.expect("max lifetime must be set");
+                    tracing::debug!(?timeout, "Closing connection at its maximum lifetime");
+                    metrics.inc(&route, Outcome::MaxLifetime);
+                    Err(errors::OpaqRouteMaxLifetime(timeout).into())
+                }
+            }
+        }))
+    }
+}
+
+/// When a filter is configured more than once, the most restrictive
+/// configuration applies.
+fn min<T: Ord>(current: Option<T>, value: T) -> Option<T> {
+    Some(match current {
+        Some(current) => std::cmp::min(current, value),
+        None => value,
+    })
+}
+
+// === impl Route ===
+
+impl Route {
+    fn from_policy(policy: &policy::ClientPolicy) -> Option<Self> {
+        let route = match policy.protocol {
+            policy::Protocol::Opaque(policy::opaq::Opaque {
+                policy: Some(ref route),
+            }) => route,
+            _ => return None,
+        };
+        Some(Self {
+            parent_ref: ParentRef(policy.parent.clone()),
+            route_ref: RouteRef(route.meta.clone()),
+            filters: route.filters.clone(),
+        })
+    }
+}
+
+// === impl ConnectionLimits ===
+
+impl ConnectionLimits {
+    /// Returns the semaphore that limits the route's connections.
+    ///
+    /// If the route's limit has changed, a new semaphore is used for new
+    /// connections. Connections that hold a permit from the prior semaphore
+    /// are not counted against the new limit.
+    fn semaphore(&self, route: &RouteRef, limit: NonZeroUsize) -> Arc<Semaphore> {
+        let mut limits = self.0.lock();
+        if let Some(current) = limits.get(route) {
+            if current.limit == limit {
+                if let Some(semaphore) = current.semaphore.upgrade() {
+                    return semaphore;
+                }
+            }
+        }
+
+        // Drop the semaphores of routes that no longer have open connections.
+        limits.retain(|_, l| l.semaphore.strong_count() > 0);
+        let semaphore = Arc::new(Semaphore::new(limit.get()));
+        limits.insert(
+            route.clone(),
+            ConnectionLimit {
+                limit,
+                semaphore: Arc::downgrade(&semaphore),
+            },
+        );
+        semaphore
+    }
+}
+
+// === impl Activity ===
+
+impl Activity {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new(time::Instant::now())))
+    }
+
+    /// Completes once no data has been read or written for `timeout`.
+    async fn idle(&self, timeout: time::Duration) {
+        loop {
+            let deadline = *self.0.lock() + timeout;
+            if deadline <= time::Instant::now() {
+                return;
+            }
+            time::sleep_until(deadline).await;
+        }
+    }
+
+    fn touch(&mut self, sz: usize) {
+        if sz > 0 {
+            *self.0.lock() = time::Instant::now();
+        }
+    }
+}
+
+impl io::Sensor for Activity {
+    fn record_read(&mut self, sz: usize) {
+        self.touch(sz)
+    }
+
+    fn record_write(&mut self, sz: usize) {
+        self.touch(sz)
+    }
+
+    fn record_close(&mut self, _: Option<linkerd_errno::Errno>) {}
+
+    fn record_error<T>(&mut self, op: io::Poll<T>) -> io::Poll<T> {
+        op
+    }
+}
+
+// === impl RouteFilterMetrics ===
+
+impl RouteFilterMetrics {
+    pub fn register(reg: &mut prom::Registry) -> Self {
+        let connections = prom::Family::default();
+        reg.register(
+            "filtered_connections",
+            "The total number of connections refused or closed by a route filter",
+            connections.clone(),
+        );
+        Self { connections }
+    }
+
+    fn inc(&self, route: &Route, outcome: Outcome) {
+        self.connections
+            .get_or_create(&Labels {
+                parent: route.parent_ref.clone(),
+                route: route.route_ref.clone(),
+                outcome,
+            })
+            .inc();
+    }
+}
+
+// === impl Labels ===
+
+impl prom::EncodeLabelSetMut for Labels {
+    fn encode_label_set(&self, enc: &mut prom::encoding::LabelSetEncoder<'_>) -> std::fmt::Result {
+        use prom::encoding::EncodeLabel;
+
+        self.parent.encode_label_set(enc)?;
+        self.route.encode_label_set(enc)?;
+        ("outcome", self.outcome.as_str()).encode(enc.encode_label())?;
+        Ok(())
+    }
+}
+
+impl prom::encoding::EncodeLabelSet for Labels {
+    fn encode(&self, mut enc: prom::encoding::LabelSetEncoder<'_>) -> std::fmt::Result {
+        prom::EncodeLabelSetMut::encode_label_set(self, &mut enc)
+    }
+}
+
+// === impl Outcome ===
+
+impl Outcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::ConnectionLimit => "connection_limit",
+            Self::IdleTimeout => "idle_timeout",
+            Self::MaxLifetime => "max_lifetime",
+            Self::InjectedFailure => "injected_failure",
+            Self::InjectedReset => "injected_reset",
+        }
+    }
+}
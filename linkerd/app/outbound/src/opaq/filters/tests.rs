@@ -0,0 +1,307 @@
+use super::*;
+use linkerd_app_core::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    svc::{Layer, NewService, Service, ServiceExt},
+};
+use std::future::Future;
+use tokio::sync::watch;
+
+#[derive(Clone, Debug)]
+struct Target(Filters);
+
+#[tokio::test(flavor = "current_thread")]
+async fn limits_connections() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let metrics = RouteFilterMetrics::default();
+    let filters = [policy::opaq::Filter::ConnectionLimit(
+        NonZeroUsize::new(1).unwrap(),
+    )];
+    let mut svc = mk_filters(metrics.clone(), filters, |_| future::pending());
+
+    let (_client0, server0) = io::duplex(100);
+    let conn0 = svc.ready().await.unwrap().call(server0);
+
+    let (_client1, server1) = io::duplex(100);
+    let error = svc
+        .ready()
+        .await
+        .unwrap()
+        .call(server1)
+        .await
+        .expect_err("connection must be refused");
+    assert!(error.is::<errors::OpaqRouteConnectionLimit>(), "{error}");
+    assert_eq!(counter(&metrics, Outcome::ConnectionLimit).get(), 1);
+
+    // Once the first connection completes, new connections are permitted.
+    drop(conn0);
+    let (_client2, server2) = io::duplex(100);
+    let conn2 = svc.ready().await.unwrap().call(server2);
+    assert!(conn2.now_or_never().is_none());
+}
+
+/// Tests that a route's connection limit is shared by all of the services that
+/// are built for the route.
+#[tokio::test(flavor = "current_thread")]
+async fn limits_connections_across_targets() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let metrics = RouteFilterMetrics::default();
+    let filters = mk_route([policy::opaq::Filter::ConnectionLimit(
+        NonZeroUsize::new(1).unwrap(),
+    )]);
+    let new_filters = mk_new_filters(metrics.clone(), |_| future::pending());
+    let mut svc0 = new_filters.new_service(Target(filters.clone()));
+    let mut svc1 = new_filters.new_service(Target(filters));
+
+    let (_client0, server0) = io::duplex(100);
+    let _conn0 = svc0.ready().await.unwrap().call(server0);
+
+    let (_client1, server1) = io::duplex(100);
+    let error = svc1
+        .ready()
+        .await
+        .unwrap()
+        .call(server1)
+        .await
+        .expect_err("connection must be refused");
+    assert!(error.is::<errors::OpaqRouteConnectionLimit>(), "{error}");
+    assert_eq!(counter(&metrics, Outcome::ConnectionLimit).get(), 1);
+}
+
+/// Tests that filters are read from the client policy as it is updated.
+#[tokio::test(flavor = "current_thread")]
+async fn applies_policy_updates() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let metrics = RouteFilterMetrics::default();
+    let limit = |n| {
+        let filters = [policy::opaq::Filter::ConnectionLimit(
+            NonZeroUsize::new(n).unwrap(),
+        )];
+        mk_policy(filters)
+    };
+    let (tx, rx) = watch::channel(limit(1));
+    let mut svc = mk_new_filters(metrics.clone(), |_| future::pending())
+        .new_service(Target(Filters::Policy(rx)));
+
+    let (_client0, server0) = io::duplex(100);
+    let _conn0 = svc.ready().await.unwrap().call(server0);
+
+    let (_client1, server1) = io::duplex(100);
+    let error = svc
+        .ready()
+        .await
+        .unwrap()
+        .call(server1)
+        .await
+        .expect_err("connection must be refused");
+    assert!(error.is::<errors::OpaqRouteConnectionLimit>(), "{error}");
+
+    // When the policy raises the limit, new connections are permitted.
+    tx.send(limit(2)).unwrap();
+    let (_client2, server2) = io::duplex(100);
+    let conn2 = svc.ready().await.unwrap().call(server2);
+    assert!(conn2.now_or_never().is_none());
+    assert_eq!(counter(&metrics, Outcome::ConnectionLimit).get(), 1);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn closes_idle_connections() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let metrics = RouteFilterMetrics::default();
+    let filters = [policy::opaq::Filter::IdleTimeout(
+        time::Duration::from_secs(10),
+    )];
+    let svc = mk_filters(metrics.clone(), filters, |mut conn| async move {
+        let mut buf = Vec::new();
+        conn.read_to_end(&mut buf).await?;
+        Ok(())
+    });
+
+    let (mut client, server) = io::duplex(100);
+    let start = time::Instant::now();
+    let conn = tokio::spawn(svc.oneshot(server));
+
+    // Activity on the connection resets the idle timeout.
+    time::sleep(time::Duration::from_secs(5)).await;
+    client.write_all(b"hello").await.unwrap();
+    time::sleep(time::Duration::from_secs(6)).await;
+    assert!(!conn.is_finished());
+
+    let error = conn
+        .await
+        .unwrap()
+        .expect_err("idle connection must be closed");
+    assert!(error.is::<errors::OpaqRouteIdleTimeout>(), "{error}");
+    assert_eq!(
+        time::Instant::now().saturating_duration_since(start),
+        time::Duration::from_secs(15)
+    );
+    assert_eq!(counter(&metrics, Outcome::IdleTimeout).get(), 1);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn closes_connections_at_max_lifetime() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let metrics = RouteFilterMetrics::default();
+    let filters = [policy::opaq::Filter::MaxLifetime(
+        time::Duration::from_secs(30),
+    )];
+    let svc = mk_filters(metrics.clone(), filters, |_| future::pending());
+
+    let (_client, server) = io::duplex(100);
+    let start = time::Instant::now();
+    let error = svc
+        .oneshot(server)
+        .await
+        .expect_err("connection must be closed");
+    assert!(error.is::<errors::OpaqRouteMaxLifetime>(), "{error}");
+    assert_eq!(
+        time::Instant::now().saturating_duration_since(start),
+        time::Duration::from_secs(30)
+    );
+    assert_eq!(counter(&metrics, Outcome::MaxLifetime).get(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn injects_failures() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let metrics = RouteFilterMetrics::default();
+    let filters = [policy::opaq::Filter::InjectFailure(
+        policy::opaq::InjectFailure {
+            response: "oops".into(),
+            distribution: policy::opaq::Distribution::from_ratio(1, 1).unwrap(),
+        },
+    )];
+    let svc = mk_filters(metrics.clone(), filters, |_| future::pending());
+
+    let (_client, server) = io::duplex(100);
+    let error = svc.oneshot(server).await.expect_err("connection must fail");
+    let error = error
+        .downcast_ref::<errors::OpaqRouteInjectedFailure>()
+        .expect("connection must fail with an injected failure");
+    assert_eq!(&*error.message, "oops");
+    assert_eq!(counter(&metrics, Outcome::InjectedFailure).get(), 1);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn injects_resets() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let metrics = RouteFilterMetrics::default();
+    let filters = [policy::opaq::Filter::InjectReset(
+        policy::opaq::Distribution::from_ratio(1, 1).unwrap(),
+    )];
+    let svc = mk_filters(metrics.clone(), filters, |_| future::pending());
+
+    let (_client, server) = io::duplex(100);
+    let error = svc.oneshot(server).await.expect_err("connection must fail");
+    assert!(error.is::<errors::OpaqRouteInjectedReset>(), "{error}");
+    assert_eq!(counter(&metrics, Outcome::InjectedReset).get(), 1);
+}
+
+/// Tests that a reset connection is closed with a TCP RST.
+#[tokio::test(flavor = "current_thread")]
+async fn resets_tcp_connections() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut client = tokio::net::TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (server, _) = listener.accept().await.unwrap();
+
+    let filters = mk_route([policy::opaq::Filter::InjectReset(
+        policy::opaq::Distribution::from_ratio(1, 1).unwrap(),
+    )]);
+    let svc = NewApplyFilters::layer(RouteFilterMetrics::default())
+        .layer(|_: Target| {
+            svc::mk(|_: FilteredIo<tokio::net::TcpStream>| future::pending::<Result<()>>())
+        })
+        .new_service(Target(filters));
+    svc.oneshot(server)
+        .await
+        .expect_err("connection must be reset");
+
+    let mut buf = [0u8; 1];
+    let error = client
+        .read(&mut buf)
+        .await
+        .expect_err("client must observe a reset");
+    assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+}
+
+fn mk_filters<F, Fut>(
+    metrics: RouteFilterMetrics,
+    filters: impl IntoIterator<Item = policy::opaq::Filter>,
+    call: F,
+) -> ApplyFilters<svc::BoxTcp<FilteredIo<io::DuplexStream>>>
+where
+    F: Fn(FilteredIo<io::DuplexStream>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    mk_new_filters(metrics, call).new_service(Target(mk_route(filters)))
+}
+
+fn mk_new_filters<F, Fut>(
+    metrics: RouteFilterMetrics,
+    call: F,
+) -> NewApplyFilters<
+    impl svc::NewService<Target, Service = svc::BoxTcp<FilteredIo<io::DuplexStream>>>,
+>
+where
+    F: Fn(FilteredIo<io::DuplexStream>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let inner = move |_: Target| svc::BoxTcp::new(svc::mk(call.clone()));
+    NewApplyFilters::layer(metrics).layer(inner)
+}
+
+fn mk_route(filters: impl IntoIterator<Item = policy::opaq::Filter>) -> Filters {
+    Filters::Route(Route {
+        parent_ref: ParentRef(policy::Meta::new_default("parent")),
+        route_ref: RouteRef(policy::Meta::new_default("route")),
+        filters: filters.into_iter().collect(),
+    })
+}
+
+fn mk_policy(filters: impl IntoIterator<Item = policy::opaq::Filter>) -> policy::ClientPolicy {
+    policy::ClientPolicy {
+        parent: policy::Meta::new_default("parent"),
+        protocol: policy::Protocol::Opaque(policy::opaq::Opaque {
+            policy: Some(policy::opaq::Policy {
+                meta: policy::Meta::new_default("route"),
+                filters: filters.into_iter().collect(),
+                failure_policy: Default::default(),
+                request_timeout: None,
+                retry: None,
+                hedge: None,
+                distribution: policy::RouteDistribution::Empty,
+            }),
+        }),
+        backends: Arc::new([]),
+    }
+}
+
+fn counter(metrics: &RouteFilterMetrics, outcome: Outcome) -> prom::Counter {
+    metrics
+        .connections
+        .get_or_create(&Labels {
+            parent: ParentRef(policy::Meta::new_default("parent")),
+            route: RouteRef(policy::Meta::new_default("route")),
+            outcome,
+        })
+        .clone()
+}
+
+// === impl Target ===
+
+impl svc::Param<Option<Filters>> for Target {
+    fn param(&self) -> Option<Filters> {
+        Some(self.0.clone())
+    }
+}
@@ -1,5 +1,5 @@
-use super::concrete;
-use crate::Outbound;
+use super::{concrete, filters};
+use crate::{policy, Outbound};
 use linkerd_app_core::{
     io,
     profiles::{self, Profile},
@@ -74,17 +74,25 @@ impl<N> Outbound<N> {
     /// services. Only available inner services are used for routing. When
     /// there are no available backends, requests are failed with a
     /// [`svc::stack::LoadShedError`].
-    pub fn push_opaq_logical<T, I, NSvc>(self) -> Outbound<svc::ArcNewCloneTcp<T, I>>
+    ///
+    /// The filters of the target's opaque route policy, if any, are enforced
+    /// on each connection.
+    pub fn push_opaq_logical<T, I, NSvc>(
+        self,
+        metrics: filters::RouteFilterMetrics,
+    ) -> Outbound<svc::ArcNewCloneTcp<T, I>>
     where
         // Opaque logical target.
         T: svc::Param<Logical>,
+        T: svc::Param<Option<filters::Filters>>,
         T: Eq + Hash + Clone + Debug + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + Debug + Send + Unpin + 'static,
+        I: io::AsyncRead + io::AsyncWrite + io::Reset + Debug + Send + Unpin + 'static,
         // Concrete stack.
         N: svc::NewService<Concrete<T>, Service = NSvc> + Clone + Send + Sync + 'static,
-        NSvc: svc::Service<I, Response = ()> + Clone + Send + Sync + 'static,
-        NSvc::Future: Send,
+        NSvc: svc::Service<filters::FilteredIo<I>, Response = ()>,
+        NSvc: Clone + Send + Sync + 'static,
+        NSvc::Future: Send + 'static,
         NSvc::Error: Into<Error>,
     {
         self.map_stack(|_, _, concrete| {
@@ -135,6 +143,8 @@ impl<N> Outbound<N> {
                     },
                     concrete.into_inner(),
                 )
+                // Enforce the route's filters on each connection.
+                .push(filters::NewApplyFilters::layer(metrics))
                 .arc_new_clone_tcp()
         })
     }
@@ -158,6 +168,7 @@ where
         const EWMA: balance::EwmaConfig = balance::EwmaConfig {
             default_rtt: time::Duration::from_millis(30),
             decay: time::Duration::from_secs(10),
+            slow_start: None,
         };
 
         // Create concrete targets for all of the profile's routes.
@@ -286,6 +297,20 @@ impl<T> svc::Param<concrete::Dispatch> for Concrete<T> {
 
 // === impl Logical ===
 
+// Logical targets do not carry a client policy, so no route filters apply.
+
+impl svc::Param<Option<policy::Receiver>> for Logical {
+    fn param(&self) -> Option<policy::Receiver> {
+        None
+    }
+}
+
+impl svc::Param<Option<filters::Filters>> for Logical {
+    fn param(&self) -> Option<filters::Filters> {
+        None
+    }
+}
+
 impl std::cmp::PartialEq for Logical {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -41,7 +41,7 @@ async fn forward() {
             future::ok::<_, support::io::Error>((io.build(), local))
         }))
         .push_opaq_concrete(&mut Default::default(), resolve)
-        .push_opaq_logical()
+        .push_opaq_logical(Default::default())
         .into_inner();
 
     // Build a client to the endpoint and proxy a connection.
@@ -106,7 +106,7 @@ async fn balances() {
             },
         ))
         .push_opaq_concrete(&mut Default::default(), resolve)
-        .push_opaq_logical()
+        .push_opaq_logical(Default::default())
         .into_inner()
         .new_service(logical);
 
@@ -15,7 +15,7 @@ use linkerd_app_core::{
     transport::addrs::*,
     Error,
 };
-use std::fmt::Debug;
+use std::{convert::Infallible, fmt::Debug};
 use tokio::sync::watch;
 use tracing::info_span;
 
@@ -49,7 +49,7 @@ impl Outbound<()> {
         T: svc::Param<OrigDstAddr>,
         T: Clone + Send + Sync + 'static,
         // Server-side socket.
-        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr,
+        I: io::AsyncRead + io::AsyncWrite + io::Peek + io::PeerAddr + io::Reset,
         I: Debug + Unpin + Send + Sync + 'static,
         // Endpoint resolver.
         R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
@@ -59,6 +59,28 @@ impl Outbound<()> {
             .to_tcp_connect()
             .push_opaq_cached(registry.sub_registry_with_prefix("tcp"), resolve.clone());
 
+        let sni = self
+            .to_tcp_connect()
+            .push_sni_cached(registry.sub_registry_with_prefix("tls"), resolve.clone());
+
+        // Connections to targets with TLS routes are routed by their SNI
+        // value. All other opaque connections use the opaque stack.
+        let opaq = opaq.map_stack(|_, _, stk| {
+            stk.push_switch(
+                |parent: Sidecar| -> Result<_, Infallible> {
+                    let is_tls =
+                        matches!(parent.policy.borrow().protocol, policy::Protocol::Tls(_));
+                    Ok(if is_tls {
+                        svc::Either::B(parent)
+                    } else {
+                        svc::Either::A(parent)
+                    })
+                },
+                sni.into_inner(),
+            )
+            .arc_new_clone_tcp()
+        });
+
         let http = self
             .to_tcp_connect()
             .push_tcp_endpoint()
@@ -125,6 +147,18 @@ impl svc::Param<Option<profiles::Receiver>> for Sidecar {
     }
 }
 
+impl svc::Param<policy::Receiver> for Sidecar {
+    fn param(&self) -> policy::Receiver {
+        self.policy.clone()
+    }
+}
+
+impl svc::Param<Option<policy::Receiver>> for Sidecar {
+    fn param(&self) -> Option<policy::Receiver> {
+        Some(self.policy.clone())
+    }
+}
+
 impl svc::Param<Protocol> for Sidecar {
     fn param(&self) -> Protocol {
         if let Some(rx) = svc::Param::<Option<profiles::Receiver>>::param(self) {
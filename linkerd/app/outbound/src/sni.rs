@@ -0,0 +1,97 @@
+//! Routes TLS connections by the SNI value in the client's ClientHello.
+//!
+//! TLS is not terminated: connections are proxied as opaque TCP to the
+//! backends of the best-matching TLS route.
+
+use crate::{opaq::filters, policy, tcp, Outbound};
+use linkerd_app_core::{
+    detect, io,
+    metrics::prom,
+    proxy::{
+        api_resolve::{ConcreteAddr, Metadata},
+        core::Resolve,
+    },
+    svc, tls,
+    transport::addrs::*,
+    Error,
+};
+use std::{fmt::Debug, hash::Hash};
+
+mod logical;
+
+/// A target for connections to `T` with a given SNI value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sni<T> {
+    sni: Option<tls::ServerName>,
+    parent: T,
+}
+
+// === impl Outbound ===
+
+impl<C> Outbound<C> {
+    /// Builds a stack that routes TLS connections by their SNI value.
+    ///
+    /// Each connection's ClientHello is buffered to determine its SNI value.
+    /// A router is cached for each target and SNI value so that it may be
+    /// reused across connections.
+    pub fn push_sni_cached<T, I, R>(
+        self,
+        registry: &mut prom::Registry,
+        resolve: R,
+    ) -> Outbound<svc::ArcNewCloneTcp<T, I>>
+    where
+        // Target with TLS routes.
+        T: svc::Param<policy::Receiver>,
+        T: Eq + Hash + Clone + Debug + Send + Sync + 'static,
+        // Server-side connection
+        I: io::AsyncRead + io::AsyncWrite + io::Reset,
+        I: Debug + Send + Sync + Unpin + 'static,
+        // Endpoint discovery
+        R: Resolve<ConcreteAddr, Endpoint = Metadata, Error = Error>,
+        R::Resolution: Unpin,
+        // TCP endpoint stack.
+        C: svc::MakeConnection<tcp::Connect, Metadata = Local<ClientAddr>, Error = io::Error>,
+        C: Clone + Send + Sync + Unpin + 'static,
+        C::Connection: Send + Unpin,
+        C::Future: Send + Unpin,
+    {
+        let filters =
+            filters::RouteFilterMetrics::register(registry.sub_registry_with_prefix("route"));
+        self.push_tcp_endpoint()
+            .push_opaq_concrete(registry, resolve)
+            .push_sni_logical(filters)
+            .map_stack(|config, _rt, stk| {
+                stk.push_new_idle_cached(config.discovery_idle_timeout)
+                    .push_map_target(
+                        |(result, parent): (detect::Result<tls::ServerName>, T)| Sni {
+                            sni: detect::allow_timeout(result),
+                            parent,
+                        },
+                    )
+                    // `DetectService` oneshots the inner service, so we add
+                    // a loadshed to prevent leaking tasks if (for some
+                    // unexpected reason) the inner service is not ready.
+                    .push_on_service(svc::LoadShed::layer())
+                    .lift_new_with_target::<(detect::Result<tls::ServerName>, T)>()
+                    .push(detect::NewDetectService::layer(config.proxy.detect_sni()))
+                    .arc_new_clone_tcp()
+            })
+    }
+}
+
+// === impl Sni ===
+
+impl<T> svc::Param<Option<tls::ServerName>> for Sni<T> {
+    fn param(&self) -> Option<tls::ServerName> {
+        self.sni.clone()
+    }
+}
+
+impl<T> svc::Param<policy::Receiver> for Sni<T>
+where
+    T: svc::Param<policy::Receiver>,
+{
+    fn param(&self) -> policy::Receiver {
+        self.parent.param()
+    }
+}
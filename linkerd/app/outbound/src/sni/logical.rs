@@ -0,0 +1,290 @@
+use crate::{
+    opaq::{concrete, filters},
+    policy, Outbound, ParentRef, RouteRef,
+};
+use linkerd_app_core::{
+    io, profiles, proxy::tcp::balance, svc, tls, transport::addrs::*, Error, NameAddr,
+};
+use linkerd_distribute as distribute;
+use std::{fmt::Debug, hash::Hash, time};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Concrete<T> {
+    target: concrete::Dispatch,
+    parent: T,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("no TLS route matches SNI {}", display_sni(.sni))]
+pub struct NoRoute {
+    sni: Option<tls::ServerName>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Params<T: Eq + Hash + Clone + Debug> {
+    sni: Option<tls::ServerName>,
+    route: Option<RouteParams<T>>,
+    backends: distribute::Backends<Concrete<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct RouteParams<T> {
+    parent: T,
+    filters: filters::Route,
+    distribution: Distribution<T>,
+}
+
+type NewBackendCache<T, N, S> = distribute::NewBackendCache<Concrete<T>, (), N, S>;
+type NewDistribute<T, N> = distribute::NewDistribute<Concrete<T>, (), N>;
+type Distribution<T> = distribute::Distribution<Concrete<T>>;
+
+/// TCP balancers only support peak-EWMA load estimation, so it is used for all
+/// balanced backends. This configuration is used when the backend is
+/// configured with another load estimator.
+const DEFAULT_EWMA: balance::EwmaConfig = balance::EwmaConfig {
+    default_rtt: time::Duration::from_millis(30),
+    decay: time::Duration::from_secs(10),
+    slow_start: None,
+};
+
+// === impl Outbound ===
+
+impl<N> Outbound<N> {
+    /// Builds a `NewService` that produces a router service for each target.
+    ///
+    /// The router watches the target's client policy and selects the TLS
+    /// route that best matches the target's SNI value. Connections are
+    /// distributed over the route's available backends. When no route
+    /// matches, connections are failed with a [`NoRoute`] error.
+    ///
+    /// Each route's filters are enforced on the connections it routes.
+    pub fn push_sni_logical<T, I, NSvc>(
+        self,
+        metrics: filters::RouteFilterMetrics,
+    ) -> Outbound<svc::ArcNewCloneTcp<T, I>>
+    where
+        // Target with an SNI value.
+        T: svc::Param<Option<tls::ServerName>>,
+        T: svc::Param<policy::Receiver>,
+        T: Eq + Hash + Clone + Debug + Send + Sync + 'static,
+        // Server-side socket.
+        I: io::AsyncRead + io::AsyncWrite + io::Reset + Debug + Send + Unpin + 'static,
+        // Concrete stack.
+        N: svc::NewService<Concrete<T>, Service = NSvc> + Clone + Send + Sync + 'static,
+        NSvc: svc::Service<filters::FilteredIo<I>, Response = ()>,
+        NSvc: Clone + Send + Sync + 'static,
+        NSvc::Future: Send + 'static,
+        NSvc::Error: Into<Error>,
+    {
+        self.map_stack(|_, _, concrete| {
+            let route = svc::layers()
+                .lift_new()
+                .push(NewDistribute::layer())
+                // The router does not take the backend's availability into
+                // consideration, so we must eagerly fail connections to
+                // prevent leaking tasks onto the runtime.
+                .push_on_service(svc::LoadShed::layer())
+                // Enforce the route's filters on each connection.
+                .push(filters::NewApplyFilters::layer(metrics));
+
+            // A `NewService`--instantiated once per target--that caches a set
+            // of concrete services so that, as the watch provides new
+            // `Params`, we can reuse inner services.
+            let router = svc::layers()
+                .lift_new()
+                .push(NewBackendCache::layer())
+                .push_on_service(route)
+                .push(svc::NewOneshotRoute::<Params<T>, _, _>::layer_cached());
+
+            concrete
+                // Share the concrete stack with each router stack.
+                .lift_new()
+                // Rebuild this router stack every time the policy changes.
+                .push_on_service(router)
+                .push(svc::NewSpawnWatch::<policy::ClientPolicy, _>::layer_into::<
+                    Params<T>,
+                >())
+                .arc_new_clone_tcp()
+        })
+    }
+}
+
+// === impl Params ===
+
+impl<T> From<(policy::ClientPolicy, T)> for Params<T>
+where
+    T: svc::Param<Option<tls::ServerName>>,
+    T: Eq + Hash + Clone + Debug,
+{
+    fn from((policy, parent): (policy::ClientPolicy, T)) -> Self {
+        let sni: Option<tls::ServerName> = parent.param();
+
+        // If the policy no longer configures TLS routes, connections are not
+        // routed.
+        let route = match policy.protocol {
+            policy::Protocol::Tls(ref tls) => policy::tls::find(&tls.routes, sni.as_ref()),
+            _ => None,
+        };
+        let Some(route) = route else {
+            tracing::debug!(?sni, "No TLS route matches");
+            return Self {
+                sni,
+                route: None,
+                backends: std::iter::empty().collect(),
+            };
+        };
+        tracing::debug!(?sni, route = ?route.policy.meta, "Routing TLS connections");
+
+        let mk_concrete = |backend: &policy::Backend| Concrete {
+            target: mk_dispatch(backend),
+            parent: parent.clone(),
+        };
+        let (backends, distribution) = match route.policy.distribution {
+            policy::RouteDistribution::Empty => (std::iter::empty().collect(), Distribution::Empty),
+            policy::RouteDistribution::FirstAvailable(ref backends) => {
+                let concretes = backends
+                    .iter()
+                    .map(|rb| mk_concrete(&rb.backend))
+                    .collect::<Vec<_>>();
+                (
+                    concretes.iter().cloned().collect(),
+                    Distribution::first_available(concretes),
+                )
+            }
+            policy::RouteDistribution::RandomAvailable(ref backends) => {
+                let weighted = backends
+                    .iter()
+                    .map(|(rb, weight)| (mk_concrete(&rb.backend), *weight))
+                    .collect::<Vec<_>>();
+                (
+                    weighted.iter().map(|(c, _)| c.clone()).collect(),
+                    Distribution::random_available(weighted).expect("distribution must be valid"),
+                )
+            }
+            policy::RouteDistribution::RandomWeighted(ref backends) => {
+                let weighted = backends
+                    .iter()
+                    .map(|(rb, weight)| (mk_concrete(&rb.backend), *weight))
+                    .collect::<Vec<_>>();
+                (
+                    weighted.iter().map(|(c, _)| c.clone()).collect(),
+                    Distribution::random_weighted(weighted).expect("distribution must be valid"),
+                )
+            }
+        };
+
+        Self {
+            sni,
+            route: Some(RouteParams {
+                parent,
+                filters: filters::Route {
+                    parent_ref: ParentRef(policy.parent.clone()),
+                    route_ref: RouteRef(route.policy.meta.clone()),
+                    filters: route.policy.filters.clone(),
+                },
+                distribution,
+            }),
+            backends,
+        }
+    }
+}
+
+fn mk_dispatch(backend: &policy::Backend) -> concrete::Dispatch {
+    match backend.dispatcher {
+        policy::BackendDispatcher::BalanceP2c(
+            ref load,
+            policy::EndpointDiscovery::DestinationGet { ref path },
+        ) => {
+            let ewma = match *load {
+                policy::Load::PeakEwma(policy::PeakEwma {
+                    decay,
+                    default_rtt,
+                    slow_start,
+                }) => balance::EwmaConfig {
+                    decay,
+                    default_rtt,
+                    slow_start,
+                },
+                _ => DEFAULT_EWMA,
+            };
+            // XXX TLS routes have no logical name, so the concrete name
+            // is used for both.
+            let addr = path
+                .parse::<NameAddr>()
+                .expect("destination must be a nameaddr");
+            concrete::Dispatch::Balance(addr.clone(), addr, ewma)
+        }
+        policy::BackendDispatcher::Forward(addr, ref md) => {
+            concrete::Dispatch::Forward(Remote(ServerAddr(addr)), md.clone())
+        }
+        policy::BackendDispatcher::Fail { ref message } => concrete::Dispatch::Fail {
+            message: message.clone(),
+        },
+    }
+}
+
+impl<T> svc::Param<distribute::Backends<Concrete<T>>> for Params<T>
+where
+    T: Clone + Eq + Hash + Debug,
+{
+    fn param(&self) -> distribute::Backends<Concrete<T>> {
+        self.backends.clone()
+    }
+}
+
+impl<T, I> svc::router::SelectRoute<I> for Params<T>
+where
+    T: Clone + Eq + Hash + Debug,
+{
+    type Key = RouteParams<T>;
+    type Error = NoRoute;
+
+    fn select(&self, _: &I) -> Result<Self::Key, Self::Error> {
+        self.route.clone().ok_or_else(|| NoRoute {
+            sni: self.sni.clone(),
+        })
+    }
+}
+
+fn display_sni(sni: &Option<tls::ServerName>) -> &str {
+    sni.as_ref().map(|n| n.as_str()).unwrap_or("<none>")
+}
+
+// === impl RouteParams ===
+
+impl<T: Clone> svc::Param<Distribution<T>> for RouteParams<T> {
+    fn param(&self) -> Distribution<T> {
+        self.distribution.clone()
+    }
+}
+
+impl<T> svc::Param<Option<filters::Filters>> for RouteParams<T> {
+    fn param(&self) -> Option<filters::Filters> {
+        Some(filters::Filters::Route(self.filters.clone()))
+    }
+}
+
+// === impl Concrete ===
+
+impl<T> std::ops::Deref for Concrete<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.parent
+    }
+}
+
+impl<T> svc::Param<Option<profiles::LogicalAddr>> for Concrete<T> {
+    fn param(&self) -> Option<profiles::LogicalAddr> {
+        None
+    }
+}
+
+impl<T> svc::Param<concrete::Dispatch> for Concrete<T> {
+    fn param(&self) -> concrete::Dispatch {
+        self.target.clone()
+    }
+}
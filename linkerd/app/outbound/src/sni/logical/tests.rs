@@ -0,0 +1,189 @@
+use super::*;
+use crate::{sni::Sni, test_util::*};
+use linkerd_app_core::{
+    errors,
+    io::{self, AsyncReadExt, AsyncWriteExt},
+    svc::{NewService, ServiceExt},
+};
+use std::{net::SocketAddr, sync::Arc};
+use tokio::sync::watch;
+
+const EP0: ([u8; 4], u16) = ([192, 0, 2, 30], 443);
+const EP1: ([u8; 4], u16) = ([192, 0, 2, 31], 443);
+const EP2: ([u8; 4], u16) = ([192, 0, 2, 32], 443);
+
+#[derive(Clone, Debug)]
+struct Target(policy::Receiver);
+
+/// Tests that the best-matching route is selected for each SNI value.
+#[test]
+fn selects_route_by_sni() {
+    let policy = policy(vec![
+        route(&[], EP2),
+        route(&["*.example.com"], EP1),
+        route(&["api.example.com"], EP0),
+    ]);
+
+    let forwards_to = |sni: Option<&str>| {
+        let sni = sni.map(|s| s.parse::<tls::ServerName>().unwrap());
+        let params = Params::from((policy.clone(), Sni { sni, parent: () }));
+        match params.route.expect("a route must match").distribution {
+            distribute::Distribution::FirstAvailable(keys) => match keys[0].target {
+                concrete::Dispatch::Forward(Remote(ServerAddr(addr)), _) => addr,
+                ref target => panic!("unexpected target: {target:?}"),
+            },
+            distribution => panic!("unexpected distribution: {distribution:?}"),
+        }
+    };
+    assert_eq!(forwards_to(Some("api.example.com")), EP0.into());
+    assert_eq!(forwards_to(Some("web.example.com")), EP1.into());
+    assert_eq!(forwards_to(Some("example.org")), EP2.into());
+    assert_eq!(forwards_to(None), EP2.into());
+
+    // Without a default route, unmatched connections are not routed.
+    let policy = self::policy(vec![route(&["api.example.com"], EP0)]);
+    let params = Params::from((
+        policy,
+        Sni::<()> {
+            sni: Some("web.example.com".parse().unwrap()),
+            parent: (),
+        },
+    ));
+    assert!(params.route.is_none());
+}
+
+/// Tests that connections are forwarded to the backend of the route matching
+/// their SNI value, and that connections that match no route are failed.
+#[tokio::test]
+async fn forwards_by_sni() {
+    let _trace = linkerd_tracing::test::trace_init();
+
+    let (_tx, rx) = watch::channel(policy(vec![
+        route(&["api.example.com"], EP0),
+        route(&["*.example.com"], EP1),
+    ]));
+
+    let (rt, _shutdown) = runtime();
+    let stack = Outbound::new(default_config(), rt)
+        .with_stack(svc::mk(
+            move |ep: concrete::Endpoint<Concrete<Sni<Target>>>| {
+                let Remote(ServerAddr(addr)) = svc::Param::param(&ep);
+                let msg: &[u8] = if addr == SocketAddr::from(EP0) {
+                    b"ep0"
+                } else {
+                    b"ep1"
+                };
+                let mut io = support::io();
+                io.write(b"who r u?").read(msg);
+                let local = Local(ClientAddr(([0, 0, 0, 0], 4444).into()));
+                future::ok::<_, support::io::Error>((io.build(), local))
+            },
+        ))
+        .push_opaq_concrete(&mut Default::default(), support::resolver())
+        .push_sni_logical(Default::default())
+        .into_inner();
+
+    let connect = |sni: &str| {
+        let svc = stack.new_service(Sni {
+            sni: Some(sni.parse().unwrap()),
+            parent: Target(rx.clone()),
+        });
+        async move {
+            let (io, task) = spawn_io();
+            svc.oneshot(io).await?;
+            Ok::<_, Error>(task.await.unwrap().unwrap())
+        }
+    };
+
+    assert_eq!(connect("api.example.com").await.unwrap(), "ep0");
+    assert_eq!(connect("web.example.com").await.unwrap(), "ep1");
+
+    let error = connect("example.org")
+        .await
+        .expect_err("unmatched connections must fail");
+    assert!(errors::is_caused_by::<NoRoute>(&*error), "{error}");
+}
+
+fn policy(routes: Vec<policy::tls::Route>) -> policy::ClientPolicy {
+    let backends = routes
+        .iter()
+        .flat_map(|route| match route.policy.distribution {
+            policy::RouteDistribution::FirstAvailable(ref backends) => {
+                backends.iter().map(|rb| rb.backend.clone()).collect()
+            }
+            _ => vec![],
+        })
+        .collect();
+    policy::ClientPolicy {
+        parent: policy::Meta::new_default("test"),
+        protocol: policy::Protocol::Tls(policy::tls::Tls {
+            routes: routes.into(),
+        }),
+        backends,
+    }
+}
+
+fn route(snis: &[&str], addr: impl Into<SocketAddr>) -> policy::tls::Route {
+    let backend = policy::Backend {
+        meta: policy::Meta::new_default("test"),
+        queue: policy::Queue {
+            capacity: 10,
+            failfast_timeout: time::Duration::from_secs(1),
+        },
+        dispatcher: policy::BackendDispatcher::Forward(addr.into(), Default::default()),
+        tls: None,
+    };
+    policy::tls::Route {
+        snis: snis.iter().map(|s| s.parse().unwrap()).collect(),
+        policy: policy::opaq::Policy {
+            meta: policy::Meta::new_default("test"),
+            filters: Arc::new([]),
+            distribution: policy::RouteDistribution::FirstAvailable(Arc::new([
+                policy::RouteBackend {
+                    filters: Arc::new([]),
+                    backend,
+                    request_timeout: None,
+                },
+            ])),
+            failure_policy: policy::opaq::NonIoErrors,
+            request_timeout: None,
+            retry: None,
+            hedge: None,
+        },
+    }
+}
+
+fn spawn_io() -> (
+    io::DuplexStream,
+    tokio::task::JoinHandle<io::Result<String>>,
+) {
+    let (mut client_io, server_io) = io::duplex(100);
+    let task = tokio::spawn(async move {
+        client_io.write_all(b"who r u?").await?;
+
+        let mut buf = String::with_capacity(100);
+        client_io.read_to_string(&mut buf).await?;
+        Ok(buf)
+    });
+    (server_io, task)
+}
+
+// === impl Target ===
+
+impl PartialEq for Target {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Target {}
+
+impl std::hash::Hash for Target {
+    fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+}
+
+impl svc::Param<policy::Receiver> for Target {
+    fn param(&self) -> policy::Receiver {
+        self.0.clone()
+    }
+}
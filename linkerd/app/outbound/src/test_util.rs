@@ -62,6 +62,7 @@ pub(crate) fn runtime() -> (ProxyRuntime, drain::Signal) {
         metrics: metrics.proxy,
         tap,
         span_sink: None,
+        access_log_headers: Default::default(),
         drain,
     };
     (runtime, drain_tx)
@@ -1,10 +1,11 @@
-use crate::{dns, gateway, identity, inbound, oc_collector, outbound, policy};
+use crate::{dns, gateway, identity, inbound, outbound, policy, trace_collector};
 use linkerd_app_core::{
     addr,
     config::*,
     control::{Config as ControlConfig, ControlAddr},
     proxy::http::{h1, h2},
     tls,
+    trace_context::Sampler,
     transport::{Keepalive, ListenAddr},
     Addr, AddrMatch, Conditional, IpNet,
 };
@@ -88,6 +89,10 @@ pub enum ParseError {
     InvalidTrustAnchors,
     #[error("not a valid port policy: {0}")]
     InvalidPortPolicy(String),
+    #[error("not a valid trace collector protocol")]
+    InvalidTraceProtocol,
+    #[error("not a valid sampling ratio")]
+    NotARatio,
 }
 
 // Environment variables to look at when loading the configuration
@@ -186,6 +191,15 @@ pub const ENV_INBOUND_IPS: &str = "LINKERD2_PROXY_INBOUND_IPS";
 pub const ENV_IDENTITY_DISABLED: &str = "LINKERD2_PROXY_IDENTITY_DISABLED";
 pub const ENV_IDENTITY_DIR: &str = "LINKERD2_PROXY_IDENTITY_DIR";
 pub const ENV_IDENTITY_TRUST_ANCHORS: &str = "LINKERD2_PROXY_IDENTITY_TRUST_ANCHORS";
+
+/// Configures trust anchors for federated SPIFFE trust domains as a
+/// comma-separated list of `<trust-domain>=<path>` pairs, where each path refers
+/// to a PEM-encoded trust bundle.
+///
+/// Peers with a SPIFFE ID in one of these trust domains are verified against
+/// the trust domain's bundle rather than `LINKERD2_PROXY_IDENTITY_TRUST_ANCHORS`.
+pub const ENV_IDENTITY_FEDERATED_TRUST_ANCHORS: &str =
+    "LINKERD2_PROXY_IDENTITY_FEDERATED_TRUST_ANCHORS";
 pub const ENV_IDENTITY_IDENTITY_LOCAL_NAME: &str = "LINKERD2_PROXY_IDENTITY_LOCAL_NAME";
 pub const ENV_IDENTITY_TOKEN_FILE: &str = "LINKERD2_PROXY_IDENTITY_TOKEN_FILE";
 pub const ENV_IDENTITY_MIN_REFRESH: &str = "LINKERD2_PROXY_IDENTITY_MIN_REFRESH";
@@ -193,12 +207,61 @@ pub const ENV_IDENTITY_MAX_REFRESH: &str = "LINKERD2_PROXY_IDENTITY_MAX_REFRESH"
 
 pub const ENV_IDENTITY_SVC_BASE: &str = "LINKERD2_PROXY_IDENTITY_SVC";
 
+/// Configures the path of a SPIFFE Workload API Unix domain socket.
+///
+/// When set, the proxy's identity is fetched from the Workload API instead of
+/// being certified by the identity controller, so `LINKERD2_PROXY_IDENTITY_DIR`,
+/// `LINKERD2_PROXY_IDENTITY_TOKEN_FILE`, and `LINKERD2_PROXY_IDENTITY_SVC_*` are
+/// not used. `LINKERD2_PROXY_IDENTITY_TRUST_ANCHORS` is optional, as trust
+/// bundles are provided by the Workload API.
+pub const ENV_IDENTITY_SPIFFE_WORKLOAD_API_SOCKET: &str =
+    "LINKERD2_PROXY_IDENTITY_SPIFFE_WORKLOAD_API_SOCKET";
+
+/// The SPIFFE ID of the X.509-SVID that the proxy uses as its identity.
+pub const ENV_IDENTITY_SPIFFE_ID: &str = "LINKERD2_PROXY_IDENTITY_SPIFFE_ID";
+
+/// Configures the path of a PEM-encoded certificate chain to use as the proxy's
+/// identity.
+///
+/// When set, the proxy's identity is loaded from files rather than being
+/// certified by the identity controller, so `LINKERD2_PROXY_IDENTITY_KEY_FILE`
+/// and `LINKERD2_PROXY_IDENTITY_TRUST_ANCHORS_FILE` must also be set. The files
+/// are reloaded whenever they change.
+pub const ENV_IDENTITY_CERTIFICATE_FILE: &str = "LINKERD2_PROXY_IDENTITY_CERTIFICATE_FILE";
+/// The path of the PEM-encoded PKCS#8 private key for
+/// `LINKERD2_PROXY_IDENTITY_CERTIFICATE_FILE`.
+pub const ENV_IDENTITY_KEY_FILE: &str = "LINKERD2_PROXY_IDENTITY_KEY_FILE";
+/// The path of PEM-encoded trust anchors used with
+/// `LINKERD2_PROXY_IDENTITY_CERTIFICATE_FILE`.
+pub const ENV_IDENTITY_TRUST_ANCHORS_FILE: &str = "LINKERD2_PROXY_IDENTITY_TRUST_ANCHORS_FILE";
+/// How often identity files are checked for changes.
+pub const ENV_IDENTITY_FILES_POLL_INTERVAL: &str = "LINKERD2_PROXY_IDENTITY_FILES_POLL_INTERVAL";
+
 pub const ENV_DESTINATION_SVC_BASE: &str = "LINKERD2_PROXY_DESTINATION_SVC";
 
 pub const ENV_HOSTNAME: &str = "HOSTNAME";
 
 pub const ENV_TRACE_COLLECTOR_SVC_BASE: &str = "LINKERD2_PROXY_TRACE_COLLECTOR_SVC";
 
+/// The protocol used to export spans to the trace collector.
+///
+/// Either `opencensus` (the default) or `opentelemetry`.
+pub const ENV_TRACE_PROTOCOL: &str = "LINKERD2_PROXY_TRACE_PROTOCOL";
+
+/// The ratio, between 0.0 and 1.0, of requests without a trace context for
+/// which the proxy starts a new trace. Defaults to 0.0, i.e. the proxy only
+/// records spans for traces started by its callers.
+pub const ENV_TRACE_SAMPLING_RATIO: &str = "LINKERD2_PROXY_TRACE_SAMPLING_RATIO";
+
+/// Overrides the sampling ratio for requests whose path starts with a given
+/// prefix, as a comma-separated list of `prefix=ratio` pairs, e.g.
+/// `/api=0.5,/healthz=0`.
+pub const ENV_TRACE_ROUTE_SAMPLING_RATIOS: &str = "LINKERD2_PROXY_TRACE_ROUTE_SAMPLING_RATIOS";
+
+/// When true, spans are emitted for failed requests even if their trace was
+/// not sampled.
+pub const ENV_TRACE_SAMPLE_ERRORS: &str = "LINKERD2_PROXY_TRACE_SAMPLE_ERRORS";
+
 pub const ENV_DESTINATION_CONTEXT: &str = "LINKERD2_PROXY_DESTINATION_CONTEXT";
 pub const ENV_DESTINATION_PROFILE_INITIAL_TIMEOUT: &str =
     "LINKERD2_PROXY_DESTINATION_PROFILE_INITIAL_TIMEOUT";
@@ -253,6 +316,11 @@ const DEFAULT_OUTBOUND_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
 const DEFAULT_OUTBOUND_CONNECT_BACKOFF: ExponentialBackoff =
     ExponentialBackoff::new_unchecked(Duration::from_millis(100), Duration::from_millis(500), 0.1);
 
+const DEFAULT_SPIFFE_WORKLOAD_API_BACKOFF: ExponentialBackoff =
+    ExponentialBackoff::new_unchecked(Duration::from_millis(100), Duration::from_secs(10), 0.1);
+
+const DEFAULT_IDENTITY_FILES_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
 const DEFAULT_CONTROL_QUEUE_CAPACITY: usize = 100;
 const DEFAULT_CONTROL_FAILFAST_TIMEOUT: Duration = Duration::from_secs(10);
 
@@ -314,6 +382,7 @@ const DEFAULT_IDENTITY_MAX_REFRESH: Duration = Duration::from_secs(60 * 60 * 24)
 
 const INBOUND_CONNECT_BASE: &str = "INBOUND_CONNECT";
 const OUTBOUND_CONNECT_BASE: &str = "OUTBOUND_CONNECT";
+const SPIFFE_WORKLOAD_API_BASE: &str = "IDENTITY_SPIFFE_WORKLOAD_API";
 
 /// Load a `App` by reading ENV variables.
 pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError> {
@@ -382,6 +451,14 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
     let oc_attributes_file_path = strings.get(ENV_TRACE_ATTRIBUTES_PATH);
 
     let trace_collector_addr = parse_control_addr(strings, ENV_TRACE_COLLECTOR_SVC_BASE);
+    let trace_protocol = parse(strings, ENV_TRACE_PROTOCOL, parse_trace_protocol);
+    let trace_sampling_ratio = parse(strings, ENV_TRACE_SAMPLING_RATIO, parse_sampling_ratio);
+    let trace_route_sampling_ratios = parse(
+        strings,
+        ENV_TRACE_ROUTE_SAMPLING_RATIOS,
+        parse_route_sampling_ratios,
+    );
+    let trace_sample_errors = parse(strings, ENV_TRACE_SAMPLE_ERRORS, parse_bool);
 
     let gateway_suffixes = parse(strings, ENV_INBOUND_GATEWAY_SUFFIXES, parse_dns_suffixes);
 
@@ -733,8 +810,8 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
             .into(),
     };
 
-    let oc_collector = match trace_collector_addr? {
-        None => oc_collector::Config::Disabled,
+    let trace_collector = match trace_collector_addr? {
+        None => trace_collector::Config::Disabled,
         Some(addr) => {
             let connect = if addr.addr.is_loopback() {
                 inbound.proxy.connect.clone()
@@ -752,10 +829,20 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
                     None => HashMap::new(),
                 })
                 .unwrap_or_default();
+            let sampler = trace_route_sampling_ratios?
+                .unwrap_or_default()
+                .into_iter()
+                .fold(
+                    Sampler::new(trace_sampling_ratio?.unwrap_or(0.0)),
+                    |sampler, (prefix, ratio)| sampler.with_route(prefix, ratio),
+                )
+                .with_sample_errors(trace_sample_errors?.unwrap_or(false));
 
-            oc_collector::Config::Enabled(Box::new(oc_collector::EnabledConfig {
+            trace_collector::Config::Enabled(Box::new(trace_collector::EnabledConfig {
                 attributes,
                 hostname: hostname?,
+                protocol: trace_protocol?.unwrap_or_default(),
+                sampler,
                 control: ControlConfig {
                     addr,
                     connect,
@@ -779,31 +866,44 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
         })
         .unwrap_or(super::tap::Config::Disabled);
 
-    let identity = {
-        let (addr, certify, params) = identity_config?;
-        // If the address doesn't have a server identity, then we're on localhost.
-        let connect = if addr.addr.is_loopback() {
-            inbound.proxy.connect.clone()
-        } else {
-            outbound.proxy.connect.clone()
-        };
-        let failfast_timeout = if addr.addr.is_loopback() {
-            inbound.http_request_queue.failfast_timeout
-        } else {
-            outbound.http_request_queue.failfast_timeout
-        };
-        identity::Config {
+    let identity = match identity_config? {
+        IdentityConfig::Linkerd {
+            addr,
             certify,
-            control: ControlConfig {
-                addr,
-                connect,
-                buffer: QueueConfig {
-                    capacity: DEFAULT_CONTROL_QUEUE_CAPACITY,
-                    failfast_timeout,
-                },
-            },
             params,
+        } => {
+            // If the address doesn't have a server identity, then we're on localhost.
+            let connect = if addr.addr.is_loopback() {
+                inbound.proxy.connect.clone()
+            } else {
+                outbound.proxy.connect.clone()
+            };
+            let failfast_timeout = if addr.addr.is_loopback() {
+                inbound.http_request_queue.failfast_timeout
+            } else {
+                outbound.http_request_queue.failfast_timeout
+            };
+            identity::Config::Linkerd {
+                certify,
+                control: ControlConfig {
+                    addr,
+                    connect,
+                    buffer: QueueConfig {
+                        capacity: DEFAULT_CONTROL_QUEUE_CAPACITY,
+                        failfast_timeout,
+                    },
+                },
+                params,
+            }
         }
+        IdentityConfig::Spiffe {
+            workload_api,
+            params,
+        } => identity::Config::Spiffe {
+            workload_api,
+            params,
+        },
+        IdentityConfig::Files { files, params } => identity::Config::Files { files, params },
     };
 
     Ok(super::Config {
@@ -811,7 +911,7 @@ pub fn parse_config<S: Strings>(strings: &S) -> Result<super::Config, EnvError>
         dns,
         dst,
         tap,
-        oc_collector,
+        trace_collector,
         policy,
         identity,
         outbound,
@@ -896,6 +996,32 @@ fn parse_tap_config(
     Ok(None)
 }
 
+fn parse_trace_protocol(s: &str) -> Result<trace_collector::CollectorProtocol, ParseError> {
+    s.parse().map_err(|_| ParseError::InvalidTraceProtocol)
+}
+
+fn parse_sampling_ratio(s: &str) -> Result<f64, ParseError> {
+    let ratio = s.trim().parse::<f64>()?;
+    if (0.0..=1.0).contains(&ratio) {
+        Ok(ratio)
+    } else {
+        Err(ParseError::NotARatio)
+    }
+}
+
+fn parse_route_sampling_ratios(s: &str) -> Result<Vec<(String, f64)>, ParseError> {
+    let mut routes = Vec::new();
+    for route in s.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        let (prefix, ratio) = route.rsplit_once('=').ok_or(ParseError::NotARatio)?;
+        let prefix = prefix.trim();
+        if !prefix.starts_with('/') {
+            return Err(ParseError::NotARatio);
+        }
+        routes.push((prefix.to_string(), parse_sampling_ratio(ratio)?));
+    }
+    Ok(routes)
+}
+
 fn parse_bool(s: &str) -> Result<bool, ParseError> {
     s.parse().map_err(Into::into)
 }
@@ -1002,6 +1128,16 @@ pub(super) fn parse_identity(s: &str) -> Result<identity::Id, ParseError> {
     })
 }
 
+fn parse_spiffe_id(s: &str) -> Result<identity::Id, ParseError> {
+    match identity::Id::parse_uri(s) {
+        Ok(id) if id.spiffe_trust_domain().is_some() => Ok(id),
+        _ => {
+            error!("Not a valid SPIFFE ID: {}", s);
+            Err(ParseError::NameError)
+        }
+    }
+}
+
 pub(super) fn parse<T, Parse>(
     strings: &dyn Strings,
     name: &str,
@@ -1058,6 +1194,33 @@ fn parse_dns_suffixes(list: &str) -> Result<HashSet<dns::Suffix>, ParseError> {
     Ok(suffixes)
 }
 
+fn parse_federated_trust_anchors(list: &str) -> Result<HashMap<String, PathBuf>, ParseError> {
+    let mut paths = HashMap::new();
+    for item in list.split(',') {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+
+        let (trust_domain, path) = item
+            .split_once('=')
+            .map(|(td, path)| (td.trim(), path.trim()))
+            .ok_or(ParseError::InvalidTrustAnchors)?;
+        if trust_domain.is_empty() || path.is_empty() {
+            return Err(ParseError::InvalidTrustAnchors);
+        }
+        if paths
+            .insert(trust_domain.to_string(), PathBuf::from(path))
+            .is_some()
+        {
+            error!(%trust_domain, "Duplicate federated trust domain");
+            return Err(ParseError::InvalidTrustAnchors);
+        }
+    }
+
+    Ok(paths)
+}
+
 fn parse_dns_suffix(s: &str) -> Result<dns::Suffix, ParseError> {
     if s == "." {
         return Ok(dns::Suffix::Root);
@@ -1164,9 +1327,25 @@ pub fn parse_control_addr<S: Strings>(
     }
 }
 
-pub fn parse_identity_config<S: Strings>(
-    strings: &S,
-) -> Result<(ControlAddr, identity::certify::Config, identity::TlsParams), EnvError> {
+/// Identity configuration loaded from the environment.
+#[derive(Clone, Debug)]
+pub enum IdentityConfig {
+    Linkerd {
+        addr: ControlAddr,
+        certify: identity::certify::Config,
+        params: identity::TlsParams,
+    },
+    Spiffe {
+        workload_api: identity::spiffe::Config,
+        params: identity::TlsParams,
+    },
+    Files {
+        files: identity::files::Config,
+        params: identity::TlsParams,
+    },
+}
+
+pub fn parse_identity_config<S: Strings>(strings: &S) -> Result<IdentityConfig, EnvError> {
     let control = parse_control_addr(strings, ENV_IDENTITY_SVC_BASE);
     let ta = parse(strings, ENV_IDENTITY_TRUST_ANCHORS, |s| {
         if s.is_empty() {
@@ -1174,6 +1353,11 @@ pub fn parse_identity_config<S: Strings>(
         }
         Ok(s.to_string())
     });
+    let fta = parse(
+        strings,
+        ENV_IDENTITY_FEDERATED_TRUST_ANCHORS,
+        parse_federated_trust_anchors,
+    );
     let dir = parse(strings, ENV_IDENTITY_DIR, |ref s| Ok(PathBuf::from(s)));
     let tok = parse(strings, ENV_IDENTITY_TOKEN_FILE, |ref s| {
         identity::TokenSource::if_nonempty_file(s.to_string()).map_err(|e| {
@@ -1184,6 +1368,18 @@ pub fn parse_identity_config<S: Strings>(
     let li = parse(strings, ENV_IDENTITY_IDENTITY_LOCAL_NAME, parse_dns_name);
     let min_refresh = parse(strings, ENV_IDENTITY_MIN_REFRESH, parse_duration);
     let max_refresh = parse(strings, ENV_IDENTITY_MAX_REFRESH, parse_duration);
+    let spiffe_socket = parse(strings, ENV_IDENTITY_SPIFFE_WORKLOAD_API_SOCKET, |s| {
+        Ok(PathBuf::from(s))
+    });
+    let spiffe_id = parse(strings, ENV_IDENTITY_SPIFFE_ID, parse_spiffe_id);
+    let crt_file = parse(strings, ENV_IDENTITY_CERTIFICATE_FILE, |s| {
+        Ok(PathBuf::from(s))
+    });
+    let key_file = parse(strings, ENV_IDENTITY_KEY_FILE, |s| Ok(PathBuf::from(s)));
+    let ta_file = parse(strings, ENV_IDENTITY_TRUST_ANCHORS_FILE, |s| {
+        Ok(PathBuf::from(s))
+    });
+    let poll_interval = parse(strings, ENV_IDENTITY_FILES_POLL_INTERVAL, parse_duration);
 
     if strings
         .get(ENV_IDENTITY_DISABLED)?
@@ -1197,6 +1393,110 @@ pub fn parse_identity_config<S: Strings>(
         return Err(EnvError::InvalidEnvVar);
     }
 
+    let federated_trust_anchors_pem = fta?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(trust_domain, path)| {
+            let pem = std::fs::read_to_string(&path).map_err(|error| {
+                error!(%error, %trust_domain, path = %path.display(), "Failed to read federated trust anchors");
+                EnvError::InvalidEnvVar
+            })?;
+            Ok((trust_domain, pem))
+        })
+        .collect::<Result<HashMap<_, _>, EnvError>>()?;
+
+    let (spiffe_socket, crt_file) = (spiffe_socket?, crt_file?);
+    if spiffe_socket.is_some() && crt_file.is_some() {
+        error!(
+            "{} and {} may not be set together.",
+            ENV_IDENTITY_SPIFFE_WORKLOAD_API_SOCKET, ENV_IDENTITY_CERTIFICATE_FILE
+        );
+        return Err(EnvError::InvalidEnvVar);
+    }
+
+    if let Some(certificate) = crt_file {
+        return match (key_file?, ta_file?, li?, ta?) {
+            (Some(key), Some(trust_anchors), Some(local_name), trust_anchors_pem) => {
+                let files = identity::files::Config {
+                    certificate,
+                    key,
+                    trust_anchors,
+                    federated_trust_anchors_pem: federated_trust_anchors_pem.clone(),
+                    poll_interval: poll_interval?.unwrap_or(DEFAULT_IDENTITY_FILES_POLL_INTERVAL),
+                };
+                // Trust anchors are loaded from the trust anchors file, but may
+                // be configured so that peers can be verified before the files
+                // are first loaded.
+                let params = identity::TlsParams {
+                    server_id: identity::Id::Dns(local_name.clone()),
+                    server_name: local_name,
+                    trust_anchors_pem: trust_anchors_pem.unwrap_or_default(),
+                    federated_trust_anchors_pem,
+                };
+                Ok(IdentityConfig::Files { files, params })
+            }
+            (key, trust_anchors, local_name, _) => {
+                for (unset, name) in &[
+                    (key.is_none(), ENV_IDENTITY_KEY_FILE),
+                    (trust_anchors.is_none(), ENV_IDENTITY_TRUST_ANCHORS_FILE),
+                    (local_name.is_none(), ENV_IDENTITY_IDENTITY_LOCAL_NAME),
+                ] {
+                    if *unset {
+                        error!(
+                            "{} must be set when {} is set.",
+                            name, ENV_IDENTITY_CERTIFICATE_FILE
+                        );
+                    }
+                }
+                Err(EnvError::InvalidEnvVar)
+            }
+        };
+    }
+
+    if let Some(socket) = spiffe_socket {
+        let backoff = parse_backoff(
+            strings,
+            SPIFFE_WORKLOAD_API_BASE,
+            DEFAULT_SPIFFE_WORKLOAD_API_BACKOFF,
+        )?;
+        return match (spiffe_id?, li?, ta?) {
+            (Some(id), Some(local_name), trust_anchors_pem) => {
+                let workload_api = identity::spiffe::Config {
+                    socket,
+                    id: id.clone(),
+                    backoff,
+                };
+                // Trust anchors are provided by the Workload API, but may be
+                // configured so that peers can be verified before the first
+                // X.509-SVID is fetched.
+                let params = identity::TlsParams {
+                    server_id: id,
+                    server_name: local_name,
+                    trust_anchors_pem: trust_anchors_pem.unwrap_or_default(),
+                    federated_trust_anchors_pem,
+                };
+                Ok(IdentityConfig::Spiffe {
+                    workload_api,
+                    params,
+                })
+            }
+            (id, local_name, _) => {
+                for (unset, name) in &[
+                    (id.is_none(), ENV_IDENTITY_SPIFFE_ID),
+                    (local_name.is_none(), ENV_IDENTITY_IDENTITY_LOCAL_NAME),
+                ] {
+                    if *unset {
+                        error!(
+                            "{} must be set when {} is set.",
+                            name, ENV_IDENTITY_SPIFFE_WORKLOAD_API_SOCKET
+                        );
+                    }
+                }
+                Err(EnvError::InvalidEnvVar)
+            }
+        };
+    }
+
     match (control?, ta?, dir?, li?, tok?, min_refresh?, max_refresh?) {
         (
             Some(control),
@@ -1220,8 +1520,13 @@ pub fn parse_identity_config<S: Strings>(
                 server_id: identity::Id::Dns(local_name.clone()),
                 server_name: local_name,
                 trust_anchors_pem,
+                federated_trust_anchors_pem,
             };
-            Ok((control, certify, params))
+            Ok(IdentityConfig::Linkerd {
+                addr: control,
+                certify,
+                params,
+            })
         }
         (addr, trust_anchors, end_entity_dir, local_id, token, _minr, _maxr) => {
             let s = format!("{0}_ADDR and {0}_NAME", ENV_IDENTITY_SVC_BASE);
@@ -1403,6 +1708,109 @@ mod tests {
         );
     }
 
+    #[test]
+    fn federated_trust_anchors() {
+        fn p(s: &str) -> Result<Vec<(String, PathBuf)>, ParseError> {
+            let mut tas = parse_federated_trust_anchors(s)?
+                .into_iter()
+                .collect::<Vec<_>>();
+            tas.sort();
+            Ok(tas)
+        }
+
+        assert_eq!(p(""), Ok(vec![]), "empty string");
+        assert_eq!(p(",,,"), Ok(vec![]), "empty list components are ignored");
+        assert_eq!(
+            p(" a.example = /a.pem , b.example=/b.pem "),
+            Ok(vec![
+                ("a.example".to_owned(), PathBuf::from("/a.pem")),
+                ("b.example".to_owned(), PathBuf::from("/b.pem")),
+            ]),
+            "whitespace is ignored"
+        );
+        assert_eq!(
+            p("a.example"),
+            Err(ParseError::InvalidTrustAnchors),
+            "a path is required"
+        );
+        assert_eq!(
+            p("=/a.pem"),
+            Err(ParseError::InvalidTrustAnchors),
+            "a trust domain is required"
+        );
+        assert_eq!(
+            p("a.example=/a.pem,a.example=/b.pem"),
+            Err(ParseError::InvalidTrustAnchors),
+            "trust domains may not be repeated"
+        );
+    }
+
+    #[test]
+    fn spiffe_id() {
+        assert!(parse_spiffe_id("spiffe://example.org/ns/default/sa/web").is_ok());
+        assert_eq!(
+            parse_spiffe_id("web.default.serviceaccount.identity.linkerd.cluster.local"),
+            Err(ParseError::NameError),
+            "DNS-like identities are not SPIFFE IDs"
+        );
+        assert_eq!(
+            parse_spiffe_id("https://example.org/ns/default/sa/web"),
+            Err(ParseError::NameError),
+            "the spiffe scheme is required"
+        );
+    }
+
+    #[test]
+    fn trace_protocol() {
+        use trace_collector::CollectorProtocol;
+        assert_eq!(
+            parse_trace_protocol("opencensus"),
+            Ok(CollectorProtocol::OpenCensus)
+        );
+        assert_eq!(
+            parse_trace_protocol("OpenTelemetry"),
+            Ok(CollectorProtocol::OpenTelemetry)
+        );
+        assert_eq!(
+            parse_trace_protocol("zipkin"),
+            Err(ParseError::InvalidTraceProtocol)
+        );
+    }
+
+    #[test]
+    fn sampling_ratios() {
+        assert_eq!(parse_sampling_ratio("0.25"), Ok(0.25));
+        assert_eq!(parse_sampling_ratio("1"), Ok(1.0));
+        assert_eq!(parse_sampling_ratio("1.5"), Err(ParseError::NotARatio));
+        assert_eq!(parse_sampling_ratio("-0.1"), Err(ParseError::NotARatio));
+        assert!(parse_sampling_ratio("half").is_err());
+
+        assert_eq!(
+            parse_route_sampling_ratios("/api=0.5, /healthz=0,"),
+            Ok(vec![
+                ("/api".to_string(), 0.5),
+                ("/healthz".to_string(), 0.0)
+            ])
+        );
+        assert_eq!(
+            parse_route_sampling_ratios("/q?a=b=1"),
+            Ok(vec![("/q?a=b".to_string(), 1.0)])
+        );
+        assert_eq!(
+            parse_route_sampling_ratios("api=0.5"),
+            Err(ParseError::NotARatio),
+            "prefixes must be absolute paths"
+        );
+        assert_eq!(
+            parse_route_sampling_ratios("/api"),
+            Err(ParseError::NotARatio)
+        );
+        assert_eq!(
+            parse_route_sampling_ratios("/api=2"),
+            Err(ParseError::NotARatio)
+        );
+    }
+
     #[test]
     fn ip_sets() {
         let ips = &[
@@ -1,26 +1,42 @@
 pub use linkerd_app_core::identity::{
-    client::{certify, TokenSource},
-    Id,
+    client::{certify, files, TokenSource},
+    spiffe, Id,
 };
 use linkerd_app_core::{
     control, dns,
     exp_backoff::{ExponentialBackoff, ExponentialBackoffStream},
     identity::{
-        client::{Certify, Metrics as IdentityMetrics},
+        client::{Certify, Files, Metrics as IdentityMetrics},
         creds, Credentials, DerX509, Mode,
     },
     metrics::{prom, ControlHttp as ClientMetrics},
     Error, Result,
 };
-use std::{future::Future, pin::Pin};
+use std::{collections::HashMap, future::Future, pin::Pin};
 use tokio::sync::watch;
 use tracing::Instrument;
 
 #[derive(Clone, Debug)]
-pub struct Config {
-    pub control: control::Config,
-    pub certify: certify::Config,
-    pub params: TlsParams,
+#[allow(clippy::large_enum_variant)]
+pub enum Config {
+    /// Certifies the proxy's identity with Linkerd's identity controller.
+    Linkerd {
+        control: control::Config,
+        certify: certify::Config,
+        params: TlsParams,
+    },
+
+    /// Fetches the proxy's identity from a SPIFFE Workload API endpoint.
+    Spiffe {
+        workload_api: spiffe::Config,
+        params: TlsParams,
+    },
+
+    /// Loads the proxy's identity from files, reloading them as they change.
+    Files {
+        files: files::Config,
+        params: TlsParams,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -28,10 +44,14 @@ pub struct TlsParams {
     pub server_id: Id,
     pub server_name: dns::Name,
     pub trust_anchors_pem: String,
+
+    /// PEM-encoded trust anchors for federated SPIFFE trust domains, keyed by
+    /// trust domain.
+    pub federated_trust_anchors_pem: HashMap<String, String>,
 }
 
 pub struct Identity {
-    addr: control::ControlAddr,
+    addr: Option<control::ControlAddr>,
     receiver: creds::Receiver,
     ready: watch::Receiver<bool>,
     metrics: IdentityMetrics,
@@ -59,32 +79,65 @@ impl Config {
         client_metrics: ClientMetrics,
         registry: &mut prom::Registry,
     ) -> Result<Identity> {
-        let name = self.params.server_name.clone();
+        let params = match &self {
+            Self::Linkerd { params, .. }
+            | Self::Spiffe { params, .. }
+            | Self::Files { params, .. } => params,
+        };
+        let name = params.server_name.clone();
         let (store, receiver) = Mode::default().watch(
-            name.clone().into(),
+            params.server_id.clone(),
             name.clone(),
-            &self.params.trust_anchors_pem,
+            &params.trust_anchors_pem,
+            &params.federated_trust_anchors_pem,
         )?;
 
-        let certify = Certify::from(self.certify);
-        let metrics = certify.metrics();
-
-        let addr = self.control.addr.clone();
-
         let (tx, ready) = watch::channel(false);
+        let cred = NotifyReady { store, tx };
 
         // Save to be spawned on an auxiliary runtime.
-        let task = Box::pin({
-            let addr = addr.clone();
-            let svc = self
-                .control
-                .build(dns, client_metrics, registry, receiver.new_client());
-
-            let cred = NotifyReady { store, tx };
-            certify
-                .run(name, cred, svc)
-                .instrument(tracing::debug_span!("identity", server.addr = %addr).or_current())
-        });
+        let (addr, metrics, task): (_, _, Task) = match self {
+            Self::Linkerd {
+                control, certify, ..
+            } => {
+                let certify = Certify::from(certify);
+                let metrics = certify.metrics();
+
+                let addr = control.addr.clone();
+                let task = Box::pin({
+                    let addr = addr.clone();
+                    let svc = control.build(dns, client_metrics, registry, receiver.new_client());
+
+                    certify.run(name, cred, svc).instrument(
+                        tracing::debug_span!("identity", server.addr = %addr).or_current(),
+                    )
+                });
+                (Some(addr), metrics, task)
+            }
+
+            Self::Spiffe { workload_api, .. } => {
+                let socket = workload_api.socket.clone();
+                let client = spiffe::Client::from(workload_api);
+                let metrics = client.metrics();
+
+                let task = Box::pin(client.run(cred).instrument(
+                    tracing::debug_span!("spiffe", socket = %socket.display()).or_current(),
+                ));
+                (None, metrics, task)
+            }
+
+            Self::Files { files, .. } => {
+                let files = Files::from(files);
+                let metrics = files.metrics();
+
+                let task = Box::pin(
+                    files
+                        .run(cred)
+                        .instrument(tracing::debug_span!("files").or_current()),
+                );
+                (None, metrics, task)
+            }
+        };
 
         Ok(Identity {
             addr,
@@ -102,12 +155,22 @@ impl Credentials for NotifyReady {
         let _ = self.tx.send(true);
         Ok(())
     }
+
+    fn set_trust_bundles(
+        &mut self,
+        roots: Vec<DerX509>,
+        federated: HashMap<String, Vec<DerX509>>,
+    ) -> Result<()> {
+        self.store.set_trust_bundles(roots, federated)
+    }
 }
 
 // === impl Identity ===
 
 impl Identity {
-    pub fn addr(&self) -> control::ControlAddr {
+    /// Returns the address of the identity controller, if the proxy's identity
+    /// is certified by Linkerd.
+    pub fn addr(&self) -> Option<control::ControlAddr> {
         self.addr.clone()
     }
 
@@ -7,9 +7,9 @@
 pub mod dst;
 pub mod env;
 pub mod identity;
-pub mod oc_collector;
 pub mod policy;
 pub mod tap;
+pub mod trace_collector;
 
 pub use self::metrics::Metrics;
 use futures::{future, Future, FutureExt};
@@ -60,7 +60,7 @@ pub struct Config {
     pub policy: policy::Config,
     pub admin: admin::Config,
     pub tap: tap::Config,
-    pub oc_collector: oc_collector::Config,
+    pub trace_collector: trace_collector::Config,
 
     /// Grace period for graceful shutdowns.
     ///
@@ -75,10 +75,10 @@ pub struct App {
     dst: ControlAddr,
     identity: identity::Identity,
     inbound_addr: Local<ServerAddr>,
-    oc_collector: oc_collector::OcCollector,
     outbound_addr: Local<ServerAddr>,
     start_proxy: Pin<Box<dyn std::future::Future<Output = ()> + Send + 'static>>,
     tap: tap::Tap,
+    trace_collector: trace_collector::TraceCollector,
 }
 
 impl Config {
@@ -121,10 +121,10 @@ impl Config {
             policy,
             identity,
             inbound,
-            oc_collector,
             outbound,
             gateway,
             tap,
+            trace_collector,
             ..
         } = self;
         debug!("Building app");
@@ -173,22 +173,34 @@ impl Config {
                 .in_scope(|| policy.build(dns, metrics, registry, identity.receiver().new_client()))
         }?;
 
-        debug!(config = ?oc_collector, "Building client");
-        let oc_collector = {
-            let registry = registry.sub_registry_with_prefix("opencensus");
+        debug!(config = ?trace_collector, "Building client");
+        let trace_collector = {
             let identity = identity.receiver().new_client();
             let dns = dns.resolver;
             let client_metrics = metrics.control.clone();
-            let metrics = metrics.opencensus;
-            info_span!("opencensus")
-                .in_scope(|| oc_collector.build(identity, dns, metrics, registry, client_metrics))
+            let oc_metrics = metrics.opencensus;
+            let otel_metrics = metrics.opentelemetry;
+            info_span!("tracing").in_scope(|| {
+                trace_collector.build(
+                    identity,
+                    dns,
+                    oc_metrics,
+                    otel_metrics,
+                    &mut registry,
+                    client_metrics,
+                )
+            })
         }?;
 
         let runtime = ProxyRuntime {
             identity: identity.receiver(),
             metrics: metrics.proxy,
             tap: tap.registry(),
-            span_sink: oc_collector.span_sink(),
+            span_sink: trace_collector.span_sink(),
+            access_log_headers: log_level
+                .access_log()
+                .map(|access_log| access_log.headers().clone())
+                .unwrap_or_default(),
             drain: drain_rx.clone(),
         };
         let inbound = Inbound::new(inbound, runtime.clone());
@@ -259,6 +271,11 @@ impl Config {
         };
 
         metrics::process::register(registry.sub_registry_with_prefix("process"));
+        if let Some(access_log) = log_level.access_log() {
+            access_log
+                .metrics()
+                .register(registry.sub_registry_with_prefix("access_log"));
+        }
         registry.register("proxy_build_info", "Proxy build info", BUILD_INFO.metric());
 
         let admin = {
@@ -290,10 +307,10 @@ impl Config {
             drain: drain_tx,
             identity,
             inbound_addr,
-            oc_collector,
             outbound_addr,
             start_proxy,
             tap,
+            trace_collector,
         })
     }
 
@@ -341,14 +358,14 @@ impl App {
         self.identity.receiver().server_name().clone()
     }
 
-    pub fn identity_addr(&self) -> ControlAddr {
+    pub fn identity_addr(&self) -> Option<ControlAddr> {
         self.identity.addr()
     }
 
-    pub fn opencensus_addr(&self) -> Option<&ControlAddr> {
-        match self.oc_collector {
-            oc_collector::OcCollector::Disabled { .. } => None,
-            oc_collector::OcCollector::Enabled(ref oc) => Some(&oc.addr),
+    pub fn tracing_addr(&self) -> Option<(trace_collector::CollectorProtocol, &ControlAddr)> {
+        match self.trace_collector {
+            trace_collector::TraceCollector::Disabled => None,
+            trace_collector::TraceCollector::Enabled(ref c) => Some((c.protocol, &c.addr)),
         }
     }
 
@@ -357,9 +374,9 @@ impl App {
             admin,
             drain,
             identity,
-            oc_collector,
             start_proxy,
             tap,
+            trace_collector,
             ..
         } = self;
 
@@ -422,8 +439,10 @@ impl App {
                             tokio::spawn(serve.instrument(info_span!("tap").or_current()));
                         }
 
-                        if let oc_collector::OcCollector::Enabled(oc) = oc_collector {
-                            tokio::spawn(oc.task.instrument(info_span!("opencensus").or_current()));
+                        if let trace_collector::TraceCollector::Enabled(collector) = trace_collector
+                        {
+                            let span = info_span!("tracing", protocol = %collector.protocol);
+                            tokio::spawn(collector.task.instrument(span.or_current()));
                         }
 
                         // we don't care if the admin shutdown channel is
@@ -0,0 +1,158 @@
+use linkerd_app_core::{
+    control, dns,
+    http_tracing::SpanSink,
+    identity,
+    metrics::{prom, ControlHttp as HttpMetrics},
+    opencensus, opentelemetry,
+    proxy::http,
+    svc::{self, NewService},
+    trace_context::Sampler,
+    Error,
+};
+use std::{collections::HashMap, future::Future, pin::Pin, str::FromStr};
+
+pub mod oc_collector;
+pub mod otel_collector;
+
+const SPAN_BUFFER_CAPACITY: usize = 100;
+const SERVICE_NAME: &str = "linkerd-proxy";
+
+#[derive(Clone, Debug)]
+pub enum Config {
+    Disabled,
+    Enabled(Box<EnabledConfig>),
+}
+
+#[derive(Clone, Debug)]
+pub struct EnabledConfig {
+    pub control: control::Config,
+    pub attributes: HashMap<String, String>,
+    pub hostname: Option<String>,
+    pub protocol: CollectorProtocol,
+    pub sampler: Sampler,
+}
+
+/// The protocol used to export spans to the trace collector.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CollectorProtocol {
+    #[default]
+    OpenCensus,
+    OpenTelemetry,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid trace collector protocol: {0}")]
+pub struct InvalidProtocol(String);
+
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+type Client =
+    svc::BoxCloneSyncService<http::Request<tonic::body::BoxBody>, http::Response<control::RspBody>>;
+
+pub enum TraceCollector {
+    Disabled,
+    Enabled(Box<EnabledCollector>),
+}
+
+pub struct EnabledCollector {
+    pub addr: control::ControlAddr,
+    pub protocol: CollectorProtocol,
+    pub span_sink: SpanSink,
+    pub task: Task,
+}
+
+// === impl Config ===
+
+impl Config {
+    pub fn build(
+        self,
+        identity: identity::NewClient,
+        dns: dns::Resolver,
+        oc_metrics: opencensus::metrics::Registry,
+        otel_metrics: opentelemetry::metrics::Registry,
+        registry: &mut prom::Registry,
+        client_metrics: HttpMetrics,
+    ) -> Result<TraceCollector, Error> {
+        match self {
+            Config::Disabled => Ok(TraceCollector::Disabled),
+            Config::Enabled(inner) => {
+                let EnabledConfig {
+                    control,
+                    attributes,
+                    hostname,
+                    protocol,
+                    sampler,
+                } = *inner;
+                let addr = control.addr.clone();
+                let registry = registry.sub_registry_with_prefix(protocol.as_str());
+                let svc = control
+                    .build(dns, client_metrics, registry, identity)
+                    .new_service(());
+
+                let (exporter, task) = match protocol {
+                    CollectorProtocol::OpenCensus => {
+                        oc_collector::create_collector(&addr, hostname, attributes, svc, oc_metrics)
+                    }
+                    CollectorProtocol::OpenTelemetry => otel_collector::create_collector(
+                        &addr,
+                        hostname,
+                        attributes,
+                        svc,
+                        otel_metrics,
+                    ),
+                };
+                Ok(TraceCollector::Enabled(Box::new(EnabledCollector {
+                    addr,
+                    protocol,
+                    span_sink: SpanSink { exporter, sampler },
+                    task,
+                })))
+            }
+        }
+    }
+}
+
+// === impl CollectorProtocol ===
+
+impl CollectorProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OpenCensus => "opencensus",
+            Self::OpenTelemetry => "opentelemetry",
+        }
+    }
+}
+
+impl FromStr for CollectorProtocol {
+    type Err = InvalidProtocol;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("opencensus") {
+            Ok(Self::OpenCensus)
+        } else if s.eq_ignore_ascii_case("opentelemetry") {
+            Ok(Self::OpenTelemetry)
+        } else {
+            Err(InvalidProtocol(s.to_string()))
+        }
+    }
+}
+
+impl std::fmt::Display for CollectorProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenCensus => "OpenCensus".fmt(f),
+            Self::OpenTelemetry => "OpenTelemetry".fmt(f),
+        }
+    }
+}
+
+// === impl TraceCollector ===
+
+impl TraceCollector {
+    pub fn span_sink(&self) -> Option<SpanSink> {
+        match self {
+            TraceCollector::Disabled => None,
+            TraceCollector::Enabled(inner) => Some(inner.span_sink.clone()),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::{Client, Task, SERVICE_NAME, SPAN_BUFFER_CAPACITY};
+use linkerd_app_core::{control::ControlAddr, http_tracing::Exporter, opencensus};
+use linkerd_opencensus::{metrics, proto};
+use std::{collections::HashMap, time::SystemTime};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::Instrument;
+
+pub(super) fn create_collector(
+    addr: &ControlAddr,
+    hostname: Option<String>,
+    attributes: HashMap<String, String>,
+    svc: Client,
+    metrics: metrics::Registry,
+) -> (Exporter, Task) {
+    let (span_sink, spans_rx) = mpsc::channel(SPAN_BUFFER_CAPACITY);
+    let spans_rx = ReceiverStream::new(spans_rx);
+
+    let task: Task = {
+        use self::proto::agent::common::v1 as oc;
+
+        let node = oc::Node {
+            identifier: Some(oc::ProcessIdentifier {
+                host_name: hostname.unwrap_or_default(),
+                pid: std::process::id(),
+                start_timestamp: Some(SystemTime::now().into()),
+            }),
+            service_info: Some(oc::ServiceInfo {
+                name: SERVICE_NAME.to_string(),
+            }),
+            attributes,
+            ..oc::Node::default()
+        };
+
+        Box::pin(
+            opencensus::export_spans(svc, node, spans_rx, metrics)
+                .instrument(tracing::debug_span!("opencensus", peer.addr = %addr).or_current()),
+        )
+    };
+
+    (Exporter::OpenCensus(span_sink), task)
+}
@@ -0,0 +1,65 @@
+use super::{Client, Task, SERVICE_NAME, SPAN_BUFFER_CAPACITY};
+use linkerd_app_core::{control::ControlAddr, http_tracing::Exporter, opentelemetry};
+use linkerd_opentelemetry::{
+    metrics,
+    proto::{
+        common::v1::{any_value, AnyValue, KeyValue},
+        resource::v1::Resource,
+    },
+};
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::Instrument;
+
+pub(super) fn create_collector(
+    addr: &ControlAddr,
+    hostname: Option<String>,
+    attributes: HashMap<String, String>,
+    svc: Client,
+    metrics: metrics::Registry,
+) -> (Exporter, Task) {
+    let (span_sink, spans_rx) = mpsc::channel(SPAN_BUFFER_CAPACITY);
+    let spans_rx = ReceiverStream::new(spans_rx);
+
+    let task: Task = {
+        // Attributes are described by OpenTelemetry's resource semantic
+        // conventions. User-provided attributes may not override them.
+        let mut resource = vec![
+            attr(
+                "service.name",
+                any_value::Value::StringValue(SERVICE_NAME.to_string()),
+            ),
+            attr(
+                "process.pid",
+                any_value::Value::IntValue(std::process::id().into()),
+            ),
+        ];
+        if let Some(hostname) = hostname {
+            resource.push(attr("host.name", any_value::Value::StringValue(hostname)));
+        }
+        for (k, v) in attributes {
+            if !resource.iter().any(|kv| kv.key == k) {
+                resource.push(attr(k, any_value::Value::StringValue(v)));
+            }
+        }
+        let resource = Resource {
+            attributes: resource,
+            dropped_attributes_count: 0,
+        };
+
+        Box::pin(
+            opentelemetry::export_spans(svc, resource, spans_rx, metrics)
+                .instrument(tracing::debug_span!("opentelemetry", peer.addr = %addr).or_current()),
+        )
+    };
+
+    (Exporter::OpenTelemetry(span_sink), task)
+}
+
+fn attr(key: impl Into<String>, value: any_value::Value) -> KeyValue {
+    KeyValue {
+        key: key.into(),
+        value: Some(AnyValue { value: Some(value) }),
+    }
+}
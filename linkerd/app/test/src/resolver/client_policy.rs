@@ -46,6 +46,7 @@ impl ClientPolicies {
                     let load = Load::PeakEwma(PeakEwma {
                         default_rtt: Duration::from_millis(30),
                         decay: Duration::from_secs(10),
+                        slow_start: None,
                     });
                     let disco = EndpointDiscovery::DestinationGet {
                         path: addr.to_string(),
@@ -62,6 +63,7 @@ impl ClientPolicies {
                 meta: Meta::new_default("test"),
                 queue,
                 dispatcher,
+                tls: None,
             }
         };
 
@@ -74,6 +76,8 @@ impl ClientPolicies {
                     filters: Arc::new([]),
                     failure_policy: Default::default(),
                     request_timeout: None,
+                    retry: None,
+                    hedge: None,
                     distribution: RouteDistribution::FirstAvailable(Arc::new([RouteBackend {
                         filters: Arc::new([]),
                         backend: backend.clone(),
@@ -99,6 +103,8 @@ impl ClientPolicies {
                     filters: Arc::new([]),
                     failure_policy: Default::default(),
                     request_timeout: None,
+                    retry: None,
+                    hedge: None,
                     distribution: RouteDistribution::FirstAvailable(Arc::new([RouteBackend {
                         filters: Arc::new([]),
                         backend: backend.clone(),
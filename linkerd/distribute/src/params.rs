@@ -21,6 +21,11 @@ pub enum Distribution<K> {
     /// A distribution that uses the first available backend when randomly
     /// selecting over a weighted distribution of backends.
     RandomAvailable(Arc<WeightedKeys<K>>),
+
+    /// A distribution that randomly selects a backend over a weighted
+    /// distribution of backends, regardless of the backend's availability.
+    /// Requests wait for the selected backend to become ready.
+    RandomWeighted(Arc<WeightedKeys<K>>),
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -79,19 +84,24 @@ impl<K> Distribution<K> {
         if keys.len() < 2 {
             return Ok(Self::first_available(keys));
         }
-        // Error if the distribution is invalid.
-        let _index = WeightedIndex::new(weights.iter().copied())?;
-        Ok(Self::RandomAvailable(Arc::new(WeightedKeys {
-            keys,
-            weights,
-        })))
+        Ok(Self::RandomAvailable(WeightedKeys::new(keys, weights)?))
+    }
+
+    pub fn random_weighted<T: IntoIterator<Item = (K, u32)>>(
+        iter: T,
+    ) -> Result<Self, WeightedError> {
+        let (keys, weights): (Vec<_>, Vec<_>) = iter.into_iter().filter(|(_, w)| *w > 0).unzip();
+        if keys.len() < 2 {
+            return Ok(Self::first_available(keys));
+        }
+        Ok(Self::RandomWeighted(WeightedKeys::new(keys, weights)?))
     }
 
     pub(crate) fn keys(&self) -> &[K] {
         match self {
             Self::Empty => &[],
             Self::FirstAvailable(keys) => keys,
-            Self::RandomAvailable(keys) => keys.keys(),
+            Self::RandomAvailable(keys) | Self::RandomWeighted(keys) => keys.keys(),
         }
     }
 }
@@ -99,6 +109,12 @@ impl<K> Distribution<K> {
 // === impl WeightedKeys ===
 
 impl<K> WeightedKeys<K> {
+    fn new(keys: Vec<K>, weights: Vec<u32>) -> Result<Arc<Self>, WeightedError> {
+        // Error if the distribution is invalid.
+        let _index = WeightedIndex::new(weights.iter().copied())?;
+        Ok(Arc::new(Self { keys, weights }))
+    }
+
     pub(crate) fn keys(&self) -> &[K] {
         &self.keys
     }
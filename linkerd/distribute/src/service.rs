@@ -2,7 +2,7 @@ use super::{Distribution, WeightedKeys};
 use indexmap::IndexMap;
 use linkerd_stack::Service;
 use rand::{
-    distributions::{Distribution as _, WeightedError},
+    distributions::{Distribution as _, WeightedError, WeightedIndex},
     rngs::SmallRng,
     SeedableRng,
 };
@@ -33,6 +33,15 @@ enum Selection<K> {
         keys: Arc<WeightedKeys<K>>,
         rng: SmallRng,
     },
+    RandomWeighted {
+        index: WeightedIndex<u32>,
+        rng: SmallRng,
+
+        /// Stores the index of the backend that was selected for the next
+        /// request while it is not ready, so that the selection is not
+        /// biased towards available backends.
+        selected_idx: Option<usize>,
+    },
 }
 
 // === impl Distribute ===
@@ -119,6 +128,35 @@ where
                     }
                 }
             }
+
+            // Choose a random index (via the weighted distribution) and wait
+            // for that backend to become ready. The backend's queue is
+            // expected to fail requests if it does not become ready in time.
+            Selection::RandomWeighted {
+                ref index,
+                ref mut rng,
+                ref mut selected_idx,
+            } => {
+                let idx = *selected_idx.get_or_insert_with(|| index.sample(rng));
+                let (_, svc) = self
+                    .backends
+                    .get_index_mut(idx)
+                    .expect("distributions must not reference unknown backends");
+
+                // If the backend fails, the selection is reset so that the
+                // next request is distributed anew.
+                let ready = svc.poll_ready(cx).map_err(|error| {
+                    *selected_idx = None;
+                    error
+                })?;
+                if ready.is_ready() {
+                    *selected_idx = None;
+                    self.ready_idx = Some(idx);
+                    return Poll::Ready(Ok(()));
+                }
+                tracing::trace!(idx, "selected backend is not ready");
+                return Poll::Pending;
+            }
         }
 
         debug_assert!(self.ready_idx.is_none());
@@ -173,6 +211,11 @@ impl<K> From<Distribution<K>> for Selection<K> {
                 keys,
                 rng: SmallRng::from_rng(rand::thread_rng()).expect("RNG must initialize"),
             },
+            Distribution::RandomWeighted(keys) => Self::RandomWeighted {
+                index: keys.index(),
+                rng: SmallRng::from_rng(rand::thread_rng()).expect("RNG must initialize"),
+                selected_idx: None,
+            },
         }
     }
 }
@@ -186,6 +229,11 @@ impl<K> Clone for Selection<K> {
                 keys: keys.clone(),
                 rng: SmallRng::from_rng(rand::thread_rng()).expect("RNG must initialize"),
             },
+            Self::RandomWeighted { index, .. } => Self::RandomWeighted {
+                index: index.clone(),
+                rng: SmallRng::from_rng(rand::thread_rng()).expect("RNG must initialize"),
+                selected_idx: None,
+            },
         }
     }
 }
@@ -342,4 +390,42 @@ mod tests {
         }
         assert_ready_ok!(call.poll());
     }
+
+    #[test]
+    fn random_weighted_follows_weight() {
+        let (mulder, mut mulder_ctl) = mock::pair();
+        let (scully, mut scully_ctl) = mock::pair();
+        let (skinner, mut skinner_ctl) = mock::pair();
+        let mut dist_svc = mock::Spawn::new(Distribute::new(
+            vec![("mulder", mulder), ("scully", scully), ("skinner", skinner)]
+                .into_iter()
+                .collect(),
+            Distribution::random_weighted([("mulder", 1), ("scully", 99998), ("skinner", 1)])
+                .unwrap(),
+        ));
+
+        // The selected backend is used even though other backends are ready.
+        mulder_ctl.allow(1);
+        scully_ctl.allow(0);
+        skinner_ctl.allow(1);
+        assert_pending!(dist_svc.poll_ready());
+        assert_pending!(dist_svc.poll_ready());
+
+        scully_ctl.allow(1);
+        assert!(dist_svc.is_woken());
+        assert_ready_ok!(dist_svc.poll_ready());
+        assert_eq!(dist_svc.get_ref().ready_idx, Some(1));
+        let mut call = task::spawn(dist_svc.call(()));
+        match assert_ready!(scully_ctl.poll_request()) {
+            Some(((), rsp)) => rsp.send_response(()),
+            _ => panic!("expected request"),
+        }
+        assert_ready_ok!(call.poll());
+    }
+
+    #[test]
+    fn random_weighted_single_backend() {
+        let dist = Distribution::random_weighted([("mulder", 1), ("scully", 0)]).unwrap();
+        assert_eq!(dist, Distribution::FirstAvailable(Arc::new(["mulder"])));
+    }
 }
@@ -1,12 +1,23 @@
 #![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
 #![forbid(unsafe_code)]
 
+mod outbound;
+mod record;
+#[cfg(test)]
+mod tests;
+
+pub use self::{
+    outbound::{NewOutboundAccessLog, OutboundAccessLog},
+    record::{AccessLogField, AccessLogSpan, NewRecordAccessLog, RecordAccessLog},
+};
 use futures_core::TryFuture;
 use linkerd_identity as identity;
 use linkerd_proxy_transport::{ClientAddr, Remote};
 use linkerd_stack as svc;
 use linkerd_tls as tls;
-use linkerd_tracing::access_log::TRACE_TARGET;
+use linkerd_tracing::access_log::{
+    self, CaptureHeaders, REQUEST_HEADERS, RESPONSE_HEADERS, TRACE_TARGET,
+};
 use pin_project::pin_project;
 use std::{
     future::Future,
@@ -21,6 +32,7 @@ use tracing::{field, span, Level, Span};
 
 #[derive(Clone, Debug)]
 pub struct NewAccessLog<N> {
+    headers: CaptureHeaders,
     inner: N,
 }
 
@@ -29,10 +41,12 @@ pub struct AccessLogContext<S> {
     inner: S,
     client_addr: SocketAddr,
     client_id: Option<identity::Id>,
+    headers: CaptureHeaders,
 }
 
 struct ResponseFutureInner {
     span: Span,
+    headers: CaptureHeaders,
     start: Instant,
     processing: Duration,
 }
@@ -58,9 +72,15 @@ impl<N> NewAccessLog<N> {
     /// Recording the access log will introduce additional overhead in the
     /// request path, but this is largely avoided when access logging is not
     /// enabled.
+    ///
+    /// The values of the given request and response headers are captured in
+    /// each access log.
     #[inline]
-    pub fn layer() -> impl svc::layer::Layer<N, Service = Self> {
-        svc::layer::mk(|inner| NewAccessLog { inner })
+    pub fn layer(headers: CaptureHeaders) -> impl svc::layer::Layer<N, Service = Self> {
+        svc::layer::mk(move |inner| NewAccessLog {
+            headers: headers.clone(),
+            inner,
+        })
     }
 }
 
@@ -82,6 +102,7 @@ where
             inner,
             client_addr,
             client_id,
+            headers: self.headers.clone(),
         }
     }
 }
@@ -100,24 +121,6 @@ where
     }
 
     fn call(&mut self, request: http::Request<B1>) -> Self::Future {
-        let get_header = |name: http::header::HeaderName| {
-            request
-                .headers()
-                .get(name)
-                .and_then(|x| x.to_str().ok())
-                .unwrap_or_default()
-        };
-
-        let trace_id = || {
-            let headers = request.headers();
-            headers
-                .get("x-b3-traceid")
-                .or_else(|| headers.get("x-request-id"))
-                .or_else(|| headers.get("x-amzn-trace-id"))
-                .and_then(|x| x.to_str().ok())
-                .unwrap_or_default()
-        };
-
         let client_id: std::borrow::Cow<'_, str> = self
             .client_id
             .as_ref()
@@ -131,14 +134,17 @@ where
             method = request.method().as_str(),
             uri =  %request.uri(),
             version = ?request.version(),
-            trace_id = trace_id(),
-            request_bytes = get_header(http::header::CONTENT_LENGTH),
+            trace_id = trace_id(&request),
+            request_bytes = get_header(&request, http::header::CONTENT_LENGTH),
             status = field::Empty,
             response_bytes = field::Empty,
             total_ns = field::Empty,
             processing_ns = field::Empty,
-            user_agent = get_header(http::header::USER_AGENT),
-            host = get_header(http::header::HOST),
+            user_agent = get_header(&request, http::header::USER_AGENT),
+            host = get_header(&request, http::header::HOST),
+            referer = get_header(&request, http::header::REFERER),
+            request_headers = field::Empty,
+            response_headers = field::Empty,
         );
 
         // The access log span is only enabled by the `tracing` subscriber if
@@ -151,9 +157,16 @@ where
             };
         }
 
+        record_headers(
+            &span,
+            REQUEST_HEADERS,
+            self.headers.request(),
+            request.headers(),
+        );
         AccessLogFuture {
             data: Some(ResponseFutureInner {
                 span,
+                headers: self.headers.clone(),
                 start: Instant::now(),
                 processing: Duration::from_secs(0),
             }),
@@ -204,11 +217,46 @@ where
         span.record("status", response.status().as_u16());
         span.record("total_ns", &field::display(total_ns));
         span.record("processing_ns", &field::display(processing_ns));
+        record_headers(
+            span,
+            RESPONSE_HEADERS,
+            data.headers.response(),
+            response.headers(),
+        );
 
         Poll::Ready(Ok(response))
     }
 }
 
+fn get_header<B>(request: &http::Request<B>, name: http::header::HeaderName) -> &str {
+    request
+        .headers()
+        .get(name)
+        .and_then(|x| x.to_str().ok())
+        .unwrap_or_default()
+}
+
+/// Records the values of the named headers on an access log span.
+fn record_headers(span: &Span, field: &str, names: &[Box<str>], headers: &http::HeaderMap) {
+    if names.is_empty() {
+        return;
+    }
+    let values = names
+        .iter()
+        .map(|name| headers.get(&**name).and_then(|v| v.to_str().ok()));
+    span.record(field, access_log::encode_headers(values).as_str());
+}
+
+fn trace_id<B>(request: &http::Request<B>) -> &str {
+    let headers = request.headers();
+    headers
+        .get("x-b3-traceid")
+        .or_else(|| headers.get("x-request-id"))
+        .or_else(|| headers.get("x-amzn-trace-id"))
+        .and_then(|x| x.to_str().ok())
+        .unwrap_or_default()
+}
+
 #[inline]
 fn now() -> humantime::Rfc3339Timestamp {
     humantime::format_rfc3339(SystemTime::now())
@@ -0,0 +1,140 @@
+use super::{
+    get_header, now, record_headers, trace_id, AccessLogFuture, AccessLogSpan, ResponseFutureInner,
+    TRACE_TARGET,
+};
+use linkerd_proxy_http::ClientHandle;
+use linkerd_stack as svc;
+use linkerd_tracing::access_log::{CaptureHeaders, REQUEST_HEADERS};
+use std::{
+    task::{Context, Poll},
+    time::Duration,
+};
+use svc::NewService;
+use tokio::time::Instant;
+use tracing::{field, span, Level};
+
+/// A `NewService` that records access logs for requests sent by the outbound
+/// proxy.
+///
+/// In addition to the fields recorded by the inbound access log, outbound
+/// access logs describe how each request was routed: the matched route, the
+/// chosen backend and endpoint, and the number of times it was retried. These
+/// are recorded by inner stacks via the [`AccessLogSpan`] request extension.
+#[derive(Clone, Debug)]
+pub struct NewOutboundAccessLog<N> {
+    headers: CaptureHeaders,
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub struct OutboundAccessLog<S> {
+    headers: CaptureHeaders,
+    inner: S,
+}
+
+// === impl NewOutboundAccessLog ===
+
+impl<N> NewOutboundAccessLog<N> {
+    /// Returns a new `NewOutboundAccessLog` layer that wraps an inner service
+    /// with access logging middleware.
+    ///
+    /// As with the inbound access log, the span is only enabled when access
+    /// logging is enabled, in which case an [`AccessLogSpan`] is added to each
+    /// request's extensions.
+    #[inline]
+    pub fn layer(headers: CaptureHeaders) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(move |inner| Self {
+            headers: headers.clone(),
+            inner,
+        })
+    }
+}
+
+impl<N, T> NewService<T> for NewOutboundAccessLog<N>
+where
+    N: NewService<T>,
+{
+    type Service = OutboundAccessLog<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        OutboundAccessLog {
+            headers: self.headers.clone(),
+            inner: self.inner.new_service(target),
+        }
+    }
+}
+
+// === impl OutboundAccessLog ===
+
+impl<S, B1, B2> svc::Service<http::Request<B1>> for OutboundAccessLog<S>
+where
+    S: svc::Service<http::Request<B1>, Response = http::Response<B2>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = AccessLogFuture<S::Future>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B1>) -> Self::Future {
+        // The HTTP server sets a ClientHandle with the client's address.
+        let client_addr = request
+            .extensions()
+            .get::<ClientHandle>()
+            .map(|ClientHandle { addr, .. }| addr.to_string());
+
+        let span = span!(target: TRACE_TARGET, Level::INFO, "http",
+            client.addr = client_addr.as_deref().unwrap_or("-"),
+            client.id = "-",
+            timestamp = %now(),
+            method = request.method().as_str(),
+            uri =  %request.uri(),
+            version = ?request.version(),
+            trace_id = trace_id(&request),
+            request_bytes = get_header(&request, http::header::CONTENT_LENGTH),
+            status = field::Empty,
+            response_bytes = field::Empty,
+            total_ns = field::Empty,
+            processing_ns = field::Empty,
+            user_agent = get_header(&request, http::header::USER_AGENT),
+            host = get_header(&request, http::header::HOST),
+            referer = get_header(&request, http::header::REFERER),
+            request_headers = field::Empty,
+            response_headers = field::Empty,
+            route = field::Empty,
+            backend = field::Empty,
+            endpoint.addr = field::Empty,
+            retries = field::Empty,
+        );
+
+        // The access log span is only enabled by the `tracing` subscriber if
+        // access logs are being recorded. If it's disabled, we can skip
+        // recording additional data in the response future.
+        if span.is_disabled() {
+            return AccessLogFuture {
+                data: None,
+                inner: self.inner.call(request),
+            };
+        }
+
+        record_headers(
+            &span,
+            REQUEST_HEADERS,
+            self.headers.request(),
+            request.headers(),
+        );
+        request.extensions_mut().insert(AccessLogSpan(span.clone()));
+        AccessLogFuture {
+            data: Some(ResponseFutureInner {
+                span,
+                headers: self.headers.clone(),
+                start: Instant::now(),
+                processing: Duration::from_secs(0),
+            }),
+            inner: self.inner.call(request),
+        }
+    }
+}
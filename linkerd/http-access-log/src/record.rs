@@ -0,0 +1,120 @@
+use linkerd_stack as svc;
+use std::{
+    fmt,
+    net::SocketAddr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use svc::{ExtractParam, NewService};
+use tracing::{field::display, Span};
+
+/// A handle to a request's access log span.
+///
+/// When access logging is enabled, the outbound access log inserts this handle
+/// into each request's extensions so that inner stacks can record how the
+/// request was routed.
+#[derive(Clone, Debug)]
+pub struct AccessLogSpan(pub(crate) Span);
+
+/// Describes how a request was routed, as recorded in its access log.
+#[derive(Clone, Debug)]
+pub enum AccessLogField {
+    /// The name of the route that matched the request.
+    Route(Arc<str>),
+    /// The name of the backend that the request was dispatched to.
+    Backend(Arc<str>),
+    /// The address of the endpoint that the request was sent to.
+    Endpoint(SocketAddr),
+}
+
+/// A `NewService` that records a target-derived [`AccessLogField`] on the
+/// access log span of each request, if one is present.
+#[derive(Clone, Debug)]
+pub struct NewRecordAccessLog<X, N> {
+    extract: X,
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordAccessLog<S> {
+    field: AccessLogField,
+    inner: S,
+}
+
+// === impl AccessLogSpan ===
+
+impl AccessLogSpan {
+    /// Records a detail about how the request was routed.
+    pub fn record(&self, field: &AccessLogField) {
+        match field {
+            AccessLogField::Route(name) => self.0.record("route", &**name),
+            AccessLogField::Backend(name) => self.0.record("backend", &**name),
+            AccessLogField::Endpoint(addr) => self.0.record("endpoint.addr", display(addr)),
+        };
+    }
+
+    /// Records the number of times the request was retried.
+    pub fn record_retries(&self, retries: usize) {
+        self.0.record("retries", retries);
+    }
+}
+
+// === impl AccessLogField ===
+
+impl AccessLogField {
+    pub fn route(name: impl fmt::Display) -> Self {
+        Self::Route(name.to_string().into())
+    }
+
+    pub fn backend(name: impl fmt::Display) -> Self {
+        Self::Backend(name.to_string().into())
+    }
+}
+
+// === impl NewRecordAccessLog ===
+
+impl<X: Clone, N> NewRecordAccessLog<X, N> {
+    pub fn layer_via(extract: X) -> impl svc::layer::Layer<N, Service = Self> + Clone {
+        svc::layer::mk(move |inner| Self {
+            extract: extract.clone(),
+            inner,
+        })
+    }
+}
+
+impl<T, X, N> NewService<T> for NewRecordAccessLog<X, N>
+where
+    X: ExtractParam<AccessLogField, T>,
+    N: NewService<T>,
+{
+    type Service = RecordAccessLog<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let field = self.extract.extract_param(&target);
+        let inner = self.inner.new_service(target);
+        RecordAccessLog { field, inner }
+    }
+}
+
+// === impl RecordAccessLog ===
+
+impl<B, S> svc::Service<http::Request<B>> for RecordAccessLog<S>
+where
+    S: svc::Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if let Some(span) = req.extensions().get::<AccessLogSpan>() {
+            span.record(&self.field);
+        }
+        self.inner.call(req)
+    }
+}
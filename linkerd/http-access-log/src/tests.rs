@@ -0,0 +1,65 @@
+use super::*;
+use linkerd_stack::{layer::Layer, service_fn, ServiceExt};
+use linkerd_tracing::test::capture_access_logs;
+use std::convert::Infallible;
+
+#[derive(Clone, Debug)]
+struct Target;
+
+#[tokio::test(flavor = "current_thread")]
+async fn outbound_records_routing() {
+    let (_trace, logs) = capture_access_logs();
+
+    // Inner stacks record the route, backend, and endpoint, and the retry
+    // count is recorded as the request completes.
+    let svc = mk_outbound(|req: http::Request<()>| {
+        let span = req
+            .extensions()
+            .get::<AccessLogSpan>()
+            .expect("access log span must be set");
+        span.record_retries(2);
+        http::Response::builder()
+            .status(http::StatusCode::CREATED)
+            .body(())
+            .unwrap()
+    });
+    let req = http::Request::get("http://example.com/").body(()).unwrap();
+    let rsp = svc.oneshot(req).await.unwrap();
+    assert_eq!(rsp.status(), http::StatusCode::CREATED);
+
+    let log = logs.next().expect("request must be logged");
+    assert_eq!(log["method"], "GET");
+    assert_eq!(log["status"], "201");
+    assert_eq!(log["route"], "route-a");
+    assert_eq!(log["backend"], "backend-b");
+    assert_eq!(log["endpoint.addr"], "192.0.2.5:8080");
+    assert_eq!(log["retries"], "2");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn outbound_omits_span_when_disabled() {
+    // No access log subscriber is set, so the span is disabled and no span is
+    // added to requests.
+    let svc = mk_outbound(|req: http::Request<()>| {
+        assert!(req.extensions().get::<AccessLogSpan>().is_none());
+        http::Response::new(())
+    });
+    let req = http::Request::get("http://example.com/").body(()).unwrap();
+    svc.oneshot(req).await.unwrap();
+}
+
+/// Builds an outbound access log over stacks that record routing details and
+/// respond to requests with `rsp`.
+fn mk_outbound(
+    rsp: impl Fn(http::Request<()>) -> http::Response<()> + Clone,
+) -> impl svc::Service<http::Request<()>, Response = http::Response<()>, Error = Infallible> {
+    let svc = service_fn(move |req| std::future::ready(Ok(rsp(req))));
+    let endpoint = |_: &Target| AccessLogField::Endpoint(([192, 0, 2, 5], 8080).into());
+    let stack = NewOutboundAccessLog::layer(Default::default()).layer(
+        NewRecordAccessLog::layer_via(|_: &Target| AccessLogField::route("route-a")).layer(
+            NewRecordAccessLog::layer_via(|_: &Target| AccessLogField::backend("backend-b"))
+                .layer(NewRecordAccessLog::layer_via(endpoint).layer(move |_: Target| svc.clone())),
+        ),
+    );
+    stack.new_service(Target)
+}
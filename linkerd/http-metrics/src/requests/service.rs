@@ -3,7 +3,7 @@ use futures::{ready, TryFuture};
 use http_body::Body;
 use linkerd_error::Error;
 use linkerd_http_classify::{ClassifyEos, ClassifyResponse};
-use linkerd_metrics::NewMetrics;
+use linkerd_metrics::{Exemplar, NewMetrics};
 use linkerd_stack::Proxy;
 use parking_lot::Mutex;
 use pin_project::{pin_project, pinned_drop};
@@ -44,6 +44,7 @@ where
     classify: Option<C>,
     metrics: Option<Arc<Mutex<Metrics<C::Class>>>>,
     stream_open_at: Instant,
+    exemplar: Option<Exemplar>,
     #[pin]
     inner: F,
 }
@@ -72,6 +73,7 @@ where
     classify: Option<C>,
     metrics: Option<Arc<Mutex<Metrics<C::Class>>>>,
     stream_open_at: Instant,
+    exemplar: Option<Exemplar>,
     latency_recorded: bool,
     #[pin]
     inner: B,
@@ -118,6 +120,14 @@ where
     c.unwrap_or_default()
 }
 
+/// Identifies the sampled trace, if any, that a request is part of, so that
+/// the request's latency can be linked to its trace.
+#[inline]
+fn trace_exemplar<B>(req: &http::Request<B>) -> Option<Exemplar> {
+    let (trace_id, span_id) = linkerd_trace_context::sampled_span_ids(req)?;
+    Some(Exemplar::new(trace_id, span_id))
+}
+
 impl<C, P, S, A, B> Proxy<http::Request<A>, S> for HttpMetrics<P, C>
 where
     P: Proxy<http::Request<RequestBody<A, C::Class>>, S, Response = http::Response<B>>,
@@ -144,6 +154,7 @@ where
             }
         }
 
+        let exemplar = trace_exemplar(&req);
         let req = {
             let (head, inner) = req.into_parts();
             let body = RequestBody {
@@ -157,6 +168,7 @@ where
             classify: Some(classify_unwrap_if_debug_else_default(&req)),
             metrics: self.metrics.clone(),
             stream_open_at: Instant::now(),
+            exemplar,
             inner: self.inner.proxy(svc, req),
         }
     }
@@ -192,6 +204,7 @@ where
             }
         }
 
+        let exemplar = trace_exemplar(&req);
         let req = {
             let (head, inner) = req.into_parts();
             let body = RequestBody {
@@ -205,6 +218,7 @@ where
             classify: Some(classify_unwrap_if_debug_else_default(&req)),
             metrics: self.metrics.clone(),
             stream_open_at: Instant::now(),
+            exemplar,
             inner: self.inner.call(req),
         }
     }
@@ -235,6 +249,7 @@ where
                     classify,
                     metrics,
                     stream_open_at: *this.stream_open_at,
+                    exemplar: this.exemplar.take(),
                     latency_recorded: false,
                     inner,
                 };
@@ -320,6 +335,7 @@ where
             status: http::StatusCode::OK,
             inner: B::default(),
             stream_open_at: Instant::now(),
+            exemplar: None,
             classify: None,
             metrics: None,
             latency_recorded: false,
@@ -351,7 +367,9 @@ where
             .or_insert_with(StatusMetrics::default);
 
         let elapsed = now.saturating_duration_since(*this.stream_open_at);
-        status_metrics.latency.add(elapsed);
+        status_metrics
+            .latency
+            .add_with_exemplar(elapsed, this.exemplar.take());
 
         *this.latency_recorded = true;
     }
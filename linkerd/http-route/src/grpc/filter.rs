@@ -1,3 +1,4 @@
 pub mod inject_failure;
 
 pub use self::inject_failure::{Distribution, FailureResponse, InjectFailure};
+pub use crate::http::filter::{Delay, InjectDelay};
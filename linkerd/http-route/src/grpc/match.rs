@@ -75,6 +75,11 @@ impl std::cmp::Ord for RouteMatch {
 // === impl MatchRpc ===
 
 impl MatchRpc {
+    /// Returns true if the given request path names a matching RPC.
+    pub fn is_match(&self, path: &str) -> bool {
+        self.match_length(path).is_some()
+    }
+
     fn match_length(&self, path: &str) -> Option<RpcMatch> {
         let mut summary = RpcMatch::default();
 
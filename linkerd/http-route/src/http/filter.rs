@@ -1,15 +1,84 @@
+pub mod inject_delay;
 pub mod inject_failure;
 pub mod modify_header;
 pub mod redirect;
+pub mod url_rewrite;
 
 pub use self::{
+    inject_delay::{Delay, InjectDelay},
     inject_failure::{Distribution, FailureResponse, InjectFailure},
     modify_header::ModifyHeader,
     redirect::{InvalidRedirect, RedirectRequest, Redirection},
+    url_rewrite::{InvalidUrlRewrite, UrlRewrite},
 };
+use super::RouteMatch;
+use http::uri::{InvalidUri, PathAndQuery};
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum ModifyPath {
     ReplaceFullPath(String),
     ReplacePrefixMatch(String),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum InvalidModifyPath {
+    #[error("the path prefix may only be replaced when a path prefix match applied")]
+    ReplacePrefix,
+
+    #[error("invalid path: {0}")]
+    Path(#[from] InvalidUri),
+}
+
+// === impl ModifyPath ===
+
+impl ModifyPath {
+    /// Returns the modified path and query for a request.
+    ///
+    /// When `collapse_separator` is set and a path prefix is replaced with a
+    /// prefix that ends with `/` (e.g. `/`), the remaining path's leading `/`
+    /// is dropped so that the separator is not doubled.
+    //
+    // XXX This function probably does more allocation that is strictly needed.
+    // We may want to optimize it as it settles.
+    pub(crate) fn path_and_query(
+        &self,
+        orig_uri: &http::Uri,
+        rm: &RouteMatch,
+        collapse_separator: bool,
+    ) -> Result<PathAndQuery, InvalidModifyPath> {
+        use super::r#match::PathMatch;
+
+        match self {
+            // If a full path (potentially including a query) is specified, use
+            // it.
+            Self::ReplaceFullPath(p) => p.clone().try_into().map_err(Into::into),
+
+            // If a prefix rewrite is specified, use the original query
+            // parameters.
+            //
+            // XXX #fragments are not included in the rewritten path; but
+            // fragments are generally not transmitted to servers.
+            Self::ReplacePrefixMatch(new_pfx) => match rm.route.path() {
+                PathMatch::Prefix(pfx_len) if *pfx_len <= orig_uri.path().len() => {
+                    let mut new_path = new_pfx.to_string();
+                    let (_, mut rest) = orig_uri.path().split_at(*pfx_len);
+                    if collapse_separator && new_path.ends_with('/') {
+                        rest = rest.strip_prefix('/').unwrap_or(rest);
+                    } else if !rest.is_empty() && !rest.starts_with('/') {
+                        new_path.push('/');
+                    }
+                    new_path.push_str(rest);
+                    if let Some(q) = orig_uri.query() {
+                        new_path.push('?');
+                        new_path.push_str(q);
+                    }
+                    new_path.try_into().map_err(Into::into)
+                }
+
+                // If the matched rule was not a prefix match, the filter is
+                // invalid. This should cause us to fail requests with a 5XX.
+                _ => Err(InvalidModifyPath::ReplacePrefix),
+            },
+        }
+    }
+}
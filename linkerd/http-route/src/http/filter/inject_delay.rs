@@ -0,0 +1,70 @@
+use super::Distribution;
+use std::time::Duration;
+
+/// A filter that delays requests at a predictable rate.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct InjectDelay {
+    pub delay: Delay,
+    pub distribution: Distribution,
+}
+
+/// The amount of time by which a request is delayed.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Delay {
+    /// Every affected request is delayed by the same duration.
+    Fixed(Duration),
+
+    /// Each affected request is delayed by a duration sampled uniformly from
+    /// the inclusive range `min..=max`.
+    Uniform { min: Duration, max: Duration },
+}
+
+// === impl InjectDelay ===
+
+impl InjectDelay {
+    /// Returns the duration by which a request should be delayed, if the
+    /// request is selected by the filter's distribution.
+    pub fn apply(&self) -> Option<Duration> {
+        use rand::distributions::Distribution;
+
+        let mut rng = rand::thread_rng();
+        if !self.distribution.sample(&mut rng) {
+            return None;
+        }
+
+        let delay = match self.delay {
+            Delay::Fixed(delay) => delay,
+            Delay::Uniform { min, max } if min < max => rand::Rng::gen_range(&mut rng, min..=max),
+            Delay::Uniform { min, .. } => min,
+        };
+        Some(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_within_range() {
+        let min = Duration::from_millis(10);
+        let max = Duration::from_millis(20);
+        let filter = InjectDelay {
+            delay: Delay::Uniform { min, max },
+            distribution: Distribution::default(),
+        };
+        for _ in 0..100 {
+            let delay = filter.apply().expect("every request must be delayed");
+            assert!(min <= delay && delay <= max, "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn skips_unselected_requests() {
+        let filter = InjectDelay {
+            delay: Delay::Fixed(Duration::from_secs(1)),
+            distribution: Distribution::from_ratio(0, 1).unwrap(),
+        };
+        assert_eq!(filter.apply(), None);
+    }
+}
@@ -0,0 +1,218 @@
+use super::{InvalidModifyPath, ModifyPath};
+use crate::http::RouteMatch;
+use http::{
+    header::{HeaderValue, HOST},
+    uri::{Authority, InvalidUri, InvalidUriParts, Uri},
+};
+
+/// Rewrites a request's URI before it is dispatched.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct UrlRewrite {
+    /// Replaces the request's authority and `host` header.
+    pub authority: Option<Authority>,
+
+    /// Replaces the request's path.
+    pub path: Option<ModifyPath>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidUrlRewrite {
+    #[error("rewrites may only replace the path prefix when a path prefix match applied")]
+    ReplacePrefix,
+
+    #[error("rewrite produced an invalid path: {0}")]
+    Path(#[from] InvalidUri),
+
+    #[error("rewrite produced an invalid URI: {0}")]
+    Uri(#[from] InvalidUriParts),
+}
+
+// === impl UrlRewrite ===
+
+impl UrlRewrite {
+    pub fn apply<B>(
+        &self,
+        rm: &RouteMatch,
+        req: &mut http::Request<B>,
+    ) -> Result<(), InvalidUrlRewrite> {
+        if self.authority.is_none() && self.path.is_none() {
+            return Ok(());
+        }
+
+        let mut parts = req.uri().clone().into_parts();
+        if let Some(path) = &self.path {
+            // Unlike redirects, rewrites do not double the path separator when
+            // a prefix is replaced with, e.g., `/`.
+            let pq = path
+                .path_and_query(req.uri(), rm, true)
+                .map_err(|e| match e {
+                    InvalidModifyPath::ReplacePrefix => InvalidUrlRewrite::ReplacePrefix,
+                    InvalidModifyPath::Path(e) => InvalidUrlRewrite::Path(e),
+                })?;
+            parts.path_and_query = Some(pq);
+        }
+
+        if let Some(authority) = &self.authority {
+            // Origin-form URIs (i.e. HTTP/1 requests without an absolute URI)
+            // cannot include an authority, so only the `host` header is
+            // rewritten for these requests.
+            if parts.authority.is_some() {
+                parts.authority = Some(authority.clone());
+            }
+            if req.headers().contains_key(HOST) {
+                let host = HeaderValue::from_str(authority.as_str())
+                    .expect("an authority must be a valid header value");
+                req.headers_mut().insert(HOST, host);
+            }
+        }
+
+        *req.uri_mut() = Uri::from_parts(parts)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::{find, r#match::MatchPath, MatchRequest, Route, Rule};
+
+    use super::*;
+
+    macro_rules! apply {
+        ($req:expr, $rule:expr) => {{
+            let mut req = $req;
+            let routes = vec![Route {
+                hosts: vec![],
+                rules: vec![$rule],
+            }];
+            let (rm, rewrite) = find(&*routes, &req).expect("request must match");
+            rewrite.apply(&rm, &mut req).map(|()| req)
+        }};
+    }
+
+    fn req(uri: &str) -> http::Request<()> {
+        http::Request::builder().uri(uri).body(()).unwrap()
+    }
+
+    #[test]
+    fn default_noop() {
+        let rule = Rule {
+            matches: vec![MatchRequest::default()],
+            policy: UrlRewrite::default(),
+        };
+        let req = apply!(req("http://example.com/foo?a=b"), rule).expect("must apply");
+        assert_eq!(req.uri(), "http://example.com/foo?a=b");
+    }
+
+    #[test]
+    fn authority() {
+        let rule = Rule {
+            matches: vec![MatchRequest::default()],
+            policy: UrlRewrite {
+                authority: Some("example.org".parse().unwrap()),
+                ..UrlRewrite::default()
+            },
+        };
+        let mut orig = req("http://example.com/foo?a=b");
+        orig.headers_mut()
+            .insert(HOST, HeaderValue::from_static("example.com"));
+        let req = apply!(orig, rule).expect("must apply");
+        assert_eq!(req.uri(), "http://example.org/foo?a=b");
+        assert_eq!(req.headers()[HOST], "example.org");
+    }
+
+    #[test]
+    fn authority_origin_form() {
+        let rule = Rule {
+            matches: vec![MatchRequest::default()],
+            policy: UrlRewrite {
+                authority: Some("example.org".parse().unwrap()),
+                ..UrlRewrite::default()
+            },
+        };
+        let mut orig = req("/foo");
+        orig.headers_mut()
+            .insert(HOST, HeaderValue::from_static("example.com"));
+        let req = apply!(orig, rule).expect("must apply");
+        assert_eq!(req.uri(), "/foo");
+        assert_eq!(req.headers()[HOST], "example.org");
+    }
+
+    #[test]
+    fn replace_path_full() {
+        let rule = Rule {
+            matches: vec![MatchRequest::default()],
+            policy: UrlRewrite {
+                path: Some(ModifyPath::ReplaceFullPath("/bar".to_string())),
+                ..UrlRewrite::default()
+            },
+        };
+        let req = apply!(req("http://example.com/foo?a=b"), rule).expect("must apply");
+        assert_eq!(req.uri(), "http://example.com/bar");
+    }
+
+    #[test]
+    fn replace_path_prefix() {
+        let rule = Rule {
+            matches: vec![MatchRequest {
+                path: Some(MatchPath::Prefix("/foo".to_string())),
+                ..MatchRequest::default()
+            }],
+            policy: UrlRewrite {
+                path: Some(ModifyPath::ReplacePrefixMatch("/".to_string())),
+                ..UrlRewrite::default()
+            },
+        };
+        let req = apply!(req("http://example.com/foo/bar?a=b"), rule).expect("must apply");
+        assert_eq!(req.uri(), "http://example.com/bar?a=b");
+    }
+
+    #[test]
+    fn replace_path_prefix_with_root_exact() {
+        let rule = Rule {
+            matches: vec![MatchRequest {
+                path: Some(MatchPath::Prefix("/foo".to_string())),
+                ..MatchRequest::default()
+            }],
+            policy: UrlRewrite {
+                path: Some(ModifyPath::ReplacePrefixMatch("/".to_string())),
+                ..UrlRewrite::default()
+            },
+        };
+        let req = apply!(req("http://example.com/foo"), rule).expect("must apply");
+        assert_eq!(req.uri(), "http://example.com/");
+    }
+
+    #[test]
+    fn replace_path_prefix_with_trailing_slash() {
+        let rule = Rule {
+            matches: vec![MatchRequest {
+                path: Some(MatchPath::Prefix("/foo".to_string())),
+                ..MatchRequest::default()
+            }],
+            policy: UrlRewrite {
+                path: Some(ModifyPath::ReplacePrefixMatch("/qux/".to_string())),
+                ..UrlRewrite::default()
+            },
+        };
+        let req = apply!(req("http://example.com/foo/bar"), rule).expect("must apply");
+        assert_eq!(req.uri(), "http://example.com/qux/bar");
+    }
+
+    #[test]
+    fn replace_path_prefix_requires_prefix_match() {
+        let rule = Rule {
+            matches: vec![MatchRequest {
+                path: Some(MatchPath::Exact("/foo".to_string())),
+                ..MatchRequest::default()
+            }],
+            policy: UrlRewrite {
+                path: Some(ModifyPath::ReplacePrefixMatch("/bar".to_string())),
+                ..UrlRewrite::default()
+            },
+        };
+        assert!(matches!(
+            apply!(req("http://example.com/foo"), rule),
+            Err(InvalidUrlRewrite::ReplacePrefix)
+        ));
+    }
+}
@@ -49,8 +49,12 @@ impl std::str::FromStr for MatchHost {
 
 impl MatchHost {
     pub fn summarize_match(&self, uri: &Uri) -> Option<HostMatch> {
-        let mut host = uri.authority()?.host();
+        self.summarize_host_match(uri.authority()?.host())
+    }
 
+    /// Matches a bare hostname, e.g. as provided by a TLS ClientHello's SNI
+    /// extension.
+    pub fn summarize_host_match(&self, mut host: &str) -> Option<HostMatch> {
         match self {
             Self::Exact(h) => {
                 if !h.ends_with('.') {
@@ -1,5 +1,5 @@
 use linkerd_error::Result;
-use std::ops::Deref;
+use std::{collections::HashMap, ops::Deref};
 
 /// Publishes certificates to be used by TLS implementations.
 pub trait Credentials {
@@ -7,6 +7,17 @@ pub trait Credentials {
     ///
     /// Fails if the certificate is not valid.
     fn set_certificate(&mut self, leaf: DerX509, chain: Vec<DerX509>, key: Vec<u8>) -> Result<()>;
+
+    /// Replaces the trust anchors used to verify peers, including the anchors
+    /// of federated trust domains (keyed by trust domain).
+    ///
+    /// New trust anchors are used to validate the next certificate and are
+    /// published along with it.
+    fn set_trust_bundles(
+        &mut self,
+        roots: Vec<DerX509>,
+        federated: HashMap<String, Vec<DerX509>>,
+    ) -> Result<()>;
 }
 
 /// DER-formatted X.509 data.
@@ -22,6 +22,8 @@ pub enum Id {
     Uri(url::Url),
 }
 
+const SPIFFE_SCHEME: &str = "spiffe";
+
 #[derive(Debug, thiserror::Error)]
 #[error("invalid TLS id: {0}")]
 pub struct InvalidId(#[source] Error);
@@ -63,6 +65,24 @@ impl Id {
             Self::Uri(uri) => uri.as_str().into(),
         }
     }
+
+    /// Returns the trust domain of a SPIFFE ID (i.e. the authority of a
+    /// `spiffe://` URI).
+    pub fn spiffe_trust_domain(&self) -> Option<&str> {
+        self.spiffe_uri()?.host_str()
+    }
+
+    /// Returns the path of a SPIFFE ID.
+    pub fn spiffe_path(&self) -> Option<&str> {
+        self.spiffe_uri().map(|uri| uri.path())
+    }
+
+    fn spiffe_uri(&self) -> Option<&url::Url> {
+        match self {
+            Self::Uri(uri) if uri.scheme() == SPIFFE_SCHEME => Some(uri),
+            _ => None,
+        }
+    }
 }
 
 impl From<linkerd_dns_name::Name> for Id {
@@ -111,4 +131,21 @@ mod tests {
     fn cannot_parse_dns_name_as_uri() {
         assert!(Id::parse_uri("some-svc.svc.cluster.local").is_err());
     }
+
+    #[test]
+    fn spiffe_trust_domain_and_path() {
+        let id: Id = "spiffe://example.org/ns/default/sa/web".parse().unwrap();
+        assert_eq!(id.spiffe_trust_domain(), Some("example.org"));
+        assert_eq!(id.spiffe_path(), Some("/ns/default/sa/web"));
+
+        let id: Id = "http://example.org/ns/default/sa/web".parse().unwrap();
+        assert_eq!(id.spiffe_trust_domain(), None);
+        assert_eq!(id.spiffe_path(), None);
+
+        let id: Id = "web.default.serviceaccount.identity.linkerd.cluster.local"
+            .parse()
+            .unwrap();
+        assert_eq!(id.spiffe_trust_domain(), None);
+        assert_eq!(id.spiffe_path(), None);
+    }
 }
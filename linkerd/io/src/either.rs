@@ -33,6 +33,16 @@ impl<L: io::PeerAddr, R: io::PeerAddr> io::PeerAddr for EitherIo<L, R> {
     }
 }
 
+impl<L: io::Reset, R: io::Reset> io::Reset for EitherIo<L, R> {
+    #[inline]
+    fn reset_on_drop(&self) -> io::Result<()> {
+        match self {
+            Self::Left(l) => l.reset_on_drop(),
+            Self::Right(r) => r.reset_on_drop(),
+        }
+    }
+}
+
 impl<L: io::AsyncRead, R: io::AsyncRead> io::AsyncRead for EitherIo<L, R> {
     #[inline]
     fn poll_read(
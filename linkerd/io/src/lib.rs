@@ -76,3 +76,32 @@ impl PeerAddr for tokio::io::DuplexStream {
         Ok(([0, 0, 0, 0], 0).into())
     }
 }
+
+// === Reset ===
+
+pub trait Reset {
+    /// Configures the connection so that it is reset (i.e. with a TCP RST),
+    /// rather than gracefully closed, when it is dropped.
+    fn reset_on_drop(&self) -> Result<()>;
+}
+
+impl Reset for tokio::net::TcpStream {
+    fn reset_on_drop(&self) -> Result<()> {
+        // Setting a zero linger timeout causes the socket to discard any
+        // unsent data and send a RST when it is closed.
+        self.set_linger(Some(std::time::Duration::ZERO))
+    }
+}
+
+#[cfg(feature = "tokio-test")]
+impl Reset for tokio_test::io::Mock {
+    fn reset_on_drop(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Reset for tokio::io::DuplexStream {
+    fn reset_on_drop(&self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -50,6 +50,13 @@ impl<I: io::PeerAddr> io::PeerAddr for PrefixedIo<I> {
     }
 }
 
+impl<I: io::Reset> io::Reset for PrefixedIo<I> {
+    #[inline]
+    fn reset_on_drop(&self) -> io::Result<()> {
+        self.io.reset_on_drop()
+    }
+}
+
 impl<I: io::AsyncRead> io::AsyncRead for PrefixedIo<I> {
     fn poll_read(
         self: Pin<&mut Self>,
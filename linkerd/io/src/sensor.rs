@@ -1,4 +1,4 @@
-use crate::{IoSlice, PeerAddr, Poll};
+use crate::{IoSlice, PeerAddr, Poll, Reset};
 use futures::ready;
 use linkerd_errno::Errno;
 use pin_project::pin_project;
@@ -82,3 +82,9 @@ impl<T: PeerAddr, S> PeerAddr for SensorIo<T, S> {
         self.io.peer_addr()
     }
 }
+
+impl<T: Reset, S> Reset for SensorIo<T, S> {
+    fn reset_on_drop(&self) -> Result<()> {
+        self.io.reset_on_drop()
+    }
+}
@@ -3,12 +3,25 @@ use linkerd_identity as id;
 use linkerd_io as io;
 use linkerd_meshtls_verifier as verifier;
 use linkerd_stack::{NewService, Service};
-use linkerd_tls::{client::AlpnProtocols, ClientTls, NegotiatedProtocolRef, ServerName};
+use linkerd_tls::{
+    client::{AlpnProtocols, OriginateConfigs},
+    ClientTls, NegotiatedProtocolRef, ServerName,
+};
 use std::{future::Future, pin::Pin, sync::Arc, task::Context};
 use tracing::{debug, trace};
 
+mod originate;
+
 #[derive(Clone)]
-pub struct NewClient(CredsRx);
+pub struct NewClient {
+    rx: CredsRx,
+
+    /// Connectors for servers outside of the mesh, which are built once for
+    /// each distinct origination configuration.
+    originate: OriginateConfigs<OriginateConnector>,
+}
+
+type OriginateConnector = Result<boring::ssl::SslConnector, Arc<str>>;
 
 #[derive(Clone)]
 pub struct Connect {
@@ -16,6 +29,7 @@ pub struct Connect {
     alpn: Option<Arc<[Vec<u8>]>>,
     id: id::Id,
     server: ServerName,
+    originate: Option<OriginateConnector>,
 }
 
 pub type ConnectFuture<I> = Pin<Box<dyn Future<Output = io::Result<ClientIo<I>>> + Send>>;
@@ -27,7 +41,10 @@ pub struct ClientIo<I>(tokio_boring::SslStream<I>);
 
 impl NewClient {
     pub(crate) fn new(rx: CredsRx) -> Self {
-        Self(rx)
+        Self {
+            rx,
+            originate: Default::default(),
+        }
     }
 }
 
@@ -35,7 +52,12 @@ impl NewService<ClientTls> for NewClient {
     type Service = Connect;
 
     fn new_service(&self, target: ClientTls) -> Self::Service {
-        Connect::new(target, self.0.clone())
+        let originate = target.originate.as_ref().map(|originate| {
+            self.originate.get_or_build(originate, |o| {
+                originate::connector(o).map_err(|e| Arc::<str>::from(e.to_string()))
+            })
+        });
+        Connect::new(target, self.rx.clone(), originate)
     }
 }
 
@@ -48,12 +70,17 @@ impl std::fmt::Debug for NewClient {
 // === impl Connect ===
 
 impl Connect {
-    pub(crate) fn new(client_tls: ClientTls, rx: CredsRx) -> Self {
+    pub(crate) fn new(
+        client_tls: ClientTls,
+        rx: CredsRx,
+        originate: Option<OriginateConnector>,
+    ) -> Self {
         Self {
             rx,
             alpn: client_tls.alpn.map(|AlpnProtocols(ps)| ps.into()),
             server: client_tls.server_name,
             id: client_tls.server_id.into(),
+            originate,
         }
     }
 }
@@ -72,11 +99,45 @@ where
 
     fn call(&mut self, io: I) -> Self::Future {
         let server_name = self.server.clone();
+        let alpn = self.alpn.as_deref().unwrap_or(&[]);
+        if let Some(connector) = self.originate.clone() {
+            let alpn = (!alpn.is_empty())
+                .then(|| crate::creds::serialize_alpn(alpn))
+                .transpose();
+            return Box::pin(async move {
+                let mut config = connector
+                    .map_err(|e| io::Error::new(io::ErrorKind::Other, &*e))?
+                    .configure()
+                    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+                if let Some(alpn) = alpn.map_err(|e| io::Error::new(io::ErrorKind::Other, e))? {
+                    config
+                        .set_alpn_protos(&alpn)
+                        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+                }
+
+                // Servers outside of the mesh are verified by hostname.
+                let io = tokio_boring::connect(config, server_name.as_str(), io)
+                    .await
+                    .map_err(|e| match e.as_io_error() {
+                        Some(ioe) => io::Error::new(ioe.kind(), ioe.to_string()),
+                        None => {
+                            io::Error::new(io::ErrorKind::Other, "unexpected TLS handshake error")
+                        }
+                    })?;
+
+                debug!(
+                    tls = io.ssl().version_str(),
+                    peer.cert = ?io.ssl().peer_certificate().as_deref().and_then(super::fingerprint),
+                    alpn = ?io.ssl().selected_alpn_protocol(),
+                    "Originated TLS connection"
+                );
+                trace!(peer.name = %server_name);
+                Ok(ClientIo(io))
+            });
+        }
+
         let server_id = self.id.clone();
-        let connector = self
-            .rx
-            .borrow()
-            .connector(self.alpn.as_deref().unwrap_or(&[]));
+        let connector = self.rx.borrow().connector(alpn);
         Box::pin(async move {
             let config = connector
                 .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
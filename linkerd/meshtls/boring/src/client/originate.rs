@@ -0,0 +1,46 @@
+use boring::{
+    pkey::PKey,
+    ssl,
+    x509::{store::X509StoreBuilder, X509},
+};
+use linkerd_error::Result;
+use linkerd_tls::client::{ClientCert, OriginateTls, TrustRoots};
+
+/// Builds a connector that verifies servers outside of the mesh by hostname.
+///
+/// ALPN protocols are configured for each connection, since they may vary
+/// between the connections to a backend.
+pub(super) fn connector(originate: &OriginateTls) -> Result<ssl::SslConnector> {
+    // Servers outside of the mesh may not support TLSv1.3, so the default
+    // protocol versions are used.
+    let mut conn = ssl::SslConnector::builder(ssl::SslMethod::tls_client())?;
+    match originate.roots {
+        // XXX(ver) This reads the system's trust store from the filesystem,
+        // though only once per backend, since connectors are cached.
+        TrustRoots::Public => conn.set_default_verify_paths()?,
+        TrustRoots::Pem(ref pem) => {
+            let mut store = X509StoreBuilder::new()?;
+            for c in X509::stack_from_pem(pem.as_bytes())? {
+                store.add_cert(c)?;
+            }
+            conn.set_cert_store(store.build());
+        }
+    }
+
+    if let Some(ClientCert {
+        ref chain_pem,
+        ref key_pem,
+    }) = originate.client_cert
+    {
+        let mut chain = X509::stack_from_pem(chain_pem.as_bytes())?.into_iter();
+        let leaf = chain.next().ok_or("client certificate chain is empty")?;
+        conn.set_private_key(&PKey::private_key_from_pem(key_pem.as_bytes())?)?;
+        conn.set_certificate(&leaf)?;
+        conn.check_private_key()?;
+        for c in chain {
+            conn.add_extra_chain_cert(c)?;
+        }
+    }
+
+    Ok(conn.build())
+}
@@ -10,16 +10,27 @@ use boring::{
 use linkerd_dns_name as dns;
 use linkerd_error::Result;
 use linkerd_identity as id;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::watch;
 
 pub fn watch(
     local_id: id::Id,
     server_name: dns::Name,
     roots_pem: &str,
+    federated_roots_pem: &HashMap<String, String>,
 ) -> Result<(Store, Receiver)> {
+    if !federated_roots_pem.is_empty() {
+        return Err("federated trust anchors are not supported with BoringSSL".into());
+    }
+
     let creds = {
-        let roots = X509::stack_from_pem(roots_pem.as_bytes())?;
+        // If no roots are configured, no peers are trusted until trust bundles
+        // are set on the store.
+        let roots = if roots_pem.is_empty() {
+            Vec::new()
+        } else {
+            X509::stack_from_pem(roots_pem.as_bytes())?
+        };
         Arc::new(BaseCreds { roots })
     };
 
@@ -164,7 +175,7 @@ impl Creds {
 /// Encodes a list of ALPN protocols into a slice of bytes.
 ///
 /// `boring` requires that the list of protocols be encoded in the wire format.
-fn serialize_alpn(protocols: &[Vec<u8>]) -> Result<Vec<u8>> {
+pub(crate) fn serialize_alpn(protocols: &[Vec<u8>]) -> Result<Vec<u8>> {
     // Allocate a buffer to hold the encoded protocols.
     let mut bytes = {
         // One additional byte for each protocol's length prefix.
@@ -4,7 +4,7 @@ use boring::x509::{X509StoreContext, X509};
 use linkerd_error::Result;
 use linkerd_identity as id;
 use linkerd_meshtls_verifier as verifier;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 pub struct Store {
     creds: Arc<BaseCreds>,
@@ -68,4 +68,25 @@ impl id::Credentials for Store {
 
         Ok(())
     }
+
+    fn set_trust_bundles(
+        &mut self,
+        roots: Vec<id::DerX509>,
+        federated: HashMap<String, Vec<id::DerX509>>,
+    ) -> Result<()> {
+        if !federated.is_empty() {
+            return Err("federated trust anchors are not supported with BoringSSL".into());
+        }
+
+        let roots = roots
+            .into_iter()
+            .map(|id::DerX509(der)| X509::from_der(&der).map_err(Into::into))
+            .collect::<Result<Vec<_>>>()?;
+        if roots.is_empty() {
+            return Err("no trust roots loaded".into());
+        }
+
+        self.creds = Arc::new(BaseCreds { roots });
+        Ok(())
+    }
 }
@@ -178,3 +178,10 @@ impl<I: io::PeerAddr> io::PeerAddr for ServerIo<I> {
         self.0.get_ref().peer_addr()
     }
 }
+
+impl<I: io::Reset> io::Reset for ServerIo<I> {
+    #[inline]
+    fn reset_on_drop(&self) -> io::Result<()> {
+        self.0.get_ref().reset_on_drop()
+    }
+}
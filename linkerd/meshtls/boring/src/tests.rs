@@ -7,6 +7,7 @@ fn load(ent: &Entity) -> crate::creds::Store {
         ent.name.parse().unwrap(),
         ent.name.parse().unwrap(),
         roots_pem,
+        &Default::default(),
     )
     .expect("credentials must be readable");
     store
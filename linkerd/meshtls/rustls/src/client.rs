@@ -3,23 +3,34 @@ use linkerd_identity as id;
 use linkerd_io as io;
 use linkerd_meshtls_verifier as verifier;
 use linkerd_stack::{NewService, Service};
-use linkerd_tls::{client::AlpnProtocols, ClientTls, NegotiatedProtocolRef};
+use linkerd_tls::{
+    client::{AlpnProtocols, OriginateConfigs},
+    ClientTls, NegotiatedProtocolRef,
+};
 use std::{convert::TryFrom, pin::Pin, sync::Arc, task::Context};
 use tokio::sync::watch;
 use tokio_rustls::rustls::{self, ClientConfig};
 
+mod originate;
+
 /// A `NewService` that produces `Connect` services from a dynamic TLS configuration.
 #[derive(Clone)]
 pub struct NewClient {
     config: watch::Receiver<Arc<ClientConfig>>,
+
+    /// Configurations for servers outside of the mesh. Their session caches
+    /// are shared by all connections, so sessions may be resumed.
+    originate: OriginateConfigs<Result<Arc<ClientConfig>, Arc<str>>>,
 }
 
 /// A `Service` that initiates client-side TLS connections.
 #[derive(Clone)]
 pub struct Connect {
-    server_id: id::Id,
+    /// The mesh identity of the server. This is not set when originating TLS,
+    /// in which case the server's certificate is verified for `server_name`.
+    server_id: Option<id::Id>,
     server_name: rustls::ServerName,
-    config: Arc<ClientConfig>,
+    config: Result<Arc<ClientConfig>, Arc<str>>,
 }
 
 pub type ConnectFuture<I> = Pin<Box<dyn Future<Output = io::Result<ClientIo<I>>> + Send>>;
@@ -31,7 +42,10 @@ pub struct ClientIo<I>(tokio_rustls::client::TlsStream<I>);
 
 impl NewClient {
     pub(crate) fn new(config: watch::Receiver<Arc<ClientConfig>>) -> Self {
-        Self { config }
+        Self {
+            config,
+            originate: Default::default(),
+        }
     }
 }
 
@@ -39,6 +53,12 @@ impl NewService<ClientTls> for NewClient {
     type Service = Connect;
 
     fn new_service(&self, target: ClientTls) -> Self::Service {
+        if let Some(originate) = target.originate.as_ref() {
+            let config = self.originate.get_or_build(originate, |o| {
+                originate::client_config(o).map_err(|e| Arc::from(e.to_string()))
+            });
+            return Connect::originate(target, config);
+        }
         Connect::new(target, (*self.config.borrow()).clone())
     }
 }
@@ -53,28 +73,45 @@ impl std::fmt::Debug for NewClient {
 
 impl Connect {
     pub(crate) fn new(client_tls: ClientTls, config: Arc<ClientConfig>) -> Self {
-        // If ALPN protocols are configured by the endpoint, we have to clone the entire
-        // configuration and set the protocols. If there are no ALPN options, clone the Arc'd base
-        // configuration without extra allocation.
-        //
-        // TODO it would be better to avoid cloning the whole TLS config per-connection, but the
-        // Rustls API doesn't give us a lot of options.
-        let config = match client_tls.alpn {
-            None => config,
-            Some(AlpnProtocols(protocols)) => {
-                let mut c = (*config).clone();
-                c.alpn_protocols = protocols;
-                Arc::new(c)
-            }
-        };
-
-        let server_name = rustls::ServerName::try_from(client_tls.server_name.as_str())
-            .expect("identity must be a valid DNS name");
+        let server_name = server_name(&client_tls);
+        let config = with_alpn(config, client_tls.alpn);
+        Self {
+            server_id: Some(client_tls.server_id.into()),
+            server_name,
+            config: Ok(config),
+        }
+    }
 
+    /// Originates TLS to a server outside of the mesh, which is verified by the
+    /// given configuration rather than by its mesh identity.
+    fn originate(client_tls: ClientTls, config: Result<Arc<ClientConfig>, Arc<str>>) -> Self {
+        let server_name = server_name(&client_tls);
         Self {
-            server_id: client_tls.server_id.into(),
+            server_id: None,
             server_name,
-            config,
+            config: config.map(|c| with_alpn(c, client_tls.alpn)),
+        }
+    }
+}
+
+fn server_name(client_tls: &ClientTls) -> rustls::ServerName {
+    rustls::ServerName::try_from(client_tls.server_name.as_str())
+        .expect("identity must be a valid DNS name")
+}
+
+/// If ALPN protocols are configured by the endpoint, we have to clone the entire
+/// configuration and set the protocols. If there are no ALPN options, clone the Arc'd base
+/// configuration without extra allocation.
+///
+/// TODO it would be better to avoid cloning the whole TLS config per-connection, but the
+/// Rustls API doesn't give us a lot of options.
+fn with_alpn(config: Arc<ClientConfig>, alpn: Option<AlpnProtocols>) -> Arc<ClientConfig> {
+    match alpn {
+        None => config,
+        Some(AlpnProtocols(protocols)) => {
+            let mut c = (*config).clone();
+            c.alpn_protocols = protocols;
+            Arc::new(c)
         }
     }
 }
@@ -99,6 +136,13 @@ where
     }
 
     fn call(&mut self, io: I) -> Self::Future {
+        let config = match self.config.clone() {
+            Ok(config) => config,
+            Err(error) => {
+                let error = io::Error::new(io::ErrorKind::InvalidInput, &*error);
+                return Box::pin(future::err(error));
+            }
+        };
         let server_id = self.server_id.clone();
         Box::pin(
             // Connect to the server, sending the `server_name` SNI in the
@@ -106,14 +150,19 @@ where
             // `AnySanVerifier` to ignore the server certificate's DNS SANs.
             // Instead, we extract the server's leaf certificate after the
             // handshake and verify that it matches the provided `server_id``.
-            tokio_rustls::TlsConnector::from(self.config.clone())
+            //
+            // When originating TLS, the config's verifier checks the server
+            // name instead.
+            tokio_rustls::TlsConnector::from(config)
                 // XXX(eliza): it's a bummer that the server name has to be cloned here...
                 .connect(self.server_name.clone(), io)
                 .map(move |s| {
                     let s = s?;
-                    let (_, conn) = s.get_ref();
-                    let end_cert = extract_cert(conn)?;
-                    verifier::verify_id(&end_cert.0, &server_id)?;
+                    if let Some(server_id) = server_id {
+                        let (_, conn) = s.get_ref();
+                        let end_cert = extract_cert(conn)?;
+                        verifier::verify_id(&end_cert.0, &server_id)?;
+                    }
                     Ok(ClientIo(s))
                 }),
         )
@@ -0,0 +1,63 @@
+use linkerd_error::Result;
+use linkerd_tls::client::{ClientCert, OriginateTls, TrustRoots};
+use std::{io::Cursor, sync::Arc};
+use tokio_rustls::rustls::{self, ClientConfig};
+
+/// Builds a client configuration that verifies servers outside of the mesh
+/// with standard WebPKI server name verification.
+pub(super) fn client_config(originate: &OriginateTls) -> Result<Arc<ClientConfig>> {
+    let mut roots = rustls::RootCertStore::empty();
+    match originate.roots {
+        TrustRoots::Public => {
+            roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+                rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject,
+                    ta.spki,
+                    ta.name_constraints,
+                )
+            }));
+        }
+        TrustRoots::Pem(ref pem) => {
+            let certs = rustls_pemfile::certs(&mut Cursor::new(pem.as_bytes()))?;
+            let (added, _) = roots.add_parsable_certificates(&certs);
+            if added == 0 {
+                return Err("no valid trust roots".into());
+            }
+        }
+    }
+
+    // Servers outside of the mesh may not support TLSv1.3, so the default
+    // protocol versions are used.
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+    let config = match originate.client_cert {
+        None => config.with_no_client_auth(),
+        Some(ClientCert {
+            ref chain_pem,
+            ref key_pem,
+        }) => {
+            let chain = rustls_pemfile::certs(&mut Cursor::new(chain_pem.as_bytes()))?
+                .into_iter()
+                .map(rustls::Certificate)
+                .collect::<Vec<_>>();
+            if chain.is_empty() {
+                return Err("client certificate chain is empty".into());
+            }
+            config.with_client_auth_cert(chain, private_key(key_pem)?)?
+        }
+    };
+    Ok(Arc::new(config))
+}
+
+fn private_key(pem: &str) -> Result<rustls::PrivateKey> {
+    for item in rustls_pemfile::read_all(&mut Cursor::new(pem.as_bytes()))? {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(rustls::PrivateKey(key)),
+            _ => {}
+        }
+    }
+    Err("no private key".into())
+}
@@ -7,7 +7,7 @@ use linkerd_dns_name as dns;
 use linkerd_error::Result;
 use linkerd_identity as id;
 use ring::error::KeyRejected;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
 use tokio::sync::watch;
 use tokio_rustls::rustls;
@@ -21,37 +21,43 @@ pub struct InvalidKey(KeyRejected);
 #[error("invalid trust roots")]
 pub struct InvalidTrustRoots(());
 
+/// Creates a credential store that verifies peers against `roots_pem`.
+///
+/// `federated_roots_pem` holds the trust anchors of federated SPIFFE trust
+/// domains, keyed by trust domain. Peers with a SPIFFE ID in one of these trust
+/// domains are verified against that trust domain's anchors instead.
+///
+/// If `roots_pem` is empty, no peers are trusted until trust bundles are set on
+/// the store.
 pub fn watch(
     local_id: id::Id,
     server_name: dns::Name,
     roots_pem: &str,
+    federated_roots_pem: &HashMap<String, String>,
 ) -> Result<(Store, Receiver)> {
-    let mut roots = rustls::RootCertStore::empty();
-    let certs = match rustls_pemfile::certs(&mut std::io::Cursor::new(roots_pem)) {
-        Err(error) => {
-            warn!(%error, "invalid trust anchors file");
-            return Err(error.into());
-        }
-        Ok(certs) if certs.is_empty() => {
-            warn!("no valid certs in trust anchors file");
-            return Err("no trust roots in PEM file".into());
-        }
-        Ok(certs) => certs,
+    let roots = if roots_pem.is_empty() {
+        rustls::RootCertStore::empty()
+    } else {
+        load_roots(roots_pem)?
     };
-
-    let (added, skipped) = roots.add_parsable_certificates(&certs[..]);
-    if skipped != 0 {
-        warn!("Skipped {} invalid trust anchors", skipped);
-    }
-    if added == 0 {
-        return Err("no trust roots loaded".into());
-    }
+    let federated = federated_roots_pem
+        .iter()
+        .map(|(trust_domain, pem)| {
+            let roots = load_roots(pem).map_err(|error| {
+                warn!(%trust_domain, "invalid federated trust anchors");
+                error
+            })?;
+            Ok((trust_domain.clone(), roots))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+    let bundles = verify::TrustBundles::new(roots, federated);
 
     // XXX: Rustls's built-in verifiers don't let us tweak things as fully as we'd like (e.g.
     // controlling the set of trusted signature algorithms), but they provide good enough
     // defaults for now.
     // TODO: lock down the verification further.
-    let server_cert_verifier = Arc::new(verify::AnySanVerifier::new(roots.clone()));
+    let server_cert_verifier = Arc::new(verify::AnySanVerifier::new(bundles.clone()));
+    let client_cert_verifier = Arc::new(verify::AnyClientVerifier::new(&bundles));
 
     let (client_tx, client_rx) = {
         // Since we don't have a certificate yet, build a client configuration
@@ -72,13 +78,16 @@ pub fn watch(
         // that handshaking always fails. Once we get a certificate, the `Store`
         // will publish a new configuration with a server certificate resolver.
         let empty_resolver = Arc::new(rustls::server::ResolvesServerCertUsingSni::new());
-        watch::channel(store::server_config(roots.clone(), empty_resolver))
+        watch::channel(store::server_config(
+            client_cert_verifier.clone(),
+            empty_resolver,
+        ))
     };
 
     let rx = Receiver::new(local_id.clone(), server_name.clone(), client_rx, server_rx);
     let store = Store::new(
-        roots,
         server_cert_verifier,
+        client_cert_verifier,
         local_id,
         server_name,
         client_tx,
@@ -88,12 +97,38 @@ pub fn watch(
     Ok((store, rx))
 }
 
+fn load_roots(roots_pem: &str) -> Result<rustls::RootCertStore> {
+    let mut roots = rustls::RootCertStore::empty();
+    let certs = match rustls_pemfile::certs(&mut std::io::Cursor::new(roots_pem)) {
+        Err(error) => {
+            warn!(%error, "invalid trust anchors file");
+            return Err(error.into());
+        }
+        Ok(certs) if certs.is_empty() => {
+            warn!("no valid certs in trust anchors file");
+            return Err("no trust roots in PEM file".into());
+        }
+        Ok(certs) => certs,
+    };
+
+    let (added, skipped) = roots.add_parsable_certificates(&certs[..]);
+    if skipped != 0 {
+        warn!("Skipped {} invalid trust anchors", skipped);
+    }
+    if added == 0 {
+        return Err("no trust roots loaded".into());
+    }
+
+    Ok(roots)
+}
+
 #[cfg(feature = "test-util")]
 pub fn for_test(ent: &linkerd_tls_test_util::Entity) -> (Store, Receiver) {
     watch(
         ent.name.parse().expect("id must be valid"),
         ent.name.parse().expect("name must be valid"),
         std::str::from_utf8(ent.trust_anchors).expect("roots must be PEM"),
+        &Default::default(),
     )
     .expect("credentials must be valid")
 }
@@ -1,18 +1,18 @@
 use super::params::*;
-use super::InvalidKey;
+use super::{verify, InvalidKey};
 use linkerd_dns_name as dns;
 use linkerd_error::Result;
 use linkerd_identity as id;
 use linkerd_meshtls_verifier as verifier;
 use ring::{rand, signature::EcdsaKeyPair};
-use std::{convert::TryFrom, sync::Arc};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc};
 use tokio::sync::watch;
 use tokio_rustls::rustls;
-use tracing::debug;
+use tracing::{debug, warn};
 
 pub struct Store {
-    roots: rustls::RootCertStore,
     server_cert_verifier: Arc<dyn rustls::client::ServerCertVerifier>,
+    client_cert_verifier: Arc<dyn rustls::server::ClientCertVerifier>,
     server_id: id::Id,
     server_name: dns::Name,
     client_tx: watch::Sender<Arc<rustls::ClientConfig>>,
@@ -47,18 +47,10 @@ pub(super) fn client_config_builder(
 }
 
 pub(super) fn server_config(
-    roots: rustls::RootCertStore,
+    client_cert_verifier: Arc<dyn rustls::server::ClientCertVerifier>,
     resolver: Arc<dyn rustls::server::ResolvesServerCert>,
 ) -> Arc<rustls::ServerConfig> {
     // Ask TLS clients for a certificate and accept any certificate issued by our trusted CA(s).
-    //
-    // XXX: Rustls's built-in verifiers don't let us tweak things as fully as we'd like (e.g.
-    // controlling the set of trusted signature algorithms), but they provide good enough
-    // defaults for now.
-    // TODO: lock down the verification further.
-    let client_cert_verifier = Arc::new(
-        rustls::server::AllowAnyAnonymousOrAuthenticatedClient::new(roots),
-    );
     rustls::ServerConfig::builder()
         .with_cipher_suites(TLS_SUPPORTED_CIPHERSUITES)
         .with_safe_default_kx_groups()
@@ -74,16 +66,16 @@ pub(super) fn server_config(
 impl Store {
     #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
-        roots: rustls::RootCertStore,
         server_cert_verifier: Arc<dyn rustls::client::ServerCertVerifier>,
+        client_cert_verifier: Arc<dyn rustls::server::ClientCertVerifier>,
         server_id: id::Id,
         server_name: dns::Name,
         client_tx: watch::Sender<Arc<rustls::ClientConfig>>,
         server_tx: watch::Sender<Arc<rustls::ServerConfig>>,
     ) -> Self {
         Self {
-            roots,
             server_cert_verifier,
+            client_cert_verifier,
             server_id,
             server_name,
             client_tx,
@@ -154,7 +146,7 @@ impl id::Credentials for Store {
 
         // Build new client and server TLS configs.
         let client = self.client_config(resolver.clone());
-        let server = server_config(self.roots.clone(), resolver);
+        let server = server_config(self.client_cert_verifier.clone(), resolver);
 
         // Publish the new configs.
         let _ = self.client_tx.send(client);
@@ -162,6 +154,38 @@ impl id::Credentials for Store {
 
         Ok(())
     }
+
+    fn set_trust_bundles(
+        &mut self,
+        roots: Vec<id::DerX509>,
+        federated: HashMap<String, Vec<id::DerX509>>,
+    ) -> Result<()> {
+        let roots = load_der_roots(&roots)?;
+        let federated = federated
+            .into_iter()
+            .map(|(trust_domain, roots)| Ok((trust_domain, load_der_roots(&roots)?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+        let bundles = verify::TrustBundles::new(roots, federated);
+
+        // The verifiers are published with the next certificate.
+        self.server_cert_verifier = Arc::new(verify::AnySanVerifier::new(bundles.clone()));
+        self.client_cert_verifier = Arc::new(verify::AnyClientVerifier::new(&bundles));
+
+        Ok(())
+    }
+}
+
+fn load_der_roots(roots: &[id::DerX509]) -> Result<rustls::RootCertStore> {
+    let mut store = rustls::RootCertStore::empty();
+    let ders = roots.iter().map(|der| &der[..]).collect::<Vec<_>>();
+    let (added, skipped) = store.add_parsable_certificates(&ders);
+    if skipped != 0 {
+        warn!("Skipped {} invalid trust anchors", skipped);
+    }
+    if added == 0 {
+        return Err("no trust roots loaded".into());
+    }
+    Ok(store)
 }
 
 // === impl Key ===
@@ -1,19 +1,104 @@
+use linkerd_meshtls_verifier as verifier;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
 use std::time::SystemTime;
 use tokio_rustls::rustls::{
     self,
     client::{self, ServerCertVerified, ServerCertVerifier},
-    server::ParsedCertificate,
-    Certificate, RootCertStore, ServerName,
+    server::{
+        AllowAnyAnonymousOrAuthenticatedClient, ClientCertVerified, ClientCertVerifier,
+        ParsedCertificate,
+    },
+    Certificate, DistinguishedName, RootCertStore, ServerName,
 };
-use tracing::trace;
+use tracing::{debug, trace};
 
-pub(crate) struct AnySanVerifier(Arc<RootCertStore>);
+/// Trust anchors for the local trust domain, along with the trust anchors of
+/// federated SPIFFE trust domains.
+///
+/// Certificates with a SPIFFE ID in a federated trust domain are verified
+/// against that trust domain's bundle. All other certificates are verified
+/// against the local trust anchors.
+#[derive(Clone, Debug)]
+pub(crate) struct TrustBundles {
+    roots: RootCertStore,
+    federated: HashMap<String, RootCertStore>,
+}
+
+pub(crate) struct AnySanVerifier(Arc<TrustBundles>);
+
+/// Requests (but does not require) a client certificate, verifying it against
+/// the trust bundle for the client's trust domain.
+pub(crate) struct AnyClientVerifier {
+    roots: AllowAnyAnonymousOrAuthenticatedClient,
+    federated: HashMap<String, AllowAnyAnonymousOrAuthenticatedClient>,
+    subjects: Vec<DistinguishedName>,
+}
+
+/// Selects the value for the end entity certificate's SPIFFE trust domain,
+/// falling back to the default when the trust domain is not federated.
+///
+/// A trust bundle may only vouch for identities in its own trust domain, so
+/// certificates are rejected when:
+/// - a federated trust domain is selected and any of the certificate's SANs is
+///   not a SPIFFE ID in that trust domain; or
+/// - the default is selected and any of the certificate's SANs is a SPIFFE ID
+///   in a federated trust domain.
+///
+/// Otherwise, a CA in one trust domain could issue certificates with
+/// additional SANs that impersonate identities in another.
+fn select<'t, T>(
+    default: &'t T,
+    federated: &'t HashMap<String, T>,
+    end_entity: &Certificate,
+) -> Result<&'t T, rustls::Error> {
+    if federated.is_empty() {
+        return Ok(default);
+    }
+
+    let ids = verifier::identities(&end_entity.0);
+    let selected = ids
+        .iter()
+        .find_map(|id| federated.get_key_value(id.spiffe_trust_domain()?));
+    let valid = match selected {
+        Some((td, _)) => ids
+            .iter()
+            .all(|id| id.spiffe_trust_domain() == Some(td.as_str())),
+        None => !ids.iter().any(|id| {
+            id.spiffe_trust_domain()
+                .map_or(false, |td| federated.contains_key(td))
+        }),
+    };
+    if !valid {
+        debug!(?ids, "Certificate identities span multiple trust domains");
+        return Err(rustls::Error::InvalidCertificate(
+            rustls::CertificateError::ApplicationVerificationFailure,
+        ));
+    }
+
+    Ok(match selected {
+        Some((td, bundle)) => {
+            trace!(trust_domain = %td, "Using federated trust bundle");
+            bundle
+        }
+        None => default,
+    })
+}
+
+// === impl TrustBundles ===
+
+impl TrustBundles {
+    pub(crate) fn new(roots: RootCertStore, federated: HashMap<String, RootCertStore>) -> Self {
+        Self { roots, federated }
+    }
+}
+
+// === impl AnySanVerifier ===
 
 impl AnySanVerifier {
-    pub(crate) fn new(roots: impl Into<Arc<RootCertStore>>) -> Self {
-        Self(roots.into())
+    pub(crate) fn new(bundles: impl Into<Arc<TrustBundles>>) -> Self {
+        Self(bundles.into())
     }
 }
 
@@ -41,7 +126,8 @@ impl ServerCertVerifier for AnySanVerifier {
     ) -> Result<ServerCertVerified, rustls::Error> {
         let cert = ParsedCertificate::try_from(end_entity)?;
 
-        client::verify_server_cert_signed_by_trust_anchor(&cert, &self.0, intermediates, now)?;
+        let roots = select(&self.0.roots, &self.0.federated, end_entity)?;
+        client::verify_server_cert_signed_by_trust_anchor(&cert, roots, intermediates, now)?;
 
         if !ocsp_response.is_empty() {
             trace!("Unvalidated OCSP response: {ocsp_response:?}");
@@ -50,3 +136,55 @@ impl ServerCertVerifier for AnySanVerifier {
         Ok(ServerCertVerified::assertion())
     }
 }
+
+// === impl AnyClientVerifier ===
+
+impl AnyClientVerifier {
+    pub(crate) fn new(bundles: &TrustBundles) -> Self {
+        let roots = AllowAnyAnonymousOrAuthenticatedClient::new(bundles.roots.clone());
+        let federated = bundles
+            .federated
+            .iter()
+            .map(|(td, roots)| {
+                let verifier = AllowAnyAnonymousOrAuthenticatedClient::new(roots.clone());
+                (td.clone(), verifier)
+            })
+            .collect::<HashMap<_, _>>();
+        let subjects = std::iter::once(&roots)
+            .chain(federated.values())
+            .flat_map(|v| v.client_auth_root_subjects().iter().cloned())
+            .collect();
+        Self {
+            roots,
+            federated,
+            subjects,
+        }
+    }
+}
+
+impl ClientCertVerifier for AnyClientVerifier {
+    fn offer_client_auth(&self) -> bool {
+        true
+    }
+
+    fn client_auth_mandatory(&self) -> bool {
+        false
+    }
+
+    fn client_auth_root_subjects(&self) -> &[DistinguishedName] {
+        &self.subjects
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &Certificate,
+        intermediates: &[Certificate],
+        now: SystemTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        select(&self.roots, &self.federated, end_entity)?.verify_client_cert(
+            end_entity,
+            intermediates,
+            now,
+        )
+    }
+}
@@ -195,3 +195,10 @@ impl<I: io::PeerAddr> io::PeerAddr for ServerIo<I> {
         self.0.get_ref().0.peer_addr()
     }
 }
+
+impl<I: io::Reset> io::Reset for ServerIo<I> {
+    #[inline]
+    fn reset_on_drop(&self) -> io::Result<()> {
+        self.0.get_ref().0.reset_on_drop()
+    }
+}
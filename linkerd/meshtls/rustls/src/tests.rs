@@ -7,6 +7,7 @@ fn load(ent: &Entity) -> crate::creds::Store {
         ent.name.parse().unwrap(),
         ent.name.parse().unwrap(),
         roots_pem,
+        &Default::default(),
     )
     .expect("credentials must be readable");
     store
@@ -32,3 +33,167 @@ fn recognize_cert_is_not_valid_for_identity() {
         .set_certificate(DerX509(FOO_NS1.crt.to_vec()), vec![], FOO_NS1.key.to_vec())
         .is_err());
 }
+
+mod federation {
+    use crate::creds::verify::{AnyClientVerifier, AnySanVerifier, TrustBundles};
+    use rcgen::{
+        BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa, SanType,
+    };
+    use std::{collections::HashMap, convert::TryFrom, time::SystemTime};
+    use tokio_rustls::rustls::{
+        self, client::ServerCertVerifier, server::ClientCertVerifier, RootCertStore,
+    };
+
+    fn ca() -> Certificate {
+        let mut params = CertificateParams::new(vec![]);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        Certificate::from_params(params).expect("CA must be valid")
+    }
+
+    fn roots(ca: &Certificate) -> RootCertStore {
+        let mut roots = RootCertStore::empty();
+        let der = ca.serialize_der().expect("CA must serialize");
+        let (added, _) = roots.add_parsable_certificates(&[der]);
+        assert_eq!(added, 1);
+        roots
+    }
+
+    fn leaf(ca: &Certificate, spiffe_id: &str) -> rustls::Certificate {
+        leaf_with_sans(ca, vec![SanType::URI(spiffe_id.into())])
+    }
+
+    fn leaf_with_sans(ca: &Certificate, sans: Vec<SanType>) -> rustls::Certificate {
+        let mut params = CertificateParams::new(vec![]);
+        params.subject_alt_names = sans;
+        params.extended_key_usages = vec![
+            ExtendedKeyUsagePurpose::ServerAuth,
+            ExtendedKeyUsagePurpose::ClientAuth,
+        ];
+        let cert = Certificate::from_params(params).expect("cert must be valid");
+        let der = cert
+            .serialize_der_with_signer(ca)
+            .expect("cert must serialize");
+        rustls::Certificate(der)
+    }
+
+    fn verify(bundles: &TrustBundles, cert: &rustls::Certificate) -> bool {
+        let now = SystemTime::now();
+        let client = AnyClientVerifier::new(bundles)
+            .verify_client_cert(cert, &[], now)
+            .is_ok();
+        let server = AnySanVerifier::new(bundles.clone())
+            .verify_server_cert(
+                cert,
+                &[],
+                &rustls::ServerName::try_from("example.com").unwrap(),
+                &mut std::iter::empty(),
+                &[],
+                now,
+            )
+            .is_ok();
+        assert_eq!(client, server, "client and server verification must agree");
+        client
+    }
+
+    #[test]
+    fn verifies_federated_trust_domain() {
+        let local = ca();
+        let other = ca();
+        let bundles = TrustBundles::new(
+            roots(&local),
+            HashMap::from([("other.example".to_string(), roots(&other))]),
+        );
+
+        assert!(
+            verify(&bundles, &leaf(&local, "spiffe://local.example/ns/web")),
+            "local trust domain must be verified with local roots"
+        );
+        assert!(
+            verify(&bundles, &leaf(&other, "spiffe://other.example/ns/web")),
+            "federated trust domain must be verified with its bundle"
+        );
+        assert!(
+            !verify(&bundles, &leaf(&other, "spiffe://local.example/ns/web")),
+            "federated roots must not issue identities in the local trust domain"
+        );
+        assert!(
+            !verify(&bundles, &leaf(&local, "spiffe://other.example/ns/web")),
+            "local roots must not issue identities in a federated trust domain"
+        );
+    }
+
+    #[test]
+    fn rejects_unfederated_trust_domain() {
+        let local = ca();
+        let other = ca();
+        let bundles = TrustBundles::new(roots(&local), HashMap::new());
+
+        assert!(!verify(
+            &bundles,
+            &leaf(&other, "spiffe://other.example/ns/web")
+        ));
+    }
+
+    #[test]
+    fn rejects_identities_outside_of_the_verifying_trust_domain() {
+        let local = ca();
+        let other = ca();
+        let bundles = TrustBundles::new(
+            roots(&local),
+            HashMap::from([("other.example".to_string(), roots(&other))]),
+        );
+
+        assert!(
+            !verify(
+                &bundles,
+                &leaf_with_sans(
+                    &other,
+                    vec![
+                        SanType::URI("spiffe://other.example/ns/web".into()),
+                        SanType::URI("spiffe://local.example/ns/web".into()),
+                    ],
+                ),
+            ),
+            "federated roots must not vouch for identities in the local trust domain"
+        );
+        assert!(
+            !verify(
+                &bundles,
+                &leaf_with_sans(
+                    &other,
+                    vec![
+                        SanType::URI("spiffe://other.example/ns/web".into()),
+                        SanType::DnsName("web.ns.serviceaccount.identity.linkerd.local".into()),
+                    ],
+                ),
+            ),
+            "federated roots must not vouch for DNS identities"
+        );
+        assert!(
+            !verify(
+                &bundles,
+                &leaf_with_sans(
+                    &local,
+                    vec![
+                        SanType::URI("spiffe://local.example/ns/web".into()),
+                        SanType::URI("spiffe://other.example/ns/web".into()),
+                    ],
+                ),
+            ),
+            "local roots must not vouch for identities in a federated trust domain"
+        );
+        assert!(
+            verify(
+                &bundles,
+                &leaf_with_sans(
+                    &other,
+                    vec![
+                        SanType::URI("spiffe://other.example/ns/web".into()),
+                        SanType::URI("spiffe://other.example/ns/api".into()),
+                    ],
+                ),
+            ),
+            "federated roots may vouch for multiple identities in their trust domain"
+        );
+    }
+}
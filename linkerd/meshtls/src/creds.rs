@@ -2,6 +2,7 @@ use crate::{NewClient, Server};
 use linkerd_dns_name as dns;
 use linkerd_error::Result;
 use linkerd_identity::{Credentials, DerX509, Id};
+use std::collections::HashMap;
 
 #[cfg(feature = "boring")]
 pub use crate::boring;
@@ -44,6 +45,22 @@ impl Credentials for Store {
             _ => crate::no_tls!(leaf, chain, key),
         }
     }
+
+    fn set_trust_bundles(
+        &mut self,
+        roots: Vec<DerX509>,
+        federated: HashMap<String, Vec<DerX509>>,
+    ) -> Result<()> {
+        match self {
+            #[cfg(feature = "boring")]
+            Self::Boring(store) => store.set_trust_bundles(roots, federated),
+
+            #[cfg(feature = "rustls")]
+            Self::Rustls(store) => store.set_trust_bundles(roots, federated),
+            #[cfg(not(feature = "__has_any_tls_impls"))]
+            _ => crate::no_tls!(roots, federated),
+        }
+    }
 }
 
 // === impl Receiver ===
@@ -23,7 +23,7 @@ pub use self::{
 use linkerd_dns_name as dns;
 use linkerd_error::{Error, Result};
 use linkerd_identity as id;
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 #[cfg(feature = "boring")]
 pub use linkerd_meshtls_boring as boring;
@@ -86,11 +86,13 @@ impl Mode {
         local_id: id::Id,
         server_name: dns::Name,
         roots_pem: &str,
+        federated_roots_pem: &HashMap<String, String>,
     ) -> Result<(creds::Store, creds::Receiver)> {
         match self {
             #[cfg(feature = "boring")]
             Self::Boring => {
-                let (store, receiver) = boring::creds::watch(local_id, server_name, roots_pem)?;
+                let (store, receiver) =
+                    boring::creds::watch(local_id, server_name, roots_pem, federated_roots_pem)?;
                 Ok((
                     creds::Store::Boring(store),
                     creds::Receiver::Boring(receiver),
@@ -99,7 +101,8 @@ impl Mode {
 
             #[cfg(feature = "rustls")]
             Self::Rustls => {
-                let (store, receiver) = rustls::creds::watch(local_id, server_name, roots_pem)?;
+                let (store, receiver) =
+                    rustls::creds::watch(local_id, server_name, roots_pem, federated_roots_pem)?;
                 Ok((
                     creds::Store::Rustls(store),
                     creds::Receiver::Rustls(receiver),
@@ -107,7 +110,7 @@ impl Mode {
             }
 
             #[cfg(not(feature = "__has_any_tls_impls"))]
-            _ => no_tls!(local_id, server_name, roots_pem),
+            _ => no_tls!(local_id, server_name, roots_pem, federated_roots_pem),
         }
     }
 }
@@ -263,3 +263,18 @@ impl<I: io::PeerAddr> io::PeerAddr for ServerIo<I> {
         }
     }
 }
+
+impl<I: io::Reset> io::Reset for ServerIo<I> {
+    #[inline]
+    fn reset_on_drop(&self) -> io::Result<()> {
+        match self {
+            #[cfg(feature = "boring")]
+            Self::Boring(io) => io.reset_on_drop(),
+
+            #[cfg(feature = "rustls")]
+            Self::Rustls(io) => io.reset_on_drop(),
+            #[cfg(not(feature = "__has_any_tls_impls"))]
+            _ => crate::no_tls!(),
+        }
+    }
+}
@@ -123,6 +123,7 @@ fn load(
             ent.name.parse().unwrap(),
             ent.name.parse().unwrap(),
             roots_pem,
+            &Default::default(),
         )
         .expect("credentials must be readable");
 
@@ -40,6 +40,13 @@ pub fn client_identity(cert: &[u8]) -> Option<Id> {
     ids.first().cloned()
 }
 
+/// Returns all of the identities in the certificate's SANs.
+pub fn identities(cert: &[u8]) -> Vec<Id> {
+    extract_ids_from_cert(cert)
+        .map_err(|error| tracing::warn!(%error, "Failed to extract tls id from end cert"))
+        .unwrap_or_default()
+}
+
 pub fn verify_id(cert: &[u8], expected_id: &Id) -> io::Result<()> {
     let ids = extract_ids_from_cert(cert)
         .map_err(|error| tracing::warn!(%error, "Failed to extract tls id from client end cert"))
@@ -59,6 +66,7 @@ pub fn verify_id(cert: &[u8], expected_id: &Id) -> io::Result<()> {
 #[cfg(test)]
 mod tests {
     use crate::client_identity;
+    use crate::identities;
     use crate::verify_id;
     use linkerd_identity::Id;
     use rcgen::{Certificate, CertificateParams, SanType};
@@ -285,4 +293,32 @@ mod tests {
         let client_id = client_identity(&cert);
         assert_eq!(client_id, None);
     }
+
+    #[test]
+    fn can_extract_all_identities() {
+        let dns_id = "foo.ns1.serviceaccount.identity.linkerd.cluster.local";
+        let spiffe_id = "spiffe://some-trust-domain/some-system/some-component";
+
+        let cert = generate_cert_with_names(vec![
+            SanType::DnsName(dns_id.into()),
+            SanType::URI(spiffe_id.into()),
+        ]);
+        let ids = identities(&cert);
+        assert_eq!(
+            ids,
+            vec![
+                Id::parse_dns_name(dns_id).unwrap(),
+                Id::parse_uri(spiffe_id).unwrap(),
+            ]
+        );
+        assert_eq!(
+            ids.iter()
+                .filter_map(Id::spiffe_trust_domain)
+                .collect::<Vec<_>>(),
+            vec!["some-trust-domain"]
+        );
+
+        let cert = generate_cert_with_names(vec![]);
+        assert!(identities(&cert).is_empty());
+    }
 }
@@ -1,5 +1,5 @@
 use super::{
-    fmt::{FmtLabels, FmtMetric},
+    fmt::{counter_suffix, FmtLabels, FmtMetric},
     Factor,
 };
 use std::fmt::{self, Display};
@@ -72,7 +72,8 @@ impl<F: Factor> FmtMetric for Counter<F> {
     const KIND: &'static str = "counter";
 
     fn fmt_metric<N: Display>(&self, f: &mut fmt::Formatter<'_>, name: N) -> fmt::Result {
-        writeln!(f, "{} {}", name, self.value())
+        let suffix = counter_suffix(f, &name);
+        writeln!(f, "{}{} {}", name, suffix, self.value())
     }
 
     fn fmt_metric_labeled<N, L>(
@@ -85,7 +86,8 @@ impl<F: Factor> FmtMetric for Counter<F> {
         L: FmtLabels,
         N: Display,
     {
-        write!(f, "{}{{", name)?;
+        let suffix = counter_suffix(f, &name);
+        write!(f, "{}{}{{", name, suffix)?;
         labels.fmt_labels(f)?;
         writeln!(f, "}} {}", self.value())
     }
@@ -109,6 +111,25 @@ mod tests {
         assert_eq!(c.value(), 42.0);
     }
 
+    #[test]
+    fn openmetrics_samples_are_totals() {
+        struct Fmt<'c>(&'c str, &'c Counter);
+
+        impl fmt::Display for Fmt<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.1.fmt_metric(f, self.0)
+            }
+        }
+
+        let c = Counter::<()>::from(2);
+        assert_eq!(Fmt("exports", &c).to_string(), "exports 2\n");
+        assert_eq!(format!("{:#}", Fmt("exports", &c)), "exports_total 2\n");
+        assert_eq!(
+            format!("{:#}", Fmt("request_total", &c)),
+            "request_total 2\n"
+        );
+    }
+
     #[test]
     fn count_wrapping() {
         let c = Counter::<()>::from(MAX_PRECISE_UINT64 - 1);
@@ -0,0 +1,139 @@
+use crate::prom::encoding::{EncodeLabel, EncodeLabelSet, LabelSetEncoder};
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Identifies the trace in which an observation was made.
+///
+/// Exemplars are attached to histogram buckets so that an unusual observation,
+/// e.g. a latency spike, can be traced back to a request that exhibited it.
+/// They are only exposed when metrics are formatted as OpenMetrics.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Exemplar {
+    trace_id: String,
+    span_id: String,
+}
+
+/// An exemplar recorded for a histogram bucket.
+#[derive(Clone, Debug)]
+pub(crate) struct Observed {
+    exemplar: Exemplar,
+    value: f64,
+    timestamp: SystemTime,
+}
+
+// === impl Exemplar ===
+
+impl Exemplar {
+    pub fn new(trace_id: impl fmt::Display, span_id: impl fmt::Display) -> Self {
+        Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+        }
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn span_id(&self) -> &str {
+        &self.span_id
+    }
+}
+
+/// Allows exemplars to be recorded by [`prometheus_client`] histograms, i.e.
+/// `prom::HistogramWithExemplars<Exemplar>`.
+impl EncodeLabelSet for Exemplar {
+    fn encode(&self, mut enc: LabelSetEncoder<'_>) -> fmt::Result {
+        ("trace_id", self.trace_id.as_str()).encode(enc.encode_label())?;
+        ("span_id", self.span_id.as_str()).encode(enc.encode_label())?;
+        Ok(())
+    }
+}
+
+// === impl Observed ===
+
+impl Observed {
+    pub(crate) fn new(exemplar: Exemplar, value: f64) -> Self {
+        Self {
+            exemplar,
+            value,
+            timestamp: SystemTime::now(),
+        }
+    }
+}
+
+/// Formats the exemplar as an OpenMetrics exemplar suffix, e.g.
+/// ` # {trace_id="...",span_id="..."} 12 1690000000.123`.
+impl fmt::Display for Observed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            " # {{trace_id=\"{}\",span_id=\"{}\"}} {}",
+            self.exemplar.trace_id, self.exemplar.span_id, self.value
+        )?;
+        if let Ok(ts) = self.timestamp.duration_since(UNIX_EPOCH) {
+            write!(f, " {}.{:03}", ts.as_secs(), ts.subsec_millis())?;
+        }
+        Ok(())
+    }
+}
+
+/// Removes OpenMetrics exemplars from a text-formatted metric sample, so that
+/// it may be parsed by clients that only support the Prometheus text format.
+pub(crate) fn strip(line: &str) -> &str {
+    if line.starts_with('#') {
+        return line;
+    }
+
+    // Skip past the sample's labels, if any, since their values may contain
+    // any character.
+    let mut labels_end = 0;
+    if let Some(start) = line
+        .find(|c: char| c == '{' || c == ' ')
+        .filter(|&i| line[i..].starts_with('{'))
+    {
+        let (mut quoted, mut escaped) = (false, false);
+        for (i, c) in line[start..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                '}' if !quoted => {
+                    labels_end = start + i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    match line[labels_end..].find(" # ") {
+        Some(i) => &line[..labels_end + i],
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_exemplars() {
+        assert_eq!(strip("# HELP foo A # metric"), "# HELP foo A # metric");
+        assert_eq!(strip("foo_total 3"), "foo_total 3");
+        assert_eq!(
+            strip("foo_bucket{le=\"1.0\"} 3 # {trace_id=\"ab\",span_id=\"cd\"} 0.5 1.000"),
+            "foo_bucket{le=\"1.0\"} 3"
+        );
+        assert_eq!(
+            strip("foo_bucket{path=\"/a # {b}\",q=\"\\\"}\",le=\"1.0\"} 3"),
+            "foo_bucket{path=\"/a # {b}\",q=\"\\\"}\",le=\"1.0\"} 3"
+        );
+        assert_eq!(
+            strip("foo_bucket 3 # {trace_id=\"ab\",span_id=\"cd\"} 0.5"),
+            "foo_bucket 3"
+        );
+    }
+}
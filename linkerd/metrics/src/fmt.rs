@@ -22,6 +22,9 @@ pub trait FmtMetrics {
 }
 
 /// Adapts `FmtMetrics` to `fmt::Display`.
+///
+/// When formatted with the alternate flag (i.e. `{:#}`), metrics are written
+/// in the OpenMetrics text format, which includes histogram exemplars.
 pub struct DisplayMetrics<F>(F);
 
 #[derive(Clone, Debug)]
@@ -33,6 +36,28 @@ impl<F: FmtMetrics> fmt::Display for DisplayMetrics<F> {
     }
 }
 
+/// Returns true if metrics are being written in the OpenMetrics text format.
+///
+/// The format is signaled by the formatter's alternate flag, which is preserved
+/// as long as `FmtMetrics` implementations pass their formatter through.
+#[inline]
+pub(crate) fn is_openmetrics(f: &fmt::Formatter<'_>) -> bool {
+    f.alternate()
+}
+
+/// Returns the suffix that must be appended to a counter's name to form the
+/// name of its samples.
+///
+/// OpenMetrics requires that counter samples end with `_total`, though not
+/// all counters are named this way.
+pub(crate) fn counter_suffix<N: fmt::Display>(f: &fmt::Formatter<'_>, name: &N) -> &'static str {
+    if is_openmetrics(f) && !name.to_string().ends_with("_total") {
+        "_total"
+    } else {
+        ""
+    }
+}
+
 /// Writes a series of key-quoted-val pairs for use as prometheus labels.
 pub trait FmtLabels {
     fn fmt_labels(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
@@ -84,6 +109,16 @@ impl<'a, N: fmt::Display, M: FmtMetric> Metric<'a, N, M> {
 
     /// Formats help messages for this metric.
     pub fn fmt_help(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if is_openmetrics(f) && M::KIND == "counter" {
+            // OpenMetrics counter families are named without the `_total`
+            // suffix of their samples.
+            let name = self.name.to_string();
+            let name = name.strip_suffix("_total").unwrap_or(&name);
+            writeln!(f, "# HELP {} {}", name, self.help)?;
+            writeln!(f, "# TYPE {} {}", name, M::KIND)?;
+            return Ok(());
+        }
+
         writeln!(f, "# HELP {} {}", self.name, self.help)?;
         writeln!(f, "# TYPE {} {}", self.name, M::KIND)?;
         Ok(())
@@ -1,8 +1,13 @@
+use parking_lot::Mutex;
 use std::fmt;
 use std::marker::PhantomData;
 use std::{cmp, iter, slice};
 
-use super::{Counter, Factor, FmtLabels, FmtMetric};
+use super::{
+    exemplar::{Exemplar, Observed},
+    fmt::is_openmetrics,
+    Counter, Factor, FmtLabels, FmtMetric,
+};
 
 /// A series of latency values and counts.
 #[derive(Debug)]
@@ -32,6 +37,12 @@ pub struct Histogram<V: Into<u64>, F = ()> {
     //       bits.
     sum: Counter,
 
+    /// The most recent exemplar observed in each bucket.
+    ///
+    /// This is only allocated once an exemplar is observed, since most
+    /// histograms never record one.
+    exemplars: Mutex<Option<Box<[Option<Observed>]>>>,
+
     _p: PhantomData<V>,
 }
 
@@ -64,11 +75,21 @@ impl<V: Into<u64>, F: Factor> Histogram<V, F> {
             bounds,
             buckets: buckets.into_boxed_slice(),
             sum: Counter::default(),
+            exemplars: Mutex::new(None),
             _p: PhantomData,
         }
     }
 
     pub fn add<U: Into<V>>(&self, u: U) {
+        self.add_with_exemplar(u, None);
+    }
+
+    /// Records an observation along with an exemplar identifying the trace in
+    /// which it was made.
+    ///
+    /// The exemplar replaces any exemplar previously recorded for the
+    /// observation's bucket.
+    pub fn add_with_exemplar<U: Into<V>>(&self, u: U, exemplar: Option<Exemplar>) {
         let v: V = u.into();
         let value: u64 = v.into();
 
@@ -84,6 +105,56 @@ impl<V: Into<u64>, F: Factor> Histogram<V, F> {
 
         self.buckets[idx].incr();
         self.sum.add(value);
+
+        if let Some(exemplar) = exemplar {
+            let mut exemplars = self.exemplars.lock();
+            let exemplars =
+                exemplars.get_or_insert_with(|| vec![None; self.buckets.len()].into_boxed_slice());
+            exemplars[idx] = Some(Observed::new(exemplar, F::factor(value)));
+        }
+    }
+
+    /// Writes each bucket's cumulative count, followed by the bucket's
+    /// exemplar when formatting OpenMetrics.
+    fn fmt_buckets<N, L>(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        name: N,
+        labels: Option<L>,
+    ) -> fmt::Result
+    where
+        N: fmt::Display,
+        L: FmtLabels,
+    {
+        let exemplars = if is_openmetrics(f) {
+            self.exemplars.lock().clone()
+        } else {
+            None
+        };
+
+        let total = Counter::<F>::new();
+        for (i, (le, count)) in self.into_iter().enumerate() {
+            total.add(count.into());
+            write!(f, "{}_bucket{{", name)?;
+            (labels.as_ref(), Label("le", le)).fmt_labels(f)?;
+            write!(f, "}} {}", total.value())?;
+            if let Some(observed) = exemplars.as_ref().and_then(|e| e[i].as_ref()) {
+                write!(f, "{}", observed)?;
+            }
+            writeln!(f)?;
+        }
+        // The count and sum are not written as counters, since OpenMetrics
+        // counter samples must have a `_total` suffix.
+        for (suffix, value) in [("count", total.value()), ("sum", self.sum.value())] {
+            write!(f, "{}_{}", name, suffix)?;
+            if let Some(labels) = labels.as_ref() {
+                write!(f, "{{")?;
+                labels.fmt_labels(f)?;
+                write!(f, "}}")?;
+            }
+            writeln!(f, " {}", value)?;
+        }
+        Ok(())
     }
 }
 
@@ -184,14 +255,7 @@ impl<V: Into<u64>, F: Factor> FmtMetric for Histogram<V, F> {
     const KIND: &'static str = "histogram";
 
     fn fmt_metric<N: fmt::Display>(&self, f: &mut fmt::Formatter<'_>, name: N) -> fmt::Result {
-        let total = Counter::<F>::new();
-        for (le, count) in self {
-            total.add(count.into());
-            total.fmt_metric_labeled(f, format_args!("{}_bucket", &name), Label("le", le))?;
-        }
-        total.fmt_metric(f, format_args!("{}_count", &name))?;
-        self.sum.fmt_metric(f, format_args!("{}_sum", &name))?;
-        Ok(())
+        self.fmt_buckets(f, name, None::<&Label<&str, &str>>)
     }
 
     fn fmt_metric_labeled<N, L>(
@@ -204,19 +268,7 @@ impl<V: Into<u64>, F: Factor> FmtMetric for Histogram<V, F> {
         N: fmt::Display,
         L: FmtLabels,
     {
-        let total = Counter::<F>::new();
-        for (le, count) in self {
-            total.add(count.into());
-            total.fmt_metric_labeled(
-                f,
-                format_args!("{}_bucket", &name),
-                (&labels, Label("le", le)),
-            )?;
-        }
-        total.fmt_metric_labeled(f, format_args!("{}_count", &name), &labels)?;
-        self.sum
-            .fmt_metric_labeled(f, format_args!("{}_sum", &name), &labels)?;
-        Ok(())
+        self.fmt_buckets(f, name, Some(labels))
     }
 }
 
@@ -332,6 +384,38 @@ mod tests {
         Bucket::Inf,
     ]);
 
+    struct Fmt<'h>(&'h Histogram<u64>);
+
+    impl fmt::Display for Fmt<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt_metric(f, "latency")
+        }
+    }
+
+    #[test]
+    fn exemplars_only_formatted_as_openmetrics() {
+        let hist = Histogram::<u64>::new(BOUNDS);
+        hist.add_with_exemplar(15u64, Some(Exemplar::new("abc", "def")));
+        hist.add(25u64);
+
+        let prom = Fmt(&hist).to_string();
+        assert!(!prom.contains('#'), "{}", prom);
+
+        let openmetrics = format!("{:#}", Fmt(&hist));
+        let buckets = openmetrics
+            .lines()
+            .filter(|l| l.contains(" # "))
+            .collect::<Vec<_>>();
+        assert_eq!(buckets.len(), 1, "{}", openmetrics);
+        assert!(
+            buckets[0].starts_with(
+                "latency_bucket{le=\"20\"} 1 # {trace_id=\"abc\",span_id=\"def\"} 15 "
+            ),
+            "{}",
+            buckets[0]
+        );
+    }
+
     quickcheck! {
         fn bucket_incremented(obs: u64) -> bool {
             let hist = Histogram::<u64>::new(BOUNDS);
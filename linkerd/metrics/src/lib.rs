@@ -4,6 +4,7 @@
 //! Utilities for exposing metrics to Prometheus.
 
 mod counter;
+mod exemplar;
 mod fmt;
 mod gauge;
 mod histogram;
@@ -19,6 +20,7 @@ mod store;
 pub use self::new_metrics::NewMetrics;
 pub use self::{
     counter::Counter,
+    exemplar::Exemplar,
     fmt::{FmtLabels, FmtMetric, FmtMetrics, Metric},
     gauge::Gauge,
     histogram::Histogram,
@@ -35,6 +37,7 @@ pub mod prom {
     pub use prometheus_client::{
         metrics::{
             counter::{ConstCounter, Counter},
+            exemplar::HistogramWithExemplars,
             family::Family,
             gauge::{ConstGauge, Gauge},
             histogram::Histogram,
@@ -53,7 +56,22 @@ pub mod prom {
     impl crate::FmtMetrics for Report {
         #[inline]
         fn fmt_metrics(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            encoding::text::encode(f, self)
+            let mut text = String::new();
+            encoding::text::encode(&mut text, self)?;
+
+            if crate::fmt::is_openmetrics(f) {
+                // The registry may not be the last report in an OpenMetrics
+                // exposition, so its "# EOF" marker is left to the server.
+                let text = text.strip_suffix("# EOF\n").unwrap_or(&text);
+                return f.write_str(text);
+            }
+
+            // Exemplars are only supported by the OpenMetrics format, so they
+            // are removed for clients that expect the Prometheus text format.
+            for line in text.lines() {
+                writeln!(f, "{}", crate::exemplar::strip(line))?;
+            }
+            Ok(())
         }
     }
 }
@@ -5,7 +5,13 @@ use tracing::trace;
 
 use super::FmtMetrics;
 
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
 /// Serve Prometheues metrics.
+///
+/// Metrics are served in the OpenMetrics text format, which includes
+/// histogram exemplars, when the client prefers it. Otherwise, metrics are
+/// served in the Prometheus text format.
 #[derive(Debug, Clone)]
 pub struct Serve<M> {
     metrics: M,
@@ -30,26 +36,111 @@ impl<M> Serve<M> {
                     .unwrap_or(false)
             })
     }
+
+    /// Returns true if the client prefers the OpenMetrics format to the
+    /// Prometheus text format, according to the quality values in its `Accept`
+    /// headers.
+    ///
+    /// Clients that accept both formats equally are served the Prometheus text
+    /// format.
+    fn is_openmetrics<B>(req: &http::Request<B>) -> bool {
+        let mut openmetrics = 0.0_f32;
+        let mut text = 0.0_f32;
+        let ranges = req
+            .headers()
+            .get_all(http::header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for range in ranges {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default();
+            let quality = params
+                .find_map(|p| p.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse::<f32>().ok())
+                .unwrap_or(0.0);
+            match media_type {
+                "application/openmetrics-text" => openmetrics = openmetrics.max(quality),
+                "text/plain" | "text/*" | "*/*" => text = text.max(quality),
+                _ => {}
+            }
+        }
+        openmetrics > 0.0 && openmetrics > text
+    }
 }
 
 impl<M: FmtMetrics> Serve<M> {
     pub fn serve<B>(&self, req: http::Request<B>) -> std::io::Result<http::Response<Body>> {
+        let openmetrics = Self::is_openmetrics(&req);
+        let content_type = if openmetrics {
+            OPENMETRICS_CONTENT_TYPE
+        } else {
+            "text/plain"
+        };
+
         if Self::is_gzip(&req) {
-            trace!("gzipping metrics");
+            trace!(openmetrics, "gzipping metrics");
             let mut writer = GzEncoder::new(Vec::<u8>::new(), CompressionOptions::fast());
-            write!(&mut writer, "{}", self.metrics.as_display())?;
+            self.write_metrics(&mut writer, openmetrics)?;
             Ok(http::Response::builder()
                 .header(http::header::CONTENT_ENCODING, "gzip")
-                .header(http::header::CONTENT_TYPE, "text/plain")
+                .header(http::header::CONTENT_TYPE, content_type)
                 .body(writer.finish()?.into())
                 .expect("Response must be valid"))
         } else {
             let mut writer = Vec::<u8>::new();
-            write!(&mut writer, "{}", self.metrics.as_display())?;
+            self.write_metrics(&mut writer, openmetrics)?;
             Ok(http::Response::builder()
-                .header(http::header::CONTENT_TYPE, "text/plain")
+                .header(http::header::CONTENT_TYPE, content_type)
                 .body(Body::from(writer))
                 .expect("Response must be valid"))
         }
     }
+
+    fn write_metrics(&self, writer: &mut impl Write, openmetrics: bool) -> std::io::Result<()> {
+        if openmetrics {
+            // The alternate flag selects the OpenMetrics format, which must be
+            // terminated by an EOF marker.
+            write!(writer, "{:#}", self.metrics.as_display())?;
+            writeln!(writer, "# EOF")
+        } else {
+            write!(writer, "{}", self.metrics.as_display())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accepts_openmetrics(accept: &str) -> bool {
+        let req = http::Request::builder()
+            .header(http::header::ACCEPT, accept)
+            .body(())
+            .unwrap();
+        Serve::<()>::is_openmetrics(&req)
+    }
+
+    #[test]
+    fn negotiates_openmetrics() {
+        // Prometheus' default scrape header.
+        assert!(accepts_openmetrics(
+            "application/openmetrics-text;version=1.0.0,application/openmetrics-text;version=0.0.1;q=0.75,text/plain;version=0.0.4;q=0.5,*/*;q=0.1"
+        ));
+        assert!(accepts_openmetrics("application/openmetrics-text"));
+        assert!(accepts_openmetrics(
+            "text/plain;q=0.5, application/openmetrics-text; version=1.0.0; q=0.6"
+        ));
+
+        assert!(!accepts_openmetrics("*/*"));
+        assert!(!accepts_openmetrics("text/plain"));
+        assert!(!accepts_openmetrics(
+            "text/plain;version=0.0.4,application/openmetrics-text;version=1.0.0;q=0.5"
+        ));
+        assert!(!accepts_openmetrics(
+            "application/openmetrics-text,text/plain"
+        ));
+        assert!(!accepts_openmetrics("application/openmetrics-text;q=0"));
+        assert!(!accepts_openmetrics("application/openmetrics-text;q=bogus"));
+    }
 }
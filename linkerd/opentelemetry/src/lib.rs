@@ -0,0 +1,174 @@
+#![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
+#![forbid(unsafe_code)]
+
+pub mod metrics;
+#[cfg(test)]
+mod tests;
+
+use futures::stream::{Stream, StreamExt};
+use http_body::Body as HttpBody;
+use linkerd_error::Error;
+use metrics::Registry;
+pub use opentelemetry_proto as proto;
+use opentelemetry_proto::{
+    collector::trace::v1::{trace_service_client::TraceServiceClient, ExportTraceServiceRequest},
+    common::v1::InstrumentationScope,
+    resource::v1::Resource,
+    trace::v1::{ResourceSpans, ScopeSpans, Span},
+};
+use tokio::time;
+use tonic::{self as grpc, body::BoxBody, client::GrpcService};
+use tracing::{debug, trace};
+
+/// Exports spans to an OpenTelemetry collector via the OTLP/gRPC protocol.
+///
+/// Spans are accumulated into batches and each batch is sent as a unary
+/// `Export` request. Batches that cannot be exported are dropped.
+pub async fn export_spans<T, S>(client: T, resource: Resource, spans: S, metrics: Registry)
+where
+    T: GrpcService<BoxBody>,
+    T::Error: Into<Error>,
+    T::ResponseBody: HttpBody<Data = tonic::codegen::Bytes> + Send + 'static,
+    <T::ResponseBody as HttpBody>::Error: Into<Error> + Send,
+    S: Stream<Item = Span> + Unpin,
+{
+    debug!("Span exporter running");
+    SpanExporter::new(client, resource, spans, metrics)
+        .run()
+        .await
+}
+
+/// SpanExporter sends a Stream of spans to the given TraceService gRPC service.
+struct SpanExporter<T, S> {
+    client: T,
+    resource: Resource,
+    spans: S,
+    metrics: Registry,
+}
+
+#[derive(Debug)]
+struct SpanRxClosed;
+
+// === impl SpanExporter ===
+
+impl<T, S> SpanExporter<T, S>
+where
+    T: GrpcService<BoxBody>,
+    T::Error: Into<Error>,
+    T::ResponseBody: HttpBody<Data = tonic::codegen::Bytes> + Send + 'static,
+    <T::ResponseBody as HttpBody>::Error: Into<Error> + Send,
+    S: Stream<Item = Span> + Unpin,
+{
+    const MAX_BATCH_SIZE: usize = 1000;
+    const MAX_BATCH_IDLE: time::Duration = time::Duration::from_secs(10);
+    const SCOPE_NAME: &'static str = "linkerd-proxy";
+
+    fn new(client: T, resource: Resource, spans: S, metrics: Registry) -> Self {
+        Self {
+            client,
+            resource,
+            spans,
+            metrics,
+        }
+    }
+
+    async fn run(self) {
+        let Self {
+            client,
+            resource,
+            mut spans,
+            mut metrics,
+        } = self;
+
+        // Holds the batch of pending spans. Cleared as the spans are flushed.
+        // Contains no more than MAX_BATCH_SIZE spans.
+        let mut accum = Vec::new();
+
+        let mut svc = TraceServiceClient::new(client);
+        loop {
+            // Collect spans into a batch.
+            let collect = Self::collect_batch(&mut spans, &mut accum).await;
+
+            // If we collected spans, flush them.
+            if !accum.is_empty() {
+                let spans = std::mem::take(&mut accum);
+                let n = spans.len() as u64;
+                let req = ExportTraceServiceRequest {
+                    resource_spans: vec![ResourceSpans {
+                        resource: Some(resource.clone()),
+                        scope_spans: vec![ScopeSpans {
+                            scope: Some(InstrumentationScope {
+                                name: Self::SCOPE_NAME.to_string(),
+                                ..Default::default()
+                            }),
+                            spans,
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                };
+                trace!(spans = n, "Sending batch");
+                match svc.export(grpc::Request::new(req)).await {
+                    Ok(rsp) => {
+                        metrics.send(n);
+                        if let Some(partial) = rsp.into_inner().partial_success {
+                            if partial.rejected_spans > 0 || !partial.error_message.is_empty() {
+                                debug!(
+                                    rejected = partial.rejected_spans,
+                                    message = %partial.error_message,
+                                    "Collector partially accepted spans"
+                                );
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        // The batch is dropped so that an unavailable
+                        // collector does not cause spans to accumulate.
+                        debug!(%error, spans = n, "Failed to export spans");
+                        metrics.fail();
+                    }
+                }
+            }
+
+            // If the span source was closed, end the task.
+            if let Err(SpanRxClosed) = collect {
+                debug!("Span channel lost");
+                return;
+            }
+        }
+    }
+
+    /// Collects spans from the proxy into `accum`.
+    ///
+    /// Returns an error when the span stream has completed. An error may be
+    /// returned after accumulating spans.
+    async fn collect_batch(spans: &mut S, accum: &mut Vec<Span>) -> Result<(), SpanRxClosed> {
+        loop {
+            if accum.len() == Self::MAX_BATCH_SIZE {
+                trace!(capacity = Self::MAX_BATCH_SIZE, "Batch capacity reached");
+                return Ok(());
+            }
+
+            tokio::select! {
+                biased;
+
+                res = spans.next() => match res {
+                    Some(span) => {
+                        trace!(?span, "Adding to batch");
+                        accum.push(span);
+                    }
+                    None => return Err(SpanRxClosed),
+                },
+
+                // Don't hold spans indefinitely. Return if we hit an idle
+                // timeout and spans have been collected.
+                _ = time::sleep(Self::MAX_BATCH_IDLE) => {
+                    if !accum.is_empty() {
+                        trace!(spans = accum.len(), "Flushing spans due to inactivity");
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
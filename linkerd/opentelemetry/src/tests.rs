@@ -0,0 +1,112 @@
+use super::*;
+use opentelemetry_proto::{
+    collector::trace::v1::{
+        trace_service_server::{TraceService, TraceServiceServer},
+        ExportTraceServiceResponse,
+    },
+    common::v1::{any_value, AnyValue, KeyValue},
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// A stand-in for an OTLP collector that forwards each export request to
+/// the test.
+struct Collector(mpsc::UnboundedSender<ExportTraceServiceRequest>);
+
+#[tonic::async_trait]
+impl TraceService for Collector {
+    async fn export(
+        &self,
+        req: grpc::Request<ExportTraceServiceRequest>,
+    ) -> Result<grpc::Response<ExportTraceServiceResponse>, grpc::Status> {
+        self.0
+            .send(req.into_inner())
+            .map_err(|_| grpc::Status::unavailable("test ended"))?;
+        Ok(grpc::Response::new(ExportTraceServiceResponse::default()))
+    }
+}
+
+fn resource() -> Resource {
+    Resource {
+        attributes: vec![KeyValue {
+            key: "service.name".to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue("linkerd-proxy".to_string())),
+            }),
+        }],
+        ..Default::default()
+    }
+}
+
+fn span(name: &str) -> Span {
+    Span {
+        trace_id: vec![1; 16],
+        span_id: vec![2; 8],
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn exports_batched_spans() {
+    let (collected_tx, mut collected_rx) = mpsc::unbounded_channel();
+    let client = TraceServiceServer::new(Collector(collected_tx));
+
+    let (span_tx, span_rx) = mpsc::channel(10);
+    let (registry, _report) = metrics::new();
+    let exporter = tokio::spawn(export_spans(
+        client,
+        resource(),
+        ReceiverStream::new(span_rx),
+        registry,
+    ));
+
+    span_tx.send(span("a")).await.unwrap();
+    span_tx.send(span("b")).await.unwrap();
+    drop(span_tx);
+    exporter.await.expect("exporter must not panic");
+
+    let req = collected_rx.recv().await.expect("spans must be exported");
+    assert_eq!(req.resource_spans.len(), 1);
+    let ResourceSpans {
+        resource: rsc,
+        scope_spans,
+        ..
+    } = req.resource_spans.into_iter().next().unwrap();
+    assert_eq!(rsc, Some(resource()));
+    assert_eq!(scope_spans.len(), 1);
+    let ScopeSpans { scope, spans, .. } = scope_spans.into_iter().next().unwrap();
+    assert_eq!(scope.unwrap().name, "linkerd-proxy");
+    assert_eq!(
+        spans.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+        vec!["a", "b"]
+    );
+
+    assert!(
+        collected_rx.recv().await.is_none(),
+        "no further requests must be sent"
+    );
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn flushes_idle_batches() {
+    let (collected_tx, mut collected_rx) = mpsc::unbounded_channel();
+    let client = TraceServiceServer::new(Collector(collected_tx));
+
+    let (span_tx, span_rx) = mpsc::channel(10);
+    let (registry, _report) = metrics::new();
+    tokio::spawn(export_spans(
+        client,
+        resource(),
+        ReceiverStream::new(span_rx),
+        registry,
+    ));
+
+    span_tx.send(span("a")).await.unwrap();
+    let req = collected_rx
+        .recv()
+        .await
+        .expect("spans must be exported after the idle timeout");
+    assert_eq!(req.resource_spans[0].scope_spans[0].spans, vec![span("a")]);
+    drop(span_tx);
+}
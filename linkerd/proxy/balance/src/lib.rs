@@ -11,4 +11,23 @@ pub use tower::load::peak_ewma;
 pub struct EwmaConfig {
     pub default_rtt: std::time::Duration,
     pub decay: std::time::Duration,
+    /// If set, the share of traffic sent to newly discovered endpoints is
+    /// ramped up linearly over this window.
+    pub slow_start: Option<std::time::Duration>,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LeastRequestConfig {
+    /// The number of ready endpoints that are compared to select each
+    /// request's endpoint.
+    pub choice_count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RingHashConfig<H> {
+    /// Computes the hash of each request.
+    pub key: H,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RoundRobinConfig(());
@@ -1,4 +1,4 @@
-use crate::EwmaConfig;
+use crate::{EwmaConfig, LeastRequestConfig, RingHashConfig, RoundRobinConfig};
 use futures::prelude::*;
 use linkerd_error::Error;
 use linkerd_metrics::prom;
@@ -7,35 +7,85 @@ use linkerd_proxy_pool::PoolQueue;
 use linkerd_stack::{layer, queue, ExtractParam, Gate, NewService, Param, Service};
 use std::{fmt::Debug, marker::PhantomData, net::SocketAddr};
 use tokio::time;
-use tower::load::{self, PeakEwma};
+use tower::load::{self, PeakEwma, PendingRequests};
 
+mod endpoints;
+mod least_request;
 mod p2c;
+mod ring_hash;
+mod round_robin;
+mod slow_start;
 
-pub use self::p2c::{P2cMetricFamilies, P2cMetrics, P2cPool};
+pub use self::{
+    least_request::LeastRequestPool,
+    p2c::{P2cMetricFamilies, P2cMetrics, P2cPool},
+    ring_hash::{hash, HashRequest, RingHashPool},
+    round_robin::RoundRobinPool,
+};
 pub use linkerd_proxy_pool::{Pool, QueueMetricFamilies, QueueMetrics, Update};
 
+/// Identifies a load balancing algorithm.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    PeakEwma,
+    RingHash,
+    LeastRequest,
+    RoundRobin,
+}
+
+/// Builds a load balancing algorithm's [`Pool`] from its configuration.
+///
+/// Balancers that track the load on each endpoint use `C` to determine when a
+/// response completes. Pools track the endpoints that each request's attempts
+/// are dispatched to with `D`.
+pub trait MkPool<C, D, T, Req, N> {
+    type Pool: Pool<T, Req>;
+
+    fn mk_pool(self, metrics: P2cMetrics, attempts: D, new_endpoint: N) -> Self::Pool;
+}
+
+/// Tracks the endpoints that a request's attempts are dispatched to.
+///
+/// When a request is sent more than once--e.g. when it is hedged--a pool
+/// avoids dispatching an attempt to an endpoint that one of the request's
+/// prior attempts was dispatched to, if another endpoint is ready.
+///
+/// `()` does not track attempts.
+pub trait TrackAttempts<Req> {
+    /// Returns true if a prior attempt of the request was dispatched to `addr`.
+    fn attempted(&self, req: &Req, addr: &SocketAddr) -> bool;
+
+    /// Records that the request is dispatched to `addr`.
+    fn record(&self, req: &Req, addr: SocketAddr);
+}
+
 #[derive(Clone, Debug)]
 pub struct MetricFamilies<L> {
     queue: QueueMetricFamilies<L>,
-    p2c: P2cMetricFamilies<L>,
+    /// Pool metrics, registered separately for each `Algorithm`.
+    pools: [P2cMetricFamilies<L>; Algorithm::ALL.len()],
 }
 
 #[derive(Clone, Debug)]
 pub struct Metrics {
     queue: QueueMetrics,
-    p2c: P2cMetrics,
+    pool: P2cMetrics,
 }
 
 /// Configures a stack to resolve targets to balance requests over `N`-typed
-/// endpoint stacks.
+/// endpoint stacks, using the `A`-typed algorithm configured by each target.
 #[derive(Debug)]
-pub struct NewBalancePeakEwma<C, Req, X, R, N> {
+pub struct NewBalance<A, C, D, Req, X, R, N> {
     resolve: R,
     inner: N,
     params: X,
-    _marker: PhantomData<fn(Req) -> C>,
+    _marker: PhantomData<fn(Req) -> (A, C, D)>,
 }
 
+/// Balances requests over endpoints by the power-of-two-choices algorithm,
+/// comparing their peak-EWMA latencies.
+pub type NewBalancePeakEwma<C, Req, X, R, N> = NewBalance<EwmaConfig, C, (), Req, X, R, N>;
+
 pub type Balance<Req, F> = Gate<PoolQueue<Req, F>>;
 
 /// Wraps the inner services in [`PeakEwma`] services so their load is tracked
@@ -47,9 +97,37 @@ pub struct NewPeakEwma<C, Req, N> {
     _marker: PhantomData<fn(Req) -> C>,
 }
 
-// === impl NewBalancePeakEwma ===
+/// Wraps the inner services in [`PendingRequests`] services so their
+/// outstanding requests are tracked for the least request balancer.
+#[derive(Debug)]
+pub struct NewPendingRequests<C, Req, N> {
+    inner: N,
+    _marker: PhantomData<fn(Req) -> C>,
+}
+
+// === impl Algorithm ===
 
-impl<C, Req, X, R, N> NewBalancePeakEwma<C, Req, X, R, N> {
+impl Algorithm {
+    const ALL: [Self; 4] = [
+        Self::PeakEwma,
+        Self::RingHash,
+        Self::LeastRequest,
+        Self::RoundRobin,
+    ];
+
+    fn metrics_prefix(self) -> &'static str {
+        match self {
+            Self::PeakEwma => "p2c",
+            Self::RingHash => "ring_hash",
+            Self::LeastRequest => "least_request",
+            Self::RoundRobin => "round_robin",
+        }
+    }
+}
+
+// === impl NewBalance ===
+
+impl<A, C, D, Req, X, R, N> NewBalance<A, C, D, Req, X, R, N> {
     pub fn new(inner: N, resolve: R, params: X) -> Self {
         Self {
             resolve,
@@ -69,23 +147,23 @@ impl<C, Req, X, R, N> NewBalancePeakEwma<C, Req, X, R, N> {
     }
 }
 
-impl<C, T, Req, X, R, M, N, S> NewService<T> for NewBalancePeakEwma<C, Req, X, R, M>
+impl<A, C, D, T, Req, X, R, M, N> NewService<T> for NewBalance<A, C, D, Req, X, R, M>
 where
-    T: Param<EwmaConfig> + Param<queue::Capacity> + Param<queue::Timeout> + Clone + Send,
+    T: Param<A> + Param<queue::Capacity> + Param<queue::Timeout> + Clone + Send,
+    A: MkPool<C, D, R::Endpoint, Req, N> + Debug,
+    D: Default,
+    A::Pool: Send + 'static,
+    <A::Pool as Service<Req>>::Error: Into<Error> + Send + Sync,
+    <A::Pool as Service<Req>>::Future: Send + 'static,
     X: ExtractParam<Metrics, T>,
     R: Resolve<T>,
     R::Resolution: Unpin,
     R::Error: Send,
-    M: NewService<T, Service = N> + Clone,
-    N: NewService<(SocketAddr, R::Endpoint), Service = S> + Send + 'static,
-    S: Service<Req> + Send + 'static,
-    S::Future: Send + 'static,
-    S::Error: Into<Error>,
-    C: load::TrackCompletion<load::peak_ewma::Handle, S::Response> + Default + Send + 'static,
+    M: NewService<T, Service = N>,
     Req: Send + 'static,
-    Balance<Req, future::ErrInto<<PeakEwma<S, C> as Service<Req>>::Future, Error>>: Service<Req>,
+    Balance<Req, <A::Pool as Service<Req>>::Future>: Service<Req>,
 {
-    type Service = Balance<Req, future::ErrInto<<PeakEwma<S, C> as Service<Req>>::Future, Error>>;
+    type Service = Balance<Req, <A::Pool as Service<Req>>::Future>;
 
     fn new_service(&self, target: T) -> Self::Service {
         // Initialize a resolution stream to discover endpoint updates. This
@@ -106,10 +184,10 @@ where
         // The pool wraps the inner endpoint stack so that its inner ready cache
         // can be updated without requiring the service to process requests.
         let pool = {
-            let ewma = target.param();
-            tracing::debug!(?ewma);
+            let config: A = target.param();
+            tracing::debug!(?config);
             let new_endpoint = self.inner.new_service(target);
-            P2cPool::new(metrics.p2c, NewPeakEwma::new(ewma, new_endpoint))
+            config.mk_pool(metrics.pool, D::default(), new_endpoint)
         };
 
         // The queue runs on a dedicated task, owning the resolution stream and
@@ -117,12 +195,12 @@ where
         // that allows passing requests to the service. When all clones of the
         // service are dropped, the queue task completes, dropping the
         // resolution and all inner services.
-        tracing::debug!(capacity, ?failfast, "Spawning p2c pool queue");
+        tracing::debug!(capacity, ?failfast, "Spawning pool queue");
         PoolQueue::spawn(capacity, failfast, metrics.queue, disco, pool)
     }
 }
 
-impl<C, Req, X: Clone, R: Clone, N: Clone> Clone for NewBalancePeakEwma<C, Req, X, R, N> {
+impl<A, C, D, Req, X: Clone, R: Clone, N: Clone> Clone for NewBalance<A, C, D, Req, X, R, N> {
     fn clone(&self) -> Self {
         Self {
             resolve: self.resolve.clone(),
@@ -133,6 +211,106 @@ impl<C, Req, X: Clone, R: Clone, N: Clone> Clone for NewBalancePeakEwma<C, Req,
     }
 }
 
+// === impl EwmaConfig ===
+
+impl<C, D, T, Req, N, S> MkPool<C, D, T, Req, N> for EwmaConfig
+where
+    T: Clone + Eq + Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    C: load::TrackCompletion<load::peak_ewma::Handle, S::Response> + Default + Send + 'static,
+    D: TrackAttempts<Req>,
+{
+    type Pool = P2cPool<T, NewPeakEwma<C, Req, N>, Req, PeakEwma<S, C>, D>;
+
+    fn mk_pool(self, metrics: P2cMetrics, attempts: D, new_endpoint: N) -> Self::Pool {
+        let pool =
+            P2cPool::new(metrics, NewPeakEwma::new(self, new_endpoint)).with_attempts(attempts);
+        match self.slow_start {
+            Some(window) => pool.with_slow_start(window),
+            None => pool,
+        }
+    }
+}
+
+// === impl RingHashConfig ===
+
+impl<C, D, T, Req, N, S, H> MkPool<C, D, T, Req, N> for RingHashConfig<H>
+where
+    T: Clone + Eq + Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    H: HashRequest<Req>,
+    D: TrackAttempts<Req>,
+{
+    type Pool = RingHashPool<T, N, Req, S, H, D>;
+
+    fn mk_pool(self, metrics: P2cMetrics, attempts: D, new_endpoint: N) -> Self::Pool {
+        RingHashPool::new(metrics, self.key, new_endpoint).with_attempts(attempts)
+    }
+}
+
+// === impl LeastRequestConfig ===
+
+impl<C, D, T, Req, N, S> MkPool<C, D, T, Req, N> for LeastRequestConfig
+where
+    T: Clone + Eq + Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    C: load::TrackCompletion<load::pending_requests::Handle, S::Response>
+        + Default
+        + Send
+        + 'static,
+    D: TrackAttempts<Req>,
+{
+    type Pool = LeastRequestPool<T, NewPendingRequests<C, Req, N>, Req, PendingRequests<S, C>, D>;
+
+    fn mk_pool(self, metrics: P2cMetrics, attempts: D, new_endpoint: N) -> Self::Pool {
+        LeastRequestPool::new(
+            metrics,
+            self.choice_count,
+            NewPendingRequests::new(new_endpoint),
+        )
+        .with_attempts(attempts)
+    }
+}
+
+// === impl RoundRobinConfig ===
+
+impl<C, D, T, Req, N, S> MkPool<C, D, T, Req, N> for RoundRobinConfig
+where
+    T: Clone + Eq + Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    D: TrackAttempts<Req>,
+{
+    type Pool = RoundRobinPool<T, N, Req, S, D>;
+
+    fn mk_pool(self, metrics: P2cMetrics, attempts: D, new_endpoint: N) -> Self::Pool {
+        RoundRobinPool::new(metrics, new_endpoint).with_attempts(attempts)
+    }
+}
+
+// === impl TrackAttempts ===
+
+impl<Req> TrackAttempts<Req> for () {
+    #[inline]
+    fn attempted(&self, _: &Req, _: &SocketAddr) -> bool {
+        false
+    }
+
+    #[inline]
+    fn record(&self, _: &Req, _: SocketAddr) {}
+}
+
 // === impl NewPeakEwma ===
 
 impl<C, Req, N> NewPeakEwma<C, Req, N> {
@@ -175,6 +353,30 @@ where
     }
 }
 
+// === impl NewPendingRequests ===
+
+impl<C, Req, N> NewPendingRequests<C, Req, N> {
+    fn new(inner: N) -> Self {
+        Self {
+            inner,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C, T, N, Req, S> NewService<T> for NewPendingRequests<C, Req, N>
+where
+    C: load::TrackCompletion<load::pending_requests::Handle, S::Response> + Default,
+    N: NewService<T, Service = S>,
+    S: Service<Req>,
+{
+    type Service = PendingRequests<S, C>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        PendingRequests::new(self.inner.new_service(target), C::default())
+    }
+}
+
 // === impl MetricFamilies ===
 
 impl<L> MetricFamilies<L>
@@ -183,15 +385,22 @@ where
     L: Eq + Clone + Send + Sync + 'static,
 {
     pub fn register(reg: &mut prom::registry::Registry) -> Self {
-        let p2c = P2cMetricFamilies::register(reg.sub_registry_with_prefix("p2c"));
+        let pools = Algorithm::ALL.map(|algorithm| {
+            P2cMetricFamilies::register(reg.sub_registry_with_prefix(algorithm.metrics_prefix()))
+        });
         let queue = QueueMetricFamilies::register(reg.sub_registry_with_prefix("queue"));
-        Self { p2c, queue }
+        Self { pools, queue }
     }
 
+    /// Returns metrics for a p2c balancer.
     pub fn metrics(&self, labels: &L) -> Metrics {
-        tracing::trace!(?labels, "Budilding metrics");
+        self.algorithm_metrics(Algorithm::PeakEwma, labels)
+    }
+
+    pub fn algorithm_metrics(&self, algorithm: Algorithm, labels: &L) -> Metrics {
+        tracing::trace!(?algorithm, ?labels, "Building metrics");
         Metrics {
-            p2c: self.p2c.metrics(labels),
+            pool: self.pools[algorithm as usize].metrics(labels),
             queue: self.queue.metrics(labels),
         }
     }
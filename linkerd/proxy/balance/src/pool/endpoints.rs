@@ -0,0 +1,204 @@
+//! Tracks the endpoint services in a pool as service discovery updates are
+//! received.
+
+use super::{P2cMetrics, TrackAttempts, Update};
+use ahash::AHashMap;
+use linkerd_error::Error;
+use linkerd_stack::{NewService, Service};
+use std::{collections::hash_map::Entry, net::SocketAddr};
+use tower::ready_cache::ReadyCache;
+
+#[derive(Debug)]
+pub(super) struct Endpoints<T, N, Req, S, D> {
+    new_endpoint: N,
+    attempts: D,
+    pub(super) targets: AHashMap<SocketAddr, T>,
+    pub(super) ready: ReadyCache<SocketAddr, S, Req>,
+    pub(super) metrics: P2cMetrics,
+}
+
+impl<T, N, Req, S> Endpoints<T, N, Req, S, ()>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+{
+    pub(super) fn new(metrics: P2cMetrics, new_endpoint: N) -> Self {
+        Self {
+            new_endpoint,
+            attempts: (),
+            metrics,
+            targets: Default::default(),
+            ready: ReadyCache::default(),
+        }
+    }
+}
+
+impl<T, N, Req, S, D> Endpoints<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+{
+    pub(super) fn with_attempts<A>(self, attempts: A) -> Endpoints<T, N, Req, S, A> {
+        Endpoints {
+            new_endpoint: self.new_endpoint,
+            attempts,
+            targets: self.targets,
+            ready: self.ready,
+            metrics: self.metrics,
+        }
+    }
+
+    /// Dispatches a request to the ready endpoint at `idx`.
+    ///
+    /// If a prior attempt of the request was dispatched to that endpoint, the
+    /// request is instead dispatched to the next ready endpoint that has not
+    /// been attempted, if there is one.
+    pub(super) fn call_ready_index(&mut self, idx: usize, req: Req) -> S::Future
+    where
+        D: TrackAttempts<Req>,
+    {
+        let attempted = |i: usize| {
+            let (addr, _) = self.ready.get_ready_index(i).expect("invalid index");
+            self.attempts.attempted(&req, addr)
+        };
+        let len = self.ready.ready_len();
+        let idx = if attempted(idx) {
+            (1..len)
+                .map(|n| (idx + n) % len)
+                .find(|i| !attempted(*i))
+                .unwrap_or(idx)
+        } else {
+            idx
+        };
+
+        let (addr, _) = self.ready.get_ready_index(idx).expect("invalid index");
+        tracing::trace!(?addr, "Dispatching");
+        self.attempts.record(&req, *addr);
+        self.ready.call_ready_index(idx, req)
+    }
+
+    /// Dispatches a request to the ready endpoint with the given address.
+    ///
+    /// See [`Self::call_ready_index`].
+    pub(super) fn call_ready(&mut self, addr: &SocketAddr, req: Req) -> S::Future
+    where
+        D: TrackAttempts<Req>,
+    {
+        let (idx, _, _) = self.ready.get_ready(addr).expect("endpoint must be ready");
+        self.call_ready_index(idx, req)
+    }
+
+    /// Applies a service discovery update to the pool.
+    ///
+    /// Returns true if the pool was changed.
+    pub(super) fn update(&mut self, update: Update<T>) -> bool {
+        tracing::trace!(?update);
+        self.metrics.inc(&update);
+        match update {
+            Update::Reset(targets) => self.reset(targets),
+            Update::Add(targets) => self.add(targets),
+            Update::Remove(addrs) => self.remove(addrs),
+            Update::DoesNotExist => self.clear(),
+        }
+    }
+
+    /// Resets the pool to include the given targets without unnecessarily
+    /// rebuilding inner services.
+    ///
+    /// Returns true if the pool was changed.
+    fn reset(&mut self, targets: Vec<(SocketAddr, T)>) -> bool {
+        let mut changed = false;
+        let mut remaining = std::mem::take(&mut self.targets);
+        for (addr, target) in targets.into_iter() {
+            let t = remaining.remove(&addr);
+            if t.as_ref() == Some(&target) {
+                tracing::debug!(?addr, "Endpoint unchanged");
+            } else {
+                if t.is_none() {
+                    tracing::debug!(?addr, "Creating endpoint");
+                    self.metrics.endpoints.inc();
+                } else {
+                    tracing::debug!(?addr, "Updating endpoint");
+                }
+
+                let svc = self.new_endpoint.new_service((addr, target.clone()));
+                self.ready.push(addr, svc);
+                changed = true;
+            }
+
+            self.targets.insert(addr, target);
+        }
+
+        for (addr, _) in remaining.drain() {
+            tracing::debug!(?addr, "Removing endpoint");
+            self.ready.evict(&addr);
+            self.metrics.endpoints.dec();
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Adds endpoints to the pool without unnecessarily rebuilding inner
+    /// services.
+    ///
+    /// Returns true if the pool was changed.
+    fn add(&mut self, targets: Vec<(SocketAddr, T)>) -> bool {
+        let mut changed = false;
+        for (addr, target) in targets.into_iter() {
+            match self.targets.entry(addr) {
+                Entry::Occupied(e) if e.get() == &target => {
+                    tracing::debug!(?addr, "Endpoint unchanged");
+                    continue;
+                }
+                Entry::Occupied(mut e) => {
+                    e.insert(target.clone());
+                }
+                Entry::Vacant(e) => {
+                    e.insert(target.clone());
+                    self.metrics.endpoints.inc();
+                }
+            }
+            tracing::debug!(?addr, "Creating endpoint");
+            let svc = self.new_endpoint.new_service((addr, target));
+            self.ready.push(addr, svc);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Removes endpoint services.
+    ///
+    /// Returns true if the pool was changed.
+    fn remove(&mut self, addrs: Vec<SocketAddr>) -> bool {
+        let mut changed = false;
+        for addr in addrs.into_iter() {
+            if self.targets.remove(&addr).is_some() {
+                tracing::debug!(?addr, "Removing endpoint");
+                self.ready.evict(&addr);
+                self.metrics.endpoints.dec();
+                changed = true;
+            } else {
+                tracing::debug!(?addr, "Unknown endpoint");
+            }
+        }
+        changed
+    }
+
+    /// Clear all endpoints from the pool.
+    ///
+    /// Returns true if the pool was changed.
+    fn clear(&mut self) -> bool {
+        let changed = !self.targets.is_empty();
+        for (addr, _) in self.targets.drain() {
+            tracing::debug!(?addr, "Clearing endpoint");
+            self.ready.evict(&addr);
+            self.metrics.endpoints.dec();
+        }
+        changed
+    }
+}
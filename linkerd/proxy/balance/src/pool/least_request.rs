@@ -0,0 +1,222 @@
+//! A pool that dispatches requests to the least loaded of a random sample of
+//! endpoints.
+
+use super::{endpoints::Endpoints, P2cMetrics, Pool, TrackAttempts, Update};
+use futures_util::TryFutureExt;
+use linkerd_error::Error;
+use linkerd_stack::{NewService, Service};
+use rand::{rngs::SmallRng, seq::index, thread_rng, SeedableRng};
+use std::{
+    net::SocketAddr,
+    task::{Context, Poll},
+};
+use tower::{load::Load, ready_cache::error::Failed};
+
+/// Dispatches requests to the least loaded of `choice_count` randomly
+/// selected ready endpoints.
+#[derive(Debug)]
+pub struct LeastRequestPool<T, N, Req, S, D = ()> {
+    endpoints: Endpoints<T, N, Req, S, D>,
+    choice_count: usize,
+    rng: SmallRng,
+    next_idx: Option<usize>,
+}
+
+impl<T, N, Req, S> LeastRequestPool<T, N, Req, S>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req> + Load,
+    S::Error: Into<Error>,
+    S::Metric: std::fmt::Debug,
+{
+    pub fn new(metrics: P2cMetrics, choice_count: usize, new_endpoint: N) -> Self {
+        let rng = SmallRng::from_rng(&mut thread_rng()).expect("RNG must be seeded");
+        Self {
+            rng,
+            // At least one endpoint must be considered.
+            choice_count: choice_count.max(1),
+            next_idx: None,
+            endpoints: Endpoints::new(metrics, new_endpoint),
+        }
+    }
+}
+
+impl<T, N, Req, S, D> LeastRequestPool<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req> + Load,
+    S::Error: Into<Error>,
+    S::Metric: std::fmt::Debug,
+{
+    /// Avoids dispatching a request to the endpoints that its prior attempts
+    /// were dispatched to, as tracked by `attempts`.
+    pub fn with_attempts<A>(self, attempts: A) -> LeastRequestPool<T, N, Req, S, A> {
+        LeastRequestPool {
+            endpoints: self.endpoints.with_attempts(attempts),
+            choice_count: self.choice_count,
+            rng: self.rng,
+            next_idx: self.next_idx,
+        }
+    }
+
+    fn least_ready_index(&mut self) -> Option<usize> {
+        let len = self.endpoints.ready.ready_len();
+        if len == 0 {
+            return None;
+        }
+        let ready = &self.endpoints.ready;
+        let chosen = index::sample(&mut self.rng, len, self.choice_count.min(len))
+            .into_iter()
+            .map(|idx| {
+                let (_, svc) = ready.get_ready_index(idx).expect("invalid index");
+                (idx, svc.load())
+            })
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        tracing::trace!(?chosen, "least request");
+        chosen.map(|(idx, _)| idx)
+    }
+}
+
+impl<T, N, Req, S, D> Pool<T, Req> for LeastRequestPool<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req> + Load,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    S::Metric: std::fmt::Debug,
+    D: TrackAttempts<Req>,
+{
+    fn update_pool(&mut self, update: Update<T>) {
+        if self.endpoints.update(update) {
+            self.next_idx = None;
+        }
+    }
+
+    /// Moves pending endpoints to ready.
+    ///
+    /// This must be called from the same task that invokes Service::poll_ready.
+    fn poll_pool(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        tracing::trace!("Polling pending");
+        self.endpoints
+            .ready
+            .poll_pending(cx)
+            .map_err(|Failed(_, e)| e)
+    }
+}
+
+impl<T, N, Req, S, D> Service<Req> for LeastRequestPool<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req> + Load,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    S::Metric: std::fmt::Debug,
+    D: TrackAttempts<Req>,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = futures::future::ErrInto<S::Future, Error>;
+
+    /// Returns ready when at least one endpoint is ready.
+    ///
+    /// NOTE that this may return `Pending` when there are no endpoints. In such
+    /// cases, the caller must invoke `update_pool` and then wait for new
+    /// endpoints to become ready.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        loop {
+            tracing::trace!(
+                pending = self.endpoints.ready.pending_len(),
+                "Polling pending"
+            );
+            match self.endpoints.ready.poll_pending(cx)? {
+                Poll::Ready(()) => tracing::trace!("All endpoints are ready"),
+                Poll::Pending => tracing::trace!("Endpoints are pending"),
+            }
+
+            let idx = match self.next_idx.take().or_else(|| self.least_ready_index()) {
+                Some(idx) => idx,
+                None => {
+                    tracing::debug!("No ready endpoints");
+                    return Poll::Pending;
+                }
+            };
+
+            tracing::trace!(ready.index = idx, "Selected");
+            if !self.endpoints.ready.check_ready_index(cx, idx)? {
+                tracing::trace!(ready.index = idx, "Reverted to pending");
+                continue;
+            }
+
+            tracing::trace!(ready.index = idx, "Ready");
+            self.next_idx = Some(idx);
+            return Poll::Ready(Ok(()));
+        }
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let idx = self.next_idx.take().expect("call before ready");
+        self.endpoints.call_ready_index(idx, req).err_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::prelude::*;
+    use linkerd_stack::ServiceExt;
+    use tower::load::{CompleteOnResponse, PendingRequests};
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn selects_least_requests() {
+        let _trace = linkerd_tracing::test::with_default_filter("trace");
+
+        let addr0 = "192.168.10.10:80".parse().unwrap();
+        let (svc0, mut h0) = tower_test::mock::pair::<(), ()>();
+        h0.allow(1);
+
+        let addr1 = "192.168.10.11:80".parse().unwrap();
+        let (svc1, mut h1) = tower_test::mock::pair::<(), ()>();
+        h1.allow(1);
+
+        // Consider all endpoints so that selection is deterministic.
+        let mut pool = LeastRequestPool::new(P2cMetrics::default(), 2, |(a, ())| {
+            PendingRequests::new(
+                if a == addr0 {
+                    svc0.clone()
+                } else if a == addr1 {
+                    svc1.clone()
+                } else {
+                    panic!("unexpected address: {a}");
+                },
+                CompleteOnResponse::default(),
+            )
+        });
+        pool.update_pool(Update::Reset(vec![(addr0, ()), (addr1, ())]));
+
+        // The first request is outstanding on one endpoint, so the next
+        // request must be dispatched to the other.
+        assert!(pool.ready().now_or_never().is_some());
+        let _call0 = pool.call(());
+        let first = tokio::select! {
+            r = h0.next_request() => { r.unwrap(); addr0 }
+            r = h1.next_request() => { r.unwrap(); addr1 }
+        };
+
+        h0.allow(1);
+        h1.allow(1);
+        assert!(pool.ready().now_or_never().is_some());
+        let _call1 = pool.call(());
+        let second = tokio::select! {
+            r = h0.next_request() => { r.unwrap(); addr0 }
+            r = h1.next_request() => { r.unwrap(); addr1 }
+        };
+        assert_ne!(first, second);
+    }
+}
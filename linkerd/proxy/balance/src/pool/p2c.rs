@@ -2,44 +2,43 @@
 //!
 // Based on tower::p2c::Balance. Copyright (c) 2019 Tower Contributors
 
-use super::{Pool, Update};
-use ahash::AHashMap;
+use super::{endpoints::Endpoints, slow_start::SlowStart, Pool, TrackAttempts, Update};
 use futures_util::TryFutureExt;
 use linkerd_error::Error;
 use linkerd_metrics::prom;
 use linkerd_stack::{NewService, Service};
 use rand::{rngs::SmallRng, thread_rng, Rng, SeedableRng};
 use std::{
-    collections::hash_map::Entry,
     net::SocketAddr,
     task::{Context, Poll},
+    time::Duration,
 };
-use tower::{
-    load::Load,
-    ready_cache::{error::Failed, ReadyCache},
-};
+use tower::{load::Load, ready_cache::error::Failed};
 
 /// Dispatches requests to a pool of services selected by the
 /// power-of-two-choices algorithm.
 #[derive(Debug)]
-pub struct P2cPool<T, N, Req, S> {
-    new_endpoint: N,
-    endpoints: AHashMap<SocketAddr, T>,
-    pool: ReadyCache<SocketAddr, S, Req>,
+pub struct P2cPool<T, N, Req, S, D = ()> {
+    endpoints: Endpoints<T, N, Req, S, D>,
+    slow_start: Option<SlowStart>,
     rng: SmallRng,
-    metrics: P2cMetrics,
     next_idx: Option<usize>,
 }
 
 #[derive(Clone, Debug)]
 pub struct P2cMetricFamilies<L> {
     endpoints: prom::Family<L, prom::Gauge>,
+    warming: prom::Family<L, prom::Gauge>,
     updates: prom::Family<UpdateLabels<L>, prom::Counter>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct P2cMetrics {
-    endpoints: prom::Gauge,
+    pub(super) endpoints: prom::Gauge,
+
+    /// Measures the number of endpoints that are within their slow-start
+    /// window.
+    warming: prom::Gauge,
 
     /// Measures the number of Reset updates received from service discovery.
     updates_reset: prom::Counter,
@@ -71,7 +70,7 @@ pub enum UpdateOp {
 
 impl<T, N, Req, S> P2cPool<T, N, Req, S>
 where
-    T: Clone + Eq,
+    T: Clone + Eq + std::fmt::Debug,
     N: NewService<(SocketAddr, T), Service = S>,
     S: Service<Req> + Load,
     S::Error: Into<Error>,
@@ -81,119 +80,64 @@ where
         let rng = SmallRng::from_rng(&mut thread_rng()).expect("RNG must be seeded");
         Self {
             rng,
-            metrics,
-            new_endpoint,
             next_idx: None,
-            pool: ReadyCache::default(),
-            endpoints: Default::default(),
-        }
-    }
-
-    /// Resets the pool to include the given targets without unnecessarily
-    /// rebuilding inner services.
-    ///
-    /// Returns true if the pool was changed.
-    fn reset(&mut self, targets: Vec<(SocketAddr, T)>) -> bool {
-        let mut changed = false;
-        let mut remaining = std::mem::take(&mut self.endpoints);
-        for (addr, target) in targets.into_iter() {
-            let t = remaining.remove(&addr);
-            if t.as_ref() == Some(&target) {
-                tracing::debug!(?addr, "Endpoint unchanged");
-            } else {
-                if t.is_none() {
-                    tracing::debug!(?addr, "Creating endpoint");
-                    self.metrics.endpoints.inc();
-                } else {
-                    tracing::debug!(?addr, "Updating endpoint");
-                }
-
-                let svc = self.new_endpoint.new_service((addr, target.clone()));
-                self.pool.push(addr, svc);
-                changed = true;
-            }
-
-            self.endpoints.insert(addr, target);
-        }
-
-        for (addr, _) in remaining.drain() {
-            tracing::debug!(?addr, "Removing endpoint");
-            self.pool.evict(&addr);
-            self.metrics.endpoints.dec();
-            changed = true;
-        }
-
-        changed
-    }
-
-    /// Adds endpoints to the pool without unnecessarily rebuilding inner
-    /// services.
-    ///
-    /// Returns true if the pool was changed.
-    fn add(&mut self, targets: Vec<(SocketAddr, T)>) -> bool {
-        let mut changed = false;
-        for (addr, target) in targets.into_iter() {
-            match self.endpoints.entry(addr) {
-                Entry::Occupied(e) if e.get() == &target => {
-                    tracing::debug!(?addr, "Endpoint unchanged");
-                    continue;
-                }
-                Entry::Occupied(mut e) => {
-                    e.insert(target.clone());
-                }
-                Entry::Vacant(e) => {
-                    e.insert(target.clone());
-                    self.metrics.endpoints.inc();
-                }
-            }
-            tracing::debug!(?addr, "Creating endpoint");
-            let svc = self.new_endpoint.new_service((addr, target));
-            self.pool.push(addr, svc);
-            changed = true;
+            slow_start: None,
+            endpoints: Endpoints::new(metrics, new_endpoint),
         }
-        changed
     }
+}
 
-    /// Removes endpoint services.
-    ///
-    /// Returns true if the pool was changed.
-    fn remove(&mut self, addrs: Vec<SocketAddr>) -> bool {
-        let mut changed = false;
-        for addr in addrs.into_iter() {
-            if self.endpoints.remove(&addr).is_some() {
-                tracing::debug!(?addr, "Removing endpoint");
-                self.pool.evict(&addr);
-                self.metrics.endpoints.dec();
-                changed = true;
-            } else {
-                tracing::debug!(?addr, "Unknown endpoint");
-            }
+impl<T, N, Req, S, D> P2cPool<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req> + Load,
+    S::Error: Into<Error>,
+    S::Metric: std::fmt::Debug,
+{
+    /// Avoids dispatching a request to the endpoints that its prior attempts
+    /// were dispatched to, as tracked by `attempts`.
+    pub fn with_attempts<A>(self, attempts: A) -> P2cPool<T, N, Req, S, A> {
+        P2cPool {
+            endpoints: self.endpoints.with_attempts(attempts),
+            slow_start: self.slow_start,
+            rng: self.rng,
+            next_idx: self.next_idx,
         }
-        changed
     }
 
-    /// Clear all endpoints from the pool.
+    /// Ramps up the share of traffic sent to endpoints added to the pool
+    /// linearly over the given window.
     ///
-    /// Returns true if the pool was changed.
-    fn clear(&mut self) -> bool {
-        let changed = !self.endpoints.is_empty();
-        for (addr, _) in self.endpoints.drain() {
-            tracing::debug!(?addr, "Clearing endpoint");
-            self.pool.evict(&addr);
-            self.metrics.endpoints.dec();
-        }
-        changed
+    /// Endpoints are only warmed when they are added to a pool that already
+    /// has endpoints.
+    pub fn with_slow_start(mut self, window: Duration) -> Self {
+        let gauge = self.endpoints.metrics.warming.clone();
+        self.slow_start = Some(SlowStart::new(window, gauge));
+        self
     }
 
     fn p2c_ready_index(&mut self) -> Option<usize> {
-        match self.pool.ready_len() {
+        match self.endpoints.ready.ready_len() {
             0 => None,
             1 => Some(0),
             len => {
                 let (aidx, bidx) = gen_pair(&mut self.rng, len);
                 let aload = self.ready_index_load(aidx);
                 let bload = self.ready_index_load(bidx);
-                let chosen = if aload <= bload { aidx } else { bidx };
+                let mut chosen = if aload <= bload { aidx } else { bidx };
+                if let Some(slow_start) = self.slow_start.as_ref() {
+                    // A warming endpoint's load is inflated relative to its
+                    // peer's, so that it only wins the comparison in
+                    // proportion to how far it has warmed.
+                    let other = if chosen == aidx { bidx } else { aidx };
+                    let weight = self.ready_index_weight(slow_start, chosen)
+                        / self.ready_index_weight(slow_start, other);
+                    if weight < 1.0 && !self.rng.gen_bool(weight) {
+                        tracing::trace!(weight, "Warming");
+                        chosen = other;
+                    }
+                }
                 tracing::trace!(
                     a.index = aidx,
                     a.load = ?aload,
@@ -209,9 +153,23 @@ where
 
     /// Accesses a ready endpoint by index and returns its current load.
     fn ready_index_load(&self, index: usize) -> S::Metric {
-        let (_, svc) = self.pool.get_ready_index(index).expect("invalid index");
+        let (_, svc) = self
+            .endpoints
+            .ready
+            .get_ready_index(index)
+            .expect("invalid index");
         svc.load()
     }
+
+    /// Accesses a ready endpoint by index and returns its slow-start weight.
+    fn ready_index_weight(&self, slow_start: &SlowStart, index: usize) -> f64 {
+        let (addr, _) = self
+            .endpoints
+            .ready
+            .get_ready_index(index)
+            .expect("invalid index");
+        slow_start.weight(addr)
+    }
 }
 
 fn gen_pair(rng: &mut SmallRng, len: usize) -> (usize, usize) {
@@ -227,7 +185,7 @@ fn gen_pair(rng: &mut SmallRng, len: usize) -> (usize, usize) {
     (aidx, bidx)
 }
 
-impl<T, N, Req, S> Pool<T, Req> for P2cPool<T, N, Req, S>
+impl<T, N, Req, S, D> Pool<T, Req> for P2cPool<T, N, Req, S, D>
 where
     T: Clone + Eq + std::fmt::Debug,
     N: NewService<(SocketAddr, T), Service = S>,
@@ -235,18 +193,33 @@ where
     S::Error: Into<Error>,
     S::Future: Send + 'static,
     S::Metric: std::fmt::Debug,
+    D: TrackAttempts<Req>,
 {
     fn update_pool(&mut self, update: Update<T>) {
-        tracing::trace!(?update);
-        self.metrics.inc(&update);
-        let changed = match update {
-            Update::Reset(targets) => self.reset(targets),
-            Update::Add(targets) => self.add(targets),
-            Update::Remove(addrs) => self.remove(addrs),
-            Update::DoesNotExist => self.clear(),
+        let added = match (&update, self.slow_start.is_some()) {
+            // Endpoints are not warmed when the pool is empty, since they
+            // would receive all requests regardless.
+            (Update::Reset(targets) | Update::Add(targets), true)
+                if !self.endpoints.targets.is_empty() =>
+            {
+                targets
+                    .iter()
+                    .map(|(addr, _)| *addr)
+                    .filter(|addr| !self.endpoints.targets.contains_key(addr))
+                    .collect()
+            }
+            _ => vec![],
         };
-        if changed {
+
+        if self.endpoints.update(update) {
             self.next_idx = None;
+            if let Some(slow_start) = self.slow_start.as_mut() {
+                let targets = &self.endpoints.targets;
+                slow_start.retain(|addr| targets.contains_key(addr));
+                for addr in added {
+                    slow_start.add(addr);
+                }
+            }
         }
     }
 
@@ -258,18 +231,30 @@ where
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<(), Self::Error>> {
         tracing::trace!("Polling pending");
-        self.pool.poll_pending(cx).map_err(|Failed(_, e)| e)
+        futures::ready!(self
+            .endpoints
+            .ready
+            .poll_pending(cx)
+            .map_err(|Failed(_, e)| e))?;
+
+        // Keep polling the pool while endpoints are warming, so that they
+        // stop warming once their windows elapse.
+        match self.slow_start.as_mut() {
+            Some(slow_start) => slow_start.poll_warmed(cx).map(Ok),
+            None => Poll::Ready(Ok(())),
+        }
     }
 }
 
-impl<T, N, Req, S> Service<Req> for P2cPool<T, N, Req, S>
+impl<T, N, Req, S, D> Service<Req> for P2cPool<T, N, Req, S, D>
 where
-    T: Clone + Eq,
+    T: Clone + Eq + std::fmt::Debug,
     N: NewService<(SocketAddr, T), Service = S>,
     S: Service<Req> + Load,
     S::Error: Into<Error>,
     S::Future: Send + 'static,
     S::Metric: std::fmt::Debug,
+    D: TrackAttempts<Req>,
 {
     type Response = S::Response;
     type Error = Error;
@@ -285,8 +270,11 @@ where
     /// endpoints to become ready.
     fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         loop {
-            tracing::trace!(pending = self.pool.pending_len(), "Polling pending");
-            match self.pool.poll_pending(cx)? {
+            tracing::trace!(
+                pending = self.endpoints.ready.pending_len(),
+                "Polling pending"
+            );
+            match self.endpoints.ready.poll_pending(cx)? {
                 Poll::Ready(()) => tracing::trace!("All endpoints are ready"),
                 Poll::Pending => tracing::trace!("Endpoints are pending"),
             }
@@ -300,7 +288,7 @@ where
             };
 
             tracing::trace!(ready.index = idx, "Selected");
-            if !self.pool.check_ready_index(cx, idx)? {
+            if !self.endpoints.ready.check_ready_index(cx, idx)? {
                 tracing::trace!(ready.index = idx, "Reverted to pending");
                 continue;
             }
@@ -313,7 +301,7 @@ where
 
     fn call(&mut self, req: Req) -> Self::Future {
         let idx = self.next_idx.take().expect("call before ready");
-        self.pool.call_ready_index(idx, req).err_into()
+        self.endpoints.call_ready_index(idx, req).err_into()
     }
 }
 
@@ -339,11 +327,23 @@ where
             updates.clone(),
         );
 
-        Self { endpoints, updates }
+        let warming = prom::Family::default();
+        reg.register(
+            "warming_endpoints",
+            "The number of endpoints in the balancer that are within their slow-start window",
+            warming.clone(),
+        );
+
+        Self {
+            endpoints,
+            warming,
+            updates,
+        }
     }
 
     pub fn metrics(&self, labels: &L) -> P2cMetrics {
         let endpoints: prom::Gauge = self.endpoints.get_or_create(labels).clone();
+        let warming: prom::Gauge = self.warming.get_or_create(labels).clone();
         let updates_reset: prom::Counter = self
             .updates
             .get_or_create(&UpdateLabels {
@@ -374,6 +374,7 @@ where
             .clone();
         P2cMetrics {
             endpoints,
+            warming,
             updates_reset,
             updates_add,
             updates_rm,
@@ -385,7 +386,7 @@ where
 // === impl P2cMetrics ===
 
 impl P2cMetrics {
-    fn inc<T>(&self, up: &Update<T>) {
+    pub(super) fn inc<T>(&self, up: &Update<T>) {
         match up {
             Update::Reset(..) => &self.updates_reset,
             Update::Add(..) => &self.updates_add,
@@ -414,7 +415,7 @@ mod tests {
     use std::sync::Arc;
     use tokio::time;
     use tokio_test::{assert_pending, assert_ready_ok};
-    use tower::load::{CompleteOnResponse, PeakEwma};
+    use tower::load::{CompleteOnResponse, PeakEwma, PendingRequests};
 
     quickcheck::quickcheck! {
         fn gen_pair_distinct(len: usize) -> quickcheck::TestResult {
@@ -449,61 +450,61 @@ mod tests {
         });
 
         pool.update_pool(Update::Reset(vec![(addr0, 0)]));
-        assert_eq!(pool.endpoints.len(), 1);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr0), Some(&0));
+        assert_eq!(pool.endpoints.targets.len(), 1);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr0), Some(&0));
 
         pool.update_pool(Update::Add(vec![(addr0, 1)]));
-        assert_eq!(pool.endpoints.len(), 1);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr0), Some(&1));
+        assert_eq!(pool.endpoints.targets.len(), 1);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr0), Some(&1));
 
         pool.update_pool(Update::Reset(vec![(addr0, 1)]));
-        assert_eq!(pool.endpoints.len(), 1);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr0), Some(&1));
+        assert_eq!(pool.endpoints.targets.len(), 1);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr0), Some(&1));
 
         pool.update_pool(Update::Add(vec![(addr1, 1)]));
-        assert_eq!(pool.endpoints.len(), 2);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr1), Some(&1));
+        assert_eq!(pool.endpoints.targets.len(), 2);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr1), Some(&1));
 
         pool.update_pool(Update::Add(vec![(addr1, 1)]));
-        assert_eq!(pool.endpoints.len(), 2);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr1), Some(&1));
+        assert_eq!(pool.endpoints.targets.len(), 2);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr1), Some(&1));
 
         pool.update_pool(Update::Remove(vec![addr0]));
-        assert_eq!(pool.endpoints.len(), 1);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
+        assert_eq!(pool.endpoints.targets.len(), 1);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
 
         pool.update_pool(Update::Remove(vec![addr0]));
-        assert_eq!(pool.endpoints.len(), 1);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
+        assert_eq!(pool.endpoints.targets.len(), 1);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
 
         pool.update_pool(Update::Reset(vec![(addr0, 2), (addr1, 2)]));
-        assert_eq!(pool.endpoints.len(), 2);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr0), Some(&2));
-        assert_eq!(pool.endpoints.get(&addr1), Some(&2));
+        assert_eq!(pool.endpoints.targets.len(), 2);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr0), Some(&2));
+        assert_eq!(pool.endpoints.targets.get(&addr1), Some(&2));
 
         pool.update_pool(Update::Reset(vec![(addr0, 2)]));
-        assert_eq!(pool.endpoints.len(), 1);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr0), Some(&2));
+        assert_eq!(pool.endpoints.targets.len(), 1);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr0), Some(&2));
 
         pool.update_pool(Update::Reset(vec![(addr0, 3)]));
-        assert_eq!(pool.endpoints.len(), 1);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
-        assert_eq!(pool.endpoints.get(&addr0), Some(&3));
+        assert_eq!(pool.endpoints.targets.len(), 1);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
+        assert_eq!(pool.endpoints.targets.get(&addr0), Some(&3));
 
         pool.update_pool(Update::DoesNotExist);
-        assert_eq!(pool.endpoints.len(), 0);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
+        assert_eq!(pool.endpoints.targets.len(), 0);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
 
         pool.update_pool(Update::DoesNotExist);
-        assert_eq!(pool.endpoints.len(), 0);
-        assert_eq!(metrics.endpoints.get(), pool.endpoints.len() as i64);
+        assert_eq!(pool.endpoints.targets.len(), 0);
+        assert_eq!(metrics.endpoints.get(), pool.endpoints.targets.len() as i64);
 
         assert_eq!(metrics.updates_reset.get(), 5);
         assert_eq!(metrics.updates_add.get(), 3);
@@ -576,8 +577,8 @@ mod tests {
 
         assert!(pool.ready().now_or_never().is_some());
         assert!(pool.next_idx.is_some());
-        assert_eq!(pool.pool.ready_len(), 2);
-        assert_eq!(pool.pool.pending_len(), 1);
+        assert_eq!(pool.endpoints.ready.ready_len(), 2);
+        assert_eq!(pool.endpoints.ready.pending_len(), 1);
 
         let ctx = &mut Context::from_waker(futures_util::task::noop_waker_ref());
         assert_pending!(pool.poll_pool(ctx));
@@ -589,7 +590,120 @@ mod tests {
         assert_ready_ok!(pool.poll_pool(ctx));
 
         assert!(pool.next_idx.is_some());
-        assert_eq!(pool.pool.ready_len(), 3);
-        assert_eq!(pool.pool.pending_len(), 0);
+        assert_eq!(pool.endpoints.ready.ready_len(), 3);
+        assert_eq!(pool.endpoints.ready.pending_len(), 0);
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn slow_start() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let addr0 = "192.168.10.10:80".parse().unwrap();
+        let addr1 = "192.168.10.11:80".parse().unwrap();
+
+        let metrics = P2cMetrics::default();
+        let mut pool = P2cPool::new(metrics.clone(), |(addr, ()): (SocketAddr, ())| {
+            PendingRequests::new(
+                linkerd_stack::service_fn(move |()| {
+                    future::ok::<_, std::convert::Infallible>(addr)
+                }),
+                CompleteOnResponse::default(),
+            )
+        })
+        .with_slow_start(time::Duration::from_secs(10));
+
+        // Returns the fraction of requests dispatched to the new endpoint.
+        async fn share(
+            pool: &mut impl Service<(), Response = SocketAddr, Error = Error>,
+            addr: SocketAddr,
+        ) -> f64 {
+            let mut n = 0;
+            for _ in 0..1000 {
+                if pool.ready().await.unwrap().call(()).await.unwrap() == addr {
+                    n += 1;
+                }
+            }
+            f64::from(n) / 1000.0
+        }
+
+        // Endpoints added to an empty pool are not warmed.
+        pool.update_pool(Update::Reset(vec![(addr0, ())]));
+        assert_eq!(metrics.warming.get(), 0);
+
+        // A new endpoint does not receive requests when it is first added.
+        pool.update_pool(Update::Add(vec![(addr1, ())]));
+        assert_eq!(metrics.warming.get(), 1);
+        assert_eq!(share(&mut pool, addr1).await, 0.0);
+        assert!(future::poll_fn(|cx| pool.poll_pool(cx))
+            .now_or_never()
+            .is_none());
+
+        // Halfway through the window, it receives half of its share.
+        time::sleep(time::Duration::from_secs(5)).await;
+        let half = share(&mut pool, addr1).await;
+        assert!(0.15 < half && half < 0.35, "{half}");
+
+        // Once the window elapses, it receives its full share.
+        time::sleep(time::Duration::from_secs(5)).await;
+        assert!(future::poll_fn(|cx| pool.poll_pool(cx))
+            .now_or_never()
+            .is_some());
+        assert_eq!(metrics.warming.get(), 0);
+        let full = share(&mut pool, addr1).await;
+        assert!(0.4 < full && full < 0.6, "{full}");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn avoids_attempted_endpoints() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        /// Requests carry the addresses that their attempts were dispatched to.
+        type Attempted = Arc<Mutex<Vec<SocketAddr>>>;
+
+        struct TrackAttempted;
+
+        impl TrackAttempts<Attempted> for TrackAttempted {
+            fn attempted(&self, req: &Attempted, addr: &SocketAddr) -> bool {
+                req.lock().contains(addr)
+            }
+
+            fn record(&self, req: &Attempted, addr: SocketAddr) {
+                req.lock().push(addr);
+            }
+        }
+
+        let addrs = (0..3)
+            .map(|i| SocketAddr::from(([192, 168, 10, i], 80)))
+            .collect::<Vec<_>>();
+        let mut pool = P2cPool::new(P2cMetrics::default(), |(addr, ()): (SocketAddr, ())| {
+            PendingRequests::new(
+                linkerd_stack::service_fn(move |_: Attempted| {
+                    future::ok::<_, std::convert::Infallible>(addr)
+                }),
+                CompleteOnResponse::default(),
+            )
+        })
+        .with_attempts(TrackAttempted);
+        pool.update_pool(Update::Reset(addrs.iter().map(|a| (*a, ())).collect()));
+
+        for _ in 0..100 {
+            let attempted = Attempted::default();
+            let first = pool
+                .ready()
+                .await
+                .unwrap()
+                .call(attempted.clone())
+                .await
+                .unwrap();
+            let second = pool
+                .ready()
+                .await
+                .unwrap()
+                .call(attempted.clone())
+                .await
+                .unwrap();
+            assert_ne!(first, second, "attempts must be sent to distinct endpoints");
+            assert_eq!(*attempted.lock(), vec![first, second]);
+        }
     }
 }
@@ -0,0 +1,381 @@
+//! A pool that uses consistent hashing to select endpoints.
+//!
+//! Each endpoint is placed at a fixed number of points on a hash ring, and
+//! requests are dispatched to the first ready endpoint at or after the
+//! request's hash. Because an endpoint's points depend only on its address,
+//! adding or removing an endpoint only remaps the requests whose hashes fall
+//! on that endpoint's arcs of the ring.
+
+use super::{endpoints::Endpoints, P2cMetrics, Pool, TrackAttempts, Update};
+use futures_util::TryFutureExt;
+use linkerd_error::Error;
+use linkerd_stack::{NewService, Service};
+use rand::{rngs::SmallRng, thread_rng, Rng, SeedableRng};
+use std::{
+    hash::Hasher,
+    net::{IpAddr, SocketAddr},
+    task::{Context, Poll},
+};
+use tower::ready_cache::error::Failed;
+
+/// The number of points each endpoint occupies on the hash ring.
+const POINTS_PER_ENDPOINT: u32 = 128;
+
+/// Computes the ring hash for a request.
+pub trait HashRequest<Req> {
+    /// Returns the request's hash, or `None` if the request does not have a
+    /// hash key. Requests without a hash key are dispatched to a random ready
+    /// endpoint.
+    fn hash_request(&self, req: &Req) -> Option<u64>;
+}
+
+/// Dispatches requests to a pool of services selected by the hash of a
+/// request key.
+#[derive(Debug)]
+pub struct RingHashPool<T, N, Req, S, H, D = ()> {
+    endpoints: Endpoints<T, N, Req, S, D>,
+    hasher: H,
+    ring: Vec<(u64, SocketAddr)>,
+    rng: SmallRng,
+}
+
+/// Hashes bytes so that all proxies, regardless of their version or platform,
+/// place the same keys and endpoints at the same points on the ring.
+///
+/// Bytes are hashed with 64-bit FNV-1a. Because FNV-1a barely mixes the final
+/// bytes of its input, its result is finalized with MurmurHash3's `fmix64` so
+/// that keys that differ only in their last bytes are spread around the ring.
+pub fn hash(bytes: impl AsRef<[u8]>) -> u64 {
+    let mut hasher = fnv::FnvHasher::default();
+    hasher.write(bytes.as_ref());
+    let mut h = hasher.finish();
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xff51afd7ed558ccd);
+    h ^= h >> 33;
+    h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+    h ^= h >> 33;
+    h
+}
+
+/// Hashes the `point`th point of an endpoint from its IP address octets and
+/// port and the point, in network byte order.
+fn hash_point(addr: &SocketAddr, point: u32) -> u64 {
+    let mut bytes = Vec::with_capacity(22);
+    match addr.ip() {
+        IpAddr::V4(ip) => bytes.extend_from_slice(&ip.octets()),
+        IpAddr::V6(ip) => bytes.extend_from_slice(&ip.octets()),
+    }
+    bytes.extend_from_slice(&addr.port().to_be_bytes());
+    bytes.extend_from_slice(&point.to_be_bytes());
+    hash(bytes)
+}
+
+impl<T, N, Req, S, H> RingHashPool<T, N, Req, S, H>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    H: HashRequest<Req>,
+{
+    pub fn new(metrics: P2cMetrics, hasher: H, new_endpoint: N) -> Self {
+        let rng = SmallRng::from_rng(&mut thread_rng()).expect("RNG must be seeded");
+        Self {
+            hasher,
+            rng,
+            ring: Vec::new(),
+            endpoints: Endpoints::new(metrics, new_endpoint),
+        }
+    }
+}
+
+impl<T, N, Req, S, H, D> RingHashPool<T, N, Req, S, H, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    H: HashRequest<Req>,
+{
+    /// Avoids dispatching a request to the endpoints that its prior attempts
+    /// were dispatched to, as tracked by `attempts`.
+    pub fn with_attempts<A>(self, attempts: A) -> RingHashPool<T, N, Req, S, H, A> {
+        RingHashPool {
+            endpoints: self.endpoints.with_attempts(attempts),
+            hasher: self.hasher,
+            ring: self.ring,
+            rng: self.rng,
+        }
+    }
+
+    /// Rebuilds the ring from the pool's current endpoints.
+    fn rebuild_ring(&mut self) {
+        self.ring = self
+            .endpoints
+            .targets
+            .keys()
+            .flat_map(|addr| (0..POINTS_PER_ENDPOINT).map(move |i| (hash_point(addr, i), *addr)))
+            .collect();
+        self.ring.sort_unstable();
+        tracing::debug!(points = self.ring.len(), "Rebuilt hash ring");
+    }
+
+    /// Returns the first ready endpoint at or after `hash` on the ring.
+    fn ring_ready_addr(&self, hash: u64) -> Option<SocketAddr> {
+        let start = self.ring.partition_point(|(h, _)| *h < hash);
+        self.ring
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(self.ring.len())
+            .map(|(_, addr)| *addr)
+            .find(|addr| self.endpoints.ready.get_ready(addr).is_some())
+    }
+}
+
+impl<T, N, Req, S, H, D> Pool<T, Req> for RingHashPool<T, N, Req, S, H, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    H: HashRequest<Req>,
+    D: TrackAttempts<Req>,
+{
+    fn update_pool(&mut self, update: Update<T>) {
+        if self.endpoints.update(update) {
+            self.rebuild_ring();
+        }
+    }
+
+    /// Moves pending endpoints to ready.
+    ///
+    /// This must be called from the same task that invokes Service::poll_ready.
+    fn poll_pool(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        tracing::trace!("Polling pending");
+        self.endpoints
+            .ready
+            .poll_pending(cx)
+            .map_err(|Failed(_, e)| e)
+    }
+}
+
+impl<T, N, Req, S, H, D> Service<Req> for RingHashPool<T, N, Req, S, H, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    H: HashRequest<Req>,
+    D: TrackAttempts<Req>,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = futures::future::ErrInto<S::Future, Error>;
+
+    /// Returns ready when at least one endpoint is ready.
+    ///
+    /// Endpoints are selected when the request is dispatched, since the
+    /// selection depends on the request. Requests whose preferred endpoint is
+    /// not ready are dispatched to the next ready endpoint on the ring.
+    ///
+    /// NOTE that this may return `Pending` when there are no endpoints. In such
+    /// cases, the caller must invoke `update_pool` and then wait for new
+    /// endpoints to become ready.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        tracing::trace!(
+            pending = self.endpoints.ready.pending_len(),
+            "Polling pending"
+        );
+        match self.endpoints.ready.poll_pending(cx)? {
+            Poll::Ready(()) => tracing::trace!("All endpoints are ready"),
+            Poll::Pending => tracing::trace!("Endpoints are pending"),
+        }
+
+        if self.endpoints.ready.ready_len() == 0 {
+            tracing::debug!("No ready endpoints");
+            return Poll::Pending;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        if let Some(addr) = self
+            .hasher
+            .hash_request(&req)
+            .and_then(|hash| self.ring_ready_addr(hash))
+        {
+            tracing::trace!(?addr, "Selected by hash");
+            return self.endpoints.call_ready(&addr, req).err_into();
+        }
+
+        let len = self.endpoints.ready.ready_len();
+        assert!(len > 0, "call before ready");
+        let idx = self.rng.gen_range(0..len);
+        tracing::trace!(ready.index = idx, "Selected at random");
+        self.endpoints.call_ready_index(idx, req).err_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::prelude::*;
+    use linkerd_stack::ServiceExt;
+    use std::{collections::HashMap, convert::Infallible};
+
+    /// Uses the request itself as its hash.
+    #[derive(Debug)]
+    struct Identity;
+
+    impl HashRequest<Option<u64>> for Identity {
+        fn hash_request(&self, req: &Option<u64>) -> Option<u64> {
+            *req
+        }
+    }
+
+    type TestPool = RingHashPool<(), fn((SocketAddr, ())) -> Echo, Option<u64>, Echo, Identity>;
+
+    /// Responds with the endpoint's address.
+    #[derive(Clone, Debug)]
+    struct Echo(SocketAddr);
+
+    impl Service<Option<u64>> for Echo {
+        type Response = SocketAddr;
+        type Error = Infallible;
+        type Future = future::Ready<Result<SocketAddr, Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: Option<u64>) -> Self::Future {
+            future::ok(self.0)
+        }
+    }
+
+    fn mk_pool() -> TestPool {
+        RingHashPool::new(P2cMetrics::default(), Identity, |(addr, ())| Echo(addr))
+    }
+
+    fn addrs(n: u8) -> Vec<(SocketAddr, ())> {
+        (0..n)
+            .map(|i| (SocketAddr::from(([192, 168, 10, i], 80)), ()))
+            .collect()
+    }
+
+    async fn dispatch(pool: &mut TestPool, hash: Option<u64>) -> SocketAddr {
+        pool.ready()
+            .await
+            .expect("pool must be ready")
+            .call(hash)
+            .await
+            .expect("call must succeed")
+    }
+
+    async fn assignments(pool: &mut TestPool, keys: u64) -> HashMap<u64, SocketAddr> {
+        let mut assigned = HashMap::new();
+        for key in 0..keys {
+            let hash = hash(key.to_be_bytes());
+            assigned.insert(hash, dispatch(pool, Some(hash)).await);
+        }
+        assigned
+    }
+
+    /// Tests that hashes do not change, since all proxies must agree on them.
+    #[test]
+    fn hashes_are_stable() {
+        assert_eq!(hash(""), 0xefd01f60ba992926);
+        assert_eq!(hash("a"), 0x82a2a958a9bece5b);
+        assert_eq!(hash("foobar"), 0x2c22194922d1672b);
+    }
+
+    #[tokio::test]
+    async fn same_key_same_endpoint() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let mut pool = mk_pool();
+        pool.update_pool(Update::Reset(addrs(5)));
+
+        let hash = hash("session-a");
+        let addr = dispatch(&mut pool, Some(hash)).await;
+        for _ in 0..10 {
+            assert_eq!(dispatch(&mut pool, Some(hash)).await, addr);
+        }
+
+        // Requests without a key are still dispatched.
+        dispatch(&mut pool, None).await;
+    }
+
+    #[tokio::test]
+    async fn minimal_remapping() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let mut pool = mk_pool();
+        pool.update_pool(Update::Reset(addrs(4)));
+        let before = assignments(&mut pool, 1000).await;
+
+        // Adding an endpoint only moves keys onto the new endpoint.
+        let (added, ()) = addrs(5)[4];
+        pool.update_pool(Update::Add(vec![(added, ())]));
+        let after = assignments(&mut pool, 1000).await;
+        let mut moved = 0;
+        for (hash, addr) in after.iter() {
+            if before[hash] != *addr {
+                assert_eq!(*addr, added, "keys may only move to the new endpoint");
+                moved += 1;
+            }
+        }
+        assert!(moved > 0, "the new endpoint must receive keys");
+        assert!(moved < 400, "too many keys moved: {moved}");
+
+        // Removing the endpoint restores the original assignments.
+        pool.update_pool(Update::Remove(vec![added]));
+        assert_eq!(assignments(&mut pool, 1000).await, before);
+    }
+
+    #[tokio::test]
+    async fn skips_unready_endpoints() {
+        let _trace = linkerd_tracing::test::trace_init();
+
+        let (svc0, mut h0) = tower_test::mock::pair::<Option<u64>, ()>();
+        let (svc1, mut h1) = tower_test::mock::pair::<Option<u64>, ()>();
+        let [(addr0, ()), (addr1, ())]: [(SocketAddr, ()); 2] = addrs(2).try_into().unwrap();
+        let mut pool = RingHashPool::new(P2cMetrics::default(), Identity, move |(a, ())| {
+            if a == addr0 {
+                svc0.clone()
+            } else {
+                svc1.clone()
+            }
+        });
+        pool.update_pool(Update::Reset(vec![(addr0, ()), (addr1, ())]));
+
+        // Find a key that prefers the first endpoint.
+        let key = (0..)
+            .map(|i: u64| hash(i.to_be_bytes()))
+            .find(|h| pool.ring_addr(*h) == Some(addr0))
+            .unwrap();
+
+        h0.allow(0);
+        h1.allow(1);
+        pool.ready().await.expect("pool must be ready");
+        let call = pool.call(Some(key));
+        let (req, rsp) = h1.next_request().await.expect("request must be received");
+        assert_eq!(req, Some(key));
+        rsp.send_response(());
+        call.await.expect("call must succeed");
+    }
+
+    impl<T, N, Req, S, H, D> RingHashPool<T, N, Req, S, H, D> {
+        /// Returns the endpoint at or after `hash`, regardless of readiness.
+        fn ring_addr(&self, hash: u64) -> Option<SocketAddr> {
+            let start = self.ring.partition_point(|(h, _)| *h < hash);
+            self.ring.iter().cycle().nth(start).map(|(_, addr)| *addr)
+        }
+    }
+}
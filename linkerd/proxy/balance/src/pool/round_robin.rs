@@ -0,0 +1,177 @@
+//! A pool that dispatches requests to each of its endpoints in turn.
+
+use super::{endpoints::Endpoints, P2cMetrics, Pool, TrackAttempts, Update};
+use futures_util::TryFutureExt;
+use linkerd_error::Error;
+use linkerd_stack::{NewService, Service};
+use std::{
+    net::SocketAddr,
+    task::{Context, Poll},
+};
+use tower::ready_cache::error::Failed;
+
+/// Dispatches requests to ready endpoints in a fixed order.
+///
+/// Endpoints that are not ready when their turn comes are skipped.
+#[derive(Debug)]
+pub struct RoundRobinPool<T, N, Req, S, D = ()> {
+    endpoints: Endpoints<T, N, Req, S, D>,
+    order: Vec<SocketAddr>,
+    cursor: usize,
+    next_addr: Option<SocketAddr>,
+}
+
+impl<T, N, Req, S> RoundRobinPool<T, N, Req, S>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+{
+    pub fn new(metrics: P2cMetrics, new_endpoint: N) -> Self {
+        Self {
+            order: Vec::new(),
+            cursor: 0,
+            next_addr: None,
+            endpoints: Endpoints::new(metrics, new_endpoint),
+        }
+    }
+}
+
+impl<T, N, Req, S, D> RoundRobinPool<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+{
+    /// Avoids dispatching a request to the endpoints that its prior attempts
+    /// were dispatched to, as tracked by `attempts`.
+    pub fn with_attempts<A>(self, attempts: A) -> RoundRobinPool<T, N, Req, S, A> {
+        RoundRobinPool {
+            endpoints: self.endpoints.with_attempts(attempts),
+            order: self.order,
+            cursor: self.cursor,
+            next_addr: self.next_addr,
+        }
+    }
+}
+
+impl<T, N, Req, S, D> Pool<T, Req> for RoundRobinPool<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    D: TrackAttempts<Req>,
+{
+    fn update_pool(&mut self, update: Update<T>) {
+        if self.endpoints.update(update) {
+            self.next_addr = None;
+            self.order = self.endpoints.targets.keys().copied().collect();
+            self.order.sort_unstable();
+        }
+    }
+
+    /// Moves pending endpoints to ready.
+    ///
+    /// This must be called from the same task that invokes Service::poll_ready.
+    fn poll_pool(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        tracing::trace!("Polling pending");
+        self.endpoints
+            .ready
+            .poll_pending(cx)
+            .map_err(|Failed(_, e)| e)
+    }
+}
+
+impl<T, N, Req, S, D> Service<Req> for RoundRobinPool<T, N, Req, S, D>
+where
+    T: Clone + Eq + std::fmt::Debug,
+    N: NewService<(SocketAddr, T), Service = S>,
+    S: Service<Req>,
+    S::Error: Into<Error>,
+    S::Future: Send + 'static,
+    D: TrackAttempts<Req>,
+{
+    type Response = S::Response;
+    type Error = Error;
+    type Future = futures::future::ErrInto<S::Future, Error>;
+
+    /// Returns ready when the next ready endpoint in turn has been found.
+    ///
+    /// NOTE that this may return `Pending` when there are no endpoints. In such
+    /// cases, the caller must invoke `update_pool` and then wait for new
+    /// endpoints to become ready.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        tracing::trace!(
+            pending = self.endpoints.ready.pending_len(),
+            "Polling pending"
+        );
+        match self.endpoints.ready.poll_pending(cx)? {
+            Poll::Ready(()) => tracing::trace!("All endpoints are ready"),
+            Poll::Pending => tracing::trace!("Endpoints are pending"),
+        }
+
+        if let Some(addr) = self.next_addr {
+            if self.endpoints.ready.check_ready(cx, &addr)? {
+                return Poll::Ready(Ok(()));
+            }
+            tracing::trace!(?addr, "Reverted to pending");
+            self.next_addr = None;
+        }
+
+        for _ in 0..self.order.len() {
+            let addr = self.order[self.cursor % self.order.len()];
+            self.cursor = self.cursor.wrapping_add(1);
+            if self.endpoints.ready.check_ready(cx, &addr)? {
+                tracing::trace!(?addr, "Ready");
+                self.next_addr = Some(addr);
+                return Poll::Ready(Ok(()));
+            }
+        }
+
+        tracing::debug!("No ready endpoints");
+        Poll::Pending
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        let addr = self.next_addr.take().expect("call before ready");
+        self.endpoints.call_ready(&addr, req).err_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::prelude::*;
+    use linkerd_stack::ServiceExt;
+    use std::convert::Infallible;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn dispatches_in_turn() {
+        let _trace = linkerd_tracing::test::with_default_filter("trace");
+
+        let addrs = (0..3)
+            .map(|i| SocketAddr::from(([192, 168, 10, i], 80)))
+            .collect::<Vec<_>>();
+        let mut pool = RoundRobinPool::new(P2cMetrics::default(), |(addr, ())| {
+            linkerd_stack::service_fn(move |()| future::ok::<_, Infallible>(addr))
+        });
+        pool.update_pool(Update::Reset(addrs.iter().map(|a| (*a, ())).collect()));
+
+        let mut seen = Vec::new();
+        for _ in 0..6 {
+            let addr = pool.ready().await.unwrap().call(()).await.unwrap();
+            seen.push(addr);
+        }
+        assert_eq!(&seen[..3], &seen[3..], "endpoints must be selected in turn");
+        let mut first = seen[..3].to_vec();
+        first.sort();
+        assert_eq!(first, addrs, "each endpoint must be selected once per turn");
+    }
+}
@@ -0,0 +1,99 @@
+//! Tracks endpoints that were recently added to a pool so that their share of
+//! traffic may be ramped up over a window.
+
+use ahash::AHashMap;
+use linkerd_metrics::prom;
+use std::{
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::time;
+
+#[derive(Debug)]
+pub(super) struct SlowStart {
+    window: time::Duration,
+    /// The time at which each warming endpoint was added.
+    warming: AHashMap<SocketAddr, time::Instant>,
+    expiry: Option<Pin<Box<time::Sleep>>>,
+    gauge: prom::Gauge,
+}
+
+impl SlowStart {
+    pub(super) fn new(window: time::Duration, gauge: prom::Gauge) -> Self {
+        Self {
+            window,
+            gauge,
+            warming: AHashMap::default(),
+            expiry: None,
+        }
+    }
+
+    /// Starts warming an endpoint.
+    pub(super) fn add(&mut self, addr: SocketAddr) {
+        tracing::debug!(?addr, window = ?self.window, "Warming endpoint");
+        if self.warming.insert(addr, time::Instant::now()).is_none() {
+            self.gauge.inc();
+        }
+    }
+
+    /// Stops warming endpoints that have been removed from the pool.
+    pub(super) fn retain(&mut self, mut f: impl FnMut(&SocketAddr) -> bool) {
+        self.retain_by(|addr, _| f(addr));
+    }
+
+    /// Returns the fraction of its full share of traffic that an endpoint
+    /// should receive, increasing linearly from 0.0 when the endpoint is
+    /// added to 1.0 once the window has elapsed.
+    pub(super) fn weight(&self, addr: &SocketAddr) -> f64 {
+        match self.warming.get(addr) {
+            None => 1.0,
+            Some(t0) => {
+                let elapsed = time::Instant::now().saturating_duration_since(*t0);
+                (elapsed.as_secs_f64() / self.window.as_secs_f64()).min(1.0)
+            }
+        }
+    }
+
+    /// Stops warming endpoints whose windows have elapsed.
+    ///
+    /// Returns ready when no endpoints are warming.
+    pub(super) fn poll_warmed(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        loop {
+            let now = time::Instant::now();
+            let window = self.window;
+            self.retain_by(|_, t0| now.saturating_duration_since(*t0) < window);
+
+            let next = match self.warming.values().min() {
+                Some(t0) => *t0 + window,
+                None => return Poll::Ready(()),
+            };
+            let expiry = self
+                .expiry
+                .get_or_insert_with(|| Box::pin(time::sleep_until(next)));
+            if expiry.deadline() != next {
+                expiry.as_mut().reset(next);
+            }
+            futures::ready!(expiry.as_mut().poll(cx));
+        }
+    }
+
+    fn retain_by(&mut self, mut f: impl FnMut(&SocketAddr, &time::Instant) -> bool) {
+        let gauge = &self.gauge;
+        self.warming.retain(|addr, t0| {
+            let keep = f(addr, t0);
+            if !keep {
+                tracing::debug!(?addr, "Stopped warming endpoint");
+                gauge.dec();
+            }
+            keep
+        });
+    }
+}
+
+impl Drop for SlowStart {
+    fn drop(&mut self) {
+        self.gauge.dec_by(self.warming.len() as i64);
+    }
+}
@@ -20,6 +20,10 @@ pub struct Grpc {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Filter {
     InjectFailure(filter::InjectFailure),
+    /// Delays a fraction of requests before they are dispatched.
+    ///
+    /// This filter is only honored on a route's rules, not on its backends.
+    InjectDelay(filter::InjectDelay),
     RequestHeaders(http::filter::ModifyHeader),
     InternalError(&'static str),
 }
@@ -38,6 +42,8 @@ pub fn default(distribution: crate::RouteDistribution<Filter>) -> Route {
                 distribution,
                 failure_policy: Codes::default(),
                 request_timeout: None,
+                retry: None,
+                hedge: None,
             },
         }],
     }
@@ -230,6 +236,12 @@ pub mod proto {
                 distribution,
                 failure_policy: Codes::default(),
                 request_timeout,
+                // NOTE: The proxy API has no fields for route retries or
+                // hedging, so these features are unreachable for routes that
+                // are discovered from the control plane. They are only
+                // configured in tests until the API is extended.
+                retry: None,
+                hedge: None,
             },
         })
     }
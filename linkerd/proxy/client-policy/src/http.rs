@@ -29,12 +29,41 @@ pub struct Http2 {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Filter {
     InjectFailure(filter::InjectFailure),
+    /// Delays a fraction of requests before they are dispatched.
+    ///
+    /// This filter is only honored on a route's rules, not on its backends.
+    InjectDelay(filter::InjectDelay),
     Redirect(filter::RedirectRequest),
+    UrlRewrite(filter::UrlRewrite),
     RequestHeaders(filter::ModifyHeader),
     ResponseHeaders(filter::ModifyHeader),
+    RequestMirror(RequestMirror),
     InternalError(&'static str),
 }
 
+/// Sends a copy of a route's requests to an additional backend.
+///
+/// Mirrored requests are fire-and-forget: their responses are discarded and
+/// they never affect the response returned to the client. This filter is only
+/// honored on a route's rules, not on its backends.
+///
+/// The primary and mirrored requests share a buffered copy of the request
+/// body, so a mirrored request is not dispatched until the primary request has
+/// released its body. Mirrors of streaming or long-lived requests (e.g. gRPC
+/// streams) are therefore delayed until the primary request completes, and a
+/// primary request that is retried holds its body until its final attempt.
+/// Pending mirrors count against the limit on in-flight mirrored requests.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RequestMirror {
+    pub backend: crate::Backend,
+
+    /// Determines the fraction of requests that are mirrored.
+    pub distribution: filter::Distribution,
+
+    /// Requests with bodies larger than this are not mirrored.
+    pub max_body_bytes: usize,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct StatusRanges(pub Arc<[RangeInclusive<u16>]>);
 
@@ -49,6 +78,8 @@ pub fn default(distribution: crate::RouteDistribution<Filter>) -> Route {
                 distribution,
                 failure_policy: StatusRanges::default(),
                 request_timeout: None,
+                retry: None,
+                hedge: None,
             },
         }],
     }
@@ -156,6 +187,10 @@ pub mod proto {
         for Route { ref rules, .. } in rts {
             for Rule { ref policy, .. } in rules {
                 policy.distribution.fill_backends(set);
+                set.extend(policy.filters.iter().filter_map(|f| match f {
+                    Filter::RequestMirror(mirror) => Some(mirror.backend.clone()),
+                    _ => None,
+                }));
             }
         }
     }
@@ -251,6 +286,12 @@ pub mod proto {
                 distribution,
                 failure_policy: StatusRanges::default(),
                 request_timeout,
+                // NOTE: The proxy API has no fields for route retries or
+                // hedging, so these features are unreachable for routes that
+                // are discovered from the control plane. They are only
+                // configured in tests until the API is extended.
+                retry: None,
+                hedge: None,
             },
         })
     }
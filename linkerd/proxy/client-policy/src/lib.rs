@@ -7,6 +7,7 @@ use std::{borrow::Cow, fmt, hash::Hash, net::SocketAddr, num::NonZeroU16, sync::
 pub mod grpc;
 pub mod http;
 pub mod opaq;
+pub mod tls;
 
 pub use linkerd_http_route as route;
 pub use linkerd_proxy_api_resolve::Metadata as EndpointMetadata;
@@ -34,8 +35,7 @@ pub enum Protocol {
 
     Opaque(opaq::Opaque),
 
-    // TODO(ver) TLS-aware type
-    Tls(opaq::Opaque),
+    Tls(tls::Tls),
 }
 
 #[derive(Clone, Debug, Eq)]
@@ -73,10 +73,66 @@ pub struct RoutePolicy<T, F> {
 
     /// Configures what responses are classified as failures.
     pub failure_policy: F,
+
+    /// Configures how failed requests are retried on HTTP and gRPC routes.
+    ///
+    /// As with `request_timeout`, this field is ignored by opaque routes.
+    pub retry: Option<RouteRetry<F>>,
+
+    /// Configures hedging for HTTP and gRPC routes whose requests are
+    /// idempotent.
+    ///
+    /// As with `request_timeout`, this field is ignored by opaque routes.
+    pub hedge: Option<RouteHedge>,
+}
+
+/// Configures retries for HTTP and gRPC routes.
+///
+/// The `R` type describes which responses may be retried: status ranges for
+/// HTTP routes and response codes for gRPC routes.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RouteRetry<R> {
+    /// The maximum number of times a single request may be retried.
+    pub max_retries: usize,
+
+    /// Describes the responses that may be retried.
+    pub retryable: R,
+
+    /// A timeout applied to each individual attempt, including the initial
+    /// request.
+    pub timeout: Option<time::Duration>,
+
+    /// Limits the rate of retries across all requests on the route.
+    pub budget: RetryBudget,
+}
+
+/// Configures hedged requests for HTTP and gRPC routes.
+///
+/// When a request has not received a response after `delay`, a second attempt
+/// is dispatched and whichever response arrives first is used. HTTP routes only
+/// hedge requests with idempotent methods; gRPC routes only hedge requests for
+/// the RPCs in `idempotent_rpcs`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RouteHedge {
+    /// The time to wait for a response before issuing a hedged request.
+    pub delay: time::Duration,
+
+    /// Limits the rate of hedged requests across all requests on the route.
+    pub budget: RetryBudget,
+
+    /// The idempotent RPCs whose requests may be hedged on gRPC routes.
+    ///
+    /// This field is ignored by HTTP routes.
+    pub idempotent_rpcs: Arc<[linkerd_http_route::grpc::r#match::MatchRpc]>,
 }
 
-// TODO(ver) Weighted random WITHOUT availability awareness, as required by
-// HTTPRoute.
+/// A retry budget shared by all requests on a route.
+///
+/// Budgets are stateful, so two budgets are only considered equal if they
+/// refer to the same underlying budget.
+#[derive(Clone, Debug)]
+pub struct RetryBudget(Arc<tower::retry::budget::Budget>);
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum RouteDistribution<T> {
     Empty,
@@ -84,6 +140,11 @@ pub enum RouteDistribution<T> {
     FirstAvailable(Arc<[RouteBackend<T>]>),
 
     RandomAvailable(Arc<[(RouteBackend<T>, u32)]>),
+
+    /// Randomly selects a backend by weight, without regard to the backend's
+    /// availability, as required by HTTPRoute. Requests wait in the selected
+    /// backend's queue, subject to its failfast timeout.
+    RandomWeighted(Arc<[(RouteBackend<T>, u32)]>),
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -99,6 +160,24 @@ pub struct Backend {
     pub meta: Arc<Meta>,
     pub queue: Queue,
     pub dispatcher: BackendDispatcher,
+    /// If set, TLS is originated to the backend's endpoints that do not have a
+    /// mesh identity.
+    pub tls: Option<BackendTls>,
+}
+
+/// Configures TLS origination to endpoints outside of the mesh, so that
+/// applications may send plaintext requests that the proxy sends over TLS.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BackendTls {
+    /// The name sent as SNI and verified against the server's certificate.
+    pub server_name: linkerd_tls::ServerName,
+
+    /// If set, connections are established on this port rather than on the
+    /// endpoint's port (e.g. so that plaintext requests to port 80 are sent to
+    /// port 443).
+    pub port: Option<NonZeroU16>,
+
+    pub originate: linkerd_tls::OriginateTls,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -120,15 +199,51 @@ pub enum EndpointDiscovery {
 }
 
 /// Configures the load balancing strategy for a backend.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Load {
     PeakEwma(PeakEwma),
+
+    /// Selects endpoints by consistent hashing, so that requests with the
+    /// same key are dispatched to the same endpoint while it is available.
+    RingHash(RingHash),
+
+    /// Selects the endpoint with the fewest outstanding requests from a random
+    /// sample of endpoints.
+    LeastRequest(LeastRequest),
+
+    /// Selects each endpoint in turn.
+    RoundRobin,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct PeakEwma {
     pub decay: time::Duration,
     pub default_rtt: time::Duration,
+    /// If set, the share of traffic sent to newly discovered endpoints is
+    /// ramped up linearly over this window.
+    pub slow_start: Option<time::Duration>,
+}
+
+/// Configures a least request balancer.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LeastRequest {
+    /// The number of endpoints compared for each request.
+    pub choice_count: usize,
+}
+
+/// Configures a ring hash balancer.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RingHash {
+    pub key: HashKey,
+}
+
+/// The request attribute that is hashed to select an endpoint.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum HashKey {
+    Header(::http::HeaderName),
+    Cookie(Arc<str>),
+    SourceIp,
+    GrpcMetadata(::http::HeaderName),
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -144,6 +259,27 @@ pub enum FailureAccrual {
         /// Backoff for probing the endpoint when it is in a failed state.
         backoff: linkerd_exp_backoff::ExponentialBackoff,
     },
+    /// Endpoints are marked as unavailable when their success rate over a
+    /// sliding window falls a number of standard deviations below the mean
+    /// success rate of all endpoints in the pool.
+    SuccessRate {
+        /// The duration of the sliding window over which success rates are
+        /// computed.
+        window: time::Duration,
+        /// The minimum number of requests an endpoint must receive within the
+        /// window for its success rate to be considered.
+        min_requests: usize,
+        /// The number of standard deviations, in thousandths, below the pool's
+        /// mean success rate at which an endpoint becomes unavailable. For
+        /// example, `1900` ejects endpoints more than 1.9 standard deviations
+        /// below the mean.
+        std_dev_factor_milli: u32,
+        /// The maximum percentage of the pool's endpoints that may be
+        /// unavailable at once.
+        max_ejection_percent: u32,
+        /// Backoff for probing the endpoint when it is in a failed state.
+        backoff: linkerd_exp_backoff::ExponentialBackoff,
+    },
 }
 
 // === impl ClientPolicy ===
@@ -169,6 +305,8 @@ impl ClientPolicy {
                         distribution: RouteDistribution::Empty,
                         failure_policy: http::StatusRanges::default(),
                         request_timeout: None,
+                        retry: None,
+                        hedge: None,
                     },
                 }],
             }])
@@ -316,6 +454,38 @@ impl fmt::Display for Meta {
     }
 }
 
+// === impl RetryBudget ===
+
+impl RetryBudget {
+    /// Creates a new budget that permits `min_per_sec` retries per second plus
+    /// `retry_ratio` retries for each request observed over the `ttl` window.
+    pub fn new(ttl: time::Duration, min_per_sec: u32, retry_ratio: f32) -> Self {
+        Self(Arc::new(tower::retry::budget::Budget::new(
+            ttl,
+            min_per_sec,
+            retry_ratio,
+        )))
+    }
+
+    pub fn budget(&self) -> &Arc<tower::retry::budget::Budget> {
+        &self.0
+    }
+}
+
+impl PartialEq for RetryBudget {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for RetryBudget {}
+
+impl Hash for RetryBudget {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(Arc::as_ptr(&self.0) as usize);
+    }
+}
+
 // === impl FailureAccrual ===
 
 impl Default for FailureAccrual {
@@ -508,7 +678,10 @@ pub mod proto {
                 | Protocol::Http2(http::Http2 { ref routes, .. }) => {
                     http::proto::fill_route_backends(routes, &mut backends);
                 }
-                Protocol::Opaque(ref p) | Protocol::Tls(ref p) => {
+                Protocol::Opaque(ref p) => {
+                    p.fill_backends(&mut backends);
+                }
+                Protocol::Tls(ref p) => {
                     p.fill_backends(&mut backends);
                 }
                 Protocol::Grpc(ref p) => {
@@ -585,7 +758,7 @@ pub mod proto {
                 Self::FirstAvailable(backends) => {
                     set.extend(backends.iter().map(|b| b.backend.clone()));
                 }
-                Self::RandomAvailable(backends) => {
+                Self::RandomAvailable(backends) | Self::RandomWeighted(backends) => {
                     set.extend(backends.iter().map(|(b, _)| b.backend.clone()));
                 }
             }
@@ -662,6 +835,10 @@ pub mod proto {
                         }) => Load::PeakEwma(PeakEwma {
                             default_rtt: duration("peak EWMA default RTT", default_rtt)?,
                             decay: duration("peak EWMA decay", decay)?,
+                            // NOTE: The proxy API has no slow start field, so
+                            // slow start is unreachable for backends that are
+                            // discovered from the control plane.
+                            slow_start: None,
                         }),
                     };
                     BackendDispatcher::BalanceP2c(load, discovery)
@@ -696,6 +873,10 @@ pub mod proto {
                 queue,
                 dispatcher,
                 meta,
+                // NOTE: The proxy API has no backend TLS field, so originating
+                // TLS to a backend is unreachable for backends that are
+                // discovered from the control plane.
+                tls: None,
             };
 
             Ok(backend)
@@ -1,4 +1,7 @@
 use crate::RoutePolicy;
+use std::{num::NonZeroUsize, sync::Arc, time};
+
+pub use linkerd_http_route::http::filter::{Distribution, InjectFailure};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Opaque {
@@ -10,8 +13,26 @@ pub type Policy = RoutePolicy<Filter, NonIoErrors>;
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct NonIoErrors;
 
+/// Filters that are applied to each connection on an opaque route.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub enum Filter {}
+pub enum Filter {
+    /// Limits the number of concurrent connections on the route. Connections
+    /// in excess of the limit are refused.
+    ConnectionLimit(NonZeroUsize),
+
+    /// Closes connections that have not read or written any data within the
+    /// timeout.
+    IdleTimeout(time::Duration),
+
+    /// Closes connections that have been open longer than the timeout.
+    MaxLifetime(time::Duration),
+
+    /// Fails connections with the given message at a configured rate.
+    InjectFailure(InjectFailure<Arc<str>>),
+
+    /// Resets connections (i.e. with a TCP RST) at a configured rate.
+    InjectReset(Distribution),
+}
 
 impl NonIoErrors {
     pub fn contains(&self, e: &(dyn std::error::Error + 'static)) -> bool {
@@ -30,7 +51,6 @@ pub(crate) mod proto {
     use linkerd2_proxy_api::outbound::{self, opaque_route};
 
     use once_cell::sync::Lazy;
-    use std::sync::Arc;
 
     pub(crate) static NO_FILTERS: Lazy<Arc<[Filter]>> = Lazy::new(|| Arc::new([]));
 
@@ -129,6 +149,9 @@ pub(crate) mod proto {
             distribution,
             // Request timeouts are ignored on opaque routes.
             request_timeout: None,
+            // Retries and hedging are ignored on opaque routes.
+            retry: None,
+            hedge: None,
         })
     }
 
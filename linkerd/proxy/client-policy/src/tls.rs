@@ -0,0 +1,71 @@
+use crate::{opaq, route::MatchHost};
+use linkerd_tls::ServerName;
+use std::sync::Arc;
+
+/// Routes TLS connections by the SNI value in the client's ClientHello.
+/// Connections are proxied without terminating TLS.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tls {
+    pub routes: Arc<[Route]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Route {
+    /// A list of SNI values that this route applies to. When no matches are
+    /// present, all connections match, including those without an SNI value.
+    pub snis: Vec<MatchHost>,
+
+    /// Routes are proxied as opaque TCP, so they share the opaque route
+    /// policy.
+    pub policy: opaq::Policy,
+}
+
+// === impl Tls ===
+
+impl Default for Tls {
+    fn default() -> Self {
+        Self {
+            routes: Arc::from(Vec::new()),
+        }
+    }
+}
+
+/// Finds the route that best matches the given SNI value.
+///
+/// An exact match is preferred over a suffix match, and a longer match is
+/// preferred over a shorter one. A route without SNI matches is only used when
+/// no other route matches.
+pub fn find<'r>(routes: &'r [Route], sni: Option<&ServerName>) -> Option<&'r Route> {
+    routes
+        .iter()
+        .filter_map(|route| {
+            if route.snis.is_empty() {
+                return Some((None, route));
+            }
+            let sni = sni?;
+            let hm = route
+                .snis
+                .iter()
+                .filter_map(|m| m.summarize_host_match(sni.as_str()))
+                .max()?;
+            Some((Some(hm), route))
+        })
+        // Prefer the first route when matches are equivalent.
+        .rev()
+        .max_by(|(l, _), (r, _)| l.cmp(r))
+        .map(|(_, route)| route)
+}
+
+#[cfg(feature = "proto")]
+pub(crate) mod proto {
+    use super::*;
+    use crate::proto::BackendSet;
+
+    impl Tls {
+        pub(crate) fn fill_backends(&self, set: &mut BackendSet) {
+            for route in &*self.routes {
+                route.policy.distribution.fill_backends(set);
+            }
+        }
+    }
+}
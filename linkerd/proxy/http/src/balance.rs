@@ -1,7 +1,154 @@
+use crate::ClientHandle;
+use http::header::{HeaderName, COOKIE};
+use parking_lot::Mutex;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
 pub use hyper_balance::{PendingUntilFirstData, PendingUntilFirstDataBody};
 pub use linkerd_proxy_balance::*;
 
 pub type Body<B> = PendingUntilFirstDataBody<peak_ewma::Handle, B>;
 
-pub type NewBalancePeakEwma<B, X, R, N> =
-    linkerd_proxy_balance::NewBalancePeakEwma<PendingUntilFirstData, http::Request<B>, X, R, N>;
+pub type NewBalance<A, B, X, R, N> = linkerd_proxy_balance::NewBalance<
+    A,
+    PendingUntilFirstData,
+    TrackAttemptsExtension,
+    http::Request<B>,
+    X,
+    R,
+    N,
+>;
+
+pub type NewBalancePeakEwma<B, X, R, N> = NewBalance<EwmaConfig, B, X, R, N>;
+
+pub type NewBalanceRingHash<B, X, R, N> = NewBalance<RingHashConfig<HashKey>, B, X, R, N>;
+
+pub type NewBalanceLeastRequest<B, X, R, N> = NewBalance<LeastRequestConfig, B, X, R, N>;
+
+pub type NewBalanceRoundRobin<B, X, R, N> = NewBalance<RoundRobinConfig, B, X, R, N>;
+
+/// The request attribute used to select an endpoint in a ring hash balancer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HashKey {
+    /// Hashes the value of a request header. gRPC metadata keys are hashed as
+    /// headers.
+    Header(HeaderName),
+
+    /// Hashes the value of a request cookie.
+    Cookie(Arc<str>),
+
+    /// Hashes the client's IP address.
+    SourceIp,
+}
+
+/// A request extension that records the endpoints that a request's attempts
+/// are dispatched to.
+///
+/// A request that is sent more than once shares its `Attempts` with each of its
+/// attempts, so that balancers dispatch each attempt to a different endpoint
+/// when one is ready.
+#[derive(Clone, Debug, Default)]
+pub struct Attempts(Arc<Mutex<Vec<SocketAddr>>>);
+
+/// Tracks the endpoints that requests are dispatched to by their [`Attempts`]
+/// extensions.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TrackAttemptsExtension(());
+
+// === impl TrackAttemptsExtension ===
+
+impl<B> TrackAttempts<http::Request<B>> for TrackAttemptsExtension {
+    fn attempted(&self, req: &http::Request<B>, addr: &SocketAddr) -> bool {
+        req.extensions()
+            .get::<Attempts>()
+            .map_or(false, |Attempts(addrs)| addrs.lock().contains(addr))
+    }
+
+    fn record(&self, req: &http::Request<B>, addr: SocketAddr) {
+        if let Some(Attempts(addrs)) = req.extensions().get::<Attempts>() {
+            addrs.lock().push(addr);
+        }
+    }
+}
+
+// === impl HashKey ===
+
+impl<B> HashRequest<http::Request<B>> for HashKey {
+    fn hash_request(&self, req: &http::Request<B>) -> Option<u64> {
+        match self {
+            Self::Header(name) => req.headers().get(name).map(|v| hash(v.as_bytes())),
+            Self::Cookie(name) => req
+                .headers()
+                .get_all(COOKIE)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(';'))
+                .filter_map(|c| c.trim().split_once('='))
+                .find(|(n, _)| *n == &**name)
+                .map(|(_, v)| hash(v)),
+            Self::SourceIp => req
+                .extensions()
+                .get::<ClientHandle>()
+                .map(|c| match c.addr.ip() {
+                    IpAddr::V4(ip) => hash(ip.octets()),
+                    IpAddr::V6(ip) => hash(ip.octets()),
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(cookies: &[&'static str]) -> http::Request<()> {
+        let mut req = http::Request::builder();
+        for c in cookies {
+            req = req.header(COOKIE, *c);
+        }
+        req.body(()).unwrap()
+    }
+
+    #[test]
+    fn cookie() {
+        let key = HashKey::Cookie("session".into());
+        assert_eq!(key.hash_request(&req(&[])), None);
+        assert_eq!(key.hash_request(&req(&["other=abc"])), None);
+        assert_eq!(key.hash_request(&req(&["session=abc"])), Some(hash("abc")));
+        assert_eq!(
+            key.hash_request(&req(&["other=xyz; session=abc"])),
+            Some(hash("abc"))
+        );
+        assert_eq!(
+            key.hash_request(&req(&["other=xyz", "session=abc"])),
+            Some(hash("abc"))
+        );
+    }
+
+    #[test]
+    fn header() {
+        let key = HashKey::Header(HeaderName::from_static("x-user"));
+        let mut r = req(&[]);
+        assert_eq!(key.hash_request(&r), None);
+        r.headers_mut().insert("x-user", "alice".parse().unwrap());
+        let alice = key.hash_request(&r);
+        assert!(alice.is_some());
+        r.headers_mut().insert("x-user", "bob".parse().unwrap());
+        assert_ne!(key.hash_request(&r), alice);
+    }
+
+    #[test]
+    fn source_ip() {
+        let key = HashKey::SourceIp;
+        let mut r = req(&[]);
+        assert_eq!(key.hash_request(&r), None);
+        let (handle, _closed) = ClientHandle::new(([10, 1, 1, 1], 5432).into());
+        r.extensions_mut().insert(handle);
+        let a = key.hash_request(&r);
+        let (handle, _closed) = ClientHandle::new(([10, 1, 1, 1], 7654).into());
+        r.extensions_mut().insert(handle);
+        assert_eq!(key.hash_request(&r), a, "ports are not hashed");
+    }
+}
@@ -26,7 +26,9 @@ pub mod upgrade;
 pub mod version;
 
 pub use self::{
-    balance::NewBalancePeakEwma,
+    balance::{
+        NewBalanceLeastRequest, NewBalancePeakEwma, NewBalanceRingHash, NewBalanceRoundRobin,
+    },
     classify::{
         Classify, ClassifyEos, ClassifyResponse, NewClassifyGate, NewClassifyGateSet,
         NewInsertClassifyResponse,
@@ -0,0 +1,281 @@
+use crate::Metrics;
+use linkerd_error::Result;
+use linkerd_identity::{Credentials, DerX509};
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::time;
+use tracing::{debug, warn};
+
+/// Configures an identity that is loaded from PEM-encoded files, e.g. as
+/// provisioned by cert-manager.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// A PEM-encoded certificate chain, starting with the leaf certificate.
+    pub certificate: PathBuf,
+
+    /// A PEM-encoded PKCS#8 private key for the leaf certificate.
+    pub key: PathBuf,
+
+    /// PEM-encoded trust anchors used to verify peers.
+    pub trust_anchors: PathBuf,
+
+    /// PEM-encoded trust anchors for federated SPIFFE trust domains, keyed by
+    /// trust domain. These are not reloaded.
+    pub federated_trust_anchors_pem: HashMap<String, String>,
+
+    /// How often the files are checked for changes.
+    pub poll_interval: Duration,
+}
+
+/// Watches identity files, publishing their contents to credentials whenever
+/// they change.
+#[derive(Debug)]
+pub struct Files {
+    config: Config,
+    metrics: Metrics,
+}
+
+/// The raw contents of the identity files, used to detect changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Contents {
+    certificate: Vec<u8>,
+    key: Vec<u8>,
+    trust_anchors: Vec<u8>,
+}
+
+// === impl Files ===
+
+impl From<Config> for Files {
+    fn from(config: Config) -> Self {
+        Self {
+            config,
+            metrics: Metrics::default(),
+        }
+    }
+}
+
+impl Files {
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
+
+    pub async fn run<C: Credentials>(self, mut credentials: C) {
+        debug!(
+            certificate = %self.config.certificate.display(),
+            key = %self.config.key.display(),
+            trust_anchors = %self.config.trust_anchors.display(),
+            "Identity file watcher running",
+        );
+        let mut interval = time::interval(self.config.poll_interval);
+        interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+        let mut curr = None;
+        loop {
+            interval.tick().await;
+
+            // Files may be updated non-atomically, in which case they may be
+            // invalid until the update completes. Errors are logged and the
+            // files are read again on the next tick.
+            let contents = match Contents::read(&self.config) {
+                Ok(contents) => contents,
+                Err(error) => {
+                    warn!(%error, "Failed to read identity files");
+                    continue;
+                }
+            };
+            if curr.as_ref() == Some(&contents) {
+                continue;
+            }
+
+            match contents.apply(&self.config, &mut credentials) {
+                Ok(expiry) => {
+                    debug!(?expiry, "Identity loaded");
+                    self.metrics.refresh(expiry);
+                    curr = Some(contents);
+                }
+                Err(error) => warn!(%error, "Failed to load identity"),
+            }
+        }
+    }
+}
+
+// === impl Contents ===
+
+impl Contents {
+    /// Note that this uses blocking I/O.
+    fn read(config: &Config) -> io::Result<Self> {
+        Ok(Self {
+            certificate: std::fs::read(&config.certificate)?,
+            key: std::fs::read(&config.key)?,
+            trust_anchors: std::fs::read(&config.trust_anchors)?,
+        })
+    }
+
+    fn apply<C: Credentials>(&self, config: &Config, credentials: &mut C) -> Result<SystemTime> {
+        let mut chain = certs(&self.certificate)?.into_iter();
+        let leaf = chain.next().ok_or("certificate file has no certificates")?;
+        let intermediates = chain.collect();
+        let expiry = expiry(&leaf)?;
+        if expiry <= SystemTime::now() {
+            return Err("certificate already expired".into());
+        }
+
+        let key = pkcs8_key(&self.key)?;
+
+        let roots = certs(&self.trust_anchors)?;
+        if roots.is_empty() {
+            return Err("trust anchors file has no certificates".into());
+        }
+        let federated = config
+            .federated_trust_anchors_pem
+            .iter()
+            .map(|(trust_domain, pem)| Ok((trust_domain.clone(), certs(pem.as_bytes())?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        // Trust anchors must be updated first so that the certificate is
+        // validated against them.
+        credentials.set_trust_bundles(roots, federated)?;
+        credentials.set_certificate(leaf, intermediates, key)?;
+
+        Ok(expiry)
+    }
+}
+
+fn certs(pem: &[u8]) -> Result<Vec<DerX509>> {
+    let certs = rustls_pemfile::certs(&mut io::Cursor::new(pem))?;
+    Ok(certs.into_iter().map(DerX509).collect())
+}
+
+fn pkcs8_key(pem: &[u8]) -> Result<Vec<u8>> {
+    for item in rustls_pemfile::read_all(&mut io::Cursor::new(pem))? {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key) => return Ok(key),
+            rustls_pemfile::Item::RSAKey(_) | rustls_pemfile::Item::ECKey(_) => {
+                return Err("private key must be PKCS#8-encoded".into())
+            }
+            _ => {}
+        }
+    }
+    Err("key file has no private key".into())
+}
+
+fn expiry(leaf: &DerX509) -> Result<SystemTime> {
+    let (_, cert) = x509_parser::parse_x509_certificate(leaf)?;
+    let secs = u64::try_from(cert.validity().not_after.timestamp())
+        .map_err(|_| "certificate expires before the UNIX epoch")?;
+    Ok(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa};
+    use tokio::sync::mpsc;
+
+    const NAME: &str = "web.default.svc.cluster.local";
+
+    /// Forwards updates to a rustls credential store, recording the leaf
+    /// certificates that the store accepts.
+    struct Recorder {
+        store: linkerd_meshtls_rustls::creds::Store,
+        tx: mpsc::UnboundedSender<Vec<u8>>,
+    }
+
+    impl Credentials for Recorder {
+        fn set_certificate(
+            &mut self,
+            leaf: DerX509,
+            chain: Vec<DerX509>,
+            key: Vec<u8>,
+        ) -> Result<()> {
+            let der = leaf.to_vec();
+            self.store.set_certificate(leaf, chain, key)?;
+            let _ = self.tx.send(der);
+            Ok(())
+        }
+
+        fn set_trust_bundles(
+            &mut self,
+            roots: Vec<DerX509>,
+            federated: HashMap<String, Vec<DerX509>>,
+        ) -> Result<()> {
+            self.store.set_trust_bundles(roots, federated)
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn reloads_rotated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            certificate: dir.path().join("tls.crt"),
+            key: dir.path().join("tls.key"),
+            trust_anchors: dir.path().join("ca.crt"),
+            federated_trust_anchors_pem: Default::default(),
+            poll_interval: Duration::from_secs(10),
+        };
+        let ca = ca();
+        let leaf = write(&config, &ca);
+
+        let (store, _) = linkerd_meshtls_rustls::creds::watch(
+            NAME.parse().unwrap(),
+            NAME.parse().unwrap(),
+            "",
+            &Default::default(),
+        )
+        .expect("credentials must be valid");
+        let (tx, mut updates) = mpsc::unbounded_channel();
+        tokio::spawn(Files::from(config.clone()).run(Recorder { store, tx }));
+
+        assert_eq!(updates.recv().await, Some(leaf));
+
+        // Unchanged files are not reloaded.
+        time::sleep(config.poll_interval * 2).await;
+        assert!(updates.try_recv().is_err());
+
+        // A certificate that is not issued by the trust anchors is not loaded.
+        let (crt, key) = issue(&self::ca());
+        std::fs::write(&config.certificate, crt).unwrap();
+        std::fs::write(&config.key, key).unwrap();
+        time::sleep(config.poll_interval).await;
+        assert!(updates.try_recv().is_err());
+
+        // Rotating the trust anchors along with the certificate updates the
+        // credentials.
+        let leaf = write(&config, &self::ca());
+        assert_eq!(updates.recv().await, Some(leaf));
+    }
+
+    fn ca() -> Certificate {
+        let mut params = CertificateParams::new(vec![]);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        Certificate::from_params(params).unwrap()
+    }
+
+    /// Issues a certificate, returning its PEM-encoded certificate and key.
+    fn issue(ca: &Certificate) -> (String, String) {
+        let mut params = CertificateParams::new(vec![NAME.to_string()]);
+        params.extended_key_usages = vec![
+            ExtendedKeyUsagePurpose::ServerAuth,
+            ExtendedKeyUsagePurpose::ClientAuth,
+        ];
+        let cert = Certificate::from_params(params).unwrap();
+        (
+            cert.serialize_pem_with_signer(ca).unwrap(),
+            cert.serialize_private_key_pem(),
+        )
+    }
+
+    /// Writes a newly issued certificate and its trust anchors, returning the
+    /// DER-encoded leaf certificate.
+    fn write(config: &Config, ca: &Certificate) -> Vec<u8> {
+        let (crt, key) = issue(ca);
+        std::fs::write(&config.certificate, &crt).unwrap();
+        std::fs::write(&config.key, key).unwrap();
+        std::fs::write(&config.trust_anchors, ca.serialize_pem().unwrap()).unwrap();
+        certs(crt.as_bytes()).unwrap().remove(0).to_vec()
+    }
+}
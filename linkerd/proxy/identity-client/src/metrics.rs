@@ -32,7 +32,7 @@ impl Default for Metrics {
 }
 
 impl Metrics {
-    pub(crate) fn refresh(&self, expiry: SystemTime) {
+    pub fn refresh(&self, expiry: SystemTime) {
         self.refreshes.incr();
         *self.expiry.lock() = expiry;
     }
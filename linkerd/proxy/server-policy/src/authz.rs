@@ -1,17 +1,43 @@
 use super::Meta;
+use linkerd_http_route::{http::r#match::MatchRequest, Match};
 use std::{collections::BTreeSet, sync::Arc};
 
+pub mod jwt;
 mod network;
 
-pub use self::network::Network;
+pub use self::{jwt::Jwt, network::Network};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Authorization {
     pub networks: Vec<Network>,
     pub authentication: Authentication,
+
+    /// Restricts the authorization to HTTP requests that match any of these
+    /// conditions. When empty, the authorization applies to all requests.
+    ///
+    /// Authorizations with request conditions are ignored when authorizing
+    /// connections, since requests cannot be inspected.
+    pub requests: Vec<MatchRequest>,
+
+    /// Whether clients that match the authorization are allowed, denied, or
+    /// audited. Deny authorizations are evaluated first, followed by audit
+    /// authorizations and then allow authorizations.
+    pub action: Action,
+
     pub meta: Arc<Meta>,
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Action {
+    #[default]
+    Allow,
+    Deny,
+
+    /// Clients are allowed but are recorded as though they were denied, so
+    /// that a deny can be validated before it is enforced.
+    Audit,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Authentication {
     Unauthenticated,
@@ -19,7 +45,15 @@ pub enum Authentication {
     TlsAuthenticated {
         identities: BTreeSet<String>,
         suffixes: Vec<Suffix>,
+
+        /// Matches SPIFFE client identities by trust domain and path.
+        trust_domains: Vec<TrustDomain>,
     },
+
+    /// Authenticates requests that carry a valid JSON Web Token. JWT
+    /// authorizations never apply to connections, since requests cannot be
+    /// inspected.
+    Jwt(Jwt),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -27,6 +61,31 @@ pub struct Suffix {
     ends_with: String,
 }
 
+/// Matches SPIFFE IDs in a trust domain whose paths are equal to or nested
+/// under a path prefix.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TrustDomain {
+    name: String,
+    path_prefix: String,
+}
+
+// === impl Authorization ===
+
+impl Authorization {
+    /// Returns true if the authorization applies to all requests on a
+    /// connection.
+    #[inline]
+    pub fn is_unconditional(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Returns true if the authorization's request conditions match the
+    /// request.
+    pub fn matches_request<B>(&self, req: &http::Request<B>) -> bool {
+        self.is_unconditional() || self.requests.iter().any(|m| m.match_request(req).is_some())
+    }
+}
+
 // === impl Suffix ===
 
 impl From<Vec<String>> for Suffix {
@@ -53,6 +112,30 @@ impl Suffix {
     }
 }
 
+// === impl TrustDomain ===
+
+impl TrustDomain {
+    pub fn new(name: &str, path_prefix: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path_prefix: path_prefix.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Returns true if a SPIFFE ID with the given trust domain and path is in
+    /// this trust domain. Path prefixes only match whole path segments, so
+    /// `/ns/web` matches `/ns/web/sa/default` but not `/ns/website`.
+    pub fn contains(&self, trust_domain: &str, path: &str) -> bool {
+        if trust_domain != self.name {
+            return false;
+        }
+        match path.strip_prefix(&*self.path_prefix) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+}
+
 #[cfg(feature = "proto")]
 pub mod proto {
     use super::*;
@@ -140,6 +223,11 @@ pub mod proto {
                                 Authentication::TlsAuthenticated {
                                     identities,
                                     suffixes,
+                                    // NOTE: The proxy API has no trust domain
+                                    // field, so trust domain authentication is
+                                    // unreachable for authorizations that are
+                                    // discovered from the control plane.
+                                    trust_domains: vec![],
                                 }
                             }
                         }
@@ -159,6 +247,8 @@ pub mod proto {
             Ok(Authorization {
                 networks,
                 authentication: authn,
+                requests: vec![],
+                action: Action::Allow,
                 meta,
             })
         }
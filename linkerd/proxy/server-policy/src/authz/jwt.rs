@@ -0,0 +1,579 @@
+use http::header::{HeaderMap, HeaderName};
+use jsonwebtoken::{
+    self as jwt,
+    jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet},
+    Algorithm, DecodingKey,
+};
+use parking_lot::RwLock;
+use serde_json::{Map, Value};
+use std::{
+    hash::Hash,
+    io,
+    path::PathBuf,
+    sync::{Arc, Weak},
+};
+use tokio::time;
+use tracing::Instrument;
+
+/// Authenticates requests that carry a valid JSON Web Token.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Jwt {
+    /// The request header that carries the token.
+    ///
+    /// `Authorization` headers must use the `Bearer` scheme. The token in any
+    /// other header may be prefixed by a `Bearer` scheme, which is stripped.
+    pub header: HeaderName,
+
+    /// The value that tokens' `iss` claim must have.
+    pub issuer: String,
+
+    /// Tokens' `aud` claim must include at least one of these audiences. When
+    /// empty, the audience is not checked.
+    pub audiences: Vec<String>,
+
+    /// Claims that tokens must have.
+    pub claims: Vec<MatchClaim>,
+
+    /// The keys used to verify tokens' signatures.
+    pub jwks: Jwks,
+}
+
+/// Matches a claim whose value is one of `values` or, if the claim is an
+/// array, includes one of `values`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MatchClaim {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// A JSON Web Key Set that is loaded from a local file.
+///
+/// Key sets are reloaded as the file changes, so two key sets are only
+/// considered equal if they share the same underlying state.
+#[derive(Clone, Debug)]
+pub struct Jwks(Arc<JwksInner>);
+
+#[derive(Debug, thiserror::Error)]
+pub enum JwtError {
+    #[error("request does not have a token")]
+    Missing,
+
+    #[error("authorization header does not use the bearer scheme")]
+    Scheme,
+
+    #[error("token was not signed by a known key")]
+    UnknownKey,
+
+    #[error("token algorithm does not match its key")]
+    Algorithm,
+
+    #[error("token claims do not match")]
+    Claims,
+
+    #[error("invalid token: {0}")]
+    Invalid(#[from] jwt::errors::Error),
+}
+
+#[derive(Debug)]
+struct JwksInner {
+    path: PathBuf,
+    state: RwLock<JwksState>,
+}
+
+#[derive(Debug)]
+struct JwksState {
+    keys: JwkSet,
+    json: Vec<u8>,
+}
+
+// === impl Jwt ===
+
+impl Jwt {
+    /// Validates the token in the request's headers.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<(), JwtError> {
+        let value = headers
+            .get(&self.header)
+            .and_then(|v| v.to_str().ok())
+            .ok_or(JwtError::Missing)?;
+        let token = match bearer_token(value) {
+            Some(token) => token,
+            None if self.header == http::header::AUTHORIZATION => {
+                return Err(JwtError::Scheme);
+            }
+            None => value.trim(),
+        };
+
+        // The token's header is not trusted: its algorithm must be one that
+        // the key permits.
+        let header = jwt::decode_header(token)?;
+        let (key, algorithms) = self.jwks.decoding_key(header.kid.as_deref())?;
+        if !algorithms.contains(&header.alg) {
+            return Err(JwtError::Algorithm);
+        }
+
+        let mut validation = jwt::Validation::new(header.alg);
+        validation.algorithms = algorithms;
+        validation.set_issuer(&[&self.issuer]);
+        if self.audiences.is_empty() {
+            validation.set_required_spec_claims(&["exp", "iss"]);
+        } else {
+            validation.set_audience(&self.audiences);
+            validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        }
+        let token = jwt::decode::<Map<String, Value>>(token, &key, &validation)?;
+
+        if !self.claims.iter().all(|c| c.matches(&token.claims)) {
+            return Err(JwtError::Claims);
+        }
+        Ok(())
+    }
+}
+
+/// Returns the token in a header value that uses the `Bearer` scheme, whose
+/// name is case-insensitive.
+fn bearer_token(value: &str) -> Option<&str> {
+    let (scheme, token) = value.trim_start().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    let token = token.trim();
+    (!token.is_empty()).then_some(token)
+}
+
+// === impl MatchClaim ===
+
+impl MatchClaim {
+    fn matches(&self, claims: &Map<String, Value>) -> bool {
+        let is_value = |v: &Value| {
+            v.as_str()
+                .map_or(false, |s| self.values.iter().any(|m| m == s))
+        };
+        match claims.get(&self.name) {
+            Some(Value::Array(vs)) => vs.iter().any(is_value),
+            Some(v) => is_value(v),
+            None => false,
+        }
+    }
+}
+
+// === impl Jwks ===
+
+impl Jwks {
+    /// Loads a key set from a JSON file and spawns a background task that
+    /// reloads it as the file changes. The task completes once all handles to
+    /// the key set have been dropped.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn spawn(path: impl Into<PathBuf>, reload_interval: time::Duration) -> io::Result<Self> {
+        let jwks = Self::load(path)?;
+        let span = tracing::debug_span!("jwks", path = ?jwks.0.path);
+        tokio::spawn(jwks.watch(reload_interval).instrument(span));
+        Ok(jwks)
+    }
+
+    /// Loads a key set from a JSON file.
+    fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let state = JwksState::read(&path)?;
+        Ok(Self(Arc::new(JwksInner {
+            path,
+            state: RwLock::new(state),
+        })))
+    }
+
+    /// Reloads the key set if its file has changed since it was last read,
+    /// returning true if the key set was updated.
+    ///
+    /// If the file cannot be read, the previous keys are retained.
+    pub fn reload(&self) -> io::Result<bool> {
+        self.0.reload()
+    }
+
+    /// Returns a task that periodically reloads the key set as its file
+    /// changes. The task completes once all handles to the key set have been
+    /// dropped.
+    fn watch(&self, interval: time::Duration) -> impl std::future::Future<Output = ()> {
+        let inner = Arc::downgrade(&self.0);
+        async move {
+            let mut interval = time::interval(interval);
+            interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(inner) = Weak::upgrade(&inner) else {
+                    return;
+                };
+                match inner.reload() {
+                    Ok(true) => tracing::debug!("Reloaded JWKS"),
+                    Ok(false) => {}
+                    Err(error) => tracing::warn!(%error, "Failed to reload JWKS"),
+                }
+            }
+        }
+    }
+
+    /// Returns the key with the given ID and the algorithms it may be used
+    /// with.
+    fn decoding_key(&self, kid: Option<&str>) -> Result<(DecodingKey, Vec<Algorithm>), JwtError> {
+        let state = self.0.state.read();
+        let jwk = match kid {
+            Some(kid) => state.keys.find(kid),
+            // Tokens without a key ID may only be used with single-key sets.
+            None => match &*state.keys.keys {
+                [jwk] => Some(jwk),
+                _ => None,
+            },
+        }
+        .ok_or(JwtError::UnknownKey)?;
+
+        Ok((DecodingKey::from_jwk(jwk)?, algorithms(jwk)))
+    }
+}
+
+/// Returns the algorithms that may be used with a key: the key's `alg`, if it
+/// is set, or else all of the algorithms that apply to its key type.
+fn algorithms(jwk: &Jwk) -> Vec<Algorithm> {
+    if let Some(alg) = jwk.common.algorithm {
+        return vec![alg];
+    }
+    match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => vec![
+            Algorithm::RS256,
+            Algorithm::RS384,
+            Algorithm::RS512,
+            Algorithm::PS256,
+            Algorithm::PS384,
+            Algorithm::PS512,
+        ],
+        AlgorithmParameters::EllipticCurve(ec) => match ec.curve {
+            EllipticCurve::P256 => vec![Algorithm::ES256],
+            EllipticCurve::P384 => vec![Algorithm::ES384],
+            _ => vec![],
+        },
+        AlgorithmParameters::OctetKey(_) => {
+            vec![Algorithm::HS256, Algorithm::HS384, Algorithm::HS512]
+        }
+        AlgorithmParameters::OctetKeyPair(okp) => match okp.curve {
+            EllipticCurve::Ed25519 => vec![Algorithm::EdDSA],
+            _ => vec![],
+        },
+    }
+}
+
+impl PartialEq for Jwks {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Jwks {}
+
+impl Hash for Jwks {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(Arc::as_ptr(&self.0) as usize);
+    }
+}
+
+// === impl JwksInner ===
+
+impl JwksInner {
+    fn reload(&self) -> io::Result<bool> {
+        let state = JwksState::read(&self.path)?;
+        if state.json == self.state.read().json {
+            return Ok(false);
+        }
+        *self.state.write() = state;
+        Ok(true)
+    }
+}
+
+// === impl JwksState ===
+
+impl JwksState {
+    fn read(path: &std::path::Path) -> io::Result<Self> {
+        let json = std::fs::read(path)?;
+        let keys = serde_json::from_slice(&json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self { keys, json })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use jsonwebtoken::EncodingKey;
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"super-secret-signing-key";
+
+    fn write_jwks(path: &std::path::Path, kid: &str) {
+        let jwks = json!({
+            "keys": [{
+                "kty": "oct",
+                "kid": kid,
+                "alg": "HS256",
+                "k": base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(SECRET),
+            }]
+        });
+        std::fs::write(path, jwks.to_string()).unwrap();
+    }
+
+    fn token(kid: &str, claims: Value) -> String {
+        token_with_alg(kid, Algorithm::HS256, claims)
+    }
+
+    fn token_with_alg(kid: &str, alg: Algorithm, claims: Value) -> String {
+        let header = jwt::Header {
+            kid: Some(kid.to_string()),
+            ..jwt::Header::new(alg)
+        };
+        jwt::encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    fn headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::AUTHORIZATION,
+            format!("Bearer {token}").parse().unwrap(),
+        );
+        headers
+    }
+
+    fn exp() -> u64 {
+        jwt::get_current_timestamp() + 600
+    }
+
+    fn mk(jwks: Jwks) -> Jwt {
+        Jwt {
+            header: http::header::AUTHORIZATION,
+            issuer: "https://issuer.example.com".to_string(),
+            audiences: vec!["api".to_string()],
+            claims: vec![MatchClaim {
+                name: "groups".to_string(),
+                values: vec!["admin".to_string()],
+            }],
+            jwks,
+        }
+    }
+
+    #[test]
+    fn authenticates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwks.json");
+        write_jwks(&path, "k1");
+        let jwt = mk(Jwks::load(&path).unwrap());
+
+        let claims = json!({
+            "iss": "https://issuer.example.com",
+            "aud": "api",
+            "exp": exp(),
+            "groups": ["dev", "admin"],
+        });
+        jwt.authenticate(&headers(&token("k1", claims.clone())))
+            .expect("token must be valid");
+
+        assert!(matches!(
+            jwt.authenticate(&HeaderMap::new()),
+            Err(JwtError::Missing)
+        ));
+        assert!(matches!(
+            jwt.authenticate(&headers(&token("k2", claims.clone()))),
+            Err(JwtError::UnknownKey)
+        ));
+
+        let mut wrong_iss = claims.clone();
+        wrong_iss["iss"] = json!("https://other.example.com");
+        assert!(matches!(
+            jwt.authenticate(&headers(&token("k1", wrong_iss))),
+            Err(JwtError::Invalid(_))
+        ));
+
+        let mut wrong_aud = claims.clone();
+        wrong_aud["aud"] = json!("other");
+        assert!(matches!(
+            jwt.authenticate(&headers(&token("k1", wrong_aud))),
+            Err(JwtError::Invalid(_))
+        ));
+
+        let mut expired = claims.clone();
+        expired["exp"] = json!(jwt::get_current_timestamp() - 600);
+        assert!(matches!(
+            jwt.authenticate(&headers(&token("k1", expired))),
+            Err(JwtError::Invalid(_))
+        ));
+
+        let mut wrong_group = claims;
+        wrong_group["groups"] = json!("dev");
+        assert!(matches!(
+            jwt.authenticate(&headers(&token("k1", wrong_group))),
+            Err(JwtError::Claims)
+        ));
+    }
+
+    #[test]
+    fn parses_bearer_scheme() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwks.json");
+        write_jwks(&path, "k1");
+        let jwt = mk(Jwks::load(&path).unwrap());
+        let token = token(
+            "k1",
+            json!({
+                "iss": "https://issuer.example.com",
+                "aud": "api",
+                "exp": exp(),
+                "groups": "admin",
+            }),
+        );
+        let authorization = |value: String| {
+            let mut headers = HeaderMap::new();
+            headers.insert(http::header::AUTHORIZATION, value.parse().unwrap());
+            headers
+        };
+
+        // The scheme is case-insensitive.
+        jwt.authenticate(&authorization(format!("bearer {token}")))
+            .expect("lowercase scheme must be accepted");
+        jwt.authenticate(&authorization(format!("BEARER  {token}")))
+            .expect("uppercase scheme must be accepted");
+
+        // Authorization headers must use the bearer scheme.
+        assert!(matches!(
+            jwt.authenticate(&authorization(token.clone())),
+            Err(JwtError::Scheme)
+        ));
+        assert!(matches!(
+            jwt.authenticate(&authorization(format!("Basic {token}"))),
+            Err(JwtError::Scheme)
+        ));
+        assert!(matches!(
+            jwt.authenticate(&authorization("Bearer ".to_string())),
+            Err(JwtError::Scheme)
+        ));
+
+        // Other headers may carry a bare token.
+        let custom = Jwt {
+            header: HeaderName::from_static("x-token"),
+            ..jwt
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert("x-token", token.parse().unwrap());
+        custom
+            .authenticate(&headers)
+            .expect("bare token must be accepted");
+        headers.insert("x-token", format!("Bearer {token}").parse().unwrap());
+        custom
+            .authenticate(&headers)
+            .expect("bearer token must be accepted");
+    }
+
+    #[test]
+    fn rejects_algorithms_that_do_not_match_the_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwks.json");
+        write_jwks(&path, "k1");
+        let jwt = mk(Jwks::load(&path).unwrap());
+        let claims = json!({
+            "iss": "https://issuer.example.com",
+            "aud": "api",
+            "exp": exp(),
+            "groups": "admin",
+        });
+
+        // The key only permits HS256.
+        assert!(matches!(
+            jwt.authenticate(&headers(&token_with_alg(
+                "k1",
+                Algorithm::HS384,
+                claims.clone()
+            ))),
+            Err(JwtError::Algorithm)
+        ));
+
+        // Keys without an `alg` only permit algorithms for their key type.
+        let jwks = json!({
+            "keys": [{
+                "kty": "oct",
+                "kid": "k2",
+                "k": base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(SECRET),
+            }]
+        });
+        std::fs::write(&path, jwks.to_string()).unwrap();
+        let jwt = mk(Jwks::load(&path).unwrap());
+        jwt.authenticate(&headers(&token_with_alg(
+            "k2",
+            Algorithm::HS384,
+            claims.clone(),
+        )))
+        .expect("token must be valid");
+
+        // A token that claims an RSA algorithm is rejected for an HMAC key.
+        let header = jwt::Header {
+            kid: Some("k2".to_string()),
+            ..jwt::Header::new(Algorithm::RS256)
+        };
+        let hs256 = token("k2", claims);
+        let (_, rest) = hs256.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{rest}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .encode(serde_json::to_vec(&header).unwrap())
+        );
+        assert!(matches!(
+            jwt.authenticate(&headers(&forged)),
+            Err(JwtError::Algorithm)
+        ));
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn watches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwks.json");
+        write_jwks(&path, "k1");
+        let jwt = mk(Jwks::spawn(&path, time::Duration::from_secs(10)).unwrap());
+        let claims = json!({
+            "iss": "https://issuer.example.com",
+            "aud": "api",
+            "exp": exp(),
+            "groups": "admin",
+        });
+
+        write_jwks(&path, "k2");
+        time::sleep(time::Duration::from_secs(11)).await;
+        jwt.authenticate(&headers(&token("k2", claims)))
+            .expect("key set must be reloaded");
+    }
+
+    #[test]
+    fn reloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwks.json");
+        write_jwks(&path, "k1");
+        let jwt = mk(Jwks::load(&path).unwrap());
+        let claims = json!({
+            "iss": "https://issuer.example.com",
+            "aud": "api",
+            "exp": exp(),
+            "groups": "admin",
+        });
+
+        assert!(
+            !jwt.jwks.reload().unwrap(),
+            "unchanged file must not reload"
+        );
+
+        write_jwks(&path, "k2");
+        assert!(jwt.jwks.reload().unwrap());
+        jwt.authenticate(&headers(&token("k2", claims.clone())))
+            .expect("token must be valid");
+        assert!(jwt
+            .authenticate(&headers(&token("k1", claims.clone())))
+            .is_err());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(jwt.jwks.reload().is_err());
+        jwt.authenticate(&headers(&token("k2", claims)))
+            .expect("previous keys must be retained");
+    }
+}
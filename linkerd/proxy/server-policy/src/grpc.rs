@@ -8,7 +8,11 @@ pub type Rule = grpc::Rule<Policy>;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Filter {
     InjectFailure(filter::InjectFailure),
+    /// Delays a fraction of requests before they are dispatched.
+    InjectDelay(filter::InjectDelay),
     RequestHeaders(http::filter::ModifyHeader),
+    /// Rejects requests that exceed a local rate limit.
+    RateLimit(crate::RateLimit),
     InternalError(&'static str),
 }
 
@@ -8,8 +8,13 @@ pub type Rule = http::Rule<Policy>;
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Filter {
     InjectFailure(filter::InjectFailure),
+    /// Delays a fraction of requests before they are dispatched.
+    InjectDelay(filter::InjectDelay),
     Redirect(filter::RedirectRequest),
+    UrlRewrite(filter::UrlRewrite),
     RequestHeaders(filter::ModifyHeader),
+    /// Rejects requests that exceed a local rate limit.
+    RateLimit(crate::RateLimit),
     InternalError(&'static str),
 }
 
@@ -7,10 +7,12 @@ pub mod authz;
 pub mod grpc;
 pub mod http;
 pub mod meta;
+pub mod rate_limit;
 
 pub use self::{
-    authz::{Authentication, Authorization},
+    authz::{Action, Authentication, Authorization},
     meta::Meta,
+    rate_limit::RateLimit,
 };
 pub use linkerd_http_route as route;
 
@@ -18,6 +20,11 @@ pub use linkerd_http_route as route;
 pub struct ServerPolicy {
     pub protocol: Protocol,
     pub meta: Arc<Meta>,
+
+    /// When true, clients that are not authorized are allowed but are
+    /// recorded as though they were denied. This allows a policy to be
+    /// validated against live traffic before it is enforced.
+    pub audit: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -65,6 +72,7 @@ impl ServerPolicy {
                 }]),
                 tcp_authorizations: Arc::new([]),
             },
+            audit: false,
         }
     }
 }
@@ -142,6 +150,8 @@ pub mod proto {
                     meta: Arc::new(Meta::Default {
                         name: "localhost".into(),
                     }),
+                    requests: vec![],
+                    action: Action::Allow,
                 };
 
                 authz::proto::mk_authorizations(authorizations, &[localhost])?
@@ -182,7 +192,14 @@ pub mod proto {
             // avoid label inference.
             let meta = Meta::try_new_with_default(labels, "policy.linkerd.io", "server")?;
 
-            Ok(ServerPolicy { protocol, meta })
+            Ok(ServerPolicy {
+                protocol,
+                meta,
+                // NOTE: The proxy API has no audit field, so audit mode is
+                // unreachable for servers that are discovered from the control
+                // plane.
+                audit: false,
+            })
         }
     }
 }
@@ -0,0 +1,179 @@
+use ahash::AHashMap;
+use linkerd_identity::Id;
+use parking_lot::Mutex;
+use std::{hash::Hash, sync::Arc};
+use tokio::time;
+
+/// A token bucket that limits the rate of requests processed by this proxy.
+///
+/// Each limit is configured as a route filter and owns its buckets, so it only
+/// limits the requests that match its route. Rate limits are stateful, so two
+/// limits are only considered equal if they refer to the same underlying
+/// buckets.
+#[derive(Clone, Debug)]
+pub struct RateLimit {
+    key: Key,
+    limit: Limit,
+    buckets: Arc<Mutex<AHashMap<BucketKey, Bucket>>>,
+}
+
+/// Determines how a limit's requests are grouped into buckets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// All of the route's requests share a single bucket.
+    Global,
+
+    /// Each client identity has its own bucket. Requests from clients without
+    /// an identity share a bucket.
+    ClientId,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Limit {
+    /// The rate at which tokens are added to a bucket.
+    pub requests_per_second: u32,
+
+    /// The maximum number of tokens a bucket may hold, i.e. the number of
+    /// requests that may be processed in a burst.
+    pub burst: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum BucketKey {
+    Global,
+    ClientId(Option<Id>),
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: time::Instant,
+}
+
+// === impl RateLimit ===
+
+impl RateLimit {
+    pub fn new(key: Key, limit: Limit) -> Self {
+        Self {
+            key,
+            limit,
+            buckets: Default::default(),
+        }
+    }
+
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    pub fn limit(&self) -> Limit {
+        self.limit
+    }
+
+    /// Takes a token from the request's bucket, returning false if the bucket
+    /// is empty and the request should be rejected.
+    pub fn check(&self, client: Option<&Id>) -> bool {
+        let key = match self.key {
+            Key::Global => BucketKey::Global,
+            Key::ClientId => BucketKey::ClientId(client.cloned()),
+        };
+
+        let now = time::Instant::now();
+        let capacity = f64::from(self.limit.burst.max(1));
+        let rate = f64::from(self.limit.requests_per_second);
+        let mut buckets = self.buckets.lock();
+        if !buckets.contains_key(&key) {
+            // Buckets that have refilled are indistinguishable from new
+            // buckets, so they are dropped to bound the number of buckets.
+            buckets.retain(|_, b| b.refill(now, rate, capacity) < capacity);
+        }
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        if bucket.refill(now, rate, capacity) < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+impl PartialEq for RateLimit {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.buckets, &other.buckets)
+    }
+}
+
+impl Eq for RateLimit {}
+
+impl Hash for RateLimit {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(Arc::as_ptr(&self.buckets) as usize);
+    }
+}
+
+// === impl Bucket ===
+
+impl Bucket {
+    /// Adds the tokens accrued since the bucket was last updated, returning
+    /// the number of tokens in the bucket.
+    fn refill(&mut self, now: time::Instant, rate: f64, capacity: f64) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(capacity);
+        self.updated = now;
+        self.tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn refills() {
+        let limit = RateLimit::new(
+            Key::Global,
+            Limit {
+                requests_per_second: 2,
+                burst: 2,
+            },
+        );
+        assert!(limit.check(None));
+        assert!(limit.check(None));
+        assert!(!limit.check(None), "burst must be exhausted");
+
+        time::sleep(time::Duration::from_millis(500)).await;
+        assert!(limit.check(None), "one token must be added");
+        assert!(!limit.check(None));
+
+        time::sleep(time::Duration::from_secs(10)).await;
+        assert!(limit.check(None));
+        assert!(limit.check(None));
+        assert!(!limit.check(None), "tokens must not exceed the burst");
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn keys() {
+        let limit = Limit {
+            requests_per_second: 1,
+            burst: 1,
+        };
+        let alice = "alice.ns.serviceaccount.identity.linkerd.cluster.local"
+            .parse::<Id>()
+            .unwrap();
+        let bob = "bob.ns.serviceaccount.identity.linkerd.cluster.local"
+            .parse::<Id>()
+            .unwrap();
+
+        let global = RateLimit::new(Key::Global, limit);
+        assert!(global.check(Some(&alice)));
+        assert!(!global.check(Some(&bob)));
+
+        let client = RateLimit::new(Key::ClientId, limit);
+        assert!(client.check(Some(&alice)));
+        assert!(!client.check(Some(&alice)));
+        assert!(client.check(Some(&bob)));
+        assert!(client.check(None));
+        assert!(!client.check(None));
+    }
+}
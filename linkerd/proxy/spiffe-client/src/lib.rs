@@ -0,0 +1,130 @@
+//! A SPIFFE Workload API client that provisions the proxy's identity.
+//!
+//! The Workload API streams X.509-SVIDs and trust bundles to workloads over a
+//! Unix domain socket, so that the proxy shares its identity with other
+//! workloads attested by the same SPIFFE implementation (e.g. SPIRE).
+
+#![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
+#![forbid(unsafe_code)]
+
+mod svid;
+#[cfg(test)]
+mod tests;
+
+use self::svid::Svid;
+use futures::prelude::*;
+use linkerd_error::Result;
+use linkerd_exp_backoff::{ExponentialBackoff, ExponentialBackoffStream};
+use linkerd_identity::{Credentials, Id};
+use linkerd_proxy_http::trace;
+use linkerd_proxy_identity_client::Metrics;
+use spiffe_proto::workload::{self as api, spiffe_workload_api_client::SpiffeWorkloadApiClient};
+use std::path::PathBuf;
+use tracing::{debug, warn, Instrument};
+
+/// Configures the Workload API client.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// The path of the Workload API's Unix domain socket.
+    pub socket: PathBuf,
+
+    /// The SPIFFE ID of the SVID used as the local identity. The Workload API
+    /// may provide SVIDs for several identities.
+    pub id: Id,
+
+    /// Governs reconnects when the Workload API is unavailable.
+    pub backoff: ExponentialBackoff,
+}
+
+#[derive(Debug)]
+pub struct Client {
+    config: Config,
+    metrics: Metrics,
+}
+
+/// Workload API requests must include this header so that the server can
+/// reject requests that are not made by workloads (e.g. via SSRF).
+const WORKLOAD_API_HEADER: &str = "workload.spiffe.io";
+
+// === impl Client ===
+
+impl From<Config> for Client {
+    fn from(config: Config) -> Self {
+        Self {
+            config,
+            metrics: Metrics::default(),
+        }
+    }
+}
+
+impl Client {
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
+
+    /// Streams SVID and trust bundle rotations from the Workload API into
+    /// `credentials`, reconnecting whenever the stream fails.
+    pub async fn run<C: Credentials>(self, mut credentials: C) {
+        debug!(socket = %self.config.socket.display(), "Workload API client running");
+        let mut backoff = self.config.backoff.stream();
+        loop {
+            match self.watch(&mut credentials, &mut backoff).await {
+                Ok(()) => debug!("Workload API stream ended"),
+                Err(error) => warn!(%error, "Failed to fetch X.509-SVIDs"),
+            }
+            backoff.next().await;
+        }
+    }
+
+    async fn watch<C: Credentials>(
+        &self,
+        credentials: &mut C,
+        backoff: &mut ExponentialBackoffStream,
+    ) -> Result<()> {
+        let io = tokio::net::UnixStream::connect(&self.config.socket).await?;
+        let (client, conn) = hyper::client::conn::Builder::new()
+            .http2_only(true)
+            .executor(trace::Executor::new())
+            .handshake(io)
+            .await?;
+        tokio::spawn(
+            conn.map_err(|error| debug!(%error, "Workload API connection failed"))
+                .in_current_span(),
+        );
+
+        // The authority is ignored by the server, but HTTP/2 requests must
+        // include one.
+        let mut client = SpiffeWorkloadApiClient::with_origin(
+            client,
+            http::Uri::from_static("http://localhost"),
+        );
+        let mut req = tonic::Request::new(api::X509svidRequest {});
+        req.metadata_mut().insert(
+            WORKLOAD_API_HEADER,
+            tonic::metadata::MetadataValue::from_static("true"),
+        );
+        let mut rsps = client.fetch_x509svid(req).await?.into_inner();
+
+        while let Some(rsp) = rsps.message().await? {
+            let svid = match Svid::from_response(rsp, &self.config.id) {
+                Ok(svid) => svid,
+                Err(error) => {
+                    // Wait for the next update rather than reconnecting.
+                    warn!(%error, "Invalid X.509-SVID update");
+                    continue;
+                }
+            };
+            let expiry = svid.expiry;
+            match svid.apply(credentials) {
+                Ok(()) => {
+                    debug!(?expiry, "Identity updated");
+                    self.metrics.refresh(expiry);
+                    *backoff = self.config.backoff.stream();
+                }
+                Err(error) => warn!(%error, "Failed to update identity"),
+            }
+        }
+
+        Ok(())
+    }
+}
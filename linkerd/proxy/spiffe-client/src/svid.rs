@@ -0,0 +1,104 @@
+use linkerd_error::Result;
+use linkerd_identity::{Credentials, DerX509, Id};
+use spiffe_proto::workload as api;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::warn;
+
+/// An X.509-SVID for the local identity along with the trust bundles used to
+/// verify peers.
+#[derive(Debug)]
+pub(crate) struct Svid {
+    leaf: DerX509,
+    intermediates: Vec<DerX509>,
+    key_pkcs8: Vec<u8>,
+    roots: Vec<DerX509>,
+    federated: HashMap<String, Vec<DerX509>>,
+    pub(crate) expiry: SystemTime,
+}
+
+// === impl Svid ===
+
+impl Svid {
+    /// Extracts the SVID for `id` from a Workload API response.
+    pub(crate) fn from_response(rsp: api::X509svidResponse, id: &Id) -> Result<Self> {
+        let api::X509svidResponse {
+            svids,
+            federated_bundles,
+            ..
+        } = rsp;
+
+        let id_str = id.to_str();
+        let svid = svids
+            .into_iter()
+            .find(|svid| svid.spiffe_id == id_str)
+            .ok_or_else(|| format!("no X.509-SVID for {id}"))?;
+
+        let mut chain = split_certs(&svid.x509_svid)?.into_iter();
+        let leaf = chain.next().ok_or("X.509-SVID has no certificates")?;
+        let intermediates = chain.collect();
+        let expiry = expiry(&leaf)?;
+        if expiry <= SystemTime::now() {
+            return Err("X.509-SVID already expired".into());
+        }
+
+        let roots = split_certs(&svid.bundle)?;
+        if roots.is_empty() {
+            return Err("X.509-SVID has no trust bundle".into());
+        }
+
+        // Federated bundles are keyed by the trust domain's SPIFFE ID (e.g.
+        // `spiffe://example.org`).
+        let mut federated = HashMap::with_capacity(federated_bundles.len());
+        for (td_id, bundle) in federated_bundles {
+            let trust_domain = match Id::parse_uri(&td_id)
+                .ok()
+                .and_then(|id| id.spiffe_trust_domain().map(str::to_string))
+            {
+                Some(td) => td,
+                None => {
+                    warn!(trust_domain = %td_id, "Ignoring federated bundle for invalid trust domain");
+                    continue;
+                }
+            };
+            federated.insert(trust_domain, split_certs(&bundle)?);
+        }
+
+        Ok(Self {
+            leaf,
+            intermediates,
+            key_pkcs8: svid.x509_svid_key,
+            roots,
+            federated,
+            expiry,
+        })
+    }
+
+    pub(crate) fn apply<C: Credentials>(self, credentials: &mut C) -> Result<()> {
+        // Trust bundles must be updated first so that the new certificate is
+        // validated against them.
+        credentials.set_trust_bundles(self.roots, self.federated)?;
+        credentials.set_certificate(self.leaf, self.intermediates, self.key_pkcs8)
+    }
+}
+
+/// Splits concatenated DER-encoded certificates.
+fn split_certs(mut der: &[u8]) -> Result<Vec<DerX509>> {
+    let mut certs = Vec::new();
+    while !der.is_empty() {
+        let (rest, _) = x509_parser::parse_x509_certificate(der)?;
+        let len = der.len() - rest.len();
+        certs.push(DerX509(der[..len].to_vec()));
+        der = rest;
+    }
+    Ok(certs)
+}
+
+fn expiry(leaf: &DerX509) -> Result<SystemTime> {
+    let (_, cert) = x509_parser::parse_x509_certificate(leaf)?;
+    let secs = u64::try_from(cert.validity().not_after.timestamp())
+        .map_err(|_| "X.509-SVID expires before the UNIX epoch")?;
+    Ok(UNIX_EPOCH + Duration::from_secs(secs))
+}
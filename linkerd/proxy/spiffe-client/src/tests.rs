@@ -0,0 +1,210 @@
+use super::*;
+use linkerd_identity::DerX509;
+use prost::Message;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa, SanType,
+};
+use std::{collections::HashMap, time::Duration};
+use tokio::{net::UnixListener, sync::mpsc};
+
+const ID: &str = "spiffe://example.org/ns/default/sa/web";
+
+/// Forwards updates to a rustls credential store, recording the leaf
+/// certificates that the store accepts.
+struct Recorder {
+    store: linkerd_meshtls_rustls::creds::Store,
+    tx: mpsc::UnboundedSender<Vec<u8>>,
+}
+
+impl Credentials for Recorder {
+    fn set_certificate(&mut self, leaf: DerX509, chain: Vec<DerX509>, key: Vec<u8>) -> Result<()> {
+        let der = leaf.to_vec();
+        self.store.set_certificate(leaf, chain, key)?;
+        let _ = self.tx.send(der);
+        Ok(())
+    }
+
+    fn set_trust_bundles(
+        &mut self,
+        roots: Vec<DerX509>,
+        federated: HashMap<String, Vec<DerX509>>,
+    ) -> Result<()> {
+        self.store.set_trust_bundles(roots, federated)
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn streams_svid_rotations() {
+    let (rsps, mut updates) = spawn_client();
+
+    // The Workload API may provide SVIDs for other identities.
+    let ca = ca();
+    let (svid, leaf) = svid(&ca, ID);
+    let (other, _) = svid_for_other(&ca);
+    rsps.send(api::X509svidResponse {
+        svids: vec![other, svid],
+        federated_bundles: HashMap::from([(
+            "spiffe://other.example".to_string(),
+            ca.serialize_der().unwrap(),
+        )]),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(next(&mut updates).await, leaf);
+
+    // Rotating the trust bundle along with the SVID updates the credentials.
+    let ca = self::ca();
+    let (svid, leaf) = self::svid(&ca, ID);
+    rsps.send(api::X509svidResponse {
+        svids: vec![svid],
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(next(&mut updates).await, leaf);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn ignores_invalid_updates() {
+    let (rsps, mut updates) = spawn_client();
+
+    let ca = ca();
+    let (other, _) = svid_for_other(&ca);
+    rsps.send(api::X509svidResponse {
+        svids: vec![other],
+        ..Default::default()
+    })
+    .unwrap();
+
+    // The SVID's chain must be issued by its bundle.
+    let (mut svid, _) = svid(&ca, ID);
+    svid.bundle = self::ca().serialize_der().unwrap();
+    rsps.send(api::X509svidResponse {
+        svids: vec![svid],
+        ..Default::default()
+    })
+    .unwrap();
+
+    let (svid, leaf) = self::svid(&ca, ID);
+    rsps.send(api::X509svidResponse {
+        svids: vec![svid],
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(next(&mut updates).await, leaf);
+}
+
+fn spawn_client() -> (
+    mpsc::UnboundedSender<api::X509svidResponse>,
+    mpsc::UnboundedReceiver<Vec<u8>>,
+) {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("agent.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let (rsps_tx, rsps_rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        serve(listener, rsps_rx).await;
+        drop(dir);
+    });
+
+    let (store, _) = linkerd_meshtls_rustls::creds::watch(
+        ID.parse().unwrap(),
+        "web.default.svc.cluster.local".parse().unwrap(),
+        "",
+        &Default::default(),
+    )
+    .expect("credentials must be valid");
+    let (tx, updates) = mpsc::unbounded_channel();
+    let client = Client::from(Config {
+        socket,
+        id: ID.parse().unwrap(),
+        backoff: ExponentialBackoff::try_new(
+            Duration::from_millis(10),
+            Duration::from_millis(100),
+            0.0,
+        )
+        .unwrap(),
+    });
+    tokio::spawn(client.run(Recorder { store, tx }));
+
+    (rsps_tx, updates)
+}
+
+/// A stand-in for the Workload API that serves a single `FetchX509SVID`
+/// stream, sending responses as they are received on `rsps`.
+async fn serve(listener: UnixListener, rsps: mpsc::UnboundedReceiver<api::X509svidResponse>) {
+    let (io, _) = listener.accept().await.expect("client must connect");
+    let mut rsps = Some(rsps);
+    let svc = hyper::service::service_fn(move |req: http::Request<hyper::Body>| {
+        assert_eq!(req.uri().path(), "/SpiffeWorkloadAPI/FetchX509SVID");
+        assert_eq!(
+            req.headers().get(WORKLOAD_API_HEADER).map(|v| v.as_bytes()),
+            Some(&b"true"[..])
+        );
+
+        let mut rsps = rsps.take().expect("only one stream may be requested");
+        let (mut tx, body) = hyper::Body::channel();
+        tokio::spawn(async move {
+            while let Some(rsp) = rsps.recv().await {
+                // Frame the message with an uncompressed gRPC message header.
+                let msg = rsp.encode_to_vec();
+                let mut frame = Vec::with_capacity(5 + msg.len());
+                frame.push(0);
+                frame.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+                frame.extend(msg);
+                if tx.send_data(frame.into()).await.is_err() {
+                    return;
+                }
+            }
+            let mut trailers = http::HeaderMap::new();
+            trailers.insert("grpc-status", http::HeaderValue::from_static("0"));
+            let _ = tx.send_trailers(trailers).await;
+        });
+
+        future::ok::<_, std::convert::Infallible>(
+            http::Response::builder()
+                .header(http::header::CONTENT_TYPE, "application/grpc")
+                .body(body)
+                .unwrap(),
+        )
+    });
+    let mut server = hyper::server::conn::Http::new().with_executor(trace::Executor::new());
+    server.http2_only(true);
+    let _ = server.serve_connection(io, svc).await;
+}
+
+async fn next(updates: &mut mpsc::UnboundedReceiver<Vec<u8>>) -> Vec<u8> {
+    tokio::time::timeout(Duration::from_secs(5), updates.recv())
+        .await
+        .expect("credentials must be updated")
+        .expect("client must be running")
+}
+
+fn ca() -> Certificate {
+    let mut params = CertificateParams::new(vec![]);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    Certificate::from_params(params).unwrap()
+}
+
+/// Issues an X.509-SVID, returning it along with its leaf certificate.
+fn svid(ca: &Certificate, id: &str) -> (api::X509svid, Vec<u8>) {
+    let mut params = CertificateParams::new(vec![]);
+    params.subject_alt_names = vec![SanType::URI(id.into())];
+    params.extended_key_usages = vec![
+        ExtendedKeyUsagePurpose::ServerAuth,
+        ExtendedKeyUsagePurpose::ClientAuth,
+    ];
+    let cert = Certificate::from_params(params).unwrap();
+    let leaf = cert.serialize_der_with_signer(ca).unwrap();
+    let svid = api::X509svid {
+        spiffe_id: id.to_string(),
+        x509_svid: leaf.clone(),
+        x509_svid_key: cert.serialize_private_key_der(),
+        bundle: ca.serialize_der().unwrap(),
+        hint: String::new(),
+    };
+    (svid, leaf)
+}
+
+fn svid_for_other(ca: &Certificate) -> (api::X509svid, Vec<u8>) {
+    svid(ca, "spiffe://example.org/ns/default/sa/other")
+}
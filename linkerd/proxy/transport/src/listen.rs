@@ -17,6 +17,7 @@ pub trait Bind<T> {
         + io::AsyncWrite
         + io::Peek
         + io::PeerAddr
+        + io::Reset
         + fmt::Debug
         + Unpin
         + Send
@@ -16,6 +16,7 @@ pub struct Route {
     labels: Labels,
     response_classes: ResponseClasses,
     retries: Option<Retries>,
+    hedge: Option<Hedge>,
     timeout: Option<Duration>,
 }
 
@@ -59,6 +60,13 @@ pub struct Retries {
     budget: Arc<Budget>,
 }
 
+/// Configures hedging for an idempotent route.
+#[derive(Clone, Debug)]
+pub struct Hedge {
+    delay: Duration,
+    budget: Arc<Budget>,
+}
+
 #[derive(Clone, Default)]
 struct Labels(Arc<std::collections::BTreeMap<String, String>>);
 
@@ -87,6 +95,7 @@ impl Route {
             labels,
             response_classes: ResponseClasses(response_classes.into()),
             retries: None,
+            hedge: None,
             timeout: None,
         }
     }
@@ -103,6 +112,10 @@ impl Route {
         self.retries.as_ref()
     }
 
+    pub fn hedge(&self) -> Option<&Hedge> {
+        self.hedge.as_ref()
+    }
+
     pub fn timeout(&self) -> Option<Duration> {
         self.timeout
     }
@@ -111,6 +124,12 @@ impl Route {
         self.retries = Some(Retries { budget });
     }
 
+    /// Marks the route as idempotent so that a request that has not received
+    /// a response after `delay` may be sent a second time.
+    pub fn set_hedge(&mut self, delay: Duration, budget: Arc<Budget>) {
+        self.hedge = Some(Hedge { delay, budget });
+    }
+
     pub fn set_timeout(&mut self, timeout: Duration) {
         self.timeout = Some(timeout);
     }
@@ -220,6 +239,33 @@ impl Hash for Retries {
     }
 }
 
+// === impl Hedge ===
+
+impl Hedge {
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    pub fn budget(&self) -> &Arc<Budget> {
+        &self.budget
+    }
+}
+
+impl PartialEq for Hedge {
+    fn eq(&self, other: &Self) -> bool {
+        self.delay == other.delay && Arc::ptr_eq(&self.budget, &other.budget)
+    }
+}
+
+impl Eq for Hedge {}
+
+impl Hash for Hedge {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.delay.hash(state);
+        state.write_usize(Arc::as_ref(&self.budget) as *const _ as usize);
+    }
+}
+
 // === impl Labels ===
 
 impl PartialEq for Labels {
@@ -51,6 +51,9 @@ fn convert_route(
     if let Some(timeout) = orig.timeout {
         set_route_timeout(&mut route, timeout.try_into());
     }
+    // NOTE: The destination API's profile routes have no hedging
+    // configuration, so `Route::set_hedge` is never called for profiles that
+    // are discovered from the control plane.
     Some((req_match, route))
 }
 
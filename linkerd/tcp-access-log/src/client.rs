@@ -0,0 +1,119 @@
+use super::{now, AccessLogIo, Connection, Sensor, TRACE_TARGET};
+use futures::TryFuture;
+use linkerd_proxy_transport::{Remote, ServerAddr};
+use linkerd_stack::{layer, MakeConnection, Param, Service};
+use linkerd_tls as tls;
+use pin_project::pin_project;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tracing::{field, span, Level};
+
+/// A `MakeConnection` that logs each connection the proxy establishes.
+#[derive(Clone, Debug)]
+pub struct ConnectAccessLog<S> {
+    inner: S,
+}
+
+#[pin_project]
+pub struct ConnectFuture<F> {
+    conn: Option<Arc<Connection>>,
+
+    #[pin]
+    inner: F,
+}
+
+// === impl ConnectAccessLog ===
+
+impl<S> ConnectAccessLog<S> {
+    /// Returns a new `ConnectAccessLog` layer that logs each connection
+    /// established by the inner connector.
+    ///
+    /// The log describes the endpoint and the connection's TLS status. A
+    /// connection that cannot be established is logged with the connection
+    /// error.
+    pub fn layer() -> impl layer::Layer<S, Service = Self> + Clone {
+        layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, S> Service<T> for ConnectAccessLog<S>
+where
+    T: Param<Remote<ServerAddr>> + Param<tls::ConditionalClientTls>,
+    S: MakeConnection<T>,
+    S::Error: fmt::Display,
+{
+    type Response = (AccessLogIo<S::Connection>, S::Metadata);
+    type Error = S::Error;
+    type Future = ConnectFuture<S::Future>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, target: T) -> Self::Future {
+        let Remote(ServerAddr(server_addr)) = target.param();
+        let tls: tls::ConditionalClientTls = target.param();
+        let server_id = tls
+            .value()
+            .map(|tls| tls.server_id.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        let span = span!(target: TRACE_TARGET, Level::INFO, "tcp",
+            server.addr = %server_addr,
+            server.id = %server_id,
+            timestamp = %now(),
+            tls = %TlsStatus(&tls),
+            bytes_in = field::Empty,
+            bytes_out = field::Empty,
+            duration_ns = field::Empty,
+            close = field::Empty,
+        );
+
+        ConnectFuture {
+            conn: Connection::open(span),
+            inner: self.inner.connect(target),
+        }
+    }
+}
+
+// === impl ConnectFuture ===
+
+impl<I, M, F> Future for ConnectFuture<F>
+where
+    F: TryFuture<Ok = (I, M)>,
+    F::Error: fmt::Display,
+{
+    type Output = Result<(AccessLogIo<I>, M), F::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let res = futures::ready!(this.inner.try_poll(cx));
+        let conn = this.conn.take();
+        match res {
+            Ok((io, meta)) => Poll::Ready(Ok((AccessLogIo::new(io, Sensor(conn)), meta))),
+            Err(error) => {
+                if let Some(conn) = conn {
+                    conn.failed(&error);
+                }
+                Poll::Ready(Err(error))
+            }
+        }
+    }
+}
+
+struct TlsStatus<'t>(&'t tls::ConditionalClientTls);
+
+impl fmt::Display for TlsStatus<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            tls::ConditionalClientTls::Some(_) => f.write_str("true"),
+            tls::ConditionalClientTls::None(reason) => fmt::Display::fmt(reason, f),
+        }
+    }
+}
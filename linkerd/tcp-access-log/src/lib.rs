@@ -0,0 +1,159 @@
+#![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
+#![forbid(unsafe_code)]
+
+//! Connection-level access logging for opaque TCP flows.
+//!
+//! Each logged connection is described by a `tracing` span with the
+//! [`TRACE_TARGET`] target. The span is recorded when the connection is
+//! closed, so that it includes the number of bytes read from and written to
+//! the peer, the connection's duration, and the reason it was closed.
+
+mod client;
+mod server;
+#[cfg(test)]
+mod tests;
+
+pub use self::{
+    client::{ConnectAccessLog, ConnectFuture},
+    server::{AccessLog, AccessLogFuture, NewAccessLog},
+};
+use linkerd_errno::Errno;
+use linkerd_io as io;
+use linkerd_tracing::access_log::TRACE_TARGET;
+use parking_lot::Mutex;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::Poll,
+    time::SystemTime,
+};
+use tokio::time::Instant;
+use tracing::Span;
+
+/// An I/O type whose activity is recorded in a connection's access log.
+pub type AccessLogIo<I> = io::SensorIo<I, Sensor>;
+
+/// Records the activity of a single connection on its access log span.
+///
+/// When access logging is disabled, the sensor records nothing.
+#[derive(Debug)]
+pub struct Sensor(Option<Arc<Connection>>);
+
+/// The state of a logged connection.
+///
+/// The connection's span is recorded when all references to this state are
+/// dropped, i.e. once both the connection's I/O and the service handling it
+/// have completed.
+#[derive(Debug)]
+struct Connection {
+    span: Span,
+    start: Instant,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+    close: Mutex<Option<Close>>,
+}
+
+/// Describes why a connection was closed.
+#[derive(Debug)]
+enum Close {
+    /// The peer closed the connection.
+    Eof,
+    /// The connection failed with an error.
+    Error(String),
+}
+
+// === impl Sensor ===
+
+impl io::Sensor for Sensor {
+    fn record_read(&mut self, sz: usize) {
+        if let Some(conn) = self.0.as_ref() {
+            conn.bytes_in.fetch_add(sz as u64, Ordering::Relaxed);
+            if sz == 0 {
+                conn.closed(Close::Eof);
+            }
+        }
+    }
+
+    fn record_write(&mut self, sz: usize) {
+        if let Some(conn) = self.0.as_ref() {
+            conn.bytes_out.fetch_add(sz as u64, Ordering::Relaxed);
+        }
+    }
+
+    fn record_close(&mut self, _: Option<Errno>) {}
+
+    fn record_error<T>(&mut self, op: io::Poll<T>) -> io::Poll<T> {
+        if let (Some(conn), Poll::Ready(Err(e))) = (self.0.as_ref(), &op) {
+            if e.kind() != io::ErrorKind::WouldBlock {
+                conn.closed(Close::Error(e.to_string()));
+            }
+        }
+        op
+    }
+}
+
+// === impl Connection ===
+
+impl Connection {
+    /// Begins logging a connection, unless the access log span is disabled.
+    fn open(span: Span) -> Option<Arc<Self>> {
+        // The access log span is only enabled by the `tracing` subscriber if
+        // access logs are being recorded. If it's disabled, we can skip
+        // tracking the connection entirely.
+        if span.is_disabled() {
+            return None;
+        }
+
+        Some(Arc::new(Self {
+            span,
+            start: Instant::now(),
+            bytes_in: AtomicU64::new(0),
+            bytes_out: AtomicU64::new(0),
+            close: Mutex::new(None),
+        }))
+    }
+
+    /// Records the first reason the connection's I/O was closed.
+    fn closed(&self, close: Close) {
+        let mut c = self.close.lock();
+        if c.is_none() {
+            *c = Some(close);
+        }
+    }
+
+    /// Records that the service handling the connection failed.
+    ///
+    /// The service's error takes precedence over I/O errors, since it
+    /// typically describes the I/O error's context.
+    fn failed(&self, error: &dyn fmt::Display) {
+        *self.close.lock() = Some(Close::Error(error.to_string()));
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let duration_ns = Instant::now()
+            .saturating_duration_since(self.start)
+            .as_nanos();
+
+        let span = &self.span;
+        span.record("bytes_in", self.bytes_in.load(Ordering::Relaxed));
+        span.record("bytes_out", self.bytes_out.load(Ordering::Relaxed));
+        span.record("duration_ns", &tracing::field::display(duration_ns));
+        match self.close.get_mut().take() {
+            Some(Close::Eof) => span.record("close", "eof"),
+            Some(Close::Error(error)) => span.record("close", error.as_str()),
+            // Neither peer closed the connection nor did it fail, so it was
+            // closed by the proxy.
+            None => span.record("close", "closed"),
+        };
+    }
+}
+
+#[inline]
+fn now() -> humantime::Rfc3339Timestamp {
+    humantime::format_rfc3339(SystemTime::now())
+}
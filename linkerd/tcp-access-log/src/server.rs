@@ -0,0 +1,153 @@
+use super::{now, AccessLogIo, Connection, Sensor, TRACE_TARGET};
+use futures::TryFuture;
+use linkerd_proxy_transport::{ClientAddr, Remote, ServerAddr};
+use linkerd_stack::{layer, NewService, Param, Service};
+use linkerd_tls as tls;
+use pin_project::pin_project;
+use std::{
+    fmt,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tracing::{field, span, Level};
+
+/// A `NewService` that logs each connection accepted by the proxy.
+#[derive(Clone, Debug)]
+pub struct NewAccessLog<N> {
+    inner: N,
+}
+
+#[derive(Clone, Debug)]
+pub struct AccessLog<S> {
+    inner: S,
+    client_addr: SocketAddr,
+    server_addr: SocketAddr,
+    tls: tls::ConditionalServerTls,
+}
+
+#[pin_project]
+pub struct AccessLogFuture<F> {
+    conn: Option<Arc<Connection>>,
+
+    #[pin]
+    inner: F,
+}
+
+// === impl NewAccessLog ===
+
+impl<N> NewAccessLog<N> {
+    /// Returns a new `NewAccessLog` layer that logs each accepted connection.
+    ///
+    /// As with HTTP access logs, the log is recorded by a `tracing` span that
+    /// is only enabled when access logging is enabled, so the overhead of
+    /// tracking each connection is avoided otherwise.
+    pub fn layer() -> impl layer::Layer<N, Service = Self> + Clone {
+        layer::mk(|inner| Self { inner })
+    }
+}
+
+impl<T, N> NewService<T> for NewAccessLog<N>
+where
+    T: Param<tls::ConditionalServerTls> + Param<Remote<ClientAddr>> + Param<Remote<ServerAddr>>,
+    N: NewService<T>,
+{
+    type Service = AccessLog<N::Service>;
+
+    fn new_service(&self, target: T) -> Self::Service {
+        let Remote(ClientAddr(client_addr)) = target.param();
+        let Remote(ServerAddr(server_addr)) = target.param();
+        let tls = target.param();
+        let inner = self.inner.new_service(target);
+        AccessLog {
+            inner,
+            client_addr,
+            server_addr,
+            tls,
+        }
+    }
+}
+
+// === impl AccessLog ===
+
+impl<I, S> Service<I> for AccessLog<S>
+where
+    S: Service<AccessLogIo<I>, Response = ()>,
+    S::Error: fmt::Display,
+{
+    type Response = ();
+    type Error = S::Error;
+    type Future = AccessLogFuture<S::Future>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, io: I) -> Self::Future {
+        let client_id = self
+            .tls
+            .value()
+            .and_then(|tls| tls.client_id())
+            .map(|tls::ClientId(id)| id.to_str())
+            .unwrap_or(std::borrow::Cow::Borrowed("-"));
+
+        let span = span!(target: TRACE_TARGET, Level::INFO, "tcp",
+            client.addr = %self.client_addr,
+            client.id = %client_id,
+            timestamp = %now(),
+            server.addr = %self.server_addr,
+            tls = %TlsStatus(&self.tls),
+            bytes_in = field::Empty,
+            bytes_out = field::Empty,
+            duration_ns = field::Empty,
+            close = field::Empty,
+        );
+
+        let conn = Connection::open(span);
+        let io = AccessLogIo::new(io, Sensor(conn.clone()));
+        AccessLogFuture {
+            conn,
+            inner: self.inner.call(io),
+        }
+    }
+}
+
+// === impl AccessLogFuture ===
+
+impl<F> Future for AccessLogFuture<F>
+where
+    F: TryFuture<Ok = ()>,
+    F::Error: fmt::Display,
+{
+    type Output = Result<(), F::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let res = futures::ready!(this.inner.try_poll(cx));
+        if let Some(conn) = this.conn.take() {
+            if let Err(error) = &res {
+                conn.failed(error);
+            }
+        }
+        Poll::Ready(res)
+    }
+}
+
+struct TlsStatus<'t>(&'t tls::ConditionalServerTls);
+
+impl fmt::Display for TlsStatus<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            tls::ConditionalServerTls::Some(tls::ServerTls::Established { .. }) => {
+                f.write_str("true")
+            }
+            tls::ConditionalServerTls::Some(tls::ServerTls::Passthru { .. }) => {
+                f.write_str("passthru")
+            }
+            tls::ConditionalServerTls::None(reason) => fmt::Display::fmt(reason, f),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::*;
+use linkerd_io::{AsyncReadExt, AsyncWriteExt};
+use linkerd_proxy_transport::{ClientAddr, Remote, ServerAddr};
+use linkerd_stack::{layer::Layer, service_fn, NewService, Param, ServiceExt};
+use linkerd_tls as tls;
+use linkerd_tracing::test::capture_access_logs;
+use std::time::Duration;
+use tokio::time;
+
+#[derive(Clone, Debug)]
+struct Target;
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn records_eof() {
+    let (_trace, logs) = capture_access_logs();
+    let (mut client, server) = io::duplex(64);
+
+    client.write_all(b"ping").await.unwrap();
+    client.shutdown().await.unwrap();
+    let svc = NewAccessLog::layer()
+        .layer(|_: Target| {
+            service_fn(|mut io: AccessLogIo<io::DuplexStream>| async move {
+                let mut buf = Vec::new();
+                io.read_to_end(&mut buf).await?;
+                time::sleep(Duration::from_secs(1)).await;
+                io.write_all(b"pong!").await?;
+                Ok::<_, io::Error>(())
+            })
+        })
+        .new_service(Target);
+    svc.oneshot(server).await.expect("connection must succeed");
+
+    let log = logs.next().expect("connection must be logged");
+    assert_eq!(log["client.addr"], "192.0.2.3:50000");
+    assert_eq!(log["server.addr"], "192.0.2.4:8080");
+    assert_eq!(log["bytes_in"], "4");
+    assert_eq!(log["bytes_out"], "5");
+    assert_eq!(log["duration_ns"], "1000000000");
+    assert_eq!(log["close"], "eof");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn records_service_error() {
+    let (_trace, logs) = capture_access_logs();
+    let (_client, server) = io::duplex(64);
+
+    let svc = NewAccessLog::layer()
+        .layer(|_: Target| {
+            service_fn(|_: AccessLogIo<io::DuplexStream>| async move {
+                Err::<(), _>(io::Error::new(io::ErrorKind::Other, "boom"))
+            })
+        })
+        .new_service(Target);
+    svc.oneshot(server).await.expect_err("connection must fail");
+
+    let log = logs.next().expect("connection must be logged");
+    assert_eq!(log["bytes_in"], "0");
+    assert_eq!(log["bytes_out"], "0");
+    assert_eq!(log["close"], "boom");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn records_closed_by_proxy() {
+    let (_trace, logs) = capture_access_logs();
+    let (mut client, server) = io::duplex(64);
+
+    client.write_all(b"ping").await.unwrap();
+    let svc = NewAccessLog::layer()
+        .layer(|_: Target| {
+            service_fn(|mut io: AccessLogIo<io::DuplexStream>| async move {
+                // The connection is closed before the client closes it.
+                let mut buf = [0; 2];
+                io.read_exact(&mut buf).await?;
+                Ok::<_, io::Error>(())
+            })
+        })
+        .new_service(Target);
+    svc.oneshot(server).await.expect("connection must succeed");
+
+    let log = logs.next().expect("connection must be logged");
+    assert_eq!(log["bytes_in"], "2");
+    assert_eq!(log["close"], "closed");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn records_connect_error() {
+    let (_trace, logs) = capture_access_logs();
+
+    let connect = ConnectAccessLog::layer().layer(service_fn(|_: Target| async move {
+        Err::<(io::DuplexStream, ()), _>(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            "connection refused",
+        ))
+    }));
+    connect
+        .oneshot(Target)
+        .await
+        .expect_err("connection must fail");
+
+    let log = logs.next().expect("connection must be logged");
+    assert_eq!(log["server.addr"], "192.0.2.4:8080");
+    assert_eq!(log["tls"], "loopback");
+    assert_eq!(log["bytes_in"], "0");
+    assert_eq!(log["close"], "connection refused");
+}
+
+// === impl Target ===
+
+impl Param<Remote<ClientAddr>> for Target {
+    fn param(&self) -> Remote<ClientAddr> {
+        Remote(ClientAddr(([192, 0, 2, 3], 50000).into()))
+    }
+}
+
+impl Param<Remote<ServerAddr>> for Target {
+    fn param(&self) -> Remote<ServerAddr> {
+        Remote(ServerAddr(([192, 0, 2, 4], 8080).into()))
+    }
+}
+
+impl Param<tls::ConditionalServerTls> for Target {
+    fn param(&self) -> tls::ConditionalServerTls {
+        tls::ConditionalServerTls::None(tls::NoServerTls::Loopback)
+    }
+}
+
+impl Param<tls::ConditionalClientTls> for Target {
+    fn param(&self) -> tls::ConditionalClientTls {
+        tls::ConditionalClientTls::None(tls::NoClientTls::Loopback)
+    }
+}
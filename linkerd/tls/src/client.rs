@@ -5,12 +5,15 @@ use linkerd_dns_name as dns;
 use linkerd_identity as id;
 use linkerd_io as io;
 use linkerd_stack::{layer, MakeConnection, NewService, Oneshot, Param, Service, ServiceExt};
+use parking_lot::Mutex;
 use std::{
+    collections::HashMap,
     fmt,
     future::Future,
     ops::Deref,
     pin::Pin,
     str::FromStr,
+    sync::Arc,
     task::{Context, Poll},
 };
 use tracing::debug;
@@ -25,12 +28,54 @@ pub struct ClientTls {
     pub server_name: ServerName,
     pub server_id: ServerId,
     pub alpn: Option<AlpnProtocols>,
+
+    /// If set, the server does not participate in the mesh. Its certificate is
+    /// verified for `server_name` against the configured trust roots, and
+    /// `server_id` is not verified.
+    pub originate: Option<OriginateTls>,
 }
 
 /// A stack param that configures the available ALPN protocols.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct AlpnProtocols(pub Vec<Vec<u8>>);
 
+/// Configures TLS origination to a server outside of the mesh.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct OriginateTls {
+    pub roots: TrustRoots,
+
+    /// A client certificate presented to servers that require client
+    /// authentication.
+    pub client_cert: Option<ClientCert>,
+}
+
+/// Caches a TLS implementation's client configurations of type `C` for
+/// originating TLS, so that a configuration is only built once for each
+/// distinct [`OriginateTls`].
+///
+/// Configurations are not evicted: there is at most one for each distinct set
+/// of trust roots and client certificate that backends are configured with.
+#[derive(Debug)]
+pub struct OriginateConfigs<C>(Arc<Mutex<HashMap<OriginateTls, C>>>);
+
+/// The trust anchors used to verify a server's certificate when originating
+/// TLS.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TrustRoots {
+    /// The well-known public certificate authorities.
+    Public,
+
+    /// PEM-encoded trust anchors.
+    Pem(Arc<str>),
+}
+
+/// A PEM-encoded certificate chain and private key.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct ClientCert {
+    pub chain_pem: Arc<str>,
+    pub key_pem: Arc<str>,
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum NoClientTls {
     /// Identity is administratively disabled.
@@ -84,6 +129,18 @@ impl ClientTls {
             server_name,
             server_id,
             alpn: None,
+            originate: None,
+        }
+    }
+
+    /// Originates TLS to a server outside of the mesh, identified by its
+    /// server name.
+    pub fn originate(server_name: ServerName, originate: OriginateTls) -> Self {
+        Self {
+            server_id: ServerId(server_name.0.clone().into()),
+            server_name,
+            alpn: None,
+            originate: Some(originate),
         }
     }
 }
@@ -241,6 +298,57 @@ impl fmt::Display for NoClientTls {
     }
 }
 
+// === impl OriginateTls ===
+
+impl OriginateTls {
+    pub fn new(roots: TrustRoots, client_cert: Option<ClientCert>) -> Self {
+        Self { roots, client_cert }
+    }
+}
+
+// === impl OriginateConfigs ===
+
+impl<C: Clone> OriginateConfigs<C> {
+    /// Returns the client configuration for `originate`, building it the first
+    /// time it is needed.
+    pub fn get_or_build(
+        &self,
+        originate: &OriginateTls,
+        build: impl FnOnce(&OriginateTls) -> C,
+    ) -> C {
+        let mut configs = self.0.lock();
+        if let Some(config) = configs.get(originate) {
+            return config.clone();
+        }
+        let config = build(originate);
+        configs.insert(originate.clone(), config.clone());
+        config
+    }
+}
+
+impl<C> Clone for OriginateConfigs<C> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<C> Default for OriginateConfigs<C> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+// === impl ClientCert ===
+
+impl fmt::Debug for ClientCert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientCert")
+            .field("chain_pem", &self.chain_pem)
+            .field("key_pem", &"...")
+            .finish()
+    }
+}
+
 // === impl AlpnProtocols ===
 
 impl fmt::Debug for AlpnProtocols {
@@ -256,3 +364,38 @@ impl fmt::Debug for AlpnProtocols {
         dbg.finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn originate_config_is_built_once() {
+        let builds = AtomicUsize::new(0);
+        let build = |_: &OriginateTls| {
+            builds.fetch_add(1, Ordering::Relaxed);
+            Arc::new("config")
+        };
+
+        let configs = OriginateConfigs::default();
+        let originate = OriginateTls::new(TrustRoots::Public, None);
+        let config = configs.get_or_build(&originate, build);
+        let cloned = configs.clone().get_or_build(&originate, build);
+        assert!(Arc::ptr_eq(&config, &cloned));
+        assert_eq!(builds.load(Ordering::Relaxed), 1);
+
+        // Configurations are shared by equal, but separately constructed,
+        // values.
+        let other = OriginateTls::new(TrustRoots::Public, None);
+        let config = configs.get_or_build(&other, build);
+        assert!(Arc::ptr_eq(&config, &cloned));
+        assert_eq!(builds.load(Ordering::Relaxed), 1);
+
+        // Distinct trust roots are built separately.
+        let pem = OriginateTls::new(TrustRoots::Pem(Arc::from("roots")), None);
+        let config = configs.get_or_build(&pem, build);
+        assert!(!Arc::ptr_eq(&config, &cloned));
+        assert_eq!(builds.load(Ordering::Relaxed), 2);
+    }
+}
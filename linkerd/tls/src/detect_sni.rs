@@ -0,0 +1,74 @@
+use crate::{server::client_hello, ServerName};
+use bytes::BytesMut;
+use linkerd_detect::Detect;
+use linkerd_error::Error;
+use linkerd_io::{self as io, AsyncReadExt};
+use tracing::{debug, trace};
+
+/// Detects the SNI value of a TLS connection by reading its ClientHello.
+///
+/// Unlike server-side TLS detection, the ClientHello is buffered rather than
+/// peeked so that the connection may be proxied without terminating TLS.
+#[derive(Clone, Debug, Default)]
+pub struct DetectSni(());
+
+#[async_trait::async_trait]
+impl<I: io::AsyncRead + Send + Unpin + 'static> Detect<I> for DetectSni {
+    type Protocol = ServerName;
+
+    async fn detect(&self, io: &mut I, buf: &mut BytesMut) -> Result<Option<ServerName>, Error> {
+        // Reading into a `BytesMut` grows it as needed, so the buffer's
+        // initial capacity bounds how much is read.
+        let capacity = buf.capacity();
+        while buf.len() < capacity {
+            trace!(capacity, read = buf.len(), "Reading");
+            if io.read_buf(buf).await? == 0 {
+                break;
+            }
+
+            match client_hello::parse_sni(buf.as_ref()) {
+                Ok(sni) => {
+                    debug!(?sni, "Read TLS ClientHello");
+                    return Ok(sni);
+                }
+                Err(client_hello::Incomplete) => {}
+            }
+        }
+
+        debug!(read = buf.len(), "Could not read a TLS ClientHello");
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_test::io;
+
+    const CLIENT_HELLO: &[u8] = include_bytes!("server/testdata/example-com-client-hello.bin");
+
+    #[tokio::test]
+    async fn detects_sni() {
+        let mut buf = BytesMut::with_capacity(1024);
+        let (head, tail) = CLIENT_HELLO.split_at(CLIENT_HELLO.len() / 2);
+        let mut io = io::Builder::new().read(head).read(tail).build();
+        let sni = DetectSni::default()
+            .detect(&mut io, &mut buf)
+            .await
+            .unwrap()
+            .expect("SNI must be detected");
+        assert_eq!(sni.to_string(), "example.com");
+        assert_eq!(&buf[..], CLIENT_HELLO);
+    }
+
+    #[tokio::test]
+    async fn not_tls() {
+        let mut buf = BytesMut::with_capacity(1024);
+        let mut io = io::Builder::new().read(b"GET / HTTP/1.1\r\n\r\n").build();
+        let sni = DetectSni::default()
+            .detect(&mut io, &mut buf)
+            .await
+            .unwrap();
+        assert_eq!(sni, None);
+    }
+}
@@ -2,10 +2,14 @@
 #![forbid(unsafe_code)]
 
 pub mod client;
+mod detect_sni;
 pub mod server;
 
 pub use self::{
-    client::{Client, ClientTls, ConditionalClientTls, ConnectMeta, NoClientTls, ServerId},
+    client::{
+        Client, ClientTls, ConditionalClientTls, ConnectMeta, NoClientTls, OriginateTls, ServerId,
+    },
+    detect_sni::DetectSni,
     server::{ClientId, ConditionalServerTls, NewDetectTls, NoServerTls, ServerTls},
 };
 
@@ -1,4 +1,4 @@
-mod client_hello;
+pub(crate) mod client_hello;
 
 use crate::{NegotiatedProtocol, ServerName};
 use bytes::BytesMut;
@@ -2,9 +2,10 @@
 #![forbid(unsafe_code)]
 
 mod propagation;
+mod sampler;
 mod service;
 
-pub use self::service::TraceContext;
+pub use self::{propagation::sampled_span_ids, sampler::Sampler, service::TraceContext};
 use bytes::Bytes;
 use linkerd_error::Error;
 use rand::Rng;
@@ -15,6 +16,7 @@ use std::time::SystemTime;
 use thiserror::Error;
 
 const SPAN_ID_LEN: usize = 8;
+const TRACE_ID_LEN: usize = 16;
 
 #[derive(Debug, Default)]
 pub struct Id(Vec<u8>);
@@ -32,6 +34,8 @@ pub struct Span {
     pub span_id: Id,
     pub parent_id: Id,
     pub span_name: String,
+    /// The W3C `tracestate` propagated with the span, if any.
+    pub trace_state: Option<String>,
     pub start: SystemTime,
     pub end: SystemTime,
     pub labels: HashMap<&'static str, String>,
@@ -63,6 +67,23 @@ impl Id {
         rng.fill(bytes.as_mut_slice());
         Self(bytes)
     }
+
+    fn new_trace_id<R: Rng>(rng: &mut R) -> Self {
+        let mut bytes = vec![0; TRACE_ID_LEN];
+        rng.fill(bytes.as_mut_slice());
+        Self(bytes)
+    }
+
+    /// Returns true if the ID is unset, e.g. the parent ID of a root span.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for Id {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
 }
 
 impl From<Id> for Vec<u8> {
@@ -20,6 +20,11 @@ pub struct TraceContext {
     pub trace_id: Id,
     pub parent_id: Id,
     pub flags: Flags,
+    /// The W3C `tracestate` list, normalized. Only set for W3C contexts.
+    pub trace_state: Option<String>,
+    /// The W3C `baggage` list, normalized. Baggage is propagated regardless of
+    /// the context's propagation format.
+    pub baggage: Option<String>,
 }
 
 #[derive(Debug, Error)]
@@ -41,19 +46,50 @@ impl TraceContext {
 pub fn unpack_trace_context<B>(request: &http::Request<B>) -> Option<TraceContext> {
     // Attempt to parse as w3c first since it's the newest interface in
     // distributed tracing ecosystem
-    w3c::unpack_w3c_trace_context(request)
+    let mut context = w3c::unpack_w3c_trace_context(request)
         .or_else(|| b3::unpack_grpc_trace_context(request))
-        .or_else(|| b3::unpack_http_trace_context(request))
+        .or_else(|| b3::unpack_http_trace_context(request))?;
+    context.baggage = w3c::unpack_baggage(request);
+    Some(context)
+}
+
+/// Returns the trace and span IDs of the context propagated with a request, if
+/// the request is part of a sampled trace.
+pub fn sampled_span_ids<B>(request: &http::Request<B>) -> Option<(Id, Id)> {
+    let context = w3c::unpack_w3c_trace_context(request)
+        .or_else(|| b3::unpack_grpc_trace_context(request))
+        .or_else(|| b3::unpack_http_trace_context(request))?;
+    if !context.is_sampled() || context.parent_id.is_empty() {
+        return None;
+    }
+    Some((context.trace_id, context.parent_id))
+}
+
+/// Starts a new trace for a request that does not carry a trace context.
+///
+/// Root contexts are always propagated in the w3c format. Any baggage set by
+/// the caller is preserved.
+pub fn new_root_context<B>(request: &http::Request<B>, sampled: bool) -> TraceContext {
+    TraceContext {
+        propagation: Propagation::W3CHttp,
+        trace_id: Id::new_trace_id(&mut rand::thread_rng()),
+        parent_id: Id::default(),
+        flags: Flags(sampled as u8),
+        trace_state: None,
+        baggage: w3c::unpack_baggage(request),
+    }
 }
 
 // Generates a new span id, writes it to the request in the appropriate
 // propagation format and returns the generated span id.
 pub fn increment_span_id<B>(request: &mut http::Request<B>, context: &TraceContext) -> Id {
-    match context.propagation {
+    let span_id = match context.propagation {
         Propagation::B3Grpc => b3::increment_grpc_span_id(request, context),
         Propagation::B3Http => b3::increment_http_span_id(request),
         Propagation::W3CHttp => w3c::increment_http_span_id(request, context),
-    }
+    };
+    w3c::set_baggage(request, context.baggage.as_deref());
+    span_id
 }
 
 // === Header parse utils ===
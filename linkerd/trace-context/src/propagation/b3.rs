@@ -93,6 +93,8 @@ pub fn unpack_http_trace_context<B>(request: &http::Request<B>) -> Option<TraceC
         trace_id,
         parent_id,
         flags,
+        trace_state: None,
+        baggage: None,
     })
 }
 
@@ -106,6 +108,8 @@ fn parse_grpc_trace_context_fields(buf: &mut Bytes) -> Option<TraceContext> {
         trace_id: Default::default(),
         parent_id: Default::default(),
         flags: Default::default(),
+        trace_state: None,
+        baggage: None,
     };
 
     while !buf.is_empty() {
@@ -5,10 +5,40 @@ use super::{decode_id_with_padding, get_header_str, Propagation, TraceContext};
 use crate::{Flags, Id};
 
 static HTTP_TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+static HTTP_TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+static HTTP_BAGGAGE: HeaderName = HeaderName::from_static("baggage");
 const VERSION_00: &str = "00";
 
+// <https://www.w3.org/TR/trace-context-1/#tracestate-header-field-values>
+const MAX_TRACESTATE_MEMBERS: usize = 32;
+const MAX_TRACESTATE_KEY_LEN: usize = 256;
+const MAX_TRACESTATE_VALUE_LEN: usize = 256;
+
+// <https://www.w3.org/TR/baggage/#limits>
+const MAX_BAGGAGE_MEMBERS: usize = 180;
+const MAX_BAGGAGE_LEN: usize = 8192;
+
 pub fn unpack_w3c_trace_context<B>(request: &http::Request<B>) -> Option<TraceContext> {
-    get_header_str(request, &HTTP_TRACEPARENT).and_then(parse_context)
+    let mut context = get_header_str(request, &HTTP_TRACEPARENT).and_then(parse_context)?;
+    // The tracestate header is only meaningful alongside a valid traceparent.
+    context.trace_state = get_header_list(request, &HTTP_TRACESTATE)
+        .as_deref()
+        .and_then(parse_trace_state);
+    Some(context)
+}
+
+/// Reads the request's `baggage` header, dropping invalid list members and
+/// members that exceed the spec's limits.
+pub fn unpack_baggage<B>(request: &http::Request<B>) -> Option<String> {
+    get_header_list(request, &HTTP_BAGGAGE)
+        .as_deref()
+        .and_then(parse_baggage)
+}
+
+/// Sets the (normalized) `baggage` header on the request, or removes it if no
+/// valid baggage was propagated.
+pub fn set_baggage<B>(request: &mut http::Request<B>, baggage: Option<&str>) {
+    set_header(request, &HTTP_BAGGAGE, baggage)
 }
 
 /// Given an http request and a w3c trace context, create a new Span ID and
@@ -37,9 +67,183 @@ pub fn increment_http_span_id<B>(request: &mut http::Request<B>, context: &Trace
         debug!(header = %HTTP_TRACEPARENT, header_value = %new_header, "Invalid non-ASCII or control character in header value");
     }
 
+    // Forward the caller's tracestate unchanged (modulo normalization). An
+    // invalid or absent tracestate must not be propagated.
+    set_header(request, &HTTP_TRACESTATE, context.trace_state.as_deref());
+
     span_id
 }
 
+/// Joins all values of a list-valued header, as if they were sent as a single
+/// comma-separated header.
+fn get_header_list<B>(request: &http::Request<B>, header: &HeaderName) -> Option<String> {
+    let mut list: Option<String> = None;
+    for hv in request.headers().get_all(header) {
+        match hv.to_str() {
+            Ok(value) => match list {
+                Some(ref mut list) => {
+                    list.push(',');
+                    list.push_str(value);
+                }
+                None => list = Some(value.to_string()),
+            },
+            Err(_) => {
+                debug!(header_value = %header, "Invalid non-ASCII or control character in header value");
+            }
+        }
+    }
+    list
+}
+
+fn set_header<B>(request: &mut http::Request<B>, header: &HeaderName, value: Option<&str>) {
+    let headers = request.headers_mut();
+    headers.remove(header);
+    if let Some(value) = value {
+        if let Ok(hv) = http::HeaderValue::from_str(value) {
+            headers.insert(header, hv);
+        } else {
+            debug!(%header, header_value = %value, "Invalid non-ASCII or control character in header value");
+        }
+    }
+}
+
+/// Normalizes a `tracestate` list, dropping empty, invalid and duplicate list
+/// members. Returns `None` if no valid members remain.
+fn parse_trace_state(header_value: &str) -> Option<String> {
+    let mut members = Vec::<(&str, &str)>::new();
+    for member in header_value.split(',').map(str::trim) {
+        if member.is_empty() {
+            continue;
+        }
+        let (key, value) = match member.split_once('=') {
+            Some((key, value)) if is_trace_state_key(key) && is_trace_state_value(value) => {
+                (key, value)
+            }
+            _ => {
+                debug!(header = %HTTP_TRACESTATE, %member, "Dropping invalid tracestate list member");
+                continue;
+            }
+        };
+        if members.iter().any(|(k, _)| *k == key) {
+            debug!(header = %HTTP_TRACESTATE, %key, "Dropping duplicate tracestate key");
+            continue;
+        }
+        if members.len() == MAX_TRACESTATE_MEMBERS {
+            // Members are ordered from most to least recently updated, so the
+            // right-most members are the ones to drop.
+            debug!(header = %HTTP_TRACESTATE, "Dropping tracestate list members beyond the limit");
+            break;
+        }
+        members.push((key, value));
+    }
+
+    if members.is_empty() {
+        return None;
+    }
+    let members = members
+        .into_iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>();
+    Some(members.join(","))
+}
+
+// <https://www.w3.org/TR/trace-context-1/#key>
+fn is_trace_state_key(key: &str) -> bool {
+    fn is_key_char(c: char) -> bool {
+        matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '*' | '/')
+    }
+
+    let (tenant, system) = match key.split_once('@') {
+        Some((tenant, system)) => (tenant, Some(system)),
+        None => (key, None),
+    };
+    let valid_start = match (tenant.chars().next(), system) {
+        // Multi-tenant keys may start with a digit.
+        (Some(c), Some(_)) => c.is_ascii_lowercase() || c.is_ascii_digit(),
+        (Some(c), None) => c.is_ascii_lowercase(),
+        (None, _) => false,
+    };
+    let valid_system = system
+        .map(|s| s.starts_with(|c: char| c.is_ascii_lowercase()) && s.chars().all(is_key_char))
+        .unwrap_or(true);
+    key.len() <= MAX_TRACESTATE_KEY_LEN
+        && valid_start
+        && valid_system
+        && tenant.chars().all(is_key_char)
+}
+
+// <https://www.w3.org/TR/trace-context-1/#value>
+fn is_trace_state_value(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_TRACESTATE_VALUE_LEN
+        && !value.ends_with(' ')
+        && value
+            .chars()
+            .all(|c| matches!(c, ' '..='~') && c != ',' && c != '=')
+}
+
+/// Normalizes a `baggage` list, dropping invalid list members and members
+/// beyond the spec's limits. Returns `None` if no valid members remain.
+fn parse_baggage(header_value: &str) -> Option<String> {
+    let mut baggage = String::new();
+    let mut count = 0;
+    for member in header_value.split(',').map(str::trim) {
+        if member.is_empty() {
+            continue;
+        }
+        if !is_baggage_member(member) {
+            debug!(header = %HTTP_BAGGAGE, %member, "Dropping invalid baggage list member");
+            continue;
+        }
+        let len = if baggage.is_empty() {
+            member.len()
+        } else {
+            member.len() + 1
+        };
+        if count == MAX_BAGGAGE_MEMBERS || baggage.len() + len > MAX_BAGGAGE_LEN {
+            debug!(header = %HTTP_BAGGAGE, "Dropping baggage list members beyond the limit");
+            break;
+        }
+        if !baggage.is_empty() {
+            baggage.push(',');
+        }
+        baggage.push_str(member);
+        count += 1;
+    }
+
+    if baggage.is_empty() {
+        None
+    } else {
+        Some(baggage)
+    }
+}
+
+// <https://www.w3.org/TR/baggage/#definition>
+fn is_baggage_member(member: &str) -> bool {
+    fn is_token(s: &str) -> bool {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+    }
+
+    fn is_value(s: &str) -> bool {
+        s.chars()
+            .all(|c| matches!(c, '!'..='~') && !matches!(c, '"' | ',' | ';' | '\\'))
+    }
+
+    let mut parts = member.split(';').map(str::trim);
+    let valid_kv = parts
+        .next()
+        .and_then(|kv| kv.split_once('='))
+        .map(|(k, v)| is_token(k.trim()) && is_value(v.trim()))
+        .unwrap_or(false);
+    valid_kv
+        && parts.all(|property| match property.split_once('=') {
+            Some((k, v)) => is_token(k.trim()) && is_value(v.trim()),
+            None => is_token(property),
+        })
+}
+
 /// Parse a given header value as a w3c TraceContext value.
 fn parse_context(header_value: &str) -> Option<TraceContext> {
     let rest = match header_value.split_once('-') {
@@ -75,6 +279,8 @@ fn parse_context(header_value: &str) -> Option<TraceContext> {
         trace_id,
         parent_id,
         flags,
+        trace_state: None,
+        baggage: None,
     })
 }
 
@@ -97,7 +303,7 @@ fn parse_header_value(next_header_value: &str, pad_to: usize) -> Option<(Id, &st
 
 #[cfg(test)]
 mod tests {
-    use super::parse_context;
+    use super::{parse_baggage, parse_context, parse_trace_state};
 
     #[test]
     fn w3c_context_parsed_successfully() {
@@ -143,4 +349,60 @@ mod tests {
         let input = "00-94d7f6ec6b95f3e916179cb6cfd013901-55ccfce77972614-01";
         assert!(parse_context(input).is_none());
     }
+
+    #[test]
+    fn w3c_tracestate_normalized() {
+        assert_eq!(
+            parse_trace_state("congo=t61rcWkgMzE, rojo=00f067aa0ba902b7"),
+            Some("congo=t61rcWkgMzE,rojo=00f067aa0ba902b7".to_string())
+        );
+        assert_eq!(
+            parse_trace_state("fw529a3039@dt=1,,rojo=1"),
+            Some("fw529a3039@dt=1,rojo=1".to_string()),
+            "empty members are dropped and multi-tenant keys are valid"
+        );
+        assert_eq!(
+            parse_trace_state("rojo=1,congo=2,rojo=3"),
+            Some("rojo=1,congo=2".to_string()),
+            "duplicate keys are dropped"
+        );
+        assert_eq!(
+            parse_trace_state("Rojo=1,congo=,noequals,blanco=a=b,verde=1"),
+            Some("verde=1".to_string()),
+            "invalid members are dropped"
+        );
+        assert_eq!(parse_trace_state(" , "), None);
+    }
+
+    #[test]
+    fn w3c_tracestate_limited() {
+        let members = (0..40).map(|i| format!("k{i}=v")).collect::<Vec<_>>();
+        let expected = members[..32].join(",");
+        assert_eq!(parse_trace_state(&members.join(",")), Some(expected));
+    }
+
+    #[test]
+    fn w3c_baggage_normalized() {
+        assert_eq!(
+            parse_baggage("userId=alice, serverNode=DF%2028;prop=1 ,isProduction=false"),
+            Some("userId=alice,serverNode=DF%2028;prop=1,isProduction=false".to_string())
+        );
+        assert_eq!(
+            parse_baggage("novalue,bad key=1,ok=1,quoted=\"x\""),
+            Some("ok=1".to_string()),
+            "invalid members are dropped"
+        );
+        assert_eq!(parse_baggage(","), None);
+    }
+
+    #[test]
+    fn w3c_baggage_limited() {
+        let members = (0..200).map(|i| format!("k{i}=v")).collect::<Vec<_>>();
+        let baggage = parse_baggage(&members.join(",")).unwrap();
+        assert_eq!(baggage.split(',').count(), 180);
+
+        let big = format!("big={}", "x".repeat(8000));
+        let baggage = parse_baggage(&format!("{big},small={}", "y".repeat(200))).unwrap();
+        assert_eq!(baggage, big, "members beyond the size limit are dropped");
+    }
 }
@@ -0,0 +1,137 @@
+use rand::Rng;
+use std::sync::Arc;
+
+/// Determines which requests the proxy records spans for.
+///
+/// Requests that carry a trace context follow the caller's sampling decision.
+/// Requests without a trace context start a new trace at the configured
+/// ratio, which may be overridden for requests whose path matches a route's
+/// prefix. When `sample_errors` is set, spans for failed requests are emitted
+/// even if the trace was not sampled.
+///
+/// By default, the proxy never starts traces and only emits spans for sampled
+/// traces.
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    ratio: f64,
+    routes: Arc<[RouteRatio]>,
+    sample_errors: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct RouteRatio {
+    path_prefix: String,
+    ratio: f64,
+}
+
+// === impl Sampler ===
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self {
+            ratio: 0.0,
+            routes: Arc::from(Vec::new()),
+            sample_errors: false,
+        }
+    }
+}
+
+impl Sampler {
+    /// Starts new traces for the given ratio of untraced requests.
+    ///
+    /// The ratio is clamped to `[0.0, 1.0]`.
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio: clamp(ratio),
+            ..Self::default()
+        }
+    }
+
+    /// Overrides the ratio for untraced requests whose path starts with
+    /// `path_prefix`. When several prefixes match, the longest one is used.
+    pub fn with_route(mut self, path_prefix: impl Into<String>, ratio: f64) -> Self {
+        let mut routes = self.routes.to_vec();
+        routes.push(RouteRatio {
+            path_prefix: path_prefix.into(),
+            ratio: clamp(ratio),
+        });
+        self.routes = routes.into();
+        self
+    }
+
+    /// Emits spans for requests that fail, regardless of whether their trace
+    /// was sampled.
+    pub fn with_sample_errors(mut self, sample_errors: bool) -> Self {
+        self.sample_errors = sample_errors;
+        self
+    }
+
+    /// Decides whether to start a trace for a request with the given path
+    /// that does not carry a trace context.
+    ///
+    /// Returns `None` if no trace should be started, or whether the new trace
+    /// is sampled.
+    pub(crate) fn sample_root<R: Rng>(&self, path: &str, rng: &mut R) -> Option<bool> {
+        let ratio = self.root_ratio(path);
+        if ratio == 0.0 {
+            return None;
+        }
+        Some(rng.gen::<f64>() < ratio)
+    }
+
+    #[inline]
+    pub(crate) fn samples_errors(&self) -> bool {
+        self.sample_errors
+    }
+
+    fn root_ratio(&self, path: &str) -> f64 {
+        self.routes
+            .iter()
+            .filter(|r| path.starts_with(&r.path_prefix))
+            .max_by_key(|r| r.path_prefix.len())
+            .map(|r| r.ratio)
+            .unwrap_or(self.ratio)
+    }
+}
+
+fn clamp(ratio: f64) -> f64 {
+    if ratio.is_nan() {
+        return 0.0;
+    }
+    ratio.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let sampler = Sampler::default();
+        assert_eq!(sampler.sample_root("/", &mut rand::thread_rng()), None);
+        assert!(!sampler.samples_errors());
+    }
+
+    #[test]
+    fn route_overrides() {
+        let sampler = Sampler::new(0.5)
+            .with_route("/api", 1.0)
+            .with_route("/api/health", 0.0)
+            .with_route("/", 0.25);
+        assert_eq!(sampler.root_ratio("/api/users"), 1.0);
+        assert_eq!(sampler.root_ratio("/api/health"), 0.0);
+        assert_eq!(sampler.root_ratio("/other"), 0.25);
+        assert_eq!(Sampler::new(0.5).root_ratio("/other"), 0.5);
+
+        let mut rng = rand::thread_rng();
+        assert_eq!(sampler.sample_root("/api/health", &mut rng), None);
+        assert_eq!(sampler.sample_root("/api/users", &mut rng), Some(true));
+    }
+
+    #[test]
+    fn ratio_clamped() {
+        assert_eq!(Sampler::new(7.0).root_ratio("/"), 1.0);
+        assert_eq!(Sampler::new(-1.0).root_ratio("/"), 0.0);
+        assert_eq!(Sampler::new(f64::NAN).root_ratio("/"), 0.0);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{propagation, Span, SpanSink};
+use crate::{propagation, Sampler, Span, SpanSink};
 use futures::{future::Either, prelude::*};
 use linkerd_stack::layer;
 use std::{
@@ -19,19 +19,27 @@ use tracing::{debug, info, trace};
 /// the request. If the sampled bit of the header was set, we emit metadata
 /// about the span to the given SpanSink when the span is complete, i.e. when
 /// we receive the response.
+///
+/// The [`Sampler`] may start new traces for requests without a trace context
+/// and may emit spans for failed requests in traces that were not sampled.
 #[derive(Clone, Debug)]
 pub struct TraceContext<K, S> {
     inner: S,
     sink: K,
+    sampler: Sampler,
 }
 
 // === impl TraceContext ===
 
 impl<K: Clone, S> TraceContext<K, S> {
-    pub fn layer(sink: K) -> impl layer::Layer<S, Service = TraceContext<K, S>> + Clone {
+    pub fn layer(
+        sink: K,
+        sampler: Sampler,
+    ) -> impl layer::Layer<S, Service = TraceContext<K, S>> + Clone {
         layer::mk(move |inner| TraceContext {
             inner,
             sink: sink.clone(),
+            sampler: sampler.clone(),
         })
     }
 
@@ -62,6 +70,17 @@ impl<K: Clone, S> TraceContext<K, S> {
         labels.insert("http.status_code", rsp.status().as_str().to_string());
         labels
     }
+
+    /// Returns true if the response indicates that the request failed: either
+    /// a 5XX status or a trailers-only gRPC response with a non-OK status.
+    fn is_error<B>(rsp: &http::Response<B>) -> bool {
+        rsp.status().is_server_error()
+            || rsp
+                .headers()
+                .get("grpc-status")
+                .map(|v| v.as_bytes() != b"0")
+                .unwrap_or(false)
+    }
 }
 
 impl<K, S, ReqB, RspB> tower::Service<http::Request<ReqB>> for TraceContext<K, S>
@@ -85,25 +104,41 @@ where
 
     fn call(&mut self, mut req: http::Request<ReqB>) -> Self::Future {
         if self.sink.is_enabled() {
-            if let Some(context) = propagation::unpack_trace_context(&req) {
+            let context = propagation::unpack_trace_context(&req).or_else(|| {
+                // If the caller did not start a trace, the sampler may start one.
+                let sampled = self
+                    .sampler
+                    .sample_root(req.uri().path(), &mut rand::thread_rng())?;
+                debug!(sampled, "Starting a new trace");
+                Some(propagation::new_root_context(&req, sampled))
+            });
+
+            if let Some(context) = context {
                 // Update the trace ID if the request set one and the proxy is configured to emit
                 // spans.
                 let span_id = propagation::increment_span_id(&mut req, &context);
-                debug!(?span_id, sampled = context.is_sampled());
+                let sampled = context.is_sampled();
+                debug!(?span_id, sampled);
 
-                if context.is_sampled() {
-                    // If the request has been marked for sampling, record its metadata.
+                if sampled || self.sampler.samples_errors() {
+                    // If the request has been marked for sampling, record its metadata. Otherwise,
+                    // the metadata is only emitted if the request fails.
                     let start = SystemTime::now();
                     let req_labels = Self::request_labels(&req);
                     let mut sink = self.sink.clone();
                     let span_name = req.uri().path().to_owned();
                     return Either::Right(Box::pin(self.inner.call(req).map_ok(move |rsp| {
+                        if !sampled && !Self::is_error(&rsp) {
+                            return rsp;
+                        }
+
                         // Emit the completed span with the response metadata.
                         let span = Span {
                             span_id,
                             trace_id: context.trace_id,
                             parent_id: context.parent_id,
                             span_name,
+                            trace_state: context.trace_state,
                             start,
                             end: SystemTime::now(),
                             labels: Self::add_response_labels(req_labels, &rsp),
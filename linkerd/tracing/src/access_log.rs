@@ -1,166 +1,399 @@
-use std::fmt;
-use tracing::{field, span, Id, Level, Metadata, Subscriber};
+//! Access logging.
+//!
+//! Access logs are recorded by `tracing` spans with the [`TRACE_TARGET`]
+//! target. When access logging is enabled, the fields of these spans are
+//! collected as they are recorded. When a span closes, its fields are rendered
+//! as a single record in the configured [`Format`] and written to a dedicated
+//! output, separate from the proxy's diagnostic logs.
+//!
+//! Records are handed to the output's writer thread through a bounded buffer,
+//! so that writing access logs never blocks the proxy. Records that cannot be
+//! buffered are dropped and counted by the access log's [`Metrics`].
+
+mod format;
+mod sink;
+
+pub use self::sink::Metrics;
+use self::{
+    format::{Fields, Formatter},
+    sink::Sink,
+};
+use std::{path::PathBuf, sync::Arc};
+use tracing::{span, Id, Level, Metadata, Subscriber};
 use tracing_subscriber::{
-    field::RecordFields,
     filter::{FilterFn, Filtered},
-    fmt::{format, FormatFields, FormattedFields},
     layer::{Context, Layer},
     registry::LookupSpan,
 };
 
 pub const TRACE_TARGET: &str = "_access_log";
 
-pub(super) type AccessLogLayer<S> =
-    Filtered<Box<dyn Layer<S> + Send + Sync + 'static>, FilterFn, S>;
+/// The span field on which captured request header values are recorded.
+pub const REQUEST_HEADERS: &str = "request_headers";
 
-#[derive(Default)]
-pub(super) struct Writer<F = ApacheCommon> {
-    formatter: F,
-}
+/// The span field on which captured response header values are recorded.
+pub const RESPONSE_HEADERS: &str = "response_headers";
+
+const ENV_ACCESS_LOG: &str = "LINKERD2_PROXY_ACCESS_LOG";
+const ENV_FIELDS: &str = "LINKERD2_PROXY_ACCESS_LOG_FIELDS";
+const ENV_REQUEST_HEADERS: &str = "LINKERD2_PROXY_ACCESS_LOG_REQUEST_HEADERS";
+const ENV_RESPONSE_HEADERS: &str = "LINKERD2_PROXY_ACCESS_LOG_RESPONSE_HEADERS";
+const ENV_OUTPUT: &str = "LINKERD2_PROXY_ACCESS_LOG_OUTPUT";
+const ENV_BUFFER_CAPACITY: &str = "LINKERD2_PROXY_ACCESS_LOG_BUFFER_CAPACITY";
+
+const DEFAULT_BUFFER_CAPACITY: usize = 10_000;
 
-#[derive(Default)]
-pub(super) struct ApacheCommon {
-    _p: (),
+/// Separates captured header values in an encoded span field. Header values
+/// that can be recorded are limited to visible ASCII, so this can never occur
+/// in a value.
+const HEADER_SEPARATOR: char = '\x1f';
+
+pub(super) type AccessLogLayer<S> = Filtered<Writer, FilterFn, S>;
+
+/// Configures how access logs are formatted and where they are written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Config {
+    format: Format,
+    /// The fields included in each JSON record, in order. When unset, all of
+    /// a span's fields are included.
+    fields: Option<Arc<[Box<str>]>>,
+    headers: CaptureHeaders,
+    output: Output,
+    buffer_capacity: usize,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(super) enum Format {
+    /// The Apache Common Log Format.
     Apache,
+    /// The Apache Combined Log Format, which extends the common format with
+    /// the response size, referer, and user agent.
+    ApacheCombined,
+    /// One JSON object per record.
     Json,
 }
 
-struct ApacheCommonVisitor<'writer> {
-    res: fmt::Result,
-    writer: format::Writer<'writer>,
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Output {
+    Stderr,
+    Stdout,
+    File(PathBuf),
+}
+
+/// Names the HTTP headers whose values are captured in access logs.
+///
+/// Header names are lowercase. Captured values are appended to Apache records
+/// in the order in which the headers are configured, or recorded as JSON
+/// objects keyed by header name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaptureHeaders {
+    request: Arc<[Box<str>]>,
+    response: Arc<[Box<str>]>,
+}
+
+/// A handle to the process's access log.
+#[derive(Clone, Debug)]
+pub struct Handle {
+    headers: CaptureHeaders,
+    metrics: Metrics,
 }
 
-pub(super) fn build<S>(format: Format) -> AccessLogLayer<S>
+pub(super) struct Writer {
+    formatter: Formatter,
+    sink: Sink,
+}
+
+pub(super) fn build<S>(config: Config) -> Option<(AccessLogLayer<S>, Handle)>
 where
     S: Subscriber + for<'span> LookupSpan<'span>,
 {
-    let writer: Box<dyn Layer<S> + Send + Sync + 'static> = match format {
-        Format::Apache => Box::<Writer<ApacheCommon>>::default(),
-        Format::Json => Box::<Writer<format::JsonFields>>::default(),
+    let Config {
+        format,
+        fields,
+        headers,
+        output,
+        buffer_capacity,
+    } = config;
+
+    let sink = match Sink::spawn(&output, buffer_capacity) {
+        Ok(sink) => sink,
+        Err(error) => {
+            eprintln!("Failed to open access log output {:?}: {}", output, error);
+            return None;
+        }
+    };
+    let handle = Handle {
+        headers: headers.clone(),
+        metrics: sink.metrics().clone(),
     };
 
-    writer.with_filter(
+    let writer = Writer {
+        formatter: Formatter::new(format, fields, headers),
+        sink,
+    };
+    let layer = writer.with_filter(
         FilterFn::new(
             (|meta| meta.level() == &Level::INFO && meta.target().starts_with(TRACE_TARGET))
                 as fn(&Metadata<'_>) -> bool,
         )
         .with_max_level_hint(Level::INFO),
-    )
+    );
+
+    Some((layer, handle))
+}
+
+/// Encodes captured header values so that they may be recorded as a single
+/// [`REQUEST_HEADERS`] or [`RESPONSE_HEADERS`] span field.
+///
+/// Values must be provided in the order of the header names returned by
+/// [`CaptureHeaders`], with `None` for headers that are absent.
+pub fn encode_headers<'v>(values: impl IntoIterator<Item = Option<&'v str>>) -> String {
+    let mut encoded = String::new();
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            encoded.push(HEADER_SEPARATOR);
+        }
+        if let Some(value) = value {
+            encoded.push_str(value);
+        }
+    }
+    encoded
+}
+
+// === impl Config ===
+
+impl Config {
+    /// Loads the access log configuration from the environment.
+    ///
+    /// Returns `None` if access logging is not enabled.
+    pub(super) fn from_env() -> Option<Self> {
+        Self::parse(|name| std::env::var(name).ok())
+    }
+
+    fn parse(env: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let env = |name: &str| env(name).filter(|v| !v.trim().is_empty());
+
+        let format = {
+            let env = env(ENV_ACCESS_LOG)?;
+            match env.parse() {
+                Ok(format) => format,
+                Err(err) => {
+                    eprintln!("Invalid {}={:?}: {}", ENV_ACCESS_LOG, env, err);
+                    return None;
+                }
+            }
+        };
+
+        let fields = env(ENV_FIELDS).map(|v| parse_list(&v, false));
+
+        let headers = CaptureHeaders {
+            request: env(ENV_REQUEST_HEADERS)
+                .map(|v| parse_list(&v, true))
+                .unwrap_or_else(|| Arc::from(Vec::new())),
+            response: env(ENV_RESPONSE_HEADERS)
+                .map(|v| parse_list(&v, true))
+                .unwrap_or_else(|| Arc::from(Vec::new())),
+        };
+
+        let output = match env(ENV_OUTPUT) {
+            None => Output::Stderr,
+            Some(v) if v.trim().eq_ignore_ascii_case("stderr") => Output::Stderr,
+            Some(v) if v.trim().eq_ignore_ascii_case("stdout") => Output::Stdout,
+            Some(v) => Output::File(v.trim().into()),
+        };
+
+        let buffer_capacity = match env(ENV_BUFFER_CAPACITY) {
+            None => DEFAULT_BUFFER_CAPACITY,
+            Some(v) => match v.trim().parse::<usize>() {
+                Ok(capacity) if capacity > 0 => capacity,
+                _ => {
+                    eprintln!(
+                        "Invalid {}={:?}: expected a positive integer",
+                        ENV_BUFFER_CAPACITY, v
+                    );
+                    DEFAULT_BUFFER_CAPACITY
+                }
+            },
+        };
+
+        Some(Self {
+            format,
+            fields,
+            headers,
+            output,
+            buffer_capacity,
+        })
+    }
+}
+
+fn parse_list(list: &str, lowercase: bool) -> Arc<[Box<str>]> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            if lowercase {
+                s.to_ascii_lowercase().into_boxed_str()
+            } else {
+                s.into()
+            }
+        })
+        .collect()
+}
+
+// === impl Format ===
+
+impl std::str::FromStr for Format {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            s if s.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            s if s.eq_ignore_ascii_case("apache") => Ok(Self::Apache),
+            s if s.eq_ignore_ascii_case("apache-combined") => Ok(Self::ApacheCombined),
+            _ => Err("expected one of 'apache', 'apache-combined', or 'json'"),
+        }
+    }
+}
+
+// === impl CaptureHeaders ===
+
+impl Default for CaptureHeaders {
+    fn default() -> Self {
+        Self {
+            request: Arc::from(Vec::new()),
+            response: Arc::from(Vec::new()),
+        }
+    }
+}
+
+impl CaptureHeaders {
+    /// Returns the names of the request headers to capture.
+    pub fn request(&self) -> &[Box<str>] {
+        &self.request
+    }
+
+    /// Returns the names of the response headers to capture.
+    pub fn response(&self) -> &[Box<str>] {
+        &self.response
+    }
+}
+
+// === impl Handle ===
+
+impl Handle {
+    /// Returns the HTTP headers that should be captured in access logs.
+    pub fn headers(&self) -> &CaptureHeaders {
+        &self.headers
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
 }
 
 // === impl Writer ===
 
-impl<S, F> Layer<S> for Writer<F>
+impl<S> Layer<S> for Writer
 where
     S: Subscriber + for<'span> LookupSpan<'span>,
-    F: for<'writer> FormatFields<'writer> + 'static,
 {
     fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
         let span = ctx.span(id).expect("Span not found, this is a bug");
-        let mut extensions = span.extensions_mut();
-
-        if extensions.get_mut::<FormattedFields<F>>().is_none() {
-            let mut fields = FormattedFields::<F>::new(String::new());
-            if self
-                .formatter
-                .format_fields(fields.as_writer(), attrs)
-                .is_ok()
-            {
-                extensions.insert(fields);
-            }
-        }
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(fields);
     }
 
     fn on_record(&self, id: &Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
         let span = ctx.span(id).expect("Span not found, this is a bug");
         let mut extensions = span.extensions_mut();
-        if let Some(fields) = extensions.get_mut::<FormattedFields<F>>() {
-            let _ = self.formatter.add_fields(fields, values);
-            return;
-        }
-
-        let mut fields = FormattedFields::<F>::new(String::new());
-        if self
-            .formatter
-            .format_fields(fields.as_writer(), values)
-            .is_ok()
-        {
-            extensions.insert(fields);
+        if let Some(fields) = extensions.get_mut::<Fields>() {
+            values.record(fields);
         }
     }
 
     fn on_close(&self, id: Id, ctx: Context<'_, S>) {
         if let Some(span) = ctx.span(&id) {
-            if let Some(fields) = span.extensions().get::<FormattedFields<F>>() {
-                eprintln!("{}", fields.fields);
+            if let Some(fields) = span.extensions_mut().remove::<Fields>() {
+                let mut record = String::new();
+                if self
+                    .formatter
+                    .format(span.metadata(), &fields, &mut record)
+                    .is_ok()
+                {
+                    record.push('\n');
+                    self.sink.send(record);
+                }
             }
         }
     }
 }
 
-impl ApacheCommon {
-    const SKIPPED_FIELDS: &'static [&'static str] = &[
-        "trace_id",
-        "request_bytes",
-        "total_ns",
-        "processing_ns",
-        "response_bytes",
-        "user_agent",
-        "host",
-    ];
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
 
-impl<'writer> FormatFields<'writer> for ApacheCommon {
-    fn format_fields<R: RecordFields>(&self, writer: format::Writer<'_>, fields: R) -> fmt::Result {
-        let mut visitor = ApacheCommonVisitor {
-            writer,
-            res: Ok(()),
-        };
-        fields.record(&mut visitor);
-        visitor.res
+    fn parse(vars: &[(&str, &str)]) -> Option<Config> {
+        let vars = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        Config::parse(|name| vars.get(name).cloned())
     }
 
-    #[inline]
-    fn add_fields(
-        &self,
-        current: &mut FormattedFields<Self>,
-        fields: &span::Record<'_>,
-    ) -> fmt::Result {
-        self.format_fields(current.as_writer(), fields)
+    fn list(names: &[&str]) -> Arc<[Box<str>]> {
+        names.iter().map(|&n| n.into()).collect()
     }
-}
 
-impl field::Visit for ApacheCommonVisitor<'_> {
-    fn record_str(&mut self, field: &field::Field, val: &str) {
-        self.record_debug(field, &format_args!("{}", val))
+    #[test]
+    fn disabled_by_default() {
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(&[(ENV_ACCESS_LOG, "")]), None);
+        assert_eq!(parse(&[(ENV_ACCESS_LOG, "bogus")]), None);
     }
 
-    fn record_debug(&mut self, field: &field::Field, val: &dyn fmt::Debug) {
-        self.res = match field.name() {
-            n if ApacheCommon::SKIPPED_FIELDS.contains(&n) => return,
-            "timestamp" => write!(&mut self.writer, " [{:?}]", val),
-            "client.addr" => write!(&mut self.writer, "{:?}", val),
-            "client.id" => write!(&mut self.writer, " {:?} -", val),
-            "method" => write!(&mut self.writer, " \"{:?}", val),
-            "version" => write!(&mut self.writer, " {:?}\"", val),
-            _ => write!(&mut self.writer, " {:?}", val),
-        }
+    #[test]
+    fn defaults() {
+        let config = parse(&[(ENV_ACCESS_LOG, "apache")]).expect("must be enabled");
+        assert_eq!(config.format, Format::Apache);
+        assert_eq!(config.fields, None);
+        assert_eq!(config.headers, CaptureHeaders::default());
+        assert_eq!(config.output, Output::Stderr);
+        assert_eq!(config.buffer_capacity, DEFAULT_BUFFER_CAPACITY);
     }
-}
 
-// === impl Format ===
+    #[test]
+    fn configured() {
+        let config = parse(&[
+            (ENV_ACCESS_LOG, "APACHE-COMBINED"),
+            (ENV_FIELDS, "timestamp, client.addr,,status"),
+            (ENV_REQUEST_HEADERS, "X-Request-Id"),
+            (ENV_RESPONSE_HEADERS, "content-type, Server"),
+            (ENV_OUTPUT, "/var/log/linkerd/access.log"),
+            (ENV_BUFFER_CAPACITY, "100"),
+        ])
+        .expect("must be enabled");
+        assert_eq!(config.format, Format::ApacheCombined);
+        assert_eq!(
+            config.fields,
+            Some(list(&["timestamp", "client.addr", "status"]))
+        );
+        assert_eq!(config.headers.request, list(&["x-request-id"]));
+        assert_eq!(config.headers.response, list(&["content-type", "server"]));
+        assert_eq!(
+            config.output,
+            Output::File("/var/log/linkerd/access.log".into())
+        );
+        assert_eq!(config.buffer_capacity, 100);
 
-impl std::str::FromStr for Format {
-    type Err = &'static str;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            s if s.eq_ignore_ascii_case("json") => Ok(Self::Json),
-            s if s.eq_ignore_ascii_case("apache") => Ok(Self::Apache),
-            _ => Err("expected either 'apache' or 'json'"),
-        }
+        let config = parse(&[
+            (ENV_ACCESS_LOG, "json"),
+            (ENV_OUTPUT, "stdout"),
+            (ENV_BUFFER_CAPACITY, "0"),
+        ])
+        .expect("must be enabled");
+        assert_eq!(config.format, Format::Json);
+        assert_eq!(config.output, Output::Stdout);
+        assert_eq!(config.buffer_capacity, DEFAULT_BUFFER_CAPACITY);
     }
 }
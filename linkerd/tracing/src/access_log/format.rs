@@ -0,0 +1,384 @@
+use super::{CaptureHeaders, Format, HEADER_SEPARATOR, REQUEST_HEADERS, RESPONSE_HEADERS};
+use std::{
+    fmt::{self, Write},
+    sync::Arc,
+};
+use tracing::{field, Metadata};
+
+/// Renders access log records in the configured format.
+#[derive(Debug)]
+pub(super) struct Formatter {
+    format: Format,
+    fields: Option<Arc<[Box<str>]>>,
+    headers: CaptureHeaders,
+}
+
+/// The values recorded on an access log span, in the order they were first
+/// recorded.
+#[derive(Debug, Default)]
+pub(super) struct Fields(Vec<(&'static str, Value)>);
+
+#[derive(Debug)]
+enum Value {
+    /// A number or boolean, which is rendered as-is in JSON records.
+    Raw(String),
+    /// A string, which is quoted in JSON records.
+    Str(String),
+}
+
+/// Escapes quotes and backslashes in a quoted Apache field.
+struct Escape<'s>(&'s str);
+
+// === impl Formatter ===
+
+impl Formatter {
+    pub(super) fn new(
+        format: Format,
+        fields: Option<Arc<[Box<str>]>>,
+        headers: CaptureHeaders,
+    ) -> Self {
+        Self {
+            format,
+            fields,
+            headers,
+        }
+    }
+
+    /// Renders a record for the span with the given metadata and fields.
+    pub(super) fn format(
+        &self,
+        meta: &Metadata<'_>,
+        fields: &Fields,
+        out: &mut String,
+    ) -> fmt::Result {
+        match self.format {
+            Format::Json => self.format_json(meta, fields, out),
+            Format::Apache | Format::ApacheCombined if meta.name() == "http" => {
+                self.format_apache_http(fields, out)
+            }
+            Format::Apache | Format::ApacheCombined => Self::format_apache(meta, fields, out),
+        }
+    }
+
+    /// Renders a JSON object with the configured fields.
+    ///
+    /// When no fields are configured, every field declared by the span is
+    /// included, so that all records for a given kind of span have the same
+    /// keys. Fields that were not recorded are `null`.
+    fn format_json(&self, meta: &Metadata<'_>, fields: &Fields, out: &mut String) -> fmt::Result {
+        let names: Box<dyn Iterator<Item = &str>> = match self.fields.as_deref() {
+            Some(names) => Box::new(names.iter().map(|n| &**n)),
+            None => Box::new(meta.fields().iter().map(|f| f.name())),
+        };
+
+        out.push('{');
+        for (i, name) in names.enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json_str(name, out)?;
+            out.push(':');
+            let value = fields.get(name);
+            match name {
+                REQUEST_HEADERS => write_json_headers(self.headers.request(), value, out)?,
+                RESPONSE_HEADERS => write_json_headers(self.headers.response(), value, out)?,
+                _ => match value {
+                    Some(Value::Raw(v)) => out.push_str(v),
+                    Some(Value::Str(v)) => write_json_str(v, out)?,
+                    None => out.push_str("null"),
+                },
+            }
+        }
+        out.push('}');
+        Ok(())
+    }
+
+    /// Renders an HTTP request in the Apache Common or Combined Log Format,
+    /// followed by any captured header values.
+    fn format_apache_http(&self, fields: &Fields, out: &mut String) -> fmt::Result {
+        let get = |name| {
+            fields
+                .get(name)
+                .map(Value::as_str)
+                .filter(|v| !v.is_empty())
+                .unwrap_or("-")
+        };
+
+        write!(
+            out,
+            "{} {} - [{}] \"{} {} {}\" {}",
+            get("client.addr"),
+            get("client.id"),
+            get("timestamp"),
+            get("method"),
+            Escape(get("uri")),
+            get("version"),
+            get("status"),
+        )?;
+
+        if self.format == Format::ApacheCombined {
+            write!(
+                out,
+                " {} \"{}\" \"{}\"",
+                get("response_bytes"),
+                Escape(get("referer")),
+                Escape(get("user_agent")),
+            )?;
+        }
+
+        let request = captured(self.headers.request(), fields.get(REQUEST_HEADERS));
+        let response = captured(self.headers.response(), fields.get(RESPONSE_HEADERS));
+        for (_, value) in request.chain(response) {
+            write!(out, " \"{}\"", Escape(value.unwrap_or("-")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders other spans, such as TCP connections, as a space-separated
+    /// list of the span's fields.
+    fn format_apache(meta: &Metadata<'_>, fields: &Fields, out: &mut String) -> fmt::Result {
+        for (i, field) in meta.fields().iter().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            let value = fields
+                .get(field.name())
+                .map(Value::as_str)
+                .filter(|v| !v.is_empty())
+                .unwrap_or("-");
+            if field.name() == "timestamp" {
+                write!(out, "[{}]", value)?;
+            } else if value.contains(char::is_whitespace) {
+                write!(out, "\"{}\"", Escape(value))?;
+            } else {
+                out.push_str(value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Pairs configured header names with the values encoded in a span field.
+fn captured<'a>(
+    names: &'a [Box<str>],
+    encoded: Option<&'a Value>,
+) -> impl Iterator<Item = (&'a str, Option<&'a str>)> + 'a {
+    let mut values = encoded.map(|v| v.as_str().split(HEADER_SEPARATOR));
+    names.iter().map(move |name| {
+        let value = values
+            .as_mut()
+            .and_then(|vs| vs.next())
+            .filter(|v| !v.is_empty());
+        (&**name, value)
+    })
+}
+
+fn write_json_headers(
+    names: &[Box<str>],
+    encoded: Option<&Value>,
+    out: &mut String,
+) -> fmt::Result {
+    out.push('{');
+    for (i, (name, value)) in captured(names, encoded).enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json_str(name, out)?;
+        out.push(':');
+        match value {
+            Some(v) => write_json_str(v, out)?,
+            None => out.push_str("null"),
+        }
+    }
+    out.push('}');
+    Ok(())
+}
+
+fn write_json_str(s: &str, out: &mut String) -> fmt::Result {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    Ok(())
+}
+
+// === impl Fields ===
+
+impl Fields {
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.0.iter().find(|(n, _)| *n == name).map(|(_, v)| v)
+    }
+
+    fn insert(&mut self, name: &'static str, value: Value) {
+        match self.0.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.0.push((name, value)),
+        }
+    }
+}
+
+impl field::Visit for Fields {
+    fn record_f64(&mut self, field: &field::Field, value: f64) {
+        if value.is_finite() {
+            self.insert(field.name(), Value::Raw(value.to_string()));
+        } else {
+            self.insert(field.name(), Value::Str(value.to_string()));
+        }
+    }
+
+    fn record_i64(&mut self, field: &field::Field, value: i64) {
+        self.insert(field.name(), Value::Raw(value.to_string()));
+    }
+
+    fn record_u64(&mut self, field: &field::Field, value: u64) {
+        self.insert(field.name(), Value::Raw(value.to_string()));
+    }
+
+    fn record_bool(&mut self, field: &field::Field, value: bool) {
+        self.insert(field.name(), Value::Raw(value.to_string()));
+    }
+
+    fn record_str(&mut self, field: &field::Field, value: &str) {
+        self.insert(field.name(), Value::Str(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &field::Field, value: &dyn fmt::Debug) {
+        self.insert(field.name(), Value::Str(format!("{:?}", value)));
+    }
+}
+
+// === impl Value ===
+
+impl Value {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::Raw(v) | Self::Str(v) => v,
+        }
+    }
+}
+
+// === impl Escape ===
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            if c == '"' || c == '\\' {
+                f.write_char('\\')?;
+            }
+            f.write_char(c)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_log::encode_headers;
+
+    fn render(format: Format, names: Option<&[&str]>) -> String {
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry());
+        let span = tracing::info_span!(
+            "http",
+            client.addr = field::Empty,
+            client.id = field::Empty,
+            timestamp = field::Empty,
+            method = field::Empty,
+            uri = field::Empty,
+            version = field::Empty,
+            status = field::Empty,
+            response_bytes = field::Empty,
+            referer = field::Empty,
+            user_agent = field::Empty,
+            request_headers = field::Empty,
+            response_headers = field::Empty,
+        );
+        let meta = span.metadata().expect("span must be enabled");
+
+        let mut fields = Fields::default();
+        let s = |v: &str| Value::Str(v.to_string());
+        fields.insert("client.addr", s("10.1.2.3:45678"));
+        fields.insert("client.id", s("foo.ns.serviceaccount.identity.linkerd"));
+        fields.insert("timestamp", s("2023-01-01T00:00:00Z"));
+        fields.insert("method", s("GET"));
+        fields.insert("uri", s("http://foo.example.com/\"bar\""));
+        fields.insert("version", s("HTTP/1.1"));
+        fields.insert("status", Value::Raw("200".to_string()));
+        fields.insert("referer", s(""));
+        fields.insert("user_agent", s("curl/8.0.1"));
+        fields.insert(REQUEST_HEADERS, s(&encode_headers([None, Some("abc")])));
+
+        let headers = CaptureHeaders {
+            request: vec!["x-forwarded-for".into(), "x-request-id".into()].into(),
+            response: vec!["content-type".into()].into(),
+        };
+        let names = names.map(|ns| ns.iter().map(|&n| n.into()).collect());
+        let mut out = String::new();
+        Formatter::new(format, names, headers)
+            .format(meta, &fields, &mut out)
+            .expect("must format");
+        out
+    }
+
+    #[test]
+    fn apache_common() {
+        assert_eq!(
+            render(Format::Apache, None),
+            "10.1.2.3:45678 foo.ns.serviceaccount.identity.linkerd - [2023-01-01T00:00:00Z] \
+            \"GET http://foo.example.com/\\\"bar\\\" HTTP/1.1\" 200 \"-\" \"abc\" \"-\""
+        );
+    }
+
+    #[test]
+    fn apache_combined() {
+        assert_eq!(
+            render(Format::ApacheCombined, Some(&["ignored"])),
+            "10.1.2.3:45678 foo.ns.serviceaccount.identity.linkerd - [2023-01-01T00:00:00Z] \
+            \"GET http://foo.example.com/\\\"bar\\\" HTTP/1.1\" 200 - \"-\" \"curl/8.0.1\" \
+            \"-\" \"abc\" \"-\""
+        );
+    }
+
+    #[test]
+    fn json_all_fields() {
+        assert_eq!(
+            render(Format::Json, None),
+            "{\"client.addr\":\"10.1.2.3:45678\",\
+            \"client.id\":\"foo.ns.serviceaccount.identity.linkerd\",\
+            \"timestamp\":\"2023-01-01T00:00:00Z\",\
+            \"method\":\"GET\",\
+            \"uri\":\"http://foo.example.com/\\\"bar\\\"\",\
+            \"version\":\"HTTP/1.1\",\
+            \"status\":200,\
+            \"response_bytes\":null,\
+            \"referer\":\"\",\
+            \"user_agent\":\"curl/8.0.1\",\
+            \"request_headers\":{\"x-forwarded-for\":null,\"x-request-id\":\"abc\"},\
+            \"response_headers\":{\"content-type\":null}}"
+        );
+    }
+
+    #[test]
+    fn json_configured_fields() {
+        assert_eq!(
+            render(
+                Format::Json,
+                Some(&["timestamp", "status", "request_headers", "unknown"])
+            ),
+            "{\"timestamp\":\"2023-01-01T00:00:00Z\",\
+            \"status\":200,\
+            \"request_headers\":{\"x-forwarded-for\":null,\"x-request-id\":\"abc\"},\
+            \"unknown\":null}"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use super::Output;
+use prometheus_client::{metrics::counter::Counter, registry::Registry};
+use std::{
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    sync::mpsc,
+};
+
+/// Hands access log records to a dedicated writer thread.
+#[derive(Debug)]
+pub(super) struct Sink {
+    tx: mpsc::SyncSender<String>,
+    metrics: Metrics,
+}
+
+/// Counts the records handled by the access log's output.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    written: Counter,
+    dropped: Counter,
+}
+
+// === impl Sink ===
+
+impl Sink {
+    /// Opens the output and spawns a thread that writes records to it.
+    ///
+    /// At most `capacity` records are buffered while waiting to be written.
+    pub(super) fn spawn(output: &Output, capacity: usize) -> io::Result<Self> {
+        let out: Box<dyn Write + Send> = match output {
+            Output::Stderr => Box::new(io::stderr()),
+            Output::Stdout => Box::new(io::stdout()),
+            Output::File(path) => {
+                Box::new(OpenOptions::new().create(true).append(true).open(path)?)
+            }
+        };
+
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let metrics = Metrics::default();
+        std::thread::Builder::new()
+            .name("accesslog".into())
+            .spawn({
+                let metrics = metrics.clone();
+                move || write_records(BufWriter::new(out), rx, metrics)
+            })?;
+
+        Ok(Self { tx, metrics })
+    }
+
+    pub(super) fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Buffers a record to be written, without blocking.
+    ///
+    /// If the buffer is full, the record is dropped.
+    pub(super) fn send(&self, record: String) {
+        if self.tx.try_send(record).is_err() {
+            self.metrics.dropped.inc();
+        }
+    }
+}
+
+/// Writes records until the sink is dropped.
+///
+/// The output is flushed whenever the buffer has been drained, so that
+/// records are written promptly without flushing each one individually.
+fn write_records(mut out: impl Write, rx: mpsc::Receiver<String>, metrics: Metrics) {
+    while let Ok(record) = rx.recv() {
+        let mut written = 0;
+        let mut failed = 0;
+        for record in std::iter::once(record).chain(rx.try_iter()) {
+            if out.write_all(record.as_bytes()).is_ok() {
+                written += 1;
+            } else {
+                failed += 1;
+            }
+        }
+
+        if out.flush().is_err() {
+            // Records that were buffered but could not be written are lost.
+            failed += written;
+            written = 0;
+        }
+        metrics.written.inc_by(written);
+        metrics.dropped.inc_by(failed);
+    }
+}
+
+// === impl Metrics ===
+
+impl Metrics {
+    /// Registers the access log's counters.
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "records_written",
+            "The number of access log records written to the access log output",
+            self.written.clone(),
+        );
+        registry.register(
+            "records_dropped",
+            "The number of access log records dropped because the access log buffer was full or the output failed",
+            self.dropped.clone(),
+        );
+    }
+}
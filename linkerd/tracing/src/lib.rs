@@ -24,7 +24,6 @@ pub use tracing_subscriber::{registry, EnvFilter};
 
 const ENV_LOG_LEVEL: &str = "LINKERD2_PROXY_LOG";
 const ENV_LOG_FORMAT: &str = "LINKERD2_PROXY_LOG_FORMAT";
-const ENV_ACCESS_LOG: &str = "LINKERD2_PROXY_ACCESS_LOG";
 
 const DEFAULT_LOG_LEVEL: &str = "warn,linkerd=info,trust_dns=error";
 const DEFAULT_LOG_FORMAT: &str = "PLAIN";
@@ -35,13 +34,14 @@ pub struct Settings {
     filter: String,
     format: String,
     start_time: Option<time::Instant>,
-    access_log: Option<access_log::Format>,
+    access_log: Option<access_log::Config>,
     is_test: bool,
 }
 
 #[derive(Clone)]
 pub struct Handle {
     level: Option<level::Handle>,
+    access_log: Option<access_log::Handle>,
     #[cfg(feature = "stream")]
     stream: stream::StreamHandle<LogStack>,
 }
@@ -71,7 +71,7 @@ impl Settings {
             format: std::env::var(ENV_LOG_FORMAT)
                 .ok()
                 .unwrap_or_else(|| DEFAULT_LOG_FORMAT.to_string()),
-            access_log: Self::access_log_format(),
+            access_log: access_log::Config::from_env(),
             start_time: Some(now),
             is_test: false,
         }
@@ -82,22 +82,11 @@ impl Settings {
             filter,
             format,
             start_time: None,
-            access_log: Self::access_log_format(),
+            access_log: access_log::Config::from_env(),
             is_test: true,
         }
     }
 
-    fn access_log_format() -> Option<access_log::Format> {
-        let env = std::env::var(ENV_ACCESS_LOG).ok()?;
-        match env.parse() {
-            Ok(format) => Some(format),
-            Err(err) => {
-                eprintln!("Invalid {}={:?}: {}", ENV_ACCESS_LOG, env, err);
-                None
-            }
-        }
-    }
-
     fn timer(&self) -> Uptime {
         self.start_time
             .map(Uptime::starting_at)
@@ -156,6 +145,7 @@ impl Settings {
         if self.filter.trim().eq_ignore_ascii_case("off") {
             return Ok(Handle {
                 level: None,
+                access_log: None,
 
                 // logging is disabled, but log streaming might still be enabled later
                 #[cfg(feature = "stream")]
@@ -185,7 +175,7 @@ impl Settings {
     /// The log dispatcher handles:
     ///
     /// - process diagnostic logging to stdout;
-    /// - optional access logging to stderr;
+    /// - optional access logging to a dedicated output (stderr, by default);
     /// - if the `stream` feature is enabled, on-demand log streaming via the
     ///   returned `Handle`
     pub fn build(self) -> (Dispatch, Handle) {
@@ -221,11 +211,13 @@ impl Settings {
         };
 
         // Access logging is optionally enabled process-wide.
-        let registry = registry.with(self.access_log.map(access_log::build));
+        let (access_log, access_log_handle) = self.access_log.and_then(access_log::build).unzip();
+        let registry = registry.with(access_log);
 
         // The handle controls the logging system at runtime.
         let handle = Handle {
             level: Some(level::Handle::new(level)),
+            access_log: access_log_handle,
             #[cfg(feature = "stream")]
             stream,
         };
@@ -245,6 +237,7 @@ impl Handle {
     pub fn disabled() -> Self {
         Self {
             level: None,
+            access_log: None,
             #[cfg(feature = "stream")]
             stream: stream::StreamHandle::new().0,
         }
@@ -254,6 +247,11 @@ impl Handle {
         self.level.as_ref()
     }
 
+    /// Returns a handle to the access log, if access logging is enabled.
+    pub fn access_log(&self) -> Option<&access_log::Handle> {
+        self.access_log.as_ref()
+    }
+
     #[cfg(feature = "stream")]
     pub fn into_stream(self) -> stream::StreamHandle<LogStack> {
         self.stream
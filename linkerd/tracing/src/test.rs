@@ -28,3 +28,96 @@ pub fn with_default_filter(
 pub fn trace_init() -> (tracing::dispatcher::DefaultGuard, crate::Handle) {
     with_default_filter(DEFAULT_LOG)
 }
+
+/// The fields recorded on a closed access log span, formatted as strings.
+pub type AccessLogFields = std::collections::HashMap<String, String>;
+
+/// Receives the fields of access log spans as they are closed.
+#[derive(Debug)]
+pub struct AccessLogs(std::sync::mpsc::Receiver<AccessLogFields>);
+
+/// A layer that only enables access log spans and sends their fields to
+/// [`AccessLogs`] when they are closed.
+struct CaptureAccessLogs(std::sync::mpsc::Sender<AccessLogFields>);
+
+#[derive(Default)]
+struct Fields(AccessLogFields);
+
+/// Sets a default subscriber that captures access logs, so that tests may
+/// assert on the fields that they record.
+pub fn capture_access_logs() -> (tracing::dispatcher::DefaultGuard, AccessLogs) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let subscriber = tracing_subscriber::registry().with(CaptureAccessLogs(tx));
+    (tracing::subscriber::set_default(subscriber), AccessLogs(rx))
+}
+
+// === impl AccessLogs ===
+
+impl AccessLogs {
+    /// Returns the fields of the next access log span that was closed, if any.
+    pub fn next(&self) -> Option<AccessLogFields> {
+        self.0.try_recv().ok()
+    }
+}
+
+// === impl CaptureAccessLogs ===
+
+impl<S> Layer<S> for CaptureAccessLogs
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(
+        &self,
+        meta: &tracing::Metadata<'_>,
+        _: tracing_subscriber::layer::Context<'_, S>,
+    ) -> bool {
+        meta.target() == access_log::TRACE_TARGET
+    }
+
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(
+        &self,
+        id: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+                values.record(fields);
+            }
+        }
+    }
+
+    fn on_close(&self, id: tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(Fields(fields)) = span.extensions_mut().remove::<Fields>() {
+                let _ = self.0.send(fields);
+            }
+        }
+    }
+}
+
+// === impl Fields ===
+
+impl tracing::field::Visit for Fields {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
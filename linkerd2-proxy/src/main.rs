@@ -81,12 +81,14 @@ fn main() {
 
         // TODO distinguish ServerName and Identity.
         info!("Local identity is {}", app.local_server_name());
-        let addr = app.identity_addr();
-        match addr.identity.value() {
-            None => info!("Identity verified via {}", addr.addr),
-            Some(tls) => {
-                info!("Identity verified via {} ({})", addr.addr, tls.server_id);
-            }
+        match app.identity_addr() {
+            None => info!("Identity provisioned without the identity controller"),
+            Some(addr) => match addr.identity.value() {
+                None => info!("Identity verified via {}", addr.addr),
+                Some(tls) => {
+                    info!("Identity verified via {} ({})", addr.addr, tls.server_id);
+                }
+            },
         }
 
         let dst_addr = app.dst_addr();
@@ -98,13 +100,13 @@ fn main() {
             ),
         }
 
-        if let Some(oc) = app.opencensus_addr() {
-            match oc.identity.value() {
-                None => info!("OpenCensus tracing collector at {}", oc.addr),
+        if let Some((protocol, addr)) = app.tracing_addr() {
+            match addr.identity.value() {
+                None => info!("{} tracing collector at {}", protocol, addr.addr),
                 Some(tls) => {
                     info!(
-                        "OpenCensus tracing collector at {} ({})",
-                        oc.addr, tls.server_id
+                        "{} tracing collector at {} ({})",
+                        protocol, addr.addr, tls.server_id
                     )
                 }
             }
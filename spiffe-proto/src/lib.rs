@@ -0,0 +1,11 @@
+//! gRPC bindings for the SPIFFE Workload API.
+//!
+//! Vendored from <https://github.com/spiffe/spiffe/>.
+
+#![deny(rust_2018_idioms, clippy::disallowed_methods, clippy::disallowed_types)]
+#![allow(clippy::derive_partial_eq_without_eq)]
+#![forbid(unsafe_code)]
+
+pub mod workload {
+    include!("gen/_.rs");
+}